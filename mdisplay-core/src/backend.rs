@@ -0,0 +1,909 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::settings::{AppSettings, SaveFormat};
+
+/// A video mode advertised by an output.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OutputMode {
+    pub width: i32,
+    pub height: i32,
+    pub refresh_rate: f32,
+    pub current: bool,
+    pub preferred: bool,
+    /// Added by MDisplay rather than advertised by the output; see
+    /// [`Output::set_refresh_rate`].
+    #[serde(default)]
+    pub custom: bool,
+}
+
+/// One connected output (head) as reported by the compositor. `physical_size` is
+/// formatted as `"WxH mm"`, `transform` uses the wlr-randr names (`normal`, `90`, …).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Output {
+    pub name: String,
+    pub description: String,
+    pub make: String,
+    pub model: String,
+    pub serial: String,
+    pub physical_size: String,
+    pub position: (i32, i32),
+    pub scale: f32,
+    pub transform: String,
+    pub modes: Vec<OutputMode>,
+    pub enabled: bool,
+    /// Variable refresh rate; `None` when the compositor does not report it.
+    #[serde(default)]
+    pub adaptive_sync: Option<bool>,
+    /// RGB quantization range; `None` when the backend cannot set it. Only plugins do, so
+    /// it is kept in profiles but left out of [`config_text`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rgb_range: Option<RgbRange>,
+    /// Pixel encoding on the wire; `None` when the backend cannot set it. As with
+    /// [`Output::rgb_range`], only plugins do.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pixel_format: Option<PixelFormat>,
+    /// Physical order of the colour elements, as the compositor reports it.
+    #[serde(default)]
+    pub subpixel: Subpixel,
+}
+
+/// Subpixel order, which font antialiasing needs to match the panel; a rotated panel's
+/// horizontal stripes become vertical.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Subpixel {
+    #[default]
+    Unknown,
+    None,
+    Rgb,
+    Bgr,
+    VerticalRgb,
+    VerticalBgr,
+}
+
+impl Subpixel {
+    /// The orders that can be chosen as an override.
+    pub const KNOWN: [Subpixel; 5] = [
+        Subpixel::Rgb,
+        Subpixel::Bgr,
+        Subpixel::VerticalRgb,
+        Subpixel::VerticalBgr,
+        Subpixel::None,
+    ];
+}
+
+impl std::fmt::Display for Subpixel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Subpixel::Unknown => "Unknown",
+            Subpixel::None => "None",
+            Subpixel::Rgb => "RGB",
+            Subpixel::Bgr => "BGR",
+            Subpixel::VerticalRgb => "Vertical RGB",
+            Subpixel::VerticalBgr => "Vertical BGR",
+        })
+    }
+}
+
+/// Whether RGB values span 0–255 or the 16–235 range video equipment expects. HDMI TVs
+/// often assume limited range and look washed out when sent full range without being
+/// told.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RgbRange {
+    Full,
+    Limited,
+}
+
+impl RgbRange {
+    pub const ALL: [RgbRange; 2] = [RgbRange::Full, RgbRange::Limited];
+}
+
+impl std::fmt::Display for RgbRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            RgbRange::Full => "Full",
+            RgbRange::Limited => "Limited",
+        })
+    }
+}
+
+/// How pixels are encoded for the link. YCbCr 4:2:0 halves the bandwidth, which lets
+/// HDMI 2.0 carry 4K at 60 Hz with deep color, at the cost of blurring colored text; some
+/// TVs only show such modes that way.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PixelFormat {
+    Rgb,
+    Ycbcr420,
+}
+
+impl PixelFormat {
+    pub const ALL: [PixelFormat; 2] = [PixelFormat::Rgb, PixelFormat::Ycbcr420];
+}
+
+impl std::fmt::Display for PixelFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            PixelFormat::Rgb => "RGB",
+            PixelFormat::Ycbcr420 => "YCbCr 4:2:0",
+        })
+    }
+}
+
+impl Output {
+    /// Stable key for per-monitor preferences: the EDID make/model/serial when the
+    /// compositor reports them, otherwise the connector name.
+    pub fn identity(&self) -> String {
+        let edid = [&self.make, &self.model, &self.serial]
+            .iter()
+            .filter(|s| !s.is_empty())
+            .map(|s| s.as_str())
+            .collect::<Vec<_>>()
+            .join(" ");
+        if edid.is_empty() {
+            self.name.clone()
+        } else {
+            edid
+        }
+    }
+
+    /// Physical panel size in millimetres, if the compositor reported one.
+    pub fn physical_size_mm(&self) -> Option<(i32, i32)> {
+        let (w, h) = self.physical_size.trim_end_matches(" mm").split_once('x')?;
+        let (w, h) = (w.parse::<i32>().ok()?, h.parse::<i32>().ok()?);
+        (w > 0 && h > 0).then_some((w, h))
+    }
+
+    /// Pixels per inch of the panel at the given mode, ignoring scaling.
+    pub fn ppi(&self, mode: &OutputMode) -> Option<f32> {
+        let (w_mm, h_mm) = self.physical_size_mm()?;
+        let diag_px = ((mode.width as f32).powi(2) + (mode.height as f32).powi(2)).sqrt();
+        let diag_in = ((w_mm as f32).powi(2) + (h_mm as f32).powi(2)).sqrt() / 25.4;
+        Some(diag_px / diag_in)
+    }
+
+    /// Switches to `refresh_rate` at the current resolution, adding it as a custom mode
+    /// when the output does not advertise it. Returns whether the mode is a custom one,
+    /// which the compositor may still reject on apply.
+    pub fn set_refresh_rate(&mut self, refresh_rate: f32) -> Result<bool, String> {
+        if !(1.0..=1000.0).contains(&refresh_rate) {
+            return Err(format!("{} Hz is not a usable refresh rate", refresh_rate));
+        }
+        let (width, height) = self
+            .modes
+            .iter()
+            .find(|m| m.current)
+            .map(|m| (m.width, m.height))
+            .ok_or_else(|| format!("{} has no current mode", self.name))?;
+        let existing = self.modes.iter().position(|m| {
+            m.width == width
+                && m.height == height
+                && (m.refresh_rate - refresh_rate).abs() < REFRESH_TOLERANCE
+        });
+        let target = existing.unwrap_or_else(|| {
+            self.modes.push(OutputMode {
+                width,
+                height,
+                refresh_rate,
+                current: false,
+                preferred: false,
+                custom: true,
+            });
+            self.modes.len() - 1
+        });
+        for (i, m) in self.modes.iter_mut().enumerate() {
+            m.current = i == target;
+        }
+        Ok(existing.is_none())
+    }
+
+    /// Sorts the modes largest first, fastest first within a resolution, and drops modes
+    /// that repeat the one before to within [`DUPLICATE_REFRESH`]. xrandr lists interlaced
+    /// variants under the same size, so they collapse into their progressive twin. A
+    /// dropped mode hands on being current or preferred to the one kept.
+    pub fn tidy_modes(&mut self) {
+        self.modes.sort_by(|a, b| {
+            (b.width * b.height, b.width)
+                .cmp(&(a.width * a.height, a.width))
+                .then(b.refresh_rate.total_cmp(&a.refresh_rate))
+        });
+        let mut kept: Vec<OutputMode> = Vec::with_capacity(self.modes.len());
+        for mode in self.modes.drain(..) {
+            match kept.last_mut() {
+                Some(last)
+                    if last.width == mode.width
+                        && last.height == mode.height
+                        && (last.refresh_rate - mode.refresh_rate).abs() < DUPLICATE_REFRESH =>
+                {
+                    last.current |= mode.current;
+                    last.preferred |= mode.preferred;
+                    last.custom &= mode.custom;
+                }
+                _ => kept.push(mode),
+            }
+        }
+        self.modes = kept;
+    }
+}
+
+/// Refresh rates closer than this in Hz are the same mode: typing 59.95 finds the
+/// 59.951 Hz mode, but 23.976 does not match a 24 Hz one.
+pub const REFRESH_TOLERANCE: f32 = 0.01;
+
+/// Refresh rates closer than this in Hz are one mode listed twice, with timings that
+/// differ only below what the pickers can show.
+pub const DUPLICATE_REFRESH: f32 = 0.001;
+
+/// Where output configurations are read from and applied to.
+/// [`crate::wayland::WaylandBackend`] talks to the compositor, [`crate::plugin::ExecBackend`]
+/// delegates to a third-party executable and [`crate::mock::MockBackend`] serves fixture
+/// data so the logic above it can be tested without a Wayland session.
+pub trait Backend: Send + Sync {
+    fn fetch_outputs(&self) -> Result<Vec<Output>, String>;
+    fn apply_outputs(&self, outputs: &[Output]) -> Result<(), String>;
+
+    /// The scales this backend's compositor takes as given.
+    fn scale_steps(&self) -> ScaleSteps {
+        ScaleSteps::detect()
+    }
+
+    /// The largest framebuffer all outputs are scanned out of, where they share one.
+    fn framebuffer_limit(&self) -> Option<(i32, i32)> {
+        None
+    }
+
+    /// The shell command that applies `outputs` the way this backend does, shown by
+    /// `--dry-run` and the Preview button.
+    fn preview(&self, outputs: &[Output]) -> String {
+        wlr_randr_command(outputs)
+    }
+}
+
+/// The output scales a compositor applies unchanged. Others are rounded, often to
+/// something blurry, or refused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScaleSteps {
+    /// Multiples of 1/8, which survive both the 24.8 fixed-point encoding of
+    /// wlr-output-management and the 1/120 steps of wp-fractional-scale. wlroots
+    /// compositors round anything else.
+    Eighths,
+    /// Multiples of 1/120 that divide the mode into whole logical pixels. Hyprland
+    /// refuses other scales and picks one of these itself.
+    WholeLogicalSize,
+    /// Only 1: X11 has no output scale.
+    Unscaled,
+}
+
+impl ScaleSteps {
+    /// The steps of the compositor running this session.
+    pub fn detect() -> Self {
+        match crate::compositor::Compositor::detect() {
+            Some(crate::compositor::Compositor::Hyprland) => ScaleSteps::WholeLogicalSize,
+            _ => ScaleSteps::Eighths,
+        }
+    }
+
+    /// Whether `scale` is one of the steps. Eighths that leave a fractional logical size
+    /// are accepted too; see [`has_whole_logical_size`].
+    pub fn accepts(self, mode: &OutputMode, scale: f32) -> bool {
+        match self {
+            ScaleSteps::Eighths => is_multiple(scale, 8.0),
+            ScaleSteps::WholeLogicalSize => {
+                is_multiple(scale, 120.0) && has_whole_logical_size(mode, scale)
+            }
+            ScaleSteps::Unscaled => (scale - 1.0).abs() < 0.001,
+        }
+    }
+
+    /// The step closest to `scale` that gives `mode` a whole logical size, between 0.5
+    /// and 4.
+    pub fn nearest(self, mode: &OutputMode, scale: f32) -> Option<f32> {
+        let denominator = match self {
+            ScaleSteps::Eighths => 8,
+            ScaleSteps::WholeLogicalSize => 120,
+            ScaleSteps::Unscaled => return Some(1.0),
+        };
+        (denominator / 2..=denominator * 4)
+            .map(|step| step as f32 / denominator as f32)
+            .filter(|s| has_whole_logical_size(mode, *s))
+            .min_by(|a, b| (a - scale).abs().total_cmp(&(b - scale).abs()))
+    }
+}
+
+fn is_multiple(scale: f32, denominator: f32) -> bool {
+    let steps = scale * denominator;
+    (steps - steps.round()).abs() < 0.001
+}
+
+/// Whether `mode` divided by `scale` is a whole number of logical pixels each way;
+/// otherwise edges land between pixels and look soft.
+pub fn has_whole_logical_size(mode: &OutputMode, scale: f32) -> bool {
+    let w = mode.width as f32 / scale;
+    let h = mode.height as f32 / scale;
+    (w - w.round()).abs() < 0.01 && (h - h.round()).abs() < 0.01
+}
+
+/// The backend selected in `settings`, kept to one seat on multi-seat machines (see
+/// [`crate::seat::confine`]). Unknown plugins and tools that cannot run fall back to
+/// [`crate::tools::AutoBackend`] with a warning, so a missing plugin never leaves the
+/// user without a working tool.
+pub fn from_settings(settings: &AppSettings) -> Arc<dyn Backend> {
+    crate::seat::confine(named(settings), settings)
+}
+
+fn named(settings: &AppSettings) -> Arc<dyn Backend> {
+    let name = match settings.backend.as_deref() {
+        None => return Arc::new(crate::tools::AutoBackend::new(settings)),
+        Some("wayland") => return Arc::new(crate::wayland::WaylandBackend),
+        Some(name) => name,
+    };
+    if let Some(tool) = crate::tools::Tool::from_name(name) {
+        match tool.backend(settings) {
+            Ok(backend) => return Arc::new(backend),
+            Err(e) => tracing::warn!("Cannot use {}: {}", tool, e),
+        }
+    } else if let Some(plugin) = crate::plugin::find(name) {
+        return Arc::new(plugin);
+    } else {
+        tracing::warn!("Backend '{}' not found", name);
+    }
+    Arc::new(crate::tools::AutoBackend::new(settings))
+}
+
+/// The backend selected in the saved settings.
+pub fn active() -> Arc<dyn Backend> {
+    from_settings(&AppSettings::load())
+}
+
+/// Reads the outputs through the [`active`] backend.
+pub fn fetch_outputs() -> Result<Vec<Output>, String> {
+    active().fetch_outputs()
+}
+
+/// Enables or disables one output, leaving the rest of the layout as it is.
+pub fn set_output_enabled(name: &str, enabled: bool) -> Result<(), String> {
+    let backend = active();
+    let mut outputs = backend.fetch_outputs()?;
+    let out = outputs
+        .iter_mut()
+        .find(|o| o.name == name)
+        .ok_or_else(|| format!("No output named '{}'", name))?;
+    out.enabled = enabled;
+    crate::hooks::apply_with(backend.as_ref(), &outputs, None)
+}
+
+/// The `wlr-randr` invocation equivalent to applying `outputs`. MDisplay talks to the
+/// compositor through wlr-output-management itself, so these are for inspection and for
+/// reproducing a layout by hand.
+pub fn wlr_randr_command(outputs: &[Output]) -> String {
+    let mut command = String::from("wlr-randr");
+    for out in outputs {
+        command.push_str(" \\\n  ");
+        command.push_str(&wlr_randr_args(out).join(" "));
+    }
+    command
+}
+
+/// `arg` quoted for a POSIX shell where it needs to be.
+pub fn shell_quote(arg: &str) -> String {
+    let plain = |c: char| c.is_ascii_alphanumeric() || "-_./,:@%+=".contains(c);
+    if !arg.is_empty() && arg.chars().all(plain) {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', "'\\''"))
+    }
+}
+
+/// The `wlr-randr` arguments that configure `out`.
+pub fn wlr_randr_args(out: &Output) -> Vec<String> {
+    let mut args = vec!["--output".to_string(), out.name.clone()];
+    if !out.enabled {
+        args.push("--off".to_string());
+        return args;
+    }
+    args.push("--on".to_string());
+    if let Some(mode) = out.modes.iter().find(|m| m.current) {
+        // Modes the output does not advertise need wlr-randr's custom mode flag.
+        args.push(
+            if mode.custom {
+                "--custom-mode"
+            } else {
+                "--mode"
+            }
+            .to_string(),
+        );
+        args.push(format!(
+            "{}x{}@{:.3}Hz",
+            mode.width, mode.height, mode.refresh_rate
+        ));
+    }
+    args.extend([
+        "--pos".to_string(),
+        format!("{},{}", out.position.0, out.position.1),
+        "--scale".to_string(),
+        out.scale.to_string(),
+        "--transform".to_string(),
+        out.transform.clone(),
+    ]);
+    if let Some(enabled) = out.adaptive_sync {
+        args.push("--adaptive-sync".to_string());
+        args.push(if enabled { "enabled" } else { "disabled" }.to_string());
+    }
+    args
+}
+
+/// Groups of enabled outputs that mirror each other, as connector names in output order.
+/// wlr-output-management has no mirroring request; outputs placed at the same position
+/// show the same part of the desktop instead, and that is also how the mangowc and
+/// `wlr-randr` formats express a clone group.
+pub fn clone_groups(outputs: &[Output]) -> Vec<Vec<String>> {
+    let mut groups: Vec<((i32, i32), Vec<String>)> = Vec::new();
+    for out in outputs.iter().filter(|o| o.enabled) {
+        match groups.iter_mut().find(|(pos, _)| *pos == out.position) {
+            Some((_, names)) => names.push(out.name.clone()),
+            None => groups.push((out.position, vec![out.name.clone()])),
+        }
+    }
+    groups
+        .into_iter()
+        .map(|(_, names)| names)
+        .filter(|names| names.len() > 1)
+        .collect()
+}
+
+/// Rejects layouts with every output disabled, which would leave the session blank.
+pub fn ensure_enabled_output(outputs: &[Output]) -> Result<(), String> {
+    if outputs.iter().any(|o| o.enabled) {
+        Ok(())
+    } else {
+        Err("Refusing a configuration with no enabled outputs".to_string())
+    }
+}
+
+/// Writes the enabled outputs as mangowc `monitorrule` lines to the configured monitors
+/// file, backing up the user's original rules the first time and adding the `source=`
+/// include to the main config when enabled. In the other [`SaveFormat`]s the file is
+/// written for that compositor instead and the mangowc config is left alone. `section`
+/// is added after the layout; see [`crate::profile::Profile::sections`].
+pub fn save_config(
+    outputs: &[Output],
+    settings: &AppSettings,
+    section: Option<&str>,
+) -> Result<(), String> {
+    ensure_enabled_output(outputs)?;
+
+    if settings.save_format != SaveFormat::Mangowc {
+        return save_config_to(
+            outputs,
+            settings.save_format,
+            section,
+            &settings.monitors_path(),
+        );
+    }
+
+    let script = with_section(config_text(outputs, SaveFormat::Mangowc), section);
+
+    let expand_path = |p: &str| -> Result<PathBuf, String> {
+        if let Some(rest) = p.strip_prefix("~/") {
+            let home =
+                dirs::home_dir().ok_or_else(|| "Could not determine home directory".to_string())?;
+            Ok(home.join(rest))
+        } else {
+            Ok(PathBuf::from(p))
+        }
+    };
+
+    let monitors_path = expand_path(&settings.monitors_conf_path)?;
+    let bak_path = expand_path(&settings.monitors_bak_path)?;
+    let config_path = expand_path(&settings.config_conf_path)?;
+
+    if !bak_path.exists() {
+        if let Some(parent) = bak_path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create backup directory: {}", e))?;
+        }
+
+        let collect_monitorrules = |path: &PathBuf| -> Vec<String> {
+            let Ok(content) = fs::read_to_string(path) else {
+                return Vec::new();
+            };
+            content
+                .lines()
+                .filter(|l| {
+                    let t = l.trim();
+                    t.starts_with("monitorrule=") || t.starts_with("monitorrule =")
+                })
+                .map(|l| l.to_string())
+                .collect()
+        };
+
+        let resolve_source = |raw: &str| -> Result<PathBuf, String> {
+            if raw.starts_with("~/") || raw.starts_with('/') {
+                expand_path(raw)
+            } else {
+                let parent = config_path.parent().ok_or_else(|| {
+                    format!(
+                        "Config path '{}' has no parent directory",
+                        config_path.display()
+                    )
+                })?;
+                Ok(parent.join(raw))
+            }
+        };
+
+        let to_portable = |p: &PathBuf| -> String {
+            if let Some(home) = dirs::home_dir()
+                && let Ok(suffix) = p.strip_prefix(&home)
+            {
+                return format!("~/{}", suffix.display());
+            }
+            p.display().to_string()
+        };
+
+        let mut backup_entries: Vec<serde_json::Value> = Vec::new();
+
+        if config_path.exists() {
+            let direct_rules = collect_monitorrules(&config_path);
+            if !direct_rules.is_empty() {
+                backup_entries.push(serde_json::json!({
+                    "source_file": to_portable(&config_path),
+                    "rules": direct_rules,
+                }));
+            }
+
+            if let Ok(content) = fs::read_to_string(&config_path) {
+                for line in content.lines() {
+                    let t = line.trim();
+                    let sourced_path_str = if t.starts_with("source=") {
+                        Some(t.trim_start_matches("source=").trim())
+                    } else if t.starts_with("source =") {
+                        Some(t.trim_start_matches("source =").trim())
+                    } else {
+                        None
+                    };
+
+                    if let Some(raw) = sourced_path_str
+                        && let Ok(sourced) = resolve_source(raw)
+                    {
+                        let rules = collect_monitorrules(&sourced);
+                        if !rules.is_empty() {
+                            backup_entries.push(serde_json::json!({
+                                "source_file": to_portable(&sourced),
+                                "rules": rules,
+                            }));
+                        }
+                    }
+                }
+            }
+        }
+
+        let backup_json = serde_json::json!({ "entries": backup_entries });
+        let backup_json_str = serde_json::to_string_pretty(&backup_json)
+            .map_err(|e| format!("Failed to serialize backup data: {}", e))?;
+        fs::write(&bak_path, backup_json_str)
+            .map_err(|e| format!("Failed to write monitors.bak: {}", e))?;
+    }
+
+    if let Some(parent) = monitors_path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create monitors dir: {}", e))?;
+    }
+
+    fs::write(&monitors_path, script)
+        .map_err(|e| format!("Failed to write monitors config: {}", e))?;
+
+    if settings.auto_append_source {
+        let source_line_tilde = format!("source={}", settings.monitors_conf_path);
+        let source_line_abs = format!("source={}", monitors_path.display());
+        let source_line_abs_spaced = format!("source = {}", monitors_path.display());
+        let needs_source = if config_path.exists() {
+            let content = fs::read_to_string(&config_path)
+                .map_err(|e| format!("Failed to read config file: {}", e))?;
+            !content.contains(&source_line_tilde)
+                && !content.contains(&source_line_abs)
+                && !content.contains(&source_line_abs_spaced)
+        } else {
+            true
+        };
+
+        if needs_source {
+            use std::io::Write;
+            if let Some(parent) = config_path.parent() {
+                fs::create_dir_all(parent)
+                    .map_err(|e| format!("Failed to create config directory: {}", e))?;
+            }
+            let mut file = std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&config_path)
+                .map_err(|e| format!("Failed to open config file: {}", e))?;
+
+            writeln!(file, "\n{}", source_line_tilde)
+                .map_err(|e| format!("Failed to write config file source override: {}", e))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// The file [`save_config`] writes for `outputs` in `format`. None of the formats has an
+/// RGB range or pixel format option, so [`Output::rgb_range`] and [`Output::pixel_format`]
+/// are not written.
+pub fn config_text(outputs: &[Output], format: SaveFormat) -> String {
+    match format {
+        SaveFormat::WlrRandr => {
+            return format!(
+                "#!/bin/sh\n# Generated by mango-display\n\n{}\n",
+                wlr_randr_command(outputs)
+            );
+        }
+        SaveFormat::Hyprland | SaveFormat::Sway => {
+            let mut config = String::from("# Generated by mango-display\n\n");
+            for out in outputs {
+                let line = if format == SaveFormat::Hyprland {
+                    format!("monitor={}", crate::tools::hyprland_monitor(out))
+                } else {
+                    crate::tools::sway_command(out)
+                };
+                config.push_str(&line);
+                config.push('\n');
+            }
+            return config;
+        }
+        SaveFormat::Mangowc => {}
+    }
+
+    let mut script = String::from("# Generated by mango-display\n\n");
+
+    for out in outputs {
+        if out.enabled {
+            let rr = match out.transform.as_str() {
+                "normal" => 0,
+                "90" => 1,
+                "180" => 2,
+                "270" => 3,
+                "flipped" => 4,
+                "flipped-90" => 5,
+                "flipped-180" => 6,
+                "flipped-270" => 7,
+                _ => 0,
+            };
+
+            let mut w = 0;
+            let mut h = 0;
+            let mut r = 0.0;
+
+            if let Some(current_mode) = out.modes.iter().find(|m| m.current) {
+                w = current_mode.width;
+                h = current_mode.height;
+                r = current_mode.refresh_rate;
+            }
+
+            script.push_str(&format!(
+                "monitorrule=name:{},width:{},height:{},refresh:{:.6},x:{},y:{},scale:{:.6},rr:{}\n",
+                out.name, w, h, r, out.position.0, out.position.1, out.scale, rr
+            ));
+        }
+    }
+    script
+}
+
+/// `config` with a profile's `section` for its format after the generated lines.
+fn with_section(mut config: String, section: Option<&str>) -> String {
+    if let Some(section) = section.map(str::trim_end).filter(|s| !s.is_empty()) {
+        config.push('\n');
+        config.push_str(section);
+        config.push('\n');
+    }
+    config
+}
+
+/// Writes `outputs` in `format`, followed by `section`, to `path` and nothing else: no
+/// backup, no `source=` line. Scripts are made executable.
+pub fn save_config_to(
+    outputs: &[Output],
+    format: SaveFormat,
+    section: Option<&str>,
+    path: &Path,
+) -> Result<(), String> {
+    use std::os::unix::fs::PermissionsExt;
+
+    ensure_enabled_output(outputs)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    fs::write(path, with_section(config_text(outputs, format), section))
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    if format == SaveFormat::WlrRandr {
+        fs::set_permissions(path, fs::Permissions::from_mode(0o755))
+            .map_err(|e| format!("Failed to make {} executable: {}", path.display(), e))?;
+    }
+    Ok(())
+}
+
+/// Puts back the `monitorrule` lines captured in the backup by the first [`save_config`]
+/// and removes the generated monitors file.
+pub fn restore_default_config(settings: &AppSettings) -> Result<(), String> {
+    let expand_path = |p: &str| -> Result<PathBuf, String> {
+        if let Some(rest) = p.strip_prefix("~/") {
+            let home =
+                dirs::home_dir().ok_or_else(|| "Could not determine home directory".to_string())?;
+            Ok(home.join(rest))
+        } else {
+            Ok(PathBuf::from(p))
+        }
+    };
+
+    let config_path = expand_path(&settings.config_conf_path)?;
+    let monitors_path = expand_path(&settings.monitors_conf_path)?;
+    let bak_path = expand_path(&settings.monitors_bak_path)?;
+
+    let backup: serde_json::Value = if bak_path.exists() {
+        let raw = fs::read_to_string(&bak_path)
+            .map_err(|e| format!("Failed to read monitors.bak: {}", e))?;
+        serde_json::from_str(&raw).unwrap_or(serde_json::json!({ "entries": [] }))
+    } else {
+        serde_json::json!({ "entries": [] })
+    };
+
+    let entries = backup["entries"]
+        .as_array()
+        .cloned()
+        .unwrap_or_else(Vec::new);
+
+    let strip_monitorrules = |content: &str| -> String {
+        content
+            .lines()
+            .filter(|l| {
+                let t = l.trim();
+                !t.starts_with("monitorrule=") && !t.starts_with("monitorrule =")
+            })
+            .map(|l| format!("{}\n", l))
+            .collect()
+    };
+
+    if config_path.exists() {
+        let source_line_tilde = format!("source={}", settings.monitors_conf_path);
+        let source_line_abs = format!("source={}", monitors_path.display());
+        let source_line_abs_spaced = format!("source = {}", monitors_path.display());
+
+        let content = fs::read_to_string(&config_path)
+            .map_err(|e| format!("Failed to read config.conf: {}", e))?;
+
+        let cleaned: String = content
+            .lines()
+            .filter(|l| {
+                let t = l.trim();
+                !t.starts_with("monitorrule=")
+                    && !t.starts_with("monitorrule =")
+                    && t != source_line_tilde.as_str()
+                    && t != source_line_abs.as_str()
+                    && t != source_line_abs_spaced.as_str()
+            })
+            .map(|l| format!("{}\n", l))
+            .collect();
+
+        fs::write(&config_path, cleaned)
+            .map_err(|e| format!("Failed to write config.conf: {}", e))?;
+    }
+
+    for entry in &entries {
+        if let (Some(source_file), Some(rules)) =
+            (entry["source_file"].as_str(), entry["rules"].as_array())
+        {
+            let target_path = expand_path(source_file)?;
+            let rules_block: String = rules
+                .iter()
+                .filter_map(|r| r.as_str())
+                .map(|r| format!("{}\n", r))
+                .collect();
+
+            if rules_block.is_empty() {
+                continue;
+            }
+
+            if target_path.exists() {
+                let content = fs::read_to_string(&target_path)
+                    .map_err(|e| format!("Failed to read {}: {}", target_path.display(), e))?;
+                let cleaned = strip_monitorrules(&content);
+                let restored = format!("{}\n{}", cleaned.trim_end(), rules_block);
+                fs::write(&target_path, restored)
+                    .map_err(|e| format!("Failed to restore rules to {}: {}", source_file, e))?;
+            } else {
+                if let Some(parent) = target_path.parent() {
+                    fs::create_dir_all(parent).map_err(|e| {
+                        format!("Failed to create directory for {}: {}", source_file, e)
+                    })?;
+                }
+                fs::write(&target_path, rules_block)
+                    .map_err(|e| format!("Failed to create {}: {}", source_file, e))?;
+            }
+        }
+    }
+
+    let monitors_was_backed_up = entries.iter().any(|e| {
+        e["source_file"]
+            .as_str()
+            .map(|s| expand_path(s).map(|p| p == monitors_path).unwrap_or(false))
+            .unwrap_or(false)
+    });
+    if monitors_path.exists() && !monitors_was_backed_up {
+        fs::remove_file(&monitors_path)
+            .map_err(|e| format!("Failed to delete monitors.conf: {}", e))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockBackend;
+
+    fn mode(width: i32, height: i32, refresh_rate: f32, current: bool) -> OutputMode {
+        OutputMode {
+            width,
+            height,
+            refresh_rate,
+            current,
+            preferred: false,
+            custom: false,
+        }
+    }
+
+    #[test]
+    fn tidying_sorts_modes_and_merges_repeats() {
+        let mut out = MockBackend::from_json(include_str!("../fixtures/dual_monitor.json"))
+            .unwrap()
+            .fetch_outputs()
+            .unwrap()
+            .remove(1);
+        out.modes = vec![
+            mode(1280, 720, 60.0, false),
+            mode(1920, 1080, 59.94, false),
+            mode(1920, 1080, 60.0, false),
+            mode(1920, 1080, 59.9404, true),
+            mode(2560, 1080, 60.0, false),
+        ];
+        out.tidy_modes();
+        let modes: Vec<(i32, f32, bool)> = out
+            .modes
+            .iter()
+            .map(|m| (m.width, m.refresh_rate, m.current))
+            .collect();
+        assert_eq!(
+            modes,
+            [
+                (2560, 60.0, false),
+                (1920, 60.0, false),
+                (1920, 59.9404, true),
+                (1280, 60.0, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn writes_each_compositor_with_the_profile_section() {
+        let outputs = MockBackend::from_json(include_str!("../fixtures/dual_monitor.json"))
+            .unwrap()
+            .fetch_outputs()
+            .unwrap();
+        let hyprland = with_section(
+            config_text(&outputs, SaveFormat::Hyprland),
+            Some("workspace=1,monitor:DP-1\n"),
+        );
+        assert!(hyprland.contains("monitor=DP-1,2560x1440@143.998,0x0,1,transform,0\n"));
+        assert!(hyprland.contains("monitor=eDP-1,disable\n"));
+        assert!(hyprland.ends_with("\n\nworkspace=1,monitor:DP-1\n"));
+        let sway = config_text(&outputs, SaveFormat::Sway);
+        assert!(sway.contains(
+            "output \"HDMI-A-1\" enable mode 1920x1080@60.000Hz position 2560 0 scale 1 transform normal\n"
+        ));
+        assert_eq!(with_section(sway.clone(), Some("  ")), sway);
+    }
+}
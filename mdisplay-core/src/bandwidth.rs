@@ -0,0 +1,136 @@
+//! Whether a mode fits the link it is sent over.
+//!
+//! Compositors only report that a mode failed, or the sink quietly falls back to 4:2:0
+//! chroma. Estimating the pixel clock with reduced blanking, as digital sinks mostly use,
+//! and comparing it with the connector's usual ceiling explains those cases up front. The
+//! link version is not exposed by the kernel, so the ceilings are those of the most
+//! common generation: HDMI 2.0 and four-lane DisplayPort 1.4.
+
+use crate::backend::OutputMode;
+use crate::connector::{Connector, ConnectorKind};
+use crate::cvt::{self, Blanking};
+
+/// HDMI 2.0's highest TMDS character rate.
+const HDMI_MAX_TMDS_KHZ: u64 = 600_000;
+const DVI_SINGLE_LINK_KHZ: u64 = 165_000;
+const DVI_DUAL_LINK_KHZ: u64 = 330_000;
+/// Four HBR3 lanes after 8b/10b coding.
+const DP_MAX_BITS_PER_SECOND: u64 = 25_920_000_000;
+/// Bits per color component assumed until an output reports otherwise.
+pub const DEFAULT_BPC: u32 = 8;
+
+/// Why a mode is likely to fail or be degraded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkWarning {
+    /// Over HDMI 2.0 only with 4:2:0 chroma, which blurs colored text.
+    Chroma420,
+    /// Needs a dual-link DVI cable.
+    DualLinkDvi,
+    /// Fits DisplayPort over USB-C only with all four lanes, which a dock that also
+    /// carries USB 3 data does not give it.
+    UsbCLanes,
+    /// Beyond what the link carries at all.
+    ExceedsLink(ConnectorKind),
+    /// Above the highest pixel clock the display's EDID accepts, in MHz.
+    ExceedsDisplay(u32),
+}
+
+/// The pixel clock `mode` needs, in kHz. Custom modes use the blanking they are generated
+/// with; advertised modes are estimated with CVT-RB.
+pub fn pixel_clock_khz(mode: &OutputMode, blanking: Blanking) -> u64 {
+    let blanking = if mode.custom {
+        blanking
+    } else {
+        Blanking::Reduced
+    };
+    let timings = cvt::timings(
+        mode.width as u32,
+        mode.height as u32,
+        mode.refresh_rate as f64,
+        blanking,
+    );
+    timings.pixel_clock_khz as u64
+}
+
+/// Checks a mode needing `clock_khz` at `bpc` bits per component against `connector`.
+pub fn check(connector: &Connector, clock_khz: u64, bpc: u32) -> Option<LinkWarning> {
+    let bpc = bpc as u64;
+    let link = match connector.kind {
+        ConnectorKind::Hdmi => {
+            // Deep color raises the TMDS rate; 4:2:0 halves it.
+            let tmds = clock_khz * bpc / 8;
+            if tmds <= HDMI_MAX_TMDS_KHZ {
+                None
+            } else if tmds / 2 <= HDMI_MAX_TMDS_KHZ {
+                Some(LinkWarning::Chroma420)
+            } else {
+                Some(LinkWarning::ExceedsLink(connector.kind))
+            }
+        }
+        ConnectorKind::DisplayPort | ConnectorKind::UsbC => {
+            let bits = clock_khz * 1000 * bpc * 3;
+            if bits > DP_MAX_BITS_PER_SECOND {
+                Some(LinkWarning::ExceedsLink(connector.kind))
+            } else if connector.kind == ConnectorKind::UsbC && bits > DP_MAX_BITS_PER_SECOND / 2 {
+                Some(LinkWarning::UsbCLanes)
+            } else {
+                None
+            }
+        }
+        ConnectorKind::Dvi if clock_khz > DVI_DUAL_LINK_KHZ => {
+            Some(LinkWarning::ExceedsLink(connector.kind))
+        }
+        ConnectorKind::Dvi if clock_khz > DVI_SINGLE_LINK_KHZ => Some(LinkWarning::DualLinkDvi),
+        _ => None,
+    };
+    link.or_else(|| {
+        let max = connector.edid.as_ref()?.max_pixel_clock_mhz?;
+        (clock_khz > max as u64 * 1000).then_some(LinkWarning::ExceedsDisplay(max))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn connector(kind: ConnectorKind) -> Connector {
+        Connector {
+            kind,
+            card: None,
+            usb_c_port: None,
+            edid: None,
+        }
+    }
+
+    fn mode(width: i32, height: i32, refresh_rate: f32) -> OutputMode {
+        OutputMode {
+            width,
+            height,
+            refresh_rate,
+            preferred: false,
+            current: true,
+            custom: false,
+        }
+    }
+
+    #[test]
+    fn flags_modes_hdmi_2_0_cannot_carry_in_full() {
+        let hdmi = connector(ConnectorKind::Hdmi);
+        let uhd60 = pixel_clock_khz(&mode(3840, 2160, 60.0), Blanking::Standard);
+        assert_eq!(check(&hdmi, uhd60, 8), None);
+        assert_eq!(check(&hdmi, uhd60, 10), Some(LinkWarning::Chroma420));
+        let uhd144 = pixel_clock_khz(&mode(3840, 2160, 144.0), Blanking::Standard);
+        assert_eq!(
+            check(&hdmi, uhd144, 8),
+            Some(LinkWarning::ExceedsLink(ConnectorKind::Hdmi))
+        );
+        assert_eq!(
+            check(&connector(ConnectorKind::UsbC), uhd60, 10),
+            Some(LinkWarning::UsbCLanes)
+        );
+        assert_eq!(
+            check(&connector(ConnectorKind::DisplayPort), uhd60, 10),
+            None
+        );
+    }
+}
@@ -0,0 +1,113 @@
+//! Brightness as a percentage, through the kernel backlight for built-in panels and
+//! DDC/CI for external monitors.
+
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+use crate::connector::ConnectorKind;
+use crate::ddc;
+use crate::process;
+
+const BACKLIGHT_DIR: &str = "/sys/class/backlight";
+
+/// How an output's brightness is set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Control {
+    /// A `/sys/class/backlight` device, such as `intel_backlight`.
+    Backlight(String),
+    Ddc(ddc::Display),
+}
+
+/// The brightness control of each output in `outputs` that has one. Built-in panels get
+/// the first backlight device; everything else is looked up with `ddcutil`.
+pub fn detect(outputs: &[String]) -> Vec<(String, Control)> {
+    let backlight = fs::read_dir(BACKLIGHT_DIR).ok().and_then(|entries| {
+        let mut devices: Vec<String> = entries
+            .flatten()
+            .map(|e| e.file_name().to_string_lossy().into_owned())
+            .collect();
+        devices.sort();
+        devices.into_iter().next()
+    });
+    let mut displays = None;
+    let mut controls = Vec::new();
+    for name in outputs {
+        if ConnectorKind::from_name(name).is_builtin() {
+            if let Some(device) = &backlight {
+                controls.push((name.clone(), Control::Backlight(device.clone())));
+            }
+            continue;
+        }
+        let displays = displays.get_or_insert_with(|| {
+            ddc::detect().unwrap_or_else(|e| {
+                tracing::debug!("{}", e);
+                Vec::new()
+            })
+        });
+        if let Some((_, display)) = displays.iter().find(|(n, _)| n == name) {
+            controls.push((name.clone(), Control::Ddc(display.clone())));
+        }
+    }
+    controls
+}
+
+fn backlight_value(device: &str, file: &str) -> Result<u32, String> {
+    let path = PathBuf::from(BACKLIGHT_DIR).join(device).join(file);
+    fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?
+        .trim()
+        .parse()
+        .map_err(|e| format!("Invalid {}: {}", path.display(), e))
+}
+
+impl Control {
+    pub fn get(&self) -> Result<u8, String> {
+        let (value, max) = match self {
+            Control::Backlight(device) => (
+                backlight_value(device, "brightness")?,
+                backlight_value(device, "max_brightness")?,
+            ),
+            Control::Ddc(display) => {
+                let (value, max) = display.get(ddc::BRIGHTNESS)?;
+                (value as u32, max as u32)
+            }
+        };
+        Ok((value * 100 / max.max(1)).min(100) as u8)
+    }
+
+    pub fn set(&self, percent: u8) -> Result<(), String> {
+        let percent = percent.min(100) as u32;
+        match self {
+            Control::Backlight(device) => {
+                let value = percent * backlight_value(device, "max_brightness")? / 100;
+                let path = PathBuf::from(BACKLIGHT_DIR).join(device).join("brightness");
+                if fs::write(&path, value.to_string()).is_ok() {
+                    return Ok(());
+                }
+                // Without a udev rule only root can write the file, but logind lets the
+                // session's own user set it.
+                let status = process::status(Command::new("busctl").args([
+                    "call",
+                    "org.freedesktop.login1",
+                    "/org/freedesktop/login1/session/auto",
+                    "org.freedesktop.login1.Session",
+                    "SetBrightness",
+                    "ssu",
+                    "backlight",
+                    device,
+                    &value.to_string(),
+                ]))
+                .map_err(|e| format!("Failed to run busctl: {}", e))?;
+                if !status.success() {
+                    return Err(format!("Setting the brightness of {} failed", device));
+                }
+                Ok(())
+            }
+            Control::Ddc(display) => {
+                let (_, max) = display.get(ddc::BRIGHTNESS)?;
+                display.set(ddc::BRIGHTNESS, (percent * max as u32 / 100) as u16)
+            }
+        }
+    }
+}
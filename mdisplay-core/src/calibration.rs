@@ -0,0 +1,255 @@
+//! The calibration wizard: brightness and contrast over DDC/CI, then gamma, each judged
+//! against a test image on the output being calibrated.
+//!
+//! Up and Down change the value of the current step and Enter moves on; finishing the
+//! last step keeps the result, and Escape puts the monitor's DDC values back.
+
+use crate::ddc;
+use crate::gamma::{self, GammaControl};
+use crate::overlay::{Frame, Pattern};
+use crate::pattern::draw_number;
+use crate::settings::Calibration;
+
+const MIN_GAMMA: f32 = 0.5;
+const MAX_GAMMA: f32 = 2.5;
+const GAMMA_STEP: f32 = 0.05;
+/// sRGB level with half the luminance of full white at gamma 2.2.
+const MID_GRAY: u32 = 186;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Step {
+    /// Near-black patches on black, which should all be just visible.
+    Brightness,
+    /// Near-white patches on white, which should all be just visible.
+    Contrast,
+    /// A mid-gray square on black and white lines, which should blend in.
+    Gamma,
+}
+
+/// A DDC/CI feature's value as the wizard changes it.
+#[derive(Debug, Clone, Copy)]
+struct Feature {
+    code: u8,
+    original: u16,
+    value: u16,
+    max: u16,
+}
+
+pub struct CalibrationWizard {
+    display: Option<ddc::Display>,
+    brightness: Option<Feature>,
+    contrast: Option<Feature>,
+    gamma_control: Option<GammaControl>,
+    gamma: f32,
+    steps: Vec<Step>,
+    step: usize,
+    finished: bool,
+}
+
+impl CalibrationWizard {
+    /// Prepares the steps `output` supports, starting from `previous` if the wizard has
+    /// been run on it before. Fails when neither DDC/CI nor gamma control is available.
+    pub fn new(output: &str, previous: Option<Calibration>) -> Result<Self, String> {
+        let display = ddc::Display::find(output);
+        let feature = |code: u8| {
+            let display = display.as_ref().ok()?;
+            match display.get(code) {
+                Ok((value, max)) => Some(Feature {
+                    code,
+                    original: value,
+                    value,
+                    max,
+                }),
+                Err(e) => {
+                    tracing::warn!("{}", e);
+                    None
+                }
+            }
+        };
+        let brightness = feature(ddc::BRIGHTNESS);
+        let contrast = feature(ddc::CONTRAST);
+        let gamma_control = GammaControl::new(output);
+
+        let mut steps = Vec::new();
+        if brightness.is_some() {
+            steps.push(Step::Brightness);
+        }
+        if contrast.is_some() {
+            steps.push(Step::Contrast);
+        }
+        if gamma_control.is_ok() {
+            steps.push(Step::Gamma);
+        }
+        if steps.is_empty() {
+            let reason = |r: Result<(), String>| r.err().unwrap_or_default();
+            return Err(format!(
+                "Cannot calibrate {}: {}; {}",
+                output,
+                reason(display.map(|_| ())),
+                reason(gamma_control.map(|_| ()))
+            ));
+        }
+
+        let mut wizard = Self {
+            display: display.ok(),
+            brightness,
+            contrast,
+            gamma_control: gamma_control.ok(),
+            gamma: previous.map_or(1.0, |c| c.gamma),
+            steps,
+            step: 0,
+            finished: false,
+        };
+        wizard.apply_gamma();
+        Ok(wizard)
+    }
+
+    /// What the user settled on, once every step has been confirmed.
+    pub fn result(&self) -> Option<Calibration> {
+        self.finished.then(|| Calibration {
+            brightness: self.brightness.map(|f| f.value),
+            contrast: self.contrast.map(|f| f.value),
+            gamma: self.gamma,
+        })
+    }
+
+    fn apply_gamma(&mut self) {
+        if let Some(control) = &mut self.gamma_control
+            && let Err(e) = control.set(self.gamma, gamma::NEUTRAL_TEMPERATURE)
+        {
+            tracing::warn!("{}", e);
+        }
+    }
+
+    fn set_feature(display: Option<&ddc::Display>, feature: &Feature) {
+        if let Some(display) = display
+            && let Err(e) = display.set(feature.code, feature.value)
+        {
+            tracing::warn!("{}", e);
+        }
+    }
+
+    /// The current step's value as a fraction of its range, and as shown to the user.
+    fn gauge(&self) -> (f32, u32) {
+        let feature = |f: Option<Feature>| {
+            f.map_or((0.0, 0), |f| {
+                (f.value as f32 / f.max.max(1) as f32, f.value as u32)
+            })
+        };
+        match self.steps[self.step] {
+            Step::Brightness => feature(self.brightness),
+            Step::Contrast => feature(self.contrast),
+            Step::Gamma => (
+                (self.gamma - MIN_GAMMA) / (MAX_GAMMA - MIN_GAMMA),
+                (self.gamma * 100.0).round() as u32,
+            ),
+        }
+    }
+}
+
+impl Pattern for CalibrationWizard {
+    fn render(&mut self, frame: &mut Frame) {
+        let (w, h) = (frame.width, frame.height);
+        let patches_top = h / 3;
+        let patch = w / 20;
+        let patches_left = (w - patch * 16) / 2;
+        match self.steps[self.step] {
+            Step::Brightness => {
+                frame.fill(0x000000);
+                for i in 0..16 {
+                    let level = (i + 1) * 0x010101;
+                    frame.fill_rect(patches_left + i * patch, patches_top, patch, patch, level);
+                }
+            }
+            Step::Contrast => {
+                frame.fill(0xffffff);
+                for i in 0..16 {
+                    let level = (239 + i) * 0x010101;
+                    frame.fill_rect(patches_left + i * patch, patches_top, patch, patch, level);
+                }
+            }
+            Step::Gamma => {
+                for y in 0..h {
+                    let color = if y % 2 == 0 { 0xffffff } else { 0x000000 };
+                    frame.fill_rect(0, y, w, 1, color);
+                }
+                let size = h / 4;
+                frame.fill_rect(
+                    (w - size) / 2,
+                    (h - size) / 2 - h / 8,
+                    size,
+                    size,
+                    MID_GRAY * 0x010101,
+                );
+            }
+        }
+
+        // Step dots, a gauge and the value, on a neutral strip along the bottom.
+        let strip_top = h - h / 6;
+        frame.fill_rect(0, strip_top, w, h - strip_top, 0x303030);
+        let dot = (h / 60).max(2);
+        let dots_left = w / 2 - (self.steps.len() as u32 * dot * 3) / 2;
+        for i in 0..self.steps.len() {
+            let color = if i == self.step { 0xffffff } else { 0x707070 };
+            frame.fill_rect(
+                dots_left + i as u32 * dot * 3,
+                strip_top + dot * 2,
+                dot,
+                dot,
+                color,
+            );
+        }
+        let (fraction, shown) = self.gauge();
+        let (gauge_left, gauge_top, gauge_width) = (w / 4, strip_top + dot * 5, w / 2);
+        frame.fill_rect(gauge_left, gauge_top, gauge_width, dot, 0x707070);
+        let filled = (gauge_width as f32 * fraction.clamp(0.0, 1.0)) as u32;
+        frame.fill_rect(gauge_left, gauge_top, filled, dot, 0xffffff);
+        draw_number(frame, gauge_left, gauge_top + dot * 3, dot, shown, 0xffffff);
+    }
+
+    fn adjust(&mut self, delta: i32) -> bool {
+        let step = self.steps[self.step];
+        let feature = match step {
+            Step::Brightness => self.brightness.as_mut(),
+            Step::Contrast => self.contrast.as_mut(),
+            Step::Gamma => {
+                self.gamma = (self.gamma + delta as f32 * GAMMA_STEP).clamp(MIN_GAMMA, MAX_GAMMA);
+                self.apply_gamma();
+                return true;
+            }
+        };
+        let Some(feature) = feature else {
+            return false;
+        };
+        let increment = (feature.max / 50).max(1) as i32;
+        feature.value =
+            (feature.value as i32 + delta * increment).clamp(0, feature.max as i32) as u16;
+        Self::set_feature(self.display.as_ref(), feature);
+        true
+    }
+
+    fn advance(&mut self) -> bool {
+        if self.step + 1 < self.steps.len() {
+            self.step += 1;
+            true
+        } else {
+            self.finished = true;
+            false
+        }
+    }
+}
+
+impl Drop for CalibrationWizard {
+    /// Cancelling puts brightness and contrast back; gamma resets with the control.
+    fn drop(&mut self) {
+        if self.finished {
+            return;
+        }
+        for feature in [self.brightness, self.contrast].iter_mut().flatten() {
+            if feature.value != feature.original {
+                feature.value = feature.original;
+                Self::set_feature(self.display.as_ref(), feature);
+            }
+        }
+    }
+}
@@ -0,0 +1,413 @@
+//! Settings and actions that wlr-output-management cannot carry, like the scale filter
+//! and virtual outputs, sent to the running compositor through its own command-line tool.
+
+use std::path::Path;
+use std::process::Command;
+
+use serde::{Deserialize, Serialize};
+
+use crate::backend::{Output, Subpixel};
+use crate::process;
+use crate::settings::{AppSettings, OutputPrefs, Overscan};
+
+/// Compositors with runtime commands MDisplay knows how to use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compositor {
+    Sway,
+    Hyprland,
+}
+
+impl Compositor {
+    /// The compositor of the current session, from the sockets it exports.
+    pub fn detect() -> Option<Self> {
+        if std::env::var_os("SWAYSOCK").is_some() {
+            Some(Compositor::Sway)
+        } else if std::env::var_os("HYPRLAND_INSTANCE_SIGNATURE").is_some() {
+            Some(Compositor::Hyprland)
+        } else {
+            None
+        }
+    }
+
+    /// Whether the compositor can choose how each output is upscaled.
+    pub fn has_scale_filter(self) -> bool {
+        self == Compositor::Sway
+    }
+
+    /// Whether the compositor can override each output's subpixel order.
+    pub fn has_subpixel(self) -> bool {
+        self == Compositor::Sway
+    }
+
+    /// Whether the compositor can keep windows clear of each output's edges.
+    pub fn has_overscan(self) -> bool {
+        self == Compositor::Hyprland
+    }
+}
+
+/// Runs `command`, treating a failure exit status or, for `hyprctl`, any reply other
+/// than `ok` as an error. A `--batch` replies once per command.
+pub(crate) fn run(mut command: Command, what: &str) -> Result<(), String> {
+    let output = process::output(&mut command).map_err(|e| format!("Failed to {}: {}", what, e))?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let hyprctl = Path::new(command.get_program()).file_name() == Some("hyprctl".as_ref());
+    let replied_ok = !stdout.trim().is_empty()
+        && stdout
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+            .all(|l| l == "ok");
+    if output.status.success() && (!hyprctl || replied_ok) {
+        return Ok(());
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let reason = [stderr.trim(), stdout.trim()]
+        .into_iter()
+        .find(|s| !s.is_empty())
+        .map(str::to_string)
+        .unwrap_or_else(|| output.status.to_string());
+    Err(format!("Failed to {}: {}", what, reason))
+}
+
+/// Adds a headless output, e.g. to serve as a second screen over VNC with wayvnc. Sway
+/// only creates one when started with the headless backend in `WLR_BACKENDS`.
+pub fn create_virtual_output(compositor: Compositor) -> Result<(), String> {
+    let command = match compositor {
+        Compositor::Sway => {
+            let mut command = Command::new("swaymsg");
+            command.arg("create_output");
+            command
+        }
+        Compositor::Hyprland => {
+            let mut command = Command::new("hyprctl");
+            command.args(["output", "create", "headless"]);
+            command
+        }
+    };
+    tracing::info!("Creating a virtual output");
+    run(command, "create a virtual output")
+}
+
+/// Destroys the virtual output `name`.
+pub fn remove_virtual_output(compositor: Compositor, name: &str) -> Result<(), String> {
+    let command = match compositor {
+        Compositor::Sway => {
+            let mut command = Command::new("swaymsg");
+            command.args(["output", name, "unplug"]);
+            command
+        }
+        Compositor::Hyprland => {
+            let mut command = Command::new("hyprctl");
+            command.args(["output", "remove", name]);
+            command
+        }
+    };
+    tracing::info!("Removing the virtual output {}", name);
+    run(command, &format!("remove {}", name))
+}
+
+/// The colour depth an output is driven at. Lowering it to 8 bits fixes TVs that show
+/// wrong colours when sent deep colour; 10 bits suits HDR and wide-gamut panels.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BitDepth {
+    /// Whatever the compositor picks.
+    #[default]
+    Auto,
+    Eight,
+    Ten,
+}
+
+impl BitDepth {
+    pub const ALL: [BitDepth; 3] = [BitDepth::Auto, BitDepth::Eight, BitDepth::Ten];
+
+    /// Bits per colour component, if set.
+    pub fn bits(self) -> Option<u32> {
+        match self {
+            BitDepth::Auto => None,
+            BitDepth::Eight => Some(8),
+            BitDepth::Ten => Some(10),
+        }
+    }
+}
+
+impl std::fmt::Display for BitDepth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.bits() {
+            Some(bits) => write!(f, "{}-bit", bits),
+            None => f.write_str("Auto"),
+        }
+    }
+}
+
+/// Hyprland's number for a wlr transform name.
+pub(crate) fn hyprland_transform(transform: &str) -> u8 {
+    match transform {
+        "90" => 1,
+        "180" => 2,
+        "270" => 3,
+        "flipped" => 4,
+        "flipped-90" => 5,
+        "flipped-180" => 6,
+        "flipped-270" => 7,
+        _ => 0,
+    }
+}
+
+/// A Hyprland `monitor` rule restating `output`'s current layout, followed by `extra`.
+/// Hyprland takes options like the bit depth only as part of a whole rule.
+fn hyprland_monitor_rule(output: &Output, extra: &str) -> Option<Command> {
+    let rule = hyprland_rule(output, extra)?;
+    let mut command = Command::new("hyprctl");
+    command.args(["keyword", "monitor", &rule]);
+    Some(command)
+}
+
+/// The text of the rule [`hyprland_monitor_rule`] sends.
+pub(crate) fn hyprland_rule(output: &Output, extra: &str) -> Option<String> {
+    let mode = output.modes.iter().find(|m| m.current)?;
+    Some(format!(
+        "{},{}x{}@{:.3},{}x{},{},transform,{}{}",
+        output.name,
+        mode.width,
+        mode.height,
+        mode.refresh_rate,
+        output.position.0,
+        output.position.1,
+        output.scale,
+        hyprland_transform(&output.transform),
+        extra
+    ))
+}
+
+/// The command that sets `output`'s bit depth.
+pub fn bit_depth_command(
+    compositor: Compositor,
+    output: &Output,
+    depth: BitDepth,
+) -> Option<Command> {
+    // Both compositors default to 8 bits.
+    let bits = depth.bits().unwrap_or(8);
+    match compositor {
+        Compositor::Sway => {
+            let mut command = Command::new("swaymsg");
+            command.args([
+                "output",
+                &output.name,
+                "render_bit_depth",
+                &bits.to_string(),
+            ]);
+            Some(command)
+        }
+        Compositor::Hyprland => hyprland_monitor_rule(output, &format!(",bitdepth,{}", bits)),
+    }
+}
+
+/// The command that keeps windows inside `overscan`. Hyprland reserves the margins like
+/// a bar's, in logical pixels; fullscreen windows still cover them.
+pub fn overscan_command(
+    compositor: Compositor,
+    output: &Output,
+    overscan: Overscan,
+) -> Option<Command> {
+    match compositor {
+        Compositor::Sway => None,
+        Compositor::Hyprland => {
+            let logical = |px: u32| (px as f32 / output.scale).round() as u32;
+            let rule = format!(
+                "{},addreserved,{},{},{},{}",
+                output.name,
+                logical(overscan.top),
+                logical(overscan.bottom),
+                logical(overscan.left),
+                logical(overscan.right)
+            );
+            let mut command = Command::new("hyprctl");
+            command.args(["keyword", "monitor", &rule]);
+            Some(command)
+        }
+    }
+}
+
+/// The command that overrides `output`'s subpixel order.
+pub fn subpixel_command(
+    compositor: Compositor,
+    output: &Output,
+    subpixel: Subpixel,
+) -> Option<Command> {
+    let value = match subpixel {
+        Subpixel::Unknown => return None,
+        Subpixel::None => "none",
+        Subpixel::Rgb => "rgb",
+        Subpixel::Bgr => "bgr",
+        Subpixel::VerticalRgb => "vrgb",
+        Subpixel::VerticalBgr => "vbgr",
+    };
+    match compositor {
+        Compositor::Sway => {
+            let mut command = Command::new("swaymsg");
+            command.args(["output", &output.name, "subpixel", value]);
+            Some(command)
+        }
+        Compositor::Hyprland => None,
+    }
+}
+
+/// How the compositor resamples an output's picture when its scale is fractional or it
+/// runs below the panel's native resolution.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ScaleFilter {
+    /// Whatever the compositor does by default.
+    #[default]
+    Auto,
+    /// Smooth, for text and photos.
+    Linear,
+    /// Sharp pixels, for pixel art and retro games.
+    Nearest,
+}
+
+impl ScaleFilter {
+    pub const ALL: [ScaleFilter; 3] =
+        [ScaleFilter::Auto, ScaleFilter::Linear, ScaleFilter::Nearest];
+}
+
+impl std::fmt::Display for ScaleFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            ScaleFilter::Auto => "Auto",
+            ScaleFilter::Linear => "Linear",
+            ScaleFilter::Nearest => "Nearest",
+        })
+    }
+}
+
+/// The command that sets `output`'s scale filter, if `compositor` has one.
+pub fn scale_filter_command(
+    compositor: Compositor,
+    output: &str,
+    filter: ScaleFilter,
+) -> Option<Command> {
+    let value = match filter {
+        // Sway's own default picks nearest at integer scales and linear otherwise.
+        ScaleFilter::Auto => "smart",
+        ScaleFilter::Linear => "linear",
+        ScaleFilter::Nearest => "nearest",
+    };
+    match compositor {
+        Compositor::Sway => {
+            let mut command = Command::new("swaymsg");
+            command.args(["output", output, "scale_filter", value]);
+            Some(command)
+        }
+        Compositor::Hyprland => None,
+    }
+}
+
+/// Sends one per-output setting for each enabled output in `outputs`. Outputs left on
+/// the default are only touched once some output has been set to something else, so a
+/// compositor without the command is never bothered.
+fn apply_each<T>(
+    outputs: &[Output],
+    settings: &AppSettings,
+    what: &str,
+    pref: impl Fn(&OutputPrefs) -> T,
+    command: impl Fn(Compositor, &Output, T) -> Option<Command>,
+) -> Result<(), String>
+where
+    T: Copy + Default + PartialEq + std::fmt::Display,
+{
+    let values: Vec<(&Output, T)> = outputs
+        .iter()
+        .filter(|o| o.enabled)
+        .map(|o| (o, pref(&settings.output_prefs(&o.identity()))))
+        .collect();
+    if values.iter().all(|(_, v)| *v == T::default()) {
+        return Ok(());
+    }
+    let compositor = Compositor::detect()
+        .ok_or_else(|| format!("This compositor has no command to set the {}", what))?;
+    for (output, value) in values {
+        let Some(mut command) = command(compositor, output, value) else {
+            continue;
+        };
+        tracing::info!("Setting the {} of {} to {}", what, output.name, value);
+        let status = process::status(&mut command)
+            .map_err(|e| format!("Failed to set the {} of {}: {}", what, output.name, e))?;
+        if !status.success() {
+            return Err(format!(
+                "Setting the {} of {} exited with {}",
+                what, output.name, status
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Sends the scale filter chosen for each enabled output in `outputs`.
+pub fn apply_scale_filters(outputs: &[Output], settings: &AppSettings) -> Result<(), String> {
+    if !Compositor::detect().is_some_and(|c| c.has_scale_filter()) {
+        let all_auto = outputs
+            .iter()
+            .filter(|o| o.enabled)
+            .all(|o| settings.output_prefs(&o.identity()).scale_filter == ScaleFilter::Auto);
+        if all_auto {
+            return Ok(());
+        }
+        return Err("Scale filters are not supported by this compositor".to_string());
+    }
+    apply_each(
+        outputs,
+        settings,
+        "scale filter",
+        |p| p.scale_filter,
+        |c, o, f| scale_filter_command(c, &o.name, f),
+    )
+}
+
+/// Sends the bit depth chosen for each enabled output in `outputs`.
+pub fn apply_bit_depths(outputs: &[Output], settings: &AppSettings) -> Result<(), String> {
+    apply_each(
+        outputs,
+        settings,
+        "bit depth",
+        |p| p.bit_depth,
+        bit_depth_command,
+    )
+}
+
+/// Sends the overscan margins of each enabled output in `outputs`.
+pub fn apply_overscans(outputs: &[Output], settings: &AppSettings) -> Result<(), String> {
+    apply_each(
+        outputs,
+        settings,
+        "overscan",
+        |p| p.overscan,
+        overscan_command,
+    )
+}
+
+/// Sends the subpixel order overrides of each enabled output in `outputs`.
+pub fn apply_subpixels(outputs: &[Output], settings: &AppSettings) -> Result<(), String> {
+    apply_each(
+        outputs,
+        settings,
+        "subpixel order",
+        |p| p.subpixel,
+        subpixel_command,
+    )
+}
+
+/// Sends every setting above, logging the ones that fail rather than stopping.
+pub fn apply_output_settings(outputs: &[Output], settings: &AppSettings) {
+    for result in [
+        apply_scale_filters(outputs, settings),
+        apply_bit_depths(outputs, settings),
+        apply_overscans(outputs, settings),
+        apply_subpixels(outputs, settings),
+    ] {
+        if let Err(e) = result {
+            tracing::warn!("{}", e);
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use std::fs;
+use std::path::PathBuf;
+
+use crate::edid::{self, EdidInfo};
+
+/// The physical link an output is attached through.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectorKind {
+    Hdmi,
+    DisplayPort,
+    /// DisplayPort carried over a USB-C port in alternate mode.
+    UsbC,
+    Edp,
+    Lvds,
+    Dsi,
+    Dvi,
+    Vga,
+    /// Headless, nested or virtual outputs with no cable at all.
+    Virtual,
+    Unknown,
+}
+
+impl ConnectorKind {
+    /// Parses the DRM connector name the compositor uses, e.g. `HDMI-A-1` or `eDP-1`.
+    pub fn from_name(name: &str) -> Self {
+        let prefix = name.split('-').next().unwrap_or_default();
+        match prefix.to_ascii_uppercase().as_str() {
+            "HDMI" => ConnectorKind::Hdmi,
+            "DP" => ConnectorKind::DisplayPort,
+            "EDP" => ConnectorKind::Edp,
+            "LVDS" => ConnectorKind::Lvds,
+            "DSI" => ConnectorKind::Dsi,
+            "DVI" => ConnectorKind::Dvi,
+            "VGA" => ConnectorKind::Vga,
+            "VIRTUAL" | "HEADLESS" | "WL" | "X11" | "NOOP" => ConnectorKind::Virtual,
+            _ => ConnectorKind::Unknown,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ConnectorKind::Hdmi => "HDMI",
+            ConnectorKind::DisplayPort => "DisplayPort",
+            ConnectorKind::UsbC => "USB-C",
+            ConnectorKind::Edp => "eDP",
+            ConnectorKind::Lvds => "LVDS",
+            ConnectorKind::Dsi => "DSI",
+            ConnectorKind::Dvi => "DVI",
+            ConnectorKind::Vga => "VGA",
+            ConnectorKind::Virtual => "Virtual",
+            ConnectorKind::Unknown => "Unknown",
+        }
+    }
+
+    /// A glyph telling laptop panels, USB-C links and external monitors apart at a glance.
+    pub fn icon(self) -> &'static str {
+        match self {
+            _ if self.is_builtin() => "💻",
+            ConnectorKind::UsbC => "🔌",
+            ConnectorKind::Virtual => "▢",
+            _ => "🖥",
+        }
+    }
+
+    /// Panels wired inside the machine, such as a laptop's own screen.
+    pub fn is_builtin(self) -> bool {
+        matches!(
+            self,
+            ConnectorKind::Edp | ConnectorKind::Lvds | ConnectorKind::Dsi
+        )
+    }
+}
+
+/// What the kernel knows about an output's connector.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Connector {
+    pub kind: ConnectorKind,
+    /// The DRM device driving it, e.g. `card1`.
+    pub card: Option<String>,
+    /// The USB Type-C port carrying it, e.g. `port0`.
+    pub usb_c_port: Option<String>,
+    /// Capabilities from the EDID the kernel read over this connector.
+    pub edid: Option<EdidInfo>,
+}
+
+fn drm_path(name: &str) -> Option<(String, PathBuf)> {
+    fs::read_dir("/sys/class/drm")
+        .ok()?
+        .flatten()
+        .find_map(|entry| {
+            let file_name = entry.file_name().to_string_lossy().into_owned();
+            let (card, connector) = file_name.split_once('-')?;
+            (connector == name).then(|| (card.to_string(), entry.path()))
+        })
+}
+
+/// The DRM device driving the connector called `name`, e.g. `card1`.
+pub fn card(name: &str) -> Option<String> {
+    drm_path(name).map(|(card, _)| card)
+}
+
+/// Classifies the connector called `name`, using sysfs to recognise DisplayPort links
+/// that run over USB-C and to read the attached display's EDID.
+pub fn detect(name: &str) -> Connector {
+    let mut kind = ConnectorKind::from_name(name);
+    let drm = drm_path(name);
+    let usb_c_port = drm.as_ref().and_then(|(_, path)| {
+        let port = fs::read_link(path.join("typec_connector")).ok()?;
+        Some(port.file_name()?.to_string_lossy().into_owned())
+    });
+    if usb_c_port.is_some() && kind == ConnectorKind::DisplayPort {
+        kind = ConnectorKind::UsbC;
+    }
+    let edid = drm
+        .as_ref()
+        .and_then(|(_, path)| fs::read(path.join("edid")).ok())
+        .and_then(|bytes| edid::parse(&bytes));
+    Connector {
+        kind,
+        card: drm.map(|(card, _)| card),
+        usb_c_port,
+        edid,
+    }
+}
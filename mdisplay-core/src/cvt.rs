@@ -0,0 +1,214 @@
+//! VESA Coordinated Video Timings for custom modes.
+//!
+//! Standard CVT blanking suits CRT-era timing margins; reduced blanking (CVT-RB and the
+//! CVT 1.2 CVT-RB2) shrinks it to what digital links need, which lowers the pixel clock
+//! enough for high refresh rates to fit within HDMI and DisplayPort bandwidth. The
+//! formulas follow the CVT 1.2 spreadsheet, as `cvt` and `gtf` do.
+
+use serde::{Deserialize, Serialize};
+
+const CELL_GRANULARITY: u32 = 8;
+const MIN_V_FRONT_PORCH: u32 = 3;
+const MIN_V_BACK_PORCH: u32 = 6;
+/// Minimum vertical sync plus back porch for standard blanking, in µs.
+const MIN_VSYNC_BACK_PORCH_US: f64 = 550.0;
+/// Minimum vertical blanking for reduced blanking, in µs.
+const RB_MIN_V_BLANK_US: f64 = 460.0;
+
+/// Which CVT blanking a custom mode's timings are generated with.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Blanking {
+    #[default]
+    Standard,
+    /// CVT-RB: 160-pixel horizontal blanking.
+    Reduced,
+    /// CVT-RB2: 80-pixel horizontal blanking and a 1 kHz clock step.
+    ReducedV2,
+}
+
+impl Blanking {
+    pub const ALL: [Blanking; 3] = [Blanking::Standard, Blanking::Reduced, Blanking::ReducedV2];
+}
+
+impl std::fmt::Display for Blanking {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Blanking::Standard => "CVT",
+            Blanking::Reduced => "CVT-RB",
+            Blanking::ReducedV2 => "CVT-RB2",
+        })
+    }
+}
+
+/// A mode's full timings, in pixels and lines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Timings {
+    pub pixel_clock_khz: u32,
+    pub h_display: u32,
+    pub h_sync_start: u32,
+    pub h_sync_end: u32,
+    pub h_total: u32,
+    pub v_display: u32,
+    pub v_sync_start: u32,
+    pub v_sync_end: u32,
+    pub v_total: u32,
+    /// Standard CVT uses -hsync +vsync; reduced blanking the opposite.
+    pub h_sync_positive: bool,
+}
+
+impl Timings {
+    /// The refresh rate these timings actually produce, after clock rounding.
+    pub fn refresh_rate(&self) -> f64 {
+        self.pixel_clock_khz as f64 * 1000.0 / (self.h_total as f64 * self.v_total as f64)
+    }
+
+    /// The X11 modeline, e.g. `"1920x1080_59.96" 173.000 1920 2048 … -hsync +vsync`.
+    pub fn modeline(&self) -> String {
+        let (h_sync, v_sync) = if self.h_sync_positive {
+            ("+hsync", "-vsync")
+        } else {
+            ("-hsync", "+vsync")
+        };
+        format!(
+            "\"{}x{}_{:.2}\" {:.3} {} {} {} {} {} {} {} {} {} {}",
+            self.h_display,
+            self.v_display,
+            self.refresh_rate(),
+            self.pixel_clock_khz as f64 / 1000.0,
+            self.h_display,
+            self.h_sync_start,
+            self.h_sync_end,
+            self.h_total,
+            self.v_display,
+            self.v_sync_start,
+            self.v_sync_end,
+            self.v_total,
+            h_sync,
+            v_sync
+        )
+    }
+}
+
+/// Vertical sync width for standard CVT and CVT-RB, which encodes the aspect ratio.
+fn v_sync_width(width: u32, height: u32) -> u32 {
+    let is = |w: u32, h: u32| width * h == height * w;
+    if is(4, 3) {
+        4
+    } else if is(16, 9) {
+        5
+    } else if is(16, 10) {
+        6
+    } else if is(5, 4) || is(15, 9) {
+        7
+    } else {
+        10
+    }
+}
+
+/// Timings for `width`×`height` at `refresh_rate` Hz, progressive and without margins.
+pub fn timings(width: u32, height: u32, refresh_rate: f64, blanking: Blanking) -> Timings {
+    let frame_us = 1_000_000.0 / refresh_rate;
+    match blanking {
+        Blanking::Standard => {
+            let h_display = width / CELL_GRANULARITY * CELL_GRANULARITY;
+            let v_sync = v_sync_width(h_display, height);
+            let h_period =
+                (frame_us - MIN_VSYNC_BACK_PORCH_US) / (height + MIN_V_FRONT_PORCH) as f64;
+            let v_sync_back_porch = ((MIN_VSYNC_BACK_PORCH_US / h_period).floor() as u32 + 1)
+                .max(v_sync + MIN_V_BACK_PORCH);
+            let v_total = height + v_sync_back_porch + MIN_V_FRONT_PORCH;
+
+            // C' = 30 and M' = 300 from the default blanking formula parameters.
+            let duty_cycle = (30.0 - 300.0 * h_period / 1000.0).max(20.0);
+            let cell = 2 * CELL_GRANULARITY;
+            let h_blank = (h_display as f64 * duty_cycle / (100.0 - duty_cycle) / cell as f64)
+                .floor() as u32
+                * cell;
+            let h_total = h_display + h_blank;
+            let pixel_clock_khz =
+                ((h_total as f64 / h_period * 1000.0) / 250.0).floor() as u32 * 250;
+            let h_sync = h_total * 8 / 100 / CELL_GRANULARITY * CELL_GRANULARITY;
+            let h_sync_end = h_display + h_blank / 2;
+            Timings {
+                pixel_clock_khz,
+                h_display,
+                h_sync_start: h_sync_end - h_sync,
+                h_sync_end,
+                h_total,
+                v_display: height,
+                v_sync_start: height + MIN_V_FRONT_PORCH,
+                v_sync_end: height + MIN_V_FRONT_PORCH + v_sync,
+                v_total,
+                h_sync_positive: false,
+            }
+        }
+        Blanking::Reduced => {
+            let h_display = width / CELL_GRANULARITY * CELL_GRANULARITY;
+            let v_sync = v_sync_width(h_display, height);
+            let h_period = (frame_us - RB_MIN_V_BLANK_US) / height as f64;
+            let v_blank = ((RB_MIN_V_BLANK_US / h_period).floor() as u32 + 1)
+                .max(MIN_V_FRONT_PORCH + v_sync + MIN_V_BACK_PORCH);
+            let (v_total, h_total) = (height + v_blank, h_display + 160);
+            let pixel_clock_khz =
+                (refresh_rate * (v_total * h_total) as f64 / 1000.0 / 250.0).floor() as u32 * 250;
+            Timings {
+                pixel_clock_khz,
+                h_display,
+                h_sync_start: h_display + 48,
+                h_sync_end: h_display + 80,
+                h_total,
+                v_display: height,
+                v_sync_start: height + MIN_V_FRONT_PORCH,
+                v_sync_end: height + MIN_V_FRONT_PORCH + v_sync,
+                v_total,
+                h_sync_positive: true,
+            }
+        }
+        Blanking::ReducedV2 => {
+            const V_SYNC: u32 = 8;
+            let h_period = (frame_us - RB_MIN_V_BLANK_US) / height as f64;
+            let v_blank = ((RB_MIN_V_BLANK_US / h_period).floor() as u32 + 1)
+                .max(1 + V_SYNC + MIN_V_BACK_PORCH);
+            let (v_total, h_total) = (height + v_blank, width + 80);
+            let v_sync_start = v_total - MIN_V_BACK_PORCH - V_SYNC;
+            Timings {
+                pixel_clock_khz: (refresh_rate * (v_total * h_total) as f64 / 1000.0).floor()
+                    as u32,
+                h_display: width,
+                h_sync_start: width + 8,
+                h_sync_end: width + 40,
+                h_total,
+                v_display: height,
+                v_sync_start,
+                v_sync_end: v_sync_start + V_SYNC,
+                v_total,
+                h_sync_positive: true,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_the_cvt_tool() {
+        // `cvt 1920 1080 60` and `cvt -r 1920 1080 60`.
+        let standard = timings(1920, 1080, 60.0, Blanking::Standard);
+        assert_eq!(
+            standard.modeline(),
+            "\"1920x1080_59.96\" 173.000 1920 2048 2248 2576 1080 1083 1088 1120 -hsync +vsync"
+        );
+        let reduced = timings(1920, 1080, 60.0, Blanking::Reduced);
+        assert_eq!(
+            reduced.modeline(),
+            "\"1920x1080_59.93\" 138.500 1920 1968 2000 2080 1080 1083 1088 1111 +hsync -vsync"
+        );
+        // VESA's published CVT-RB2 timing for 3840x2160 at 60 Hz.
+        let v2 = timings(3840, 2160, 60.0, Blanking::ReducedV2);
+        assert_eq!((v2.h_total, v2.v_total), (3920, 2222));
+        assert_eq!(v2.pixel_clock_khz, 522_614);
+    }
+}
@@ -0,0 +1,89 @@
+//! Monitor brightness and contrast over DDC/CI, through the `ddcutil` command.
+
+use std::process::Command;
+
+use crate::process;
+
+/// VCP feature codes from the MCCS standard.
+pub const BRIGHTNESS: u8 = 0x10;
+pub const CONTRAST: u8 = 0x12;
+
+fn ddcutil(args: &[&str]) -> Result<String, String> {
+    let output = process::output(Command::new("ddcutil").args(args))
+        .map_err(|e| format!("Failed to run ddcutil: {}", e))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!(
+            "ddcutil {} failed: {}",
+            args.join(" "),
+            stderr.trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// An external monitor reachable over DDC/CI, identified by its I²C bus.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Display {
+    bus: String,
+}
+
+/// Every monitor `ddcutil detect` finds, by connector name. Detection probes each I²C
+/// bus and takes a second or two, so callers should do it once.
+pub fn detect() -> Result<Vec<(String, Display)>, String> {
+    let detected = ddcutil(&["detect", "--terse"])?;
+    let mut displays = Vec::new();
+    let mut bus = None;
+    for line in detected.lines().map(str::trim) {
+        if let Some(device) = line.strip_prefix("I2C bus:") {
+            bus = device.trim().strip_prefix("/dev/i2c-").map(str::to_string);
+        } else if let Some(connector) = line.strip_prefix("DRM connector:")
+            && let Some((_, name)) = connector.trim().split_once('-')
+            && let Some(bus) = bus.take()
+        {
+            displays.push((name.to_string(), Display { bus }));
+        }
+    }
+    Ok(displays)
+}
+
+impl Display {
+    /// Finds the monitor on the connector called `output`.
+    pub fn find(output: &str) -> Result<Self, String> {
+        detect()?
+            .into_iter()
+            .find(|(name, _)| name == output)
+            .map(|(_, display)| display)
+            .ok_or_else(|| format!("{} does not answer DDC/CI", output))
+    }
+
+    /// The current and maximum value of a VCP feature.
+    pub fn get(&self, code: u8) -> Result<(u16, u16), String> {
+        let reply = ddcutil(&[
+            "--bus",
+            &self.bus,
+            "getvcp",
+            &format!("{:02x}", code),
+            "--terse",
+        ])?;
+        // "VCP 10 C 50 100" for continuous features.
+        let fields: Vec<&str> = reply.split_whitespace().collect();
+        if let ["VCP", _, "C", current, max, ..] = fields.as_slice()
+            && let (Ok(current), Ok(max)) = (current.parse(), max.parse())
+        {
+            return Ok((current, max));
+        }
+        Err(format!("Unexpected ddcutil reply: {}", reply.trim()))
+    }
+
+    pub fn set(&self, code: u8, value: u16) -> Result<(), String> {
+        ddcutil(&[
+            "--bus",
+            &self.bus,
+            "setvcp",
+            &format!("{:02x}", code),
+            &value.to_string(),
+        ])
+        .map(|_| ())
+    }
+}
@@ -0,0 +1,99 @@
+//! USB-C and Thunderbolt docks, so a profile can follow the dock it belongs to.
+//!
+//! Docks are told apart by their USB (or Thunderbolt) vendor and product IDs. Most docks
+//! show up as a USB hub, so every hub is offered except the root hubs inside the
+//! machine; Thunderbolt devices and anything calling itself a dock are offered as well.
+
+use std::fs;
+use std::path::Path;
+
+const USB_DEVICES: &str = "/sys/bus/usb/devices";
+const THUNDERBOLT_DEVICES: &str = "/sys/bus/thunderbolt/devices";
+const USB_CLASS_HUB: &str = "09";
+/// The Linux Foundation, vendor of the kernel's virtual root hubs.
+const ROOT_HUB_VENDOR: &str = "1d6b";
+
+/// A connected dock.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dock {
+    /// `vendor:product` in hex, e.g. `17ef:30b4`.
+    pub id: String,
+    pub name: String,
+}
+
+fn read_trimmed(path: &Path) -> String {
+    fs::read_to_string(path)
+        .map(|s| s.trim().to_string())
+        .unwrap_or_default()
+}
+
+fn usb_dock(device: &Path) -> Option<Dock> {
+    let vendor = read_trimmed(&device.join("idVendor"));
+    let product = read_trimmed(&device.join("idProduct"));
+    if vendor.is_empty() || vendor == ROOT_HUB_VENDOR {
+        return None;
+    }
+    let name = [
+        read_trimmed(&device.join("manufacturer")),
+        read_trimmed(&device.join("product")),
+    ]
+    .iter()
+    .filter(|s| !s.is_empty())
+    .cloned()
+    .collect::<Vec<_>>()
+    .join(" ");
+    let is_hub = read_trimmed(&device.join("bDeviceClass")) == USB_CLASS_HUB;
+    if !is_hub && !name.to_lowercase().contains("dock") {
+        return None;
+    }
+    Some(Dock {
+        id: format!("{}:{}", vendor, product),
+        name,
+    })
+}
+
+fn thunderbolt_dock(device: &Path) -> Option<Dock> {
+    let id = |file: &str| {
+        let value = read_trimmed(&device.join(file));
+        u16::from_str_radix(value.trim_start_matches("0x"), 16).ok()
+    };
+    let (vendor, product) = (id("vendor")?, id("device")?);
+    let name = format!(
+        "{} {}",
+        read_trimmed(&device.join("vendor_name")),
+        read_trimmed(&device.join("device_name"))
+    );
+    Some(Dock {
+        id: format!("{:04x}:{:04x}", vendor, product),
+        name: name.trim().to_string(),
+    })
+}
+
+/// The docks connected now, sorted by name, each listed once.
+pub fn list() -> Vec<Dock> {
+    let mut docks = Vec::new();
+    if let Ok(entries) = fs::read_dir(USB_DEVICES) {
+        // Interfaces are named like `1-2:1.0`; only whole devices are wanted.
+        docks.extend(
+            entries
+                .flatten()
+                .filter(|e| !e.file_name().to_string_lossy().contains(':'))
+                .filter_map(|e| usb_dock(&e.path())),
+        );
+    }
+    if let Ok(entries) = fs::read_dir(THUNDERBOLT_DEVICES) {
+        // `0-0` is the host controller itself, and `domain0` is not a device.
+        docks.extend(
+            entries
+                .flatten()
+                .filter(|e| {
+                    let name = e.file_name().to_string_lossy().into_owned();
+                    name.contains('-') && !name.ends_with("-0") && !name.contains([':', '.'])
+                })
+                .filter_map(|e| thunderbolt_dock(&e.path())),
+        );
+    }
+    docks.sort_by(|a, b| a.name.cmp(&b.name).then(a.id.cmp(&b.id)));
+    docks.dedup_by(|a, b| a.id == b.id);
+    docks
+}
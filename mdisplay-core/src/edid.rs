@@ -0,0 +1,84 @@
+//! The few EDID fields that tell whether adaptive sync, HDR or a custom mode can work.
+
+const BLOCK: usize = 128;
+const HEADER: [u8; 8] = [0x00, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x00];
+const RANGE_LIMITS_TAG: u8 = 0xfd;
+const CTA_EXTENSION_TAG: u8 = 0x02;
+const HDR_STATIC_METADATA: u8 = 0x06;
+
+/// Capabilities advertised by a display's EDID.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EdidInfo {
+    /// Vertical refresh range in Hz from the display range limits.
+    pub refresh_range: Option<(u32, u32)>,
+    /// The display accepts any refresh rate in [`Self::refresh_range`] (EDID 1.4
+    /// continuous frequency), which adaptive sync relies on.
+    pub continuous_frequency: bool,
+    /// Highest pixel clock the display accepts, in MHz.
+    pub max_pixel_clock_mhz: Option<u32>,
+    pub hdr: Option<HdrInfo>,
+}
+
+/// HDR static metadata from the CTA-861 extension.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HdrInfo {
+    /// SMPTE ST 2084, the transfer function used by HDR10.
+    pub pq: bool,
+    /// Hybrid log-gamma.
+    pub hlg: bool,
+    /// Desired content maximum luminance in cd/m².
+    pub max_luminance: Option<f32>,
+}
+
+/// Decodes the base block and any CTA-861 extensions; `None` if `edid` is not an EDID.
+pub fn parse(edid: &[u8]) -> Option<EdidInfo> {
+    if edid.len() < BLOCK || edid[..8] != HEADER {
+        return None;
+    }
+    let mut info = EdidInfo {
+        continuous_frequency: edid[0x12] == 1 && edid[0x13] >= 4 && edid[0x18] & 0x01 != 0,
+        ..Default::default()
+    };
+
+    for descriptor in edid[54..126].chunks_exact(18) {
+        if descriptor[..3] != [0, 0, 0] || descriptor[3] != RANGE_LIMITS_TAG {
+            continue;
+        }
+        // EDID 1.4 adds 255 Hz to a limit when its offset flag is set.
+        let offset = |flag: u8| if descriptor[4] & flag != 0 { 255 } else { 0 };
+        let min = descriptor[5] as u32 + offset(0x01);
+        let max = descriptor[6] as u32 + offset(0x02);
+        info.refresh_range = Some((min, max));
+        info.max_pixel_clock_mhz = Some(descriptor[9] as u32 * 10);
+    }
+
+    for block in edid[BLOCK..].chunks_exact(BLOCK) {
+        if block[0] == CTA_EXTENSION_TAG {
+            info.hdr = info.hdr.or_else(|| hdr_metadata(block));
+        }
+    }
+    Some(info)
+}
+
+fn hdr_metadata(block: &[u8]) -> Option<HdrInfo> {
+    let end = (block[2] as usize).min(BLOCK);
+    let mut i = 4;
+    while i < end {
+        let tag = block[i] >> 5;
+        let len = (block[i] & 0x1f) as usize;
+        let payload = block.get(i + 1..(i + 1 + len).min(end))?;
+        if tag == 7 && payload.first() == Some(&HDR_STATIC_METADATA) && payload.len() >= 2 {
+            let eotfs = payload[1];
+            return Some(HdrInfo {
+                pq: eotfs & 0x04 != 0,
+                hlg: eotfs & 0x08 != 0,
+                max_luminance: payload
+                    .get(3)
+                    .filter(|cv| **cv != 0)
+                    .map(|cv| 50.0 * 2f32.powf(*cv as f32 / 32.0)),
+            });
+        }
+        i += 1 + len;
+    }
+    None
+}
@@ -0,0 +1,243 @@
+//! Colour temperature and gamma correction, applied through
+//! `wlr-gamma-control-unstable-v1`.
+//!
+//! The compositor keeps an output's gamma table only while the [`GammaControl`] that set
+//! it is alive, and allows one per output.
+
+use std::os::fd::AsFd;
+use std::os::unix::fs::FileExt;
+
+use wayland_client::protocol::{wl_output, wl_registry};
+use wayland_client::{Connection, Dispatch, EventQueue, QueueHandle, delegate_noop};
+use wayland_protocols_wlr::gamma_control::v1::client::{
+    zwlr_gamma_control_manager_v1, zwlr_gamma_control_v1,
+};
+
+use crate::settings::{NightSchedule, OutputPrefs};
+use crate::sun;
+
+/// Neutral colour temperature; gamma at this value is left untouched.
+pub const NEUTRAL_TEMPERATURE: u32 = 6500;
+pub const MIN_TEMPERATURE: u32 = 1000;
+
+/// Approximate RGB multipliers for a black-body light source at `kelvin`, normalised so
+/// the neutral temperature is pure white (Tanner Helland's curve fit).
+pub fn whitepoint(kelvin: u32) -> (f32, f32, f32) {
+    let t = kelvin.clamp(MIN_TEMPERATURE, 40000) as f32 / 100.0;
+
+    let r = if t <= 66.0 {
+        255.0
+    } else {
+        329.698_73 * (t - 60.0).powf(-0.133_204_76)
+    };
+    let g = if t <= 66.0 {
+        99.470_8 * t.ln() - 161.119_57
+    } else {
+        288.122_16 * (t - 60.0).powf(-0.075_514_846)
+    };
+    let b = if t >= 66.0 {
+        255.0
+    } else if t <= 19.0 {
+        0.0
+    } else {
+        138.517_73 * (t - 10.0).ln() - 305.044_8
+    };
+
+    (
+        (r / 255.0).clamp(0.0, 1.0),
+        (g / 255.0).clamp(0.0, 1.0),
+        (b / 255.0).clamp(0.0, 1.0),
+    )
+}
+
+/// The gamma and colour temperature `prefs` call for at `unix`, following `schedule`.
+/// `(1.0, NEUTRAL_TEMPERATURE)` means the output's gamma can be left alone.
+pub fn target(prefs: &OutputPrefs, schedule: &NightSchedule, unix: i64) -> (f32, u32) {
+    let gamma = prefs.calibration.map_or(1.0, |c| c.gamma);
+    let kelvin = match prefs.color_temperature {
+        Some(night) if schedule.enabled => {
+            let factor = sun::night_factor(
+                unix,
+                schedule.latitude,
+                schedule.longitude,
+                schedule.transition_minutes as i64 * 60,
+            );
+            let warmth = NEUTRAL_TEMPERATURE.saturating_sub(night) as f32 * factor;
+            NEUTRAL_TEMPERATURE - warmth.round() as u32
+        }
+        Some(night) => night,
+        None => NEUTRAL_TEMPERATURE,
+    };
+    (gamma, kelvin)
+}
+
+/// The gamma ramps for one output: red, then green, then blue, each `size` entries.
+/// `gamma` above 1 brightens the midtones, and `kelvin` tints towards warm white.
+pub fn ramps(size: usize, gamma: f32, kelvin: u32) -> Vec<u16> {
+    let (r, g, b) = whitepoint(kelvin);
+    let exponent = 1.0 / gamma.max(0.1);
+    let last = size.saturating_sub(1).max(1) as f32;
+    [r, g, b]
+        .iter()
+        .flat_map(|channel| {
+            (0..size).map(move |i| {
+                let level = (i as f32 / last).powf(exponent) * channel;
+                (level.clamp(0.0, 1.0) * u16::MAX as f32).round() as u16
+            })
+        })
+        .collect()
+}
+
+#[derive(Default)]
+struct GammaState {
+    manager: Option<zwlr_gamma_control_manager_v1::ZwlrGammaControlManagerV1>,
+    outputs: Vec<(wl_output::WlOutput, String)>,
+    size: Option<u32>,
+    failed: bool,
+}
+
+impl Dispatch<wl_registry::WlRegistry, ()> for GammaState {
+    fn event(
+        state: &mut Self,
+        registry: &wl_registry::WlRegistry,
+        event: wl_registry::Event,
+        _: &(),
+        _: &Connection,
+        qh: &QueueHandle<Self>,
+    ) {
+        if let wl_registry::Event::Global {
+            name,
+            interface,
+            version,
+        } = event
+        {
+            match interface.as_str() {
+                "zwlr_gamma_control_manager_v1" => {
+                    state.manager = Some(registry.bind(name, 1, qh, ()));
+                }
+                "wl_output" if version >= 4 => {
+                    let index = state.outputs.len();
+                    state
+                        .outputs
+                        .push((registry.bind(name, 4, qh, index), String::new()));
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+impl Dispatch<wl_output::WlOutput, usize> for GammaState {
+    fn event(
+        state: &mut Self,
+        _: &wl_output::WlOutput,
+        event: wl_output::Event,
+        index: &usize,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        if let wl_output::Event::Name { name } = event
+            && let Some((_, output_name)) = state.outputs.get_mut(*index)
+        {
+            *output_name = name;
+        }
+    }
+}
+
+impl Dispatch<zwlr_gamma_control_v1::ZwlrGammaControlV1, ()> for GammaState {
+    fn event(
+        state: &mut Self,
+        _: &zwlr_gamma_control_v1::ZwlrGammaControlV1,
+        event: zwlr_gamma_control_v1::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        match event {
+            zwlr_gamma_control_v1::Event::GammaSize { size } => state.size = Some(size),
+            zwlr_gamma_control_v1::Event::Failed => state.failed = true,
+            _ => {}
+        }
+    }
+}
+
+delegate_noop!(GammaState: ignore zwlr_gamma_control_manager_v1::ZwlrGammaControlManagerV1);
+
+/// Exclusive control over one output's gamma table, which the compositor resets when
+/// this is dropped.
+pub struct GammaControl {
+    output: String,
+    queue: EventQueue<GammaState>,
+    state: GammaState,
+    control: zwlr_gamma_control_v1::ZwlrGammaControlV1,
+}
+
+impl GammaControl {
+    /// Takes over the gamma of the output called `output`. Fails when the compositor
+    /// lacks the protocol or another program, such as gammastep, already holds it.
+    pub fn new(output: &str) -> Result<Self, String> {
+        let conn = Connection::connect_to_env()
+            .map_err(|e| format!("Failed to connect to Wayland: {}", e))?;
+        let mut queue = conn.new_event_queue();
+        let qh = queue.handle();
+        conn.display().get_registry(&qh, ());
+
+        let mut state = GammaState::default();
+        queue.roundtrip(&mut state).map_err(|e| e.to_string())?;
+        queue.roundtrip(&mut state).map_err(|e| e.to_string())?;
+
+        let manager = state.manager.clone().ok_or_else(|| {
+            "Compositor does not support wlr-gamma-control-unstable-v1".to_string()
+        })?;
+        let (proxy, _) = state
+            .outputs
+            .iter()
+            .find(|(_, name)| name == output)
+            .ok_or_else(|| format!("No output named '{}'", output))?;
+        let control = manager.get_gamma_control(proxy, &qh, ());
+        queue.roundtrip(&mut state).map_err(|e| e.to_string())?;
+        if state.failed || state.size.is_none() {
+            control.destroy();
+            return Err(format!(
+                "The gamma of {} is in use by another program",
+                output
+            ));
+        }
+        Ok(Self {
+            output: output.to_string(),
+            queue,
+            state,
+            control,
+        })
+    }
+
+    /// Replaces the output's gamma table with [`ramps`] for `gamma` and `kelvin`.
+    pub fn set(&mut self, gamma: f32, kelvin: u32) -> Result<(), String> {
+        let size = self.state.size.unwrap_or(0) as usize;
+        let bytes: Vec<u8> = ramps(size, gamma, kelvin)
+            .iter()
+            .flat_map(|v| v.to_ne_bytes())
+            .collect();
+        let file = crate::overlay::shm_file("gamma", bytes.len() as u64)?;
+        file.write_all_at(&bytes, 0)
+            .map_err(|e| format!("Failed to write the gamma table: {}", e))?;
+        self.control.set_gamma(file.as_fd());
+        self.queue
+            .roundtrip(&mut self.state)
+            .map_err(|e| e.to_string())?;
+        if self.state.failed {
+            return Err(format!(
+                "The compositor rejected the gamma of {}",
+                self.output
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Drop for GammaControl {
+    fn drop(&mut self) {
+        self.control.destroy();
+        let _ = self.queue.flush();
+    }
+}
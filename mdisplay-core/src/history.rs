@@ -0,0 +1,191 @@
+//! A timeline of what happened to the layout: outputs plugged in and removed, profiles
+//! applied, failures and reverts. The daemon writes it as it goes, so "why did my layout
+//! change at 14:32" has an answer after the fact.
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::paths;
+
+/// How many events are kept; older ones are dropped once the file holds twice as many.
+pub const MAX_EVENTS: usize = 500;
+
+/// Events appended by this process, so the file is only read back to trim it every
+/// [`MAX_EVENTS`] appends rather than on each one.
+static APPENDS: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum EventKind {
+    /// An output was connected or disconnected.
+    Hotplug,
+    /// A layout or profile was applied, or outputs were turned or switched.
+    Applied,
+    /// A layout from before was put back.
+    Reverted,
+    Failed,
+    /// Suspend, resume, a compositor restart or a game starting.
+    Session,
+}
+
+impl EventKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            EventKind::Hotplug => "Hotplug",
+            EventKind::Applied => "Applied",
+            EventKind::Reverted => "Reverted",
+            EventKind::Failed => "Failed",
+            EventKind::Session => "Session",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Event {
+    /// Seconds since the Unix epoch.
+    pub time: i64,
+    pub kind: EventKind,
+    pub text: String,
+}
+
+/// One event per line of JSON, oldest first.
+pub fn path() -> PathBuf {
+    paths::state_dir().join("history.jsonl")
+}
+
+/// Appends an event happening now. Failing to write it is only logged.
+pub fn record(kind: EventKind, text: impl Into<String>) {
+    let event = Event {
+        time: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs() as i64),
+        kind,
+        text: text.into(),
+    };
+    if let Err(e) = append(&event) {
+        tracing::warn!("Failed to record '{}' in the history: {}", event.text, e);
+    }
+}
+
+fn append(event: &Event) -> Result<(), String> {
+    let path = path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let mut line = serde_json::to_string(event).map_err(|e| e.to_string())?;
+    line.push('\n');
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .and_then(|mut file| file.write_all(line.as_bytes()))
+        .map_err(|e| e.to_string())?;
+    // The first append checks too, for a file grown by processes that exited early.
+    if APPENDS
+        .fetch_add(1, Ordering::Relaxed)
+        .is_multiple_of(MAX_EVENTS)
+    {
+        trim(&path)?;
+    }
+    Ok(())
+}
+
+/// Drops all but the last [`MAX_EVENTS`] events once the file holds twice as many.
+fn trim(path: &Path) -> Result<(), String> {
+    let events = load();
+    if events.len() > 2 * MAX_EVENTS {
+        let kept: String = events[events.len() - MAX_EVENTS..]
+            .iter()
+            .filter_map(|e| serde_json::to_string(e).ok())
+            .map(|e| e + "\n")
+            .collect();
+        fs::write(path, kept).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Every recorded event, oldest first. Unreadable lines are skipped.
+pub fn load() -> Vec<Event> {
+    let Ok(contents) = fs::read_to_string(path()) else {
+        return Vec::new();
+    };
+    contents
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+unsafe extern "C" {
+    /// Reloads the time zone from `TZ` or `/etc/localtime`; `localtime_r` need not.
+    fn tzset();
+}
+
+/// The local time zone's offset from UTC in seconds at `time`, which differs on either
+/// side of a daylight saving change. The standard library has no time zones, so the C
+/// library is asked. UTC when that fails.
+fn utc_offset(time: i64) -> i64 {
+    let time = time as libc::time_t;
+    // SAFETY: `tm` is plain data that `localtime_r` fills in, and both pointers are valid
+    // for the call. `tzset` only updates the C library's own time zone state.
+    unsafe {
+        tzset();
+        let mut tm: libc::tm = std::mem::zeroed();
+        if libc::localtime_r(&time, &mut tm).is_null() {
+            return 0;
+        }
+        tm.tm_gmtoff as i64
+    }
+}
+
+/// `time` as local `YYYY-MM-DD HH:MM:SS`.
+pub fn local_time(time: i64) -> String {
+    format_time(time + utc_offset(time))
+}
+
+fn format_time(time: i64) -> String {
+    let (days, seconds) = (time.div_euclid(86_400), time.rem_euclid(86_400));
+    // Days to a civil date, after Howard Hinnant's `civil_from_days`.
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        year,
+        month,
+        day,
+        seconds / 3600,
+        seconds % 3600 / 60,
+        seconds % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_unix_times_as_dates() {
+        assert_eq!(format_time(0), "1970-01-01 00:00:00");
+        assert_eq!(format_time(951_827_696), "2000-02-29 12:34:56");
+        assert_eq!(format_time(1_792_245_120), "2026-10-17 13:52:00");
+    }
+
+    #[test]
+    fn local_times_follow_daylight_saving() {
+        // SAFETY: no other test reads the time zone.
+        unsafe { std::env::set_var("TZ", "WET0WEST,M3.5.0/1,M10.5.0") };
+        assert_eq!(local_time(1_768_478_400), "2026-01-15 12:00:00");
+        assert_eq!(local_time(1_784_116_800), "2026-07-15 13:00:00");
+    }
+}
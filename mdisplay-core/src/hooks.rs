@@ -0,0 +1,97 @@
+use std::process::Command;
+
+use serde::{Deserialize, Serialize};
+
+use crate::backend::{Backend, Output};
+use crate::process;
+use crate::profile::OutputConfig;
+use crate::settings::{self, AppSettings};
+
+/// Shell commands run around every apply. Both see `MDISPLAY_PROFILE` (empty for ad-hoc
+/// layouts) and `MDISPLAY_CHANGED_OUTPUTS` (comma-separated connector names).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Hooks {
+    /// Runs before the layout is sent; a non-zero exit cancels the apply.
+    #[serde(default)]
+    pub pre_apply: Option<String>,
+    /// Runs after the compositor accepted the layout.
+    #[serde(default)]
+    pub post_apply: Option<String>,
+}
+
+/// Applies `outputs` through the active backend, running the configured hooks before and
+/// after and then re-sending the input mappings and scale filters. Layouts with no output
+/// on, or beyond what the GPU scans out, are refused whatever the backend.
+pub fn apply(outputs: &[Output], profile: Option<&str>) -> Result<(), String> {
+    apply_with(crate::backend::active().as_ref(), outputs, profile)
+}
+
+/// [`apply`] through an arbitrary backend.
+pub fn apply_with(
+    backend: &dyn Backend,
+    outputs: &[Output],
+    profile: Option<&str>,
+) -> Result<(), String> {
+    let settings = AppSettings::load();
+    let AppSettings {
+        hooks,
+        input_mappings,
+        ..
+    } = &settings;
+    let no_hooks = Hooks::default();
+    let hooks = if settings::commands_trusted() {
+        hooks
+    } else {
+        &no_hooks
+    };
+    crate::backend::ensure_enabled_output(outputs)?;
+    if let Some(problem) = crate::limits::check(outputs, backend.framebuffer_limit()) {
+        return Err(problem.to_string());
+    }
+    let current = backend.fetch_outputs().unwrap_or_default();
+    let changed = changed_outputs(&current, outputs);
+
+    if let Some(command) = &hooks.pre_apply {
+        run(command, profile, &changed).map_err(|e| format!("Pre-apply hook failed: {}", e))?;
+    }
+    backend.apply_outputs(outputs)?;
+    if let Err(e) = crate::input::apply_mappings(input_mappings) {
+        tracing::warn!("{}", e);
+    }
+    crate::compositor::apply_output_settings(outputs, &settings);
+    if let Some(command) = &hooks.post_apply
+        && let Err(e) = run(command, profile, &changed)
+    {
+        tracing::warn!("Post-apply hook failed: {}", e);
+    }
+    Ok(())
+}
+
+fn changed_outputs(current: &[Output], next: &[Output]) -> Vec<String> {
+    next.iter()
+        .filter(|out| {
+            current
+                .iter()
+                .find(|c| c.name == out.name)
+                .is_none_or(|c| OutputConfig::from_output(c) != OutputConfig::from_output(out))
+        })
+        .map(|out| out.name.clone())
+        .collect()
+}
+
+fn run(command: &str, profile: Option<&str>, changed: &[String]) -> Result<(), String> {
+    tracing::info!("Running hook: {}", command);
+    let status = process::status(
+        Command::new("sh")
+            .arg("-c")
+            .arg(command)
+            .env("MDISPLAY_PROFILE", profile.unwrap_or(""))
+            .env("MDISPLAY_CHANGED_OUTPUTS", changed.join(",")),
+    )
+    .map_err(|e| format!("Failed to run '{}': {}", command, e))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("'{}' exited with {}", command, status))
+    }
+}
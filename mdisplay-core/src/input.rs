@@ -0,0 +1,148 @@
+//! Touchscreens and drawing tablets, and mapping them onto outputs.
+//!
+//! Devices are read from `/sys/class/input`. Mappings are sent to the running compositor
+//! after every apply, since moving an output in the layout leaves its touch input behind
+//! otherwise. Sway (`input … map_to_output`) and Hyprland (`device[…]:output`) are
+//! supported; elsewhere there is nothing to run and the mapping is only stored.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use crate::compositor::Compositor;
+use crate::process;
+
+const INPUT_PROP_DIRECT: usize = 0x01;
+const BTN_TOOL_PEN: usize = 0x140;
+const ABS_X: usize = 0x00;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputKind {
+    Touchscreen,
+    Tablet,
+}
+
+/// A touchscreen or tablet as the kernel reports it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputDevice {
+    pub name: String,
+    pub kind: InputKind,
+    pub vendor: u16,
+    pub product: u16,
+}
+
+impl InputDevice {
+    /// Sway's `vendor:product:name` identifier.
+    pub fn sway_identifier(&self) -> String {
+        format!(
+            "{}:{}:{}",
+            self.vendor,
+            self.product,
+            self.name.replace(' ', "_")
+        )
+    }
+
+    /// Hyprland's device name: lowercase, with spaces as dashes.
+    pub fn hyprland_name(&self) -> String {
+        self.name.to_lowercase().replace(' ', "-")
+    }
+}
+
+/// Whether `bit` is set in a sysfs bitmap: hex words separated by spaces, most
+/// significant first.
+fn has_bit(bitmap: &str, bit: usize) -> bool {
+    bitmap
+        .split_whitespace()
+        .rev()
+        .nth(bit / 64)
+        .and_then(|word| u64::from_str_radix(word, 16).ok())
+        .is_some_and(|word| word & (1 << (bit % 64)) != 0)
+}
+
+fn read_trimmed(path: &Path) -> String {
+    fs::read_to_string(path)
+        .map(|s| s.trim().to_string())
+        .unwrap_or_default()
+}
+
+fn read_device(device: &Path) -> Option<InputDevice> {
+    let keys = read_trimmed(&device.join("capabilities/key"));
+    let abs = read_trimmed(&device.join("capabilities/abs"));
+    let properties = read_trimmed(&device.join("properties"));
+    let kind = if has_bit(&keys, BTN_TOOL_PEN) {
+        InputKind::Tablet
+    } else if has_bit(&properties, INPUT_PROP_DIRECT) && has_bit(&abs, ABS_X) {
+        InputKind::Touchscreen
+    } else {
+        return None;
+    };
+    let id = |field: &str| u16::from_str_radix(&read_trimmed(&device.join("id").join(field)), 16);
+    Some(InputDevice {
+        name: read_trimmed(&device.join("name")),
+        kind,
+        vendor: id("vendor").unwrap_or(0),
+        product: id("product").unwrap_or(0),
+    })
+}
+
+/// Every touchscreen and tablet, sorted by name. Devices that expose several event nodes
+/// are listed once.
+pub fn list_devices() -> Vec<InputDevice> {
+    let Ok(entries) = fs::read_dir("/sys/class/input") else {
+        return Vec::new();
+    };
+    let mut devices: Vec<InputDevice> = entries
+        .flatten()
+        .filter(|e| e.file_name().to_string_lossy().starts_with("event"))
+        .filter_map(|e| read_device(&e.path().join("device")))
+        .collect();
+    devices.sort_by(|a, b| a.name.cmp(&b.name));
+    devices.dedup();
+    devices
+}
+
+/// The command that maps `device` onto `output` in `compositor`.
+pub fn map_command(compositor: Compositor, device: &InputDevice, output: &str) -> Command {
+    match compositor {
+        Compositor::Sway => {
+            let mut command = Command::new("swaymsg");
+            command.args(["input", &device.sway_identifier(), "map_to_output", output]);
+            command
+        }
+        Compositor::Hyprland => {
+            let mut command = Command::new("hyprctl");
+            command.args([
+                "keyword",
+                &format!("device[{}]:output", device.hyprland_name()),
+                output,
+            ]);
+            command
+        }
+    }
+}
+
+/// Sends every mapping in `mappings` (device name to connector name) for devices that
+/// are currently plugged in.
+pub fn apply_mappings(mappings: &HashMap<String, String>) -> Result<(), String> {
+    if mappings.is_empty() {
+        return Ok(());
+    }
+    let compositor = Compositor::detect()
+        .ok_or_else(|| "Input mapping is not supported by this compositor".to_string())?;
+    for device in list_devices() {
+        let Some(output) = mappings.get(&device.name) else {
+            continue;
+        };
+        tracing::info!("Mapping {} to {}", device.name, output);
+        let status = process::status(&mut map_command(compositor, &device, output))
+            .map_err(|e| format!("Failed to map {}: {}", device.name, e))?;
+        if !status.success() {
+            return Err(format!(
+                "Mapping {} to {} exited with {}",
+                device.name, output, status
+            ));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,77 @@
+//! Display configuration logic behind mdisplay, usable without the GUI.
+//!
+//! - [`wayland`] reads and applies output configurations through
+//!   `wlr-output-management-unstable-v1`.
+//! - [`backend`] holds the [`Output`] model and writes mangowc `monitorrule` files.
+//! - [`compositor`] sends settings the protocol lacks, like the scale filter, through
+//!   the compositor's own tool.
+//! - [`bandwidth`] warns when a mode is more than its link can carry, and [`limits`]
+//!   when a layout is too large for X11 or the framebuffer.
+//! - [`connector`] tells HDMI, DisplayPort, USB-C and built-in panels apart.
+//! - [`cvt`] generates CVT and reduced-blanking timings for custom modes.
+//! - [`profile`] stores and restores named layouts.
+//! - [`report`] summarises a layout as Markdown with an SVG diagram.
+//! - [`dock`] lists connected USB-C and Thunderbolt docks.
+//! - [`edid`] decodes refresh range, HDR and pixel clock limits.
+//! - [`brightness`] sets backlight and DDC/CI brightness as a percentage.
+//! - [`ddc`] reads and sets monitor brightness and contrast over DDC/CI, and [`gamma`]
+//!   sets gamma tables; [`calibration`] is the wizard built on both.
+//! - [`history`] keeps a timeline of hotplugs, applies, failures and reverts.
+//! - [`hooks`] wraps applies with the user's pre/post commands.
+//! - [`input`] maps touchscreens and tablets onto outputs.
+//! - [`mock`] is an in-memory [`Backend`] for tests.
+//! - [`paths`] says where settings, state and caches live, and moves legacy files.
+//! - [`overlay`] covers one output with a fullscreen surface, and [`pattern`] draws
+//!   test patterns on it.
+//! - [`plugin`] runs third-party backends shipped as executables, and [`process`] runs
+//!   every helper program with a time limit.
+//! - [`seat`] keeps mdisplay to the outputs of one logind seat on multi-seat machines.
+//! - [`settings`] is the `settings.json` schema shared by every mdisplay front end.
+//! - [`status`] is the stable JSON shape used for machine-readable output.
+//! - [`sun`] computes sunrise and sunset for the night light schedule.
+//! - [`verify`] lists what the compositor changed about an applied layout.
+//! - [`tools`] falls back to wlr-randr, swaymsg, hyprctl or xrandr when the compositor
+//!   lacks wlr-output-management.
+//!
+//! ```no_run
+//! let mut outputs = mdisplay_core::wayland::fetch_outputs()?;
+//! for out in &mut outputs {
+//!     out.scale = 1.0;
+//! }
+//! mdisplay_core::wayland::apply_outputs(&outputs)?;
+//! # Ok::<(), String>(())
+//! ```
+
+pub mod backend;
+pub mod bandwidth;
+pub mod brightness;
+pub mod calibration;
+pub mod compositor;
+pub mod connector;
+pub mod cvt;
+pub mod ddc;
+pub mod dock;
+pub mod edid;
+pub mod gamma;
+pub mod history;
+pub mod hooks;
+pub mod input;
+pub mod limits;
+pub mod mock;
+pub mod overlay;
+pub mod paths;
+pub mod pattern;
+pub mod plugin;
+pub mod process;
+pub mod profile;
+pub mod report;
+pub mod seat;
+pub mod settings;
+pub mod status;
+pub mod sun;
+pub mod tools;
+pub mod verify;
+pub mod wayland;
+
+pub use backend::{Backend, Output, OutputMode};
+pub use profile::Profile;
@@ -0,0 +1,81 @@
+//! Whether a layout fits the coordinate space and framebuffer it ends up in.
+//!
+//! XWayland mirrors the compositor's layout as a single X screen, and X11 caps screens at
+//! 32767 pixels each way. Under X11 itself every output is also scanned out of one
+//! framebuffer, which older GPUs limit to as little as 8192 pixels. Going over either
+//! makes the apply fail without a reason, or leaves X11 programs unable to reach part of
+//! the desktop, so layouts are checked first.
+
+use crate::backend::Output;
+
+/// The largest X11 screen, in pixels each way.
+pub const COORDINATE_LIMIT: i32 = 32767;
+
+/// Why a layout does not fit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LimitProblem {
+    /// The layout's bounding box, in logical pixels, is beyond [`COORDINATE_LIMIT`].
+    Coordinates(i32, i32),
+    /// The layout's bounding box is larger than the biggest framebuffer (the second
+    /// pair) the GPU can scan out.
+    Framebuffer((i32, i32), (i32, i32)),
+}
+
+impl std::fmt::Display for LimitProblem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LimitProblem::Coordinates(w, h) => write!(
+                f,
+                "The layout spans {}x{} pixels, beyond the {} that X11 programs can address",
+                w, h, COORDINATE_LIMIT
+            ),
+            LimitProblem::Framebuffer((w, h), (max_w, max_h)) => write!(
+                f,
+                "The layout needs a {}x{} framebuffer but the GPU supports at most {}x{}",
+                w, h, max_w, max_h
+            ),
+        }
+    }
+}
+
+/// `out`'s size in the layout: its current mode divided by the scale, turned with the
+/// transform.
+pub fn logical_size(out: &Output) -> Option<(i32, i32)> {
+    let mode = out.modes.iter().find(|m| m.current)?;
+    let w = (mode.width as f32 / out.scale) as i32;
+    let h = (mode.height as f32 / out.scale) as i32;
+    Some(match out.transform.as_str() {
+        "90" | "270" | "flipped-90" | "flipped-270" => (h, w),
+        _ => (w, h),
+    })
+}
+
+/// The width and height of the box around the enabled outputs.
+pub fn extent(outputs: &[Output]) -> (i32, i32) {
+    let rects: Vec<(i64, i64, i64, i64)> = outputs
+        .iter()
+        .filter(|o| o.enabled)
+        .filter_map(|o| {
+            let (w, h) = logical_size(o)?;
+            let (x, y) = (o.position.0 as i64, o.position.1 as i64);
+            Some((x, y, x + w as i64, y + h as i64))
+        })
+        .collect();
+    let span = |lo: fn(&(i64, i64, i64, i64)) -> i64, hi: fn(&(i64, i64, i64, i64)) -> i64| {
+        let min = rects.iter().map(lo).min().unwrap_or(0);
+        let max = rects.iter().map(hi).max().unwrap_or(0);
+        (max - min).clamp(0, i32::MAX as i64) as i32
+    };
+    (span(|r| r.0, |r| r.2), span(|r| r.1, |r| r.3))
+}
+
+/// Checks `outputs` against the coordinate limit and, when known, the largest
+/// framebuffer.
+pub fn check(outputs: &[Output], framebuffer: Option<(i32, i32)>) -> Option<LimitProblem> {
+    let (w, h) = extent(outputs);
+    if w > COORDINATE_LIMIT || h > COORDINATE_LIMIT {
+        return Some(LimitProblem::Coordinates(w, h));
+    }
+    let (max_w, max_h) = framebuffer?;
+    (w > max_w || h > max_h).then_some(LimitProblem::Framebuffer((w, h), (max_w, max_h)))
+}
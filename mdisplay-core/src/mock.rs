@@ -0,0 +1,54 @@
+use std::sync::Mutex;
+
+use crate::backend::{Backend, Output};
+
+/// In-memory [`Backend`] seeded from fixture data. Applies replace the stored outputs and
+/// are recorded so tests can inspect what would have been sent to the compositor.
+#[derive(Debug, Default)]
+pub struct MockBackend {
+    outputs: Mutex<Vec<Output>>,
+    applied: Mutex<Vec<Vec<Output>>>,
+    apply_error: Option<String>,
+}
+
+impl MockBackend {
+    pub fn new(outputs: Vec<Output>) -> Self {
+        Self {
+            outputs: Mutex::new(outputs),
+            ..Default::default()
+        }
+    }
+
+    /// Loads outputs from a JSON array in the serde form of [`Output`].
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        serde_json::from_str(json)
+            .map(Self::new)
+            .map_err(|e| format!("Invalid fixture: {}", e))
+    }
+
+    /// Makes every apply fail with `error`, as a compositor rejecting the layout would.
+    pub fn failing_with(mut self, error: &str) -> Self {
+        self.apply_error = Some(error.to_string());
+        self
+    }
+
+    /// Every layout applied so far, oldest first.
+    pub fn applied(&self) -> Vec<Vec<Output>> {
+        self.applied.lock().unwrap().clone()
+    }
+}
+
+impl Backend for MockBackend {
+    fn fetch_outputs(&self) -> Result<Vec<Output>, String> {
+        Ok(self.outputs.lock().unwrap().clone())
+    }
+
+    fn apply_outputs(&self, outputs: &[Output]) -> Result<(), String> {
+        if let Some(error) = &self.apply_error {
+            return Err(error.clone());
+        }
+        self.applied.lock().unwrap().push(outputs.to_vec());
+        *self.outputs.lock().unwrap() = outputs.to_vec();
+        Ok(())
+    }
+}
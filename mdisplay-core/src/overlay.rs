@@ -0,0 +1,512 @@
+//! Fullscreen surfaces on a single output, for test patterns drawn in software.
+//!
+//! [`show`] covers one output with a wlr-layer-shell surface above every window and
+//! renders a [`Pattern`] at the panel's native resolution, using wp-viewporter to map the
+//! buffer onto the output's logical size. Left click, Space, Enter or Right advance the
+//! pattern, Up and Down adjust it, and Escape, Q or a right click close it.
+
+use std::fs::{File, OpenOptions};
+use std::os::fd::AsFd;
+use std::os::unix::fs::FileExt;
+use std::time::{Duration, Instant};
+
+use wayland_client::protocol::{
+    wl_buffer, wl_callback, wl_compositor, wl_keyboard, wl_output, wl_pointer, wl_registry,
+    wl_seat, wl_shm, wl_shm_pool, wl_surface,
+};
+use wayland_client::{Connection, Dispatch, QueueHandle, WEnum, delegate_noop};
+use wayland_protocols::wp::viewporter::client::{wp_viewport, wp_viewporter};
+use wayland_protocols_wlr::layer_shell::v1::client::{zwlr_layer_shell_v1, zwlr_layer_surface_v1};
+
+const BTN_LEFT: u32 = 0x110;
+const BTN_RIGHT: u32 = 0x111;
+const KEY_ESC: u32 = 1;
+const KEY_Q: u32 = 16;
+const KEY_ENTER: u32 = 28;
+const KEY_SPACE: u32 = 57;
+const KEY_UP: u32 = 103;
+const KEY_RIGHT: u32 = 106;
+const KEY_DOWN: u32 = 108;
+
+/// One frame to fill with `0x00RRGGBB` pixels, row by row.
+pub struct Frame<'a> {
+    pub pixels: &'a mut [u32],
+    pub width: u32,
+    pub height: u32,
+    /// Time since the overlay first appeared.
+    pub elapsed: Duration,
+    /// Refresh rate of the output's current mode in Hz.
+    pub refresh_rate: f32,
+}
+
+impl Frame<'_> {
+    pub fn fill(&mut self, color: u32) {
+        self.pixels.fill(color);
+    }
+
+    /// Fills the rectangle, clipped to the frame.
+    pub fn fill_rect(&mut self, x: u32, y: u32, width: u32, height: u32, color: u32) {
+        let x_end = x.saturating_add(width).min(self.width);
+        let y_end = y.saturating_add(height).min(self.height);
+        if x >= x_end {
+            return;
+        }
+        for row in y.min(y_end)..y_end {
+            let start = (row * self.width) as usize;
+            self.pixels[start + x as usize..start + x_end as usize].fill(color);
+        }
+    }
+
+    pub fn set(&mut self, x: u32, y: u32, color: u32) {
+        if x < self.width && y < self.height {
+            self.pixels[(y * self.width + x) as usize] = color;
+        }
+    }
+}
+
+/// Something to draw with [`show`].
+pub trait Pattern {
+    fn render(&mut self, frame: &mut Frame);
+
+    /// Redraw on every frame callback instead of only after input.
+    fn animated(&self) -> bool {
+        false
+    }
+
+    /// Moves to the next step; returning `false` closes the overlay.
+    fn advance(&mut self) -> bool {
+        false
+    }
+
+    /// Up (`1`) or Down (`-1`) was pressed; returns whether to redraw.
+    fn adjust(&mut self, _delta: i32) -> bool {
+        false
+    }
+
+    /// Renders at the native resolution divided by this, scaled back up by the
+    /// compositor. Animated patterns use it to keep up with high refresh rates.
+    fn downscale(&self) -> u32 {
+        1
+    }
+}
+
+enum Action {
+    Advance,
+    Adjust(i32),
+    Close,
+}
+
+struct OutputInfo {
+    proxy: wl_output::WlOutput,
+    name: String,
+    mode: (i32, i32),
+    refresh_mhz: i32,
+    rotated: bool,
+}
+
+#[derive(Default)]
+struct OverlayState {
+    compositor: Option<wl_compositor::WlCompositor>,
+    shm: Option<wl_shm::WlShm>,
+    viewporter: Option<wp_viewporter::WpViewporter>,
+    layer_shell: Option<zwlr_layer_shell_v1::ZwlrLayerShellV1>,
+    outputs: Vec<OutputInfo>,
+    pointer: Option<wl_pointer::WlPointer>,
+    keyboard: Option<wl_keyboard::WlKeyboard>,
+    /// Logical size from the last layer-surface configure.
+    size: Option<(u32, u32)>,
+    closed: bool,
+    frame_due: bool,
+    busy: [bool; 2],
+    actions: Vec<Action>,
+}
+
+impl Dispatch<wl_registry::WlRegistry, ()> for OverlayState {
+    fn event(
+        state: &mut Self,
+        registry: &wl_registry::WlRegistry,
+        event: wl_registry::Event,
+        _: &(),
+        _: &Connection,
+        qh: &QueueHandle<Self>,
+    ) {
+        let wl_registry::Event::Global {
+            name,
+            interface,
+            version,
+        } = event
+        else {
+            return;
+        };
+        match interface.as_str() {
+            "wl_compositor" => {
+                state.compositor = Some(registry.bind(name, version.min(4), qh, ()));
+            }
+            "wl_shm" => state.shm = Some(registry.bind(name, 1, qh, ())),
+            "wp_viewporter" => state.viewporter = Some(registry.bind(name, 1, qh, ())),
+            "zwlr_layer_shell_v1" => {
+                state.layer_shell = Some(registry.bind(name, version.min(4), qh, ()));
+            }
+            "wl_seat" => {
+                registry.bind::<wl_seat::WlSeat, _, _>(name, version.min(5), qh, ());
+            }
+            "wl_output" if version >= 4 => {
+                let index = state.outputs.len();
+                state.outputs.push(OutputInfo {
+                    proxy: registry.bind(name, 4, qh, index),
+                    name: String::new(),
+                    mode: (0, 0),
+                    refresh_mhz: 0,
+                    rotated: false,
+                });
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Dispatch<wl_output::WlOutput, usize> for OverlayState {
+    fn event(
+        state: &mut Self,
+        _: &wl_output::WlOutput,
+        event: wl_output::Event,
+        index: &usize,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        let Some(info) = state.outputs.get_mut(*index) else {
+            return;
+        };
+        match event {
+            wl_output::Event::Name { name } => info.name = name,
+            wl_output::Event::Mode {
+                flags: WEnum::Value(flags),
+                width,
+                height,
+                refresh,
+            } if flags.contains(wl_output::Mode::Current) => {
+                info.mode = (width, height);
+                info.refresh_mhz = refresh;
+            }
+            wl_output::Event::Geometry {
+                transform: WEnum::Value(transform),
+                ..
+            } => {
+                use wl_output::Transform;
+                info.rotated = matches!(
+                    transform,
+                    Transform::_90 | Transform::_270 | Transform::Flipped90 | Transform::Flipped270
+                );
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Dispatch<wl_seat::WlSeat, ()> for OverlayState {
+    fn event(
+        state: &mut Self,
+        seat: &wl_seat::WlSeat,
+        event: wl_seat::Event,
+        _: &(),
+        _: &Connection,
+        qh: &QueueHandle<Self>,
+    ) {
+        if let wl_seat::Event::Capabilities {
+            capabilities: WEnum::Value(capabilities),
+        } = event
+        {
+            if capabilities.contains(wl_seat::Capability::Pointer) && state.pointer.is_none() {
+                state.pointer = Some(seat.get_pointer(qh, ()));
+            }
+            if capabilities.contains(wl_seat::Capability::Keyboard) && state.keyboard.is_none() {
+                state.keyboard = Some(seat.get_keyboard(qh, ()));
+            }
+        }
+    }
+}
+
+impl Dispatch<wl_pointer::WlPointer, ()> for OverlayState {
+    fn event(
+        state: &mut Self,
+        pointer: &wl_pointer::WlPointer,
+        event: wl_pointer::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        match event {
+            // A cursor in the middle of a test pattern gets in the way.
+            wl_pointer::Event::Enter { serial, .. } => pointer.set_cursor(serial, None, 0, 0),
+            wl_pointer::Event::Button {
+                button,
+                state: WEnum::Value(wl_pointer::ButtonState::Pressed),
+                ..
+            } => match button {
+                BTN_LEFT => state.actions.push(Action::Advance),
+                BTN_RIGHT => state.actions.push(Action::Close),
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+}
+
+impl Dispatch<wl_keyboard::WlKeyboard, ()> for OverlayState {
+    fn event(
+        state: &mut Self,
+        _: &wl_keyboard::WlKeyboard,
+        event: wl_keyboard::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        if let wl_keyboard::Event::Key {
+            key,
+            state: WEnum::Value(wl_keyboard::KeyState::Pressed),
+            ..
+        } = event
+        {
+            match key {
+                KEY_ESC | KEY_Q => state.actions.push(Action::Close),
+                KEY_SPACE | KEY_ENTER | KEY_RIGHT => state.actions.push(Action::Advance),
+                KEY_UP => state.actions.push(Action::Adjust(1)),
+                KEY_DOWN => state.actions.push(Action::Adjust(-1)),
+                _ => {}
+            }
+        }
+    }
+}
+
+impl Dispatch<zwlr_layer_surface_v1::ZwlrLayerSurfaceV1, ()> for OverlayState {
+    fn event(
+        state: &mut Self,
+        layer_surface: &zwlr_layer_surface_v1::ZwlrLayerSurfaceV1,
+        event: zwlr_layer_surface_v1::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        match event {
+            zwlr_layer_surface_v1::Event::Configure {
+                serial,
+                width,
+                height,
+            } => {
+                layer_surface.ack_configure(serial);
+                state.size = Some((width, height));
+            }
+            zwlr_layer_surface_v1::Event::Closed => state.closed = true,
+            _ => {}
+        }
+    }
+}
+
+impl Dispatch<wl_buffer::WlBuffer, usize> for OverlayState {
+    fn event(
+        state: &mut Self,
+        _: &wl_buffer::WlBuffer,
+        event: wl_buffer::Event,
+        index: &usize,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        if let wl_buffer::Event::Release = event {
+            state.busy[*index] = false;
+        }
+    }
+}
+
+impl Dispatch<wl_callback::WlCallback, ()> for OverlayState {
+    fn event(
+        state: &mut Self,
+        _: &wl_callback::WlCallback,
+        event: wl_callback::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        if let wl_callback::Event::Done { .. } = event {
+            state.frame_due = true;
+        }
+    }
+}
+
+delegate_noop!(OverlayState: ignore wl_compositor::WlCompositor);
+delegate_noop!(OverlayState: ignore wl_surface::WlSurface);
+delegate_noop!(OverlayState: ignore wl_shm::WlShm);
+delegate_noop!(OverlayState: ignore wl_shm_pool::WlShmPool);
+delegate_noop!(OverlayState: ignore wp_viewporter::WpViewporter);
+delegate_noop!(OverlayState: ignore wp_viewport::WpViewport);
+delegate_noop!(OverlayState: ignore zwlr_layer_shell_v1::ZwlrLayerShellV1);
+
+/// A file of `size` bytes to share with the compositor, unlinked as soon as it is open.
+pub(crate) fn shm_file(name: &str, size: u64) -> Result<File, String> {
+    let path = dirs::runtime_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join(format!("mdisplay-{}-{}", name, std::process::id()));
+    let file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(&path)
+        .map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+    let _ = std::fs::remove_file(&path);
+    file.set_len(size)
+        .map_err(|e| format!("Failed to size shared memory: {}", e))?;
+    Ok(file)
+}
+
+/// Covers the output called `output` with `pattern` until the user closes it.
+pub fn show(output: &str, pattern: &mut dyn Pattern) -> Result<(), String> {
+    let conn =
+        Connection::connect_to_env().map_err(|e| format!("Failed to connect to Wayland: {}", e))?;
+    let mut queue = conn.new_event_queue();
+    let qh = queue.handle();
+    conn.display().get_registry(&qh, ());
+
+    let mut state = OverlayState::default();
+    queue.roundtrip(&mut state).map_err(|e| e.to_string())?;
+    queue.roundtrip(&mut state).map_err(|e| e.to_string())?;
+
+    let missing = |what: &str| format!("Compositor does not support {}", what);
+    let compositor = state
+        .compositor
+        .clone()
+        .ok_or_else(|| missing("wl_compositor"))?;
+    let shm = state.shm.clone().ok_or_else(|| missing("wl_shm"))?;
+    let viewporter = state
+        .viewporter
+        .clone()
+        .ok_or_else(|| missing("wp_viewporter"))?;
+    let layer_shell = state
+        .layer_shell
+        .clone()
+        .ok_or_else(|| missing("wlr-layer-shell-unstable-v1"))?;
+    let target = state
+        .outputs
+        .iter()
+        .find(|o| o.name == output)
+        .ok_or_else(|| format!("No output named '{}'", output))?;
+
+    let (width, height) = if target.rotated {
+        (target.mode.1, target.mode.0)
+    } else {
+        target.mode
+    };
+    if width <= 0 || height <= 0 {
+        return Err(format!("Output '{}' has no current mode", output));
+    }
+    let divisor = pattern.downscale().max(1) as i32;
+    let (width, height) = ((width / divisor).max(1), (height / divisor).max(1));
+    let refresh_rate = target.refresh_mhz as f32 / 1000.0;
+
+    let surface = compositor.create_surface(&qh, ());
+    let viewport = viewporter.get_viewport(&surface, &qh, ());
+    let layer_surface = layer_shell.get_layer_surface(
+        &surface,
+        Some(&target.proxy),
+        zwlr_layer_shell_v1::Layer::Overlay,
+        "mdisplay-pattern".to_string(),
+        &qh,
+        (),
+    );
+    {
+        use zwlr_layer_surface_v1::{Anchor, KeyboardInteractivity};
+        layer_surface.set_anchor(Anchor::Top | Anchor::Bottom | Anchor::Left | Anchor::Right);
+        layer_surface.set_exclusive_zone(-1);
+        layer_surface.set_keyboard_interactivity(KeyboardInteractivity::Exclusive);
+    }
+    surface.commit();
+
+    let stride = width * 4;
+    let buffer_size = (stride * height) as usize;
+    let file = shm_file("overlay", 2 * buffer_size as u64)?;
+    let pool = shm.create_pool(file.as_fd(), 2 * buffer_size as i32, &qh, ());
+    let buffers = [0, 1].map(|i| {
+        pool.create_buffer(
+            (i * buffer_size) as i32,
+            width,
+            height,
+            stride,
+            wl_shm::Format::Xrgb8888,
+            &qh,
+            i,
+        )
+    });
+
+    let mut pixels = vec![0u32; (width * height) as usize];
+    let mut bytes = vec![0u8; buffer_size];
+    let start = Instant::now();
+    let mut drawn_size = None;
+    let mut dirty = true;
+
+    let result = loop {
+        if let Err(e) = queue.blocking_dispatch(&mut state) {
+            break Err(e.to_string());
+        }
+        for action in state.actions.drain(..) {
+            match action {
+                Action::Close => state.closed = true,
+                Action::Advance if pattern.advance() => dirty = true,
+                Action::Advance => state.closed = true,
+                Action::Adjust(delta) => dirty |= pattern.adjust(delta),
+            }
+        }
+        if state.closed {
+            break Ok(());
+        }
+        let Some((logical_w, logical_h)) = state.size else {
+            continue;
+        };
+        if drawn_size != state.size {
+            dirty = true;
+        }
+        let redraw = dirty || (pattern.animated() && state.frame_due);
+        if !redraw {
+            continue;
+        }
+        let Some(index) = state.busy.iter().position(|busy| !busy) else {
+            continue;
+        };
+
+        pattern.render(&mut Frame {
+            pixels: &mut pixels,
+            width: width as u32,
+            height: height as u32,
+            elapsed: start.elapsed(),
+            refresh_rate,
+        });
+        for (chunk, pixel) in bytes.chunks_exact_mut(4).zip(&pixels) {
+            chunk.copy_from_slice(&pixel.to_ne_bytes());
+        }
+        if let Err(e) = file.write_all_at(&bytes, (index * buffer_size) as u64) {
+            break Err(format!("Failed to write the frame: {}", e));
+        }
+
+        surface.attach(Some(&buffers[index]), 0, 0);
+        surface.damage_buffer(0, 0, width, height);
+        if logical_w > 0 && logical_h > 0 {
+            viewport.set_destination(logical_w as i32, logical_h as i32);
+        }
+        if pattern.animated() {
+            surface.frame(&qh, ());
+            state.frame_due = false;
+        }
+        state.busy[index] = true;
+        surface.commit();
+        drawn_size = state.size;
+        dirty = false;
+    };
+
+    for buffer in &buffers {
+        buffer.destroy();
+    }
+    pool.destroy();
+    viewport.destroy();
+    layer_surface.destroy();
+    surface.destroy();
+    let _ = queue.roundtrip(&mut state);
+    result
+}
@@ -0,0 +1,109 @@
+//! Where mdisplay keeps its own files, following the XDG base directory specification:
+//! settings and profiles under `$XDG_CONFIG_HOME`, the backup of the original monitor
+//! rules and the log under `$XDG_STATE_HOME`, and caches under `$XDG_CACHE_HOME`.
+//!
+//! Older versions kept the backup next to the mangowc config; [`migrate`] moves it.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::settings::AppSettings;
+
+/// Where the backup of the original monitor rules was kept before it moved to
+/// [`backup_file`].
+pub const LEGACY_BACKUP: &str = "~/.config/mango/monitors.bak";
+
+/// `dir`, or `fallback` under the home directory when the environment names neither,
+/// with mdisplay's own directory appended.
+fn base(dir: Option<PathBuf>, fallback: &str) -> PathBuf {
+    dir.or_else(|| dirs::home_dir().map(|home| home.join(fallback)))
+        .unwrap_or_else(std::env::temp_dir)
+        .join("mdisplay")
+}
+
+pub fn config_dir() -> PathBuf {
+    base(dirs::config_dir(), ".config")
+}
+
+pub fn state_dir() -> PathBuf {
+    base(dirs::state_dir(), ".local/state")
+}
+
+pub fn cache_dir() -> PathBuf {
+    base(dirs::cache_dir(), ".cache")
+}
+
+/// The settings, which hold the profiles too.
+pub fn settings_file() -> PathBuf {
+    config_dir().join("settings.json")
+}
+
+/// The default place for the backup [`crate::backend::save_config`] takes of the
+/// monitor rules it replaces.
+pub fn backup_file() -> PathBuf {
+    state_dir().join("monitors.bak")
+}
+
+pub fn log_file() -> PathBuf {
+    state_dir().join("mdisplay.log")
+}
+
+/// `path` with a leading `~/` expanded.
+pub fn expand(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+/// `path` with the home directory written as `~`, the way paths are stored in the
+/// settings so they survive a change of user name.
+pub fn portable(path: &Path) -> String {
+    if let Some(home) = dirs::home_dir()
+        && let Ok(rest) = path.strip_prefix(&home)
+    {
+        return format!("~/{}", rest.display());
+    }
+    path.display().to_string()
+}
+
+/// Moves files older versions left at legacy paths to where they belong now and points
+/// `settings` at them. A backup path set by hand is left alone. Returns whether
+/// `settings` changed and need saving.
+pub fn migrate(settings: &mut AppSettings) -> Result<bool, String> {
+    let target = backup_file();
+    let default = portable(&target);
+    let configured = settings.monitors_bak_path.as_str();
+    if !configured.is_empty() && configured != LEGACY_BACKUP && configured != default {
+        return Ok(false);
+    }
+    let legacy = expand(LEGACY_BACKUP);
+    if legacy.exists() && !target.exists() {
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        move_file(&legacy, &target)?;
+        tracing::info!("Moved {} to {}", legacy.display(), target.display());
+    }
+    let changed = settings.monitors_bak_path != default;
+    settings.monitors_bak_path = default;
+    Ok(changed)
+}
+
+/// Renames `from` to `to`, copying when they are on different file systems.
+fn move_file(from: &Path, to: &Path) -> Result<(), String> {
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    fs::copy(from, to)
+        .and_then(|_| fs::remove_file(from))
+        .map_err(|e| {
+            format!(
+                "Failed to move {} to {}: {}",
+                from.display(),
+                to.display(),
+                e
+            )
+        })
+}
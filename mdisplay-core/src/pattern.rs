@@ -0,0 +1,370 @@
+//! Test patterns for checking a new mode: color bars, gradient ramps, an alignment grid,
+//! solid colors for finding dead pixels, moving bars for checking the refresh rate and
+//! edge rulers for measuring overscan.
+
+use std::time::Duration;
+
+use crate::overlay::{Frame, Pattern};
+use crate::settings::Overscan;
+
+const WHITE: u32 = 0xffffff;
+const BLACK: u32 = 0x000000;
+const GRID_SPACING: u32 = 64;
+
+/// SMPTE-style bars at 75% intensity.
+const BARS: [u32; 7] = [
+    0xbfbfbf, 0xbfbf00, 0x00bfbf, 0x00bf00, 0xbf00bf, 0xbf0000, 0x0000bf,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TestPattern {
+    #[default]
+    ColorBars,
+    Gradients,
+    Grid,
+}
+
+impl TestPattern {
+    pub const ALL: [TestPattern; 3] = [
+        TestPattern::ColorBars,
+        TestPattern::Gradients,
+        TestPattern::Grid,
+    ];
+}
+
+fn color_bars(frame: &mut Frame) {
+    let (w, h) = (frame.width, frame.height);
+    let top = h * 2 / 3;
+    for (i, color) in BARS.iter().enumerate() {
+        let x = w * i as u32 / 7;
+        let next = w * (i as u32 + 1) / 7;
+        frame.fill_rect(x, 0, next - x, top, *color);
+    }
+    // Full white and black below, with a ramp of eight gray steps between them.
+    for step in 0..8u32 {
+        let x = w * step / 8;
+        let next = w * (step + 1) / 8;
+        let level = (step * 255 / 7) * 0x010101;
+        frame.fill_rect(x, top, next - x, h - top, level);
+    }
+}
+
+/// Horizontal ramps for gray, red, green and blue, each a quarter of the height.
+fn gradients(frame: &mut Frame) {
+    let (w, h) = (frame.width, frame.height);
+    let channels = [0x010101, 0x010000, 0x000100, 0x000001];
+    for (i, channel) in channels.iter().enumerate() {
+        let y = h * i as u32 / 4;
+        let next = h * (i as u32 + 1) / 4;
+        for x in 0..w {
+            let level = x * 255 / w.saturating_sub(1).max(1);
+            frame.fill_rect(x, y, 1, next - y, level * channel);
+        }
+    }
+}
+
+/// White lines every 64 pixels, a one-pixel border to spot overscan, a centre
+/// crosshair, corner diagonals and a checkerboard patch for scaling artefacts.
+fn grid(frame: &mut Frame) {
+    let (w, h) = (frame.width, frame.height);
+    frame.fill(BLACK);
+    for x in (0..w).step_by(GRID_SPACING as usize) {
+        frame.fill_rect(x, 0, 1, h, 0x606060);
+    }
+    for y in (0..h).step_by(GRID_SPACING as usize) {
+        frame.fill_rect(0, y, w, 1, 0x606060);
+    }
+    frame.fill_rect(0, 0, w, 1, WHITE);
+    frame.fill_rect(0, h - 1, w, 1, WHITE);
+    frame.fill_rect(0, 0, 1, h, WHITE);
+    frame.fill_rect(w - 1, 0, 1, h, WHITE);
+    frame.fill_rect(w / 2, 0, 1, h, 0xff0000);
+    frame.fill_rect(0, h / 2, w, 1, 0xff0000);
+
+    let diagonal = w.min(h) / 4;
+    for i in 0..diagonal {
+        frame.set(i, i, 0x00ff00);
+        frame.set(w - 1 - i, i, 0x00ff00);
+        frame.set(i, h - 1 - i, 0x00ff00);
+        frame.set(w - 1 - i, h - 1 - i, 0x00ff00);
+    }
+
+    let size = GRID_SPACING * 2;
+    let (x0, y0) = (
+        (w / 2).saturating_sub(size / 2),
+        (h / 2).saturating_sub(size / 2),
+    );
+    for y in 0..size {
+        for x in 0..size {
+            let color = if (x + y) % 2 == 0 { WHITE } else { BLACK };
+            frame.set(x0 + x, y0 + y, color);
+        }
+    }
+}
+
+impl Pattern for TestPattern {
+    fn render(&mut self, frame: &mut Frame) {
+        match self {
+            TestPattern::ColorBars => color_bars(frame),
+            TestPattern::Gradients => gradients(frame),
+            TestPattern::Grid => grid(frame),
+        }
+    }
+
+    fn advance(&mut self) -> bool {
+        let next = Self::ALL.iter().position(|p| p == self).unwrap_or(0) + 1;
+        match Self::ALL.get(next) {
+            Some(pattern) => {
+                *self = *pattern;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Full-screen red, green, blue, white and black in turn, for spotting dead or stuck
+/// pixels.
+#[derive(Debug, Clone, Default)]
+pub struct PixelTest {
+    step: usize,
+}
+
+impl PixelTest {
+    pub const COLORS: [u32; 5] = [0xff0000, 0x00ff00, 0x0000ff, WHITE, BLACK];
+}
+
+impl Pattern for PixelTest {
+    fn render(&mut self, frame: &mut Frame) {
+        frame.fill(Self::COLORS[self.step]);
+    }
+
+    fn advance(&mut self) -> bool {
+        self.step += 1;
+        self.step < Self::COLORS.len()
+    }
+}
+
+/// 3×5 bitmaps for the digits 0–9, one row per three bits.
+const DIGITS: [[u8; 5]; 10] = [
+    [0b111, 0b101, 0b101, 0b101, 0b111],
+    [0b010, 0b110, 0b010, 0b010, 0b111],
+    [0b111, 0b001, 0b111, 0b100, 0b111],
+    [0b111, 0b001, 0b111, 0b001, 0b111],
+    [0b101, 0b101, 0b111, 0b001, 0b001],
+    [0b111, 0b100, 0b111, 0b001, 0b111],
+    [0b111, 0b100, 0b111, 0b101, 0b111],
+    [0b111, 0b001, 0b010, 0b010, 0b010],
+    [0b111, 0b101, 0b111, 0b101, 0b111],
+    [0b111, 0b101, 0b111, 0b001, 0b111],
+];
+
+/// Draws `value` with its top-left corner at `(x, y)`, each font pixel `size` pixels wide.
+pub(crate) fn draw_number(frame: &mut Frame, x: u32, y: u32, size: u32, value: u32, color: u32) {
+    for (i, digit) in value.to_string().bytes().enumerate() {
+        let glyph = DIGITS[(digit - b'0') as usize];
+        let left = x + i as u32 * 4 * size;
+        for (row, bits) in glyph.iter().enumerate() {
+            for col in 0..3 {
+                if bits & (0b100 >> col) != 0 {
+                    frame.fill_rect(left + col * size, y + row as u32 * size, size, size, color);
+                }
+            }
+        }
+    }
+}
+
+/// Bars crossing the screen at three speeds, a strip that lights one cell per frame and
+/// the measured frame rate next to the mode's, to check a high refresh rate is really in
+/// use. The rate turns red when frames arrive more than 3% slower than the mode promises.
+#[derive(Debug, Clone, Default)]
+pub struct MotionTest {
+    frames: u64,
+    window_start: Duration,
+    window_frames: u32,
+    measured: Option<u32>,
+}
+
+impl MotionTest {
+    /// Lane speeds in screen widths per second.
+    const SPEEDS: [f32; 3] = [0.25, 0.5, 1.0];
+}
+
+impl Pattern for MotionTest {
+    fn render(&mut self, frame: &mut Frame) {
+        let (w, h) = (frame.width, frame.height);
+        frame.fill(0x202020);
+
+        let lane_height = h / 6;
+        let bar_width = (w / 32).max(2);
+        for (i, speed) in Self::SPEEDS.iter().enumerate() {
+            let top = lane_height / 2 + i as u32 * lane_height * 3 / 2;
+            frame.fill_rect(0, top, w, lane_height, 0x404040);
+            let travel = (w + bar_width) as f32;
+            let x = (frame.elapsed.as_secs_f32() * speed * w as f32) % travel;
+            let x = x as u32;
+            let left = x.saturating_sub(bar_width);
+            frame.fill_rect(left, top, x - left, lane_height, WHITE);
+        }
+
+        // One cell per expected frame each second: a camera with a slow shutter shows a
+        // continuous run of lit cells, with gaps where frames were skipped.
+        let cells = frame.refresh_rate.round().max(1.0) as u32;
+        let cell = (w / cells).max(1);
+        let strip_top = h - lane_height / 2;
+        for i in 0..cells {
+            frame.fill_rect(
+                i * cell + 1,
+                strip_top,
+                cell.saturating_sub(2),
+                cell.min(lane_height / 3),
+                0x404040,
+            );
+        }
+        let lit = (self.frames % cells as u64) as u32;
+        frame.fill_rect(
+            lit * cell + 1,
+            strip_top,
+            cell.saturating_sub(2),
+            cell.min(lane_height / 3),
+            0x00ff00,
+        );
+
+        self.frames += 1;
+        self.window_frames += 1;
+        let window = frame.elapsed.saturating_sub(self.window_start);
+        if window >= Duration::from_secs(1) {
+            self.measured = Some((self.window_frames as f32 / window.as_secs_f32()).round() as u32);
+            self.window_start = frame.elapsed;
+            self.window_frames = 0;
+        }
+
+        // The mode's refresh rate, then the measured one, between the lanes and the strip.
+        let size = (h / 60).max(1);
+        let numbers_top = h * 3 / 4 + size * 3;
+        let expected = frame.refresh_rate.round() as u32;
+        draw_number(frame, size * 2, numbers_top, size, expected, WHITE);
+        if let Some(measured) = self.measured {
+            let color = if (measured as f32) < frame.refresh_rate * 0.97 {
+                0xff4040
+            } else {
+                0x40ff40
+            };
+            draw_number(frame, size * 20, numbers_top, size, measured, color);
+        }
+    }
+
+    fn animated(&self) -> bool {
+        true
+    }
+
+    /// Pushing native-resolution frames at 240 Hz is more than a software renderer can
+    /// keep up with, and a dropped frame would defeat the test.
+    fn downscale(&self) -> u32 {
+        2
+    }
+}
+
+/// Rulers along every edge, marked every 8 pixels and numbered every 32, with the
+/// overscan margins drawn as a green frame. Advancing picks all edges or one of them,
+/// and Up and Down move it a pixel inwards or outwards, so the frame can be lined up
+/// with the visible edge of a TV that crops the picture.
+#[derive(Debug, Clone, Default)]
+pub struct OverscanFrame {
+    pub margins: Overscan,
+    /// 0 for all edges, then top, right, bottom and left.
+    edge: usize,
+}
+
+impl OverscanFrame {
+    const TICK: u32 = 8;
+    const LABEL_EVERY: u32 = 32;
+    const MAX_MARGIN: u32 = 200;
+
+    pub fn new(margins: Overscan) -> Self {
+        Self { margins, edge: 0 }
+    }
+
+    fn edges(&mut self) -> [&mut u32; 4] {
+        let Overscan {
+            top,
+            right,
+            bottom,
+            left,
+        } = &mut self.margins;
+        [top, right, bottom, left]
+    }
+}
+
+impl Pattern for OverscanFrame {
+    fn render(&mut self, frame: &mut Frame) {
+        let (w, h) = (frame.width, frame.height);
+        frame.fill(BLACK);
+        let size = (h / 270).max(1);
+        let reach = Self::MAX_MARGIN.min(w / 4).min(h / 4);
+        for d in (0..reach).step_by(Self::TICK as usize) {
+            let long = d % Self::LABEL_EVERY == 0;
+            let length = if long { size * 12 } else { size * 6 };
+            let color = if long { WHITE } else { 0x808080 };
+            // Ticks on the top and bottom edges run down the screen, and the other way.
+            frame.fill_rect(w / 2 - length / 2, d, length, 1, color);
+            frame.fill_rect(w / 2 - length / 2, h - 1 - d, length, 1, color);
+            frame.fill_rect(d, h / 2 - length / 2, 1, length, color);
+            frame.fill_rect(w - 1 - d, h / 2 - length / 2, 1, length, color);
+            if long && d > 0 {
+                draw_number(frame, w / 2 + length, d, size, d, WHITE);
+                draw_number(frame, w / 2 + length, h - d - size * 5, size, d, WHITE);
+                draw_number(frame, d + size, h / 2 + length, size, d, WHITE);
+                draw_number(frame, w - d - size * 12, h / 2 + length, size, d, WHITE);
+            }
+        }
+
+        let Overscan {
+            top,
+            right,
+            bottom,
+            left,
+        } = self.margins;
+        let inner_w = w.saturating_sub(left + right);
+        let inner_h = h.saturating_sub(top + bottom);
+        let color = |edge: usize| {
+            if self.edge == 0 || self.edge == edge {
+                0x00ff00
+            } else {
+                0x008000
+            }
+        };
+        let thickness = size.max(2);
+        frame.fill_rect(left, top, inner_w, thickness, color(1));
+        frame.fill_rect(
+            (w - right).saturating_sub(thickness),
+            top,
+            thickness,
+            inner_h,
+            color(2),
+        );
+        frame.fill_rect(
+            left,
+            (h - bottom).saturating_sub(thickness),
+            inner_w,
+            thickness,
+            color(3),
+        );
+        frame.fill_rect(left, top, thickness, inner_h, color(4));
+    }
+
+    fn advance(&mut self) -> bool {
+        self.edge = (self.edge + 1) % 5;
+        true
+    }
+
+    fn adjust(&mut self, delta: i32) -> bool {
+        let edge = self.edge;
+        for (i, margin) in self.edges().into_iter().enumerate() {
+            if edge == 0 || edge == i + 1 {
+                *margin = margin.saturating_add_signed(delta).min(Self::MAX_MARGIN);
+            }
+        }
+        true
+    }
+}
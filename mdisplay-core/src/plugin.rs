@@ -0,0 +1,173 @@
+//! Third-party backends as executables.
+//!
+//! A plugin named `foo` is an executable called `mdisplay-backend-foo`, found in
+//! `$XDG_DATA_HOME/mdisplay/backends/` or on `PATH`. It is run with one argument:
+//!
+//! - `fetch`: print the outputs to stdout as a JSON array in the serde form of
+//!   [`Output`] and exit 0.
+//! - `apply`: read the same JSON array from stdin, configure the compositor and exit 0.
+//!
+//! Outputs a plugin can switch between full and limited RGB range should carry an
+//! `rgb_range` of `"full"` or `"limited"` when fetched, and those it can send as RGB or
+//! YCbCr 4:2:0 a `pixel_format` of `"rgb"` or `"ycbcr420"`; MDisplay then offers the
+//! choice and sends it back on apply.
+//!
+//! Any other exit status is an error, reported with the plugin's stderr. Plugins receive
+//! `MDISPLAY_PLUGIN_API` so the protocol can evolve without breaking existing plugins.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output as ProcessOutput, Stdio};
+
+use crate::backend::{Backend, Output, shell_quote};
+use crate::process;
+
+/// Version of the protocol described above, passed as `MDISPLAY_PLUGIN_API`.
+pub const PLUGIN_API: u32 = 1;
+
+const PREFIX: &str = "mdisplay-backend-";
+
+/// [`Backend`] that delegates to a plugin executable.
+#[derive(Debug, Clone)]
+pub struct ExecBackend {
+    pub name: String,
+    pub path: PathBuf,
+}
+
+impl ExecBackend {
+    fn run(&self, action: &str, input: Option<&str>) -> Result<String, String> {
+        let mut child = Command::new(&self.path)
+            .arg(action)
+            .env("MDISPLAY_PLUGIN_API", PLUGIN_API.to_string())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("Failed to run backend '{}': {}", self.name, e))?;
+        // Written from another thread, so a plugin that prints before it has read all of
+        // its input cannot fill both pipes and stall.
+        let writer = child.stdin.take().map(|mut stdin| {
+            let input = input.unwrap_or_default().to_string();
+            std::thread::spawn(move || stdin.write_all(input.as_bytes()))
+        });
+        let ProcessOutput {
+            status,
+            stdout,
+            stderr,
+        } = process::wait_with_output(child)
+            .map_err(|e| format!("Backend '{}' did not finish: {}", self.name, e))?;
+        let written = writer.map_or(Ok(()), |writer| writer.join().unwrap_or(Ok(())));
+        tracing::debug!("Backend '{}' {} exited with {}", self.name, action, status);
+        if !status.success() {
+            return Err(format!(
+                "Backend '{}' {} failed: {}",
+                self.name,
+                action,
+                String::from_utf8_lossy(&stderr).trim()
+            ));
+        }
+        written.map_err(|e| format!("Failed to write to backend '{}': {}", self.name, e))?;
+        Ok(String::from_utf8_lossy(&stdout).into_owned())
+    }
+}
+
+impl Backend for ExecBackend {
+    fn fetch_outputs(&self) -> Result<Vec<Output>, String> {
+        let json = self.run("fetch", None)?;
+        serde_json::from_str(&json)
+            .map_err(|e| format!("Backend '{}' printed invalid outputs: {}", self.name, e))
+    }
+
+    fn apply_outputs(&self, outputs: &[Output]) -> Result<(), String> {
+        let json = serde_json::to_string(outputs)
+            .map_err(|e| format!("Failed to serialize outputs: {}", e))?;
+        self.run("apply", Some(&json)).map(|_| ())
+    }
+
+    fn preview(&self, outputs: &[Output]) -> String {
+        let json = serde_json::to_string_pretty(outputs).unwrap_or_default();
+        format!(
+            "{} apply <<'EOF'\n{}\nEOF",
+            shell_quote(&self.path.to_string_lossy()),
+            json
+        )
+    }
+}
+
+fn search_dirs() -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = dirs::data_dir()
+        .map(|d| d.join("mdisplay").join("backends"))
+        .into_iter()
+        .collect();
+    if let Some(path) = std::env::var_os("PATH") {
+        dirs.extend(std::env::split_paths(&path));
+    }
+    dirs
+}
+
+pub(crate) fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata()
+        .is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+}
+
+/// Every plugin on the search path, first match per name. None while the settings are
+/// another user's, since choosing one is up to them.
+pub fn discover() -> Vec<ExecBackend> {
+    let mut found: Vec<ExecBackend> = Vec::new();
+    if !crate::settings::commands_trusted() {
+        return found;
+    }
+    for dir in search_dirs() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let file_name = entry.file_name().to_string_lossy().into_owned();
+            if let Some(name) = file_name.strip_prefix(PREFIX)
+                && is_executable(&entry.path())
+                && !found.iter().any(|p| p.name == name)
+            {
+                found.push(ExecBackend {
+                    name: name.to_string(),
+                    path: entry.path(),
+                });
+            }
+        }
+    }
+    found.sort_by(|a, b| a.name.cmp(&b.name));
+    found
+}
+
+pub fn find(name: &str) -> Option<ExecBackend> {
+    discover().into_iter().find(|p| p.name == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+
+    #[test]
+    fn a_plugin_may_print_before_reading_its_input() {
+        let dir = std::env::temp_dir().join(format!("mdisplay-plugin-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(format!("{}chatty", PREFIX));
+        // Prints more than a pipe holds, then echoes how much input it got.
+        std::fs::write(
+            &path,
+            "#!/bin/sh\nhead -c 1000000 /dev/zero | tr '\\0' x\necho\nwc -c\n",
+        )
+        .unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let plugin = ExecBackend {
+            name: "chatty".to_string(),
+            path,
+        };
+        let input = "y".repeat(1_000_000);
+        let output = plugin.run("apply", Some(&input)).unwrap();
+        assert_eq!(output.lines().last().map(str::trim), Some("1000000"));
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
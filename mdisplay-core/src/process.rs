@@ -0,0 +1,78 @@
+//! Helper programs run with a time limit.
+//!
+//! A wedged `wlr-randr`, `ddcutil`, hook or plugin would otherwise keep the apply, and every
+//! window or daemon waiting on it, hanging forever. These stand in for
+//! [`Command::output`], [`Command::status`] and [`Child::wait_with_output`], and kill the
+//! program once [`TIMEOUT`] has passed.
+
+use std::io::{self, Read};
+use std::process::{Child, Command, ExitStatus, Output, Stdio};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// How long a helper program may run before it is killed.
+pub const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How often a running program is checked on.
+const POLL: Duration = Duration::from_millis(20);
+
+/// Like [`Command::output`]: stdin empty, stdout and stderr captured.
+pub fn output(command: &mut Command) -> io::Result<Output> {
+    let child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    wait_with_output(child)
+}
+
+/// Like [`Command::status`]: everything inherited.
+pub fn status(command: &mut Command) -> io::Result<ExitStatus> {
+    wait_with_output(command.spawn()?).map(|output| output.status)
+}
+
+/// Like [`Child::wait_with_output`]. A program still running after [`TIMEOUT`] is
+/// killed and reported as [`io::ErrorKind::TimedOut`].
+pub fn wait_with_output(mut child: Child) -> io::Result<Output> {
+    // Read the pipes while waiting, or a chatty program blocks on a full pipe.
+    let stdout = drain(child.stdout.take());
+    let stderr = drain(child.stderr.take());
+    let deadline = Instant::now() + TIMEOUT;
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                format!(
+                    "no answer after {} seconds, so it was stopped",
+                    TIMEOUT.as_secs()
+                ),
+            ));
+        }
+        thread::sleep(POLL);
+    };
+    let collect = |reader: Option<JoinHandle<Vec<u8>>>| {
+        reader
+            .and_then(|reader| reader.join().ok())
+            .unwrap_or_default()
+    };
+    Ok(Output {
+        status,
+        stdout: collect(stdout),
+        stderr: collect(stderr),
+    })
+}
+
+fn drain<R: Read + Send + 'static>(pipe: Option<R>) -> Option<JoinHandle<Vec<u8>>> {
+    pipe.map(|mut pipe| {
+        thread::spawn(move || {
+            let mut bytes = Vec::new();
+            let _ = pipe.read_to_end(&mut bytes);
+            bytes
+        })
+    })
+}
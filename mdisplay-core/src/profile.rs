@@ -0,0 +1,578 @@
+use std::collections::BTreeMap;
+use std::process::Command;
+
+use serde::{Deserialize, Serialize};
+
+use crate::backend::{Backend, Output, OutputMode, PixelFormat, RgbRange, Subpixel};
+use crate::settings::SaveFormat;
+
+/// The part of an output's state that a profile restores.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OutputConfig {
+    pub name: String,
+    pub enabled: bool,
+    pub width: i32,
+    pub height: i32,
+    pub refresh_rate: f32,
+    pub position: (i32, i32),
+    pub scale: f32,
+    pub transform: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub adaptive_sync: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rgb_range: Option<RgbRange>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pixel_format: Option<PixelFormat>,
+}
+
+impl OutputConfig {
+    pub fn from_output(out: &Output) -> Self {
+        let mode = out.modes.iter().find(|m| m.current);
+        Self {
+            name: out.name.clone(),
+            enabled: out.enabled,
+            width: mode.map_or(0, |m| m.width),
+            height: mode.map_or(0, |m| m.height),
+            refresh_rate: mode.map_or(0.0, |m| m.refresh_rate),
+            position: out.position,
+            scale: out.scale,
+            transform: out.transform.clone(),
+            adaptive_sync: out.adaptive_sync,
+            rgb_range: out.rgb_range,
+            pixel_format: out.pixel_format,
+        }
+    }
+
+    /// Whether `out` is currently configured this way. Refresh rates within half a hertz
+    /// count as equal, since compositors round them differently.
+    pub fn matches(&self, out: &Output) -> bool {
+        let current = Self::from_output(out);
+        if !self.enabled || !current.enabled {
+            return self.enabled == current.enabled;
+        }
+        self.width == current.width
+            && self.height == current.height
+            && (self.refresh_rate - current.refresh_rate).abs() < 0.5
+            && self.position == current.position
+            && (self.scale - current.scale).abs() < 0.001
+            && self.transform == current.transform
+            && (self.adaptive_sync.is_none()
+                || current.adaptive_sync.is_none()
+                || self.adaptive_sync == current.adaptive_sync)
+    }
+
+    /// Writes this configuration onto `out`, picking the mode with the closest refresh
+    /// rate at the stored resolution, or a custom mode when none is within half a hertz.
+    pub fn apply_to(&self, out: &mut Output) {
+        out.enabled = self.enabled;
+        out.position = self.position;
+        out.scale = self.scale;
+        out.transform = self.transform.clone();
+        // Only compositors that report VRR, a range or a format can set them.
+        if out.adaptive_sync.is_some() && self.adaptive_sync.is_some() {
+            out.adaptive_sync = self.adaptive_sync;
+        }
+        if out.rgb_range.is_some() && self.rgb_range.is_some() {
+            out.rgb_range = self.rgb_range;
+        }
+        if out.pixel_format.is_some() && self.pixel_format.is_some() {
+            out.pixel_format = self.pixel_format;
+        }
+        let target = out
+            .modes
+            .iter()
+            .enumerate()
+            .filter(|(_, m)| m.width == self.width && m.height == self.height)
+            .min_by(|(_, a), (_, b)| {
+                let da = (a.refresh_rate - self.refresh_rate).abs();
+                let db = (b.refresh_rate - self.refresh_rate).abs();
+                da.total_cmp(&db)
+            })
+            .map(|(i, _)| i);
+        if let Some(target) = target {
+            for (i, m) in out.modes.iter_mut().enumerate() {
+                m.current = i == target;
+            }
+            if (out.modes[target].refresh_rate - self.refresh_rate).abs() >= 0.5
+                && let Err(e) = out.set_refresh_rate(self.refresh_rate)
+            {
+                tracing::warn!("{}", e);
+            }
+        }
+    }
+}
+
+/// A named layout that can be restored in one step.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Profile {
+    pub name: String,
+    pub outputs: Vec<OutputConfig>,
+    /// Shell command that sets the wallpaper for this layout, e.g. an `swww img` or
+    /// `swaybg` invocation, run after the profile is applied.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wallpaper: Option<String>,
+    /// When the daemon applies the profile by itself; `None` leaves it manual.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rule: Option<ProfileRule>,
+    /// Lines added after the generated layout when the profile is saved in a format,
+    /// e.g. a Hyprland `workspace` rule or a sway `output` option mdisplay has no field
+    /// for, so one profile serves every compositor.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub sections: BTreeMap<SaveFormat, String>,
+    /// The profile's outputs as they were when it was stored, with every mode they
+    /// offered, so the profile can be edited while they are unplugged.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub monitors: Vec<Output>,
+}
+
+/// Conditions under which a profile applies automatically. Unset conditions always hold,
+/// so a rule with none of them is a fallback for when nothing more specific matches.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProfileRule {
+    /// Among matching rules the highest priority wins, then the earliest profile.
+    #[serde(default)]
+    pub priority: i32,
+    #[serde(default)]
+    pub power: PowerCondition,
+    #[serde(default)]
+    pub lid: LidCondition,
+    /// Outputs that must be connected, by connector name or [`Output::identity`].
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub outputs: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PowerCondition {
+    #[default]
+    Any,
+    Ac,
+    Battery,
+}
+
+impl PowerCondition {
+    pub const ALL: [PowerCondition; 3] = [
+        PowerCondition::Any,
+        PowerCondition::Ac,
+        PowerCondition::Battery,
+    ];
+}
+
+impl std::fmt::Display for PowerCondition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            PowerCondition::Any => "Any power",
+            PowerCondition::Ac => "On AC",
+            PowerCondition::Battery => "On battery",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LidCondition {
+    #[default]
+    Any,
+    Open,
+    Closed,
+}
+
+impl LidCondition {
+    pub const ALL: [LidCondition; 3] =
+        [LidCondition::Any, LidCondition::Open, LidCondition::Closed];
+}
+
+impl std::fmt::Display for LidCondition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            LidCondition::Any => "Any lid",
+            LidCondition::Open => "Lid open",
+            LidCondition::Closed => "Lid closed",
+        })
+    }
+}
+
+/// The machine state rules are checked against. `None` is unknown, e.g. without UPower
+/// or on a desktop without a lid, and only satisfies the `Any` conditions.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MachineState {
+    pub on_battery: Option<bool>,
+    pub lid_closed: Option<bool>,
+}
+
+impl ProfileRule {
+    pub fn matches(&self, state: MachineState, outputs: &[Output]) -> bool {
+        let power = match self.power {
+            PowerCondition::Any => true,
+            PowerCondition::Ac => state.on_battery == Some(false),
+            PowerCondition::Battery => state.on_battery == Some(true),
+        };
+        let lid = match self.lid {
+            LidCondition::Any => true,
+            LidCondition::Open => state.lid_closed == Some(false),
+            LidCondition::Closed => state.lid_closed == Some(true),
+        };
+        power
+            && lid
+            && self.outputs.iter().all(|wanted| {
+                outputs
+                    .iter()
+                    .any(|o| &o.name == wanted || &o.identity() == wanted)
+            })
+    }
+}
+
+/// The profile whose rule matches with the highest priority, if any.
+pub fn pick<'a>(
+    profiles: &'a [Profile],
+    state: MachineState,
+    outputs: &[Output],
+) -> Option<&'a Profile> {
+    let mut best: Option<(&Profile, i32)> = None;
+    for profile in profiles {
+        let Some(rule) = &profile.rule else {
+            continue;
+        };
+        if rule.matches(state, outputs) && best.is_none_or(|(_, priority)| rule.priority > priority)
+        {
+            best = Some((profile, rule.priority));
+        }
+    }
+    best.map(|(profile, _)| profile)
+}
+
+/// What errors and the history call a layout applied without a profile.
+pub const CUSTOM_LAYOUT: &str = "the custom layout";
+
+impl Profile {
+    pub fn from_outputs(name: &str, outputs: &[Output]) -> Self {
+        Self {
+            name: name.to_string(),
+            outputs: outputs.iter().map(OutputConfig::from_output).collect(),
+            wallpaper: None,
+            rule: None,
+            sections: BTreeMap::new(),
+            monitors: outputs.to_vec(),
+        }
+    }
+
+    /// How messages refer to the profile: by name, or as [`CUSTOM_LAYOUT`] for the
+    /// unnamed ones that carry an ad-hoc layout.
+    pub fn label(&self) -> String {
+        if self.name.is_empty() {
+            CUSTOM_LAYOUT.to_string()
+        } else {
+            format!("profile '{}'", self.name)
+        }
+    }
+
+    /// The profile's outputs configured as stored, for editing it without them. Each is
+    /// the connected output of that name when there is one, else the one remembered in
+    /// [`Self::monitors`], else a stand-in offering only the stored mode.
+    pub fn offline_outputs(&self, connected: &[Output]) -> Vec<Output> {
+        self.outputs
+            .iter()
+            .map(|config| {
+                let mut out = connected
+                    .iter()
+                    .chain(&self.monitors)
+                    .find(|o| o.name == config.name)
+                    .cloned()
+                    .unwrap_or_else(|| stand_in(config));
+                config.apply_to(&mut out);
+                out
+            })
+            .collect()
+    }
+
+    /// Starts the wallpaper command without waiting for it, since tools like `swaybg`
+    /// keep running for as long as the wallpaper is shown.
+    pub fn set_wallpaper(&self) -> Result<(), String> {
+        let Some(command) = &self.wallpaper else {
+            return Ok(());
+        };
+        if !crate::settings::commands_trusted() {
+            tracing::info!("Not running another user's wallpaper command: {}", command);
+            return Ok(());
+        }
+        tracing::info!(
+            "Setting the wallpaper of profile '{}': {}",
+            self.name,
+            command
+        );
+        let mut child = Command::new("sh")
+            .arg("-c")
+            .arg(command)
+            .env("MDISPLAY_PROFILE", &self.name)
+            .spawn()
+            .map_err(|e| format!("Failed to run '{}': {}", command, e))?;
+        // Reap it whenever it exits so long-running front ends collect no zombies.
+        std::thread::spawn(move || child.wait());
+        Ok(())
+    }
+
+    /// The lines to add when the profile is saved in `format`.
+    pub fn section(&self, format: SaveFormat) -> Option<&str> {
+        self.sections.get(&format).map(String::as_str)
+    }
+
+    /// Whether every output in the profile is connected and configured as stored.
+    pub fn is_active(&self, outputs: &[Output]) -> bool {
+        self.outputs.iter().all(|config| {
+            outputs
+                .iter()
+                .find(|o| o.name == config.name)
+                .is_some_and(|o| config.matches(o))
+        })
+    }
+
+    /// Applies the profile to the matching connected outputs and returns how many matched.
+    /// Outputs the profile does not mention are left untouched.
+    pub fn apply_to(&self, outputs: &mut [Output]) -> usize {
+        let mut matched = 0;
+        for out in outputs.iter_mut() {
+            if let Some(config) = self.outputs.iter().find(|c| c.name == out.name) {
+                config.apply_to(out);
+                matched += 1;
+            }
+        }
+        matched
+    }
+}
+
+/// An output with nothing known about it but what `config` stores, as profiles saved
+/// before [`Profile::monitors`] existed have.
+fn stand_in(config: &OutputConfig) -> Output {
+    Output {
+        name: config.name.clone(),
+        description: String::new(),
+        make: String::new(),
+        model: String::new(),
+        serial: String::new(),
+        physical_size: String::new(),
+        position: config.position,
+        scale: config.scale,
+        transform: config.transform.clone(),
+        modes: vec![OutputMode {
+            width: config.width,
+            height: config.height,
+            refresh_rate: config.refresh_rate,
+            current: true,
+            preferred: true,
+            custom: false,
+        }],
+        enabled: config.enabled,
+        adaptive_sync: config.adaptive_sync,
+        rgb_range: config.rgb_range,
+        pixel_format: config.pixel_format,
+        subpixel: Subpixel::default(),
+    }
+}
+
+/// Reads the current layout, applies the profile to it and hands it to the compositor.
+pub fn activate(profile: &Profile) -> Result<(), String> {
+    activate_with(crate::backend::active().as_ref(), profile)
+}
+
+/// [`activate`] through an arbitrary backend.
+pub fn activate_with(backend: &dyn Backend, profile: &Profile) -> Result<(), String> {
+    let mut outputs = backend.fetch_outputs()?;
+    if profile.apply_to(&mut outputs) == 0 {
+        return Err(format!(
+            "None of the outputs in {} are connected",
+            profile.label()
+        ));
+    }
+    let name = (!profile.name.is_empty()).then_some(profile.name.as_str());
+    crate::hooks::apply_with(backend, &outputs, name)?;
+    if let Err(e) = profile.set_wallpaper() {
+        tracing::warn!("{}", e);
+    }
+    Ok(())
+}
+
+/// Version of the [`ExportedProfile`] format, checked on import.
+pub const EXPORT_VERSION: u32 = 1;
+
+/// A profile as a self-contained file for another machine or a bug report. Connector
+/// names differ between machines, so each output also carries its monitor's identity.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportedProfile {
+    pub mdisplay_profile: u32,
+    pub profile: Profile,
+    /// Connector names in the profile mapped to [`Output::identity`] when exported.
+    #[serde(default)]
+    pub identities: BTreeMap<String, String>,
+}
+
+/// `profile` as pretty-printed JSON, with the identities of the outputs in `outputs`.
+pub fn export(profile: &Profile, outputs: &[Output]) -> Result<String, String> {
+    let identities = outputs
+        .iter()
+        .filter(|o| profile.outputs.iter().any(|c| c.name == o.name))
+        .map(|o| (o.name.clone(), o.identity()))
+        .collect();
+    let exported = ExportedProfile {
+        mdisplay_profile: EXPORT_VERSION,
+        profile: profile.clone(),
+        identities,
+    };
+    serde_json::to_string_pretty(&exported).map_err(|e| format!("Failed to export: {}", e))
+}
+
+/// Reads an exported profile, moving each output to the connector that the same monitor
+/// is on among `outputs`. Outputs whose monitor is not connected keep their name.
+pub fn import(json: &str, outputs: &[Output]) -> Result<Profile, String> {
+    let exported: ExportedProfile =
+        serde_json::from_str(json).map_err(|e| format!("Not an exported profile: {}", e))?;
+    if exported.mdisplay_profile > EXPORT_VERSION {
+        return Err(format!(
+            "The profile was exported by a newer mdisplay (format {})",
+            exported.mdisplay_profile
+        ));
+    }
+    let mut profile = exported.profile;
+    let renames: BTreeMap<String, String> = exported
+        .identities
+        .iter()
+        .filter_map(|(name, identity)| {
+            let local = outputs.iter().find(|o| &o.identity() == identity)?;
+            Some((name.clone(), local.name.clone()))
+        })
+        .collect();
+    for config in &mut profile.outputs {
+        if let Some(local) = renames.get(&config.name) {
+            config.name = local.clone();
+        }
+    }
+    for monitor in &mut profile.monitors {
+        if let Some(local) = renames.get(&monitor.name) {
+            monitor.name = local.clone();
+        }
+    }
+    if let Some(rule) = &mut profile.rule {
+        for name in &mut rule.outputs {
+            if let Some(local) = renames.get(name) {
+                *name = local.clone();
+            }
+        }
+    }
+    Ok(profile)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockBackend;
+
+    fn fixture() -> Vec<Output> {
+        MockBackend::from_json(include_str!("../fixtures/dual_monitor.json"))
+            .unwrap()
+            .fetch_outputs()
+            .unwrap()
+    }
+
+    #[test]
+    fn a_saved_profile_is_active_until_the_layout_changes() {
+        let mut outputs = fixture();
+        let profile = Profile::from_outputs("desk", &outputs);
+        assert!(profile.is_active(&outputs));
+
+        outputs[1].position = (0, 1440);
+        assert!(!profile.is_active(&outputs));
+
+        assert_eq!(profile.apply_to(&mut outputs), 3);
+        assert!(profile.is_active(&outputs));
+    }
+
+    #[test]
+    fn an_unnamed_layout_is_called_the_custom_layout() {
+        let backend =
+            MockBackend::from_json(include_str!("../fixtures/dual_monitor.json")).unwrap();
+        let mut layout = Profile::from_outputs("", &fixture());
+        for config in &mut layout.outputs {
+            config.name.push_str("-gone");
+        }
+        assert_eq!(
+            activate_with(&backend, &layout),
+            Err("None of the outputs in the custom layout are connected".to_string())
+        );
+        layout.name = "desk".to_string();
+        assert_eq!(layout.label(), "profile 'desk'");
+    }
+
+    #[test]
+    fn applying_picks_the_closest_refresh_rate() {
+        let mut outputs = fixture();
+        let mut profile = Profile::from_outputs("desk", &outputs);
+        profile.outputs[0].refresh_rate = 60.0;
+        profile.apply_to(&mut outputs);
+        let current = outputs[0].modes.iter().find(|m| m.current).unwrap();
+        assert_eq!((current.width, current.refresh_rate), (2560, 59.951));
+    }
+
+    #[test]
+    fn rules_pick_the_highest_priority_match() {
+        let outputs = fixture();
+        let rule = |priority, power, outputs: &[&str]| ProfileRule {
+            priority,
+            power,
+            lid: LidCondition::Any,
+            outputs: outputs.iter().map(|s| s.to_string()).collect(),
+        };
+        let mut profiles = vec![
+            Profile::from_outputs("fallback", &outputs),
+            Profile::from_outputs("mobile", &outputs),
+            Profile::from_outputs("desk", &outputs),
+            Profile::from_outputs("manual", &outputs),
+        ];
+        profiles[0].rule = Some(rule(0, PowerCondition::Any, &[]));
+        profiles[1].rule = Some(rule(5, PowerCondition::Battery, &[]));
+        profiles[2].rule = Some(rule(10, PowerCondition::Ac, &[&outputs[1].name]));
+        let picked = |state| pick(&profiles, state, &outputs).map(|p| p.name.as_str());
+
+        let on_battery = |on_battery| MachineState {
+            on_battery: Some(on_battery),
+            lid_closed: None,
+        };
+        assert_eq!(picked(on_battery(false)), Some("desk"));
+        assert_eq!(picked(on_battery(true)), Some("mobile"));
+        assert_eq!(picked(MachineState::default()), Some("fallback"));
+        assert_eq!(
+            pick(&profiles, on_battery(false), &[]).map(|p| p.name.as_str()),
+            Some("fallback")
+        );
+    }
+
+    #[test]
+    fn imported_profiles_follow_their_monitors_to_other_connectors() {
+        let outputs = fixture();
+        let json = export(&Profile::from_outputs("desk", &outputs), &outputs).unwrap();
+
+        // The same monitors on another machine, plugged in the other way round.
+        let mut elsewhere = outputs.clone();
+        elsewhere.swap(0, 1);
+        let names = [outputs[0].name.clone(), outputs[1].name.clone()];
+        elsewhere[0].name = names[0].clone();
+        elsewhere[1].name = names[1].clone();
+
+        let profile = import(&json, &elsewhere).unwrap();
+        assert_eq!(profile.outputs[0].name, names[1]);
+        assert_eq!(profile.outputs[1].name, names[0]);
+        assert!(import("{}", &outputs).is_err());
+    }
+
+    #[test]
+    fn the_mock_backend_records_applies_and_blank_layouts_are_refused() {
+        let backend =
+            MockBackend::from_json(include_str!("../fixtures/dual_monitor.json")).unwrap();
+        let mut outputs = backend.fetch_outputs().unwrap();
+        outputs[1].enabled = false;
+        backend.apply_outputs(&outputs).unwrap();
+        assert_eq!(backend.applied(), vec![outputs.clone()]);
+
+        outputs[0].enabled = false;
+        assert!(crate::hooks::apply_with(&backend, &outputs, None).is_err());
+        assert_eq!(backend.applied().len(), 1);
+    }
+}
@@ -0,0 +1,180 @@
+//! A human-readable summary of a layout for documentation and support threads: a
+//! Markdown table of the outputs and an SVG diagram of where they sit.
+
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use crate::backend::Output;
+use crate::limits;
+
+/// The longer side of the diagram, in SVG pixels.
+const DIAGRAM_SIZE: f32 = 800.0;
+const MARGIN: f32 = 10.0;
+
+fn mode_text(out: &Output) -> String {
+    match out.modes.iter().find(|m| m.current) {
+        Some(mode) => format!(
+            "{}x{} @ {:.2} Hz",
+            mode.width, mode.height, mode.refresh_rate
+        ),
+        None => "-".to_string(),
+    }
+}
+
+fn monitor_text(out: &Output) -> String {
+    let monitor = [&out.make, &out.model]
+        .iter()
+        .filter(|s| !s.is_empty())
+        .map(|s| s.as_str())
+        .collect::<Vec<_>>()
+        .join(" ");
+    if monitor.is_empty() {
+        out.description.clone()
+    } else {
+        monitor
+    }
+}
+
+/// `outputs` as a Markdown document with one table row per output. `diagram` is the
+/// path of the SVG from [`svg`], relative to the document, when there is one.
+pub fn markdown(outputs: &[Output], diagram: Option<&str>) -> String {
+    let mut doc = String::from("# Display layout\n\n");
+    if let Some(diagram) = diagram {
+        let _ = writeln!(doc, "![Layout]({})\n", diagram);
+    }
+    doc.push_str("| Output | Monitor | Mode | Scale | Position | Rotation | Adaptive sync |\n");
+    doc.push_str("|---|---|---|---|---|---|---|\n");
+    for out in outputs {
+        let cell = |text: String| text.replace('|', "\\|");
+        if !out.enabled {
+            let _ = writeln!(
+                doc,
+                "| {} | {} | off | | | | |",
+                cell(out.name.clone()),
+                cell(monitor_text(out))
+            );
+            continue;
+        }
+        let adaptive_sync = match out.adaptive_sync {
+            Some(true) => "on",
+            Some(false) => "off",
+            None => "-",
+        };
+        let _ = writeln!(
+            doc,
+            "| {} | {} | {} | {} | {},{} | {} | {} |",
+            cell(out.name.clone()),
+            cell(monitor_text(out)),
+            mode_text(out),
+            out.scale,
+            out.position.0,
+            out.position.1,
+            out.transform,
+            adaptive_sync
+        );
+    }
+    let (w, h) = limits::extent(outputs);
+    let _ = writeln!(doc, "\nThe desktop spans {}x{} logical pixels.", w, h);
+    doc
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// The enabled outputs of `outputs` as boxes in an SVG image, each labelled with its
+/// connector and mode.
+pub fn svg(outputs: &[Output]) -> String {
+    let boxes: Vec<(&Output, i32, i32, i32, i32)> = outputs
+        .iter()
+        .filter(|o| o.enabled)
+        .filter_map(|o| {
+            let (w, h) = limits::logical_size(o)?;
+            Some((o, o.position.0, o.position.1, w, h))
+        })
+        .collect();
+    let left = boxes.iter().map(|b| b.1).min().unwrap_or(0);
+    let top = boxes.iter().map(|b| b.2).min().unwrap_or(0);
+    let (w, h) = limits::extent(outputs);
+    let zoom = DIAGRAM_SIZE / w.max(h).max(1) as f32;
+    let width = w as f32 * zoom + 2.0 * MARGIN;
+    let height = h as f32 * zoom + 2.0 * MARGIN;
+
+    let mut image = String::new();
+    let _ = writeln!(
+        image,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{:.0}" height="{:.0}" viewBox="0 0 {:.0} {:.0}" font-family="sans-serif">"#,
+        width, height, width, height
+    );
+    for (out, x, y, w, h) in boxes {
+        let (x, y) = (
+            MARGIN + (x - left) as f32 * zoom,
+            MARGIN + (y - top) as f32 * zoom,
+        );
+        let (w, h) = (w as f32 * zoom, h as f32 * zoom);
+        let (cx, cy) = (x + w / 2.0, y + h / 2.0);
+        let _ = writeln!(
+            image,
+            r##"  <rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" fill="#dde6f0" stroke="#34495e" stroke-width="2"/>"##,
+            x, y, w, h
+        );
+        let _ = writeln!(
+            image,
+            r#"  <text x="{:.1}" y="{:.1}" text-anchor="middle" font-size="16" font-weight="bold">{}</text>"#,
+            cx,
+            cy - 4.0,
+            escape(&out.name)
+        );
+        let _ = writeln!(
+            image,
+            r#"  <text x="{:.1}" y="{:.1}" text-anchor="middle" font-size="12">{}</text>"#,
+            cx,
+            cy + 14.0,
+            escape(&mode_text(out))
+        );
+    }
+    image.push_str("</svg>\n");
+    image
+}
+
+/// Writes the report for `outputs` to `path` and its diagram next to it, with the
+/// extension changed to `.svg`. Returns the diagram's path.
+pub fn write(path: &Path, outputs: &[Output]) -> Result<PathBuf, String> {
+    let diagram = path.with_extension("svg");
+    let link = diagram
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned());
+    std::fs::write(&diagram, svg(outputs))
+        .map_err(|e| format!("Failed to write {}: {}", diagram.display(), e))?;
+    std::fs::write(path, markdown(outputs, link.as_deref()))
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    Ok(diagram)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::Backend;
+    use crate::mock::MockBackend;
+
+    #[test]
+    fn lists_every_output_and_draws_the_enabled_ones() {
+        let outputs = MockBackend::from_json(include_str!("../fixtures/dual_monitor.json"))
+            .unwrap()
+            .fetch_outputs()
+            .unwrap();
+        let doc = markdown(&outputs, Some("layout.svg"));
+        assert!(doc.contains("![Layout](layout.svg)"));
+        assert!(doc.contains(
+            "| DP-1 | Dell Inc. DELL S2721DGF | 2560x1440 @ 144.00 Hz | 1 | 0,0 | normal | - |"
+        ));
+        assert!(doc.contains("| eDP-1 | BOE 0x095F | off | | | | |"));
+        assert!(doc.contains("spans 4480x1440"));
+        let image = svg(&outputs);
+        assert_eq!(image.matches("<rect").count(), 2);
+        assert!(image.contains(">HDMI-A-1<"));
+    }
+}
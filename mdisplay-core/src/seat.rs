@@ -0,0 +1,128 @@
+//! logind seats and the outputs on each. On a multi-seat machine every seat has its own
+//! GPUs, keyboards and user, so mdisplay only reads and changes the outputs of one seat
+//! and one user's layout never moves another user's monitors.
+
+use std::fs;
+use std::process::Command;
+use std::sync::Arc;
+
+use crate::backend::{Backend, Output, ScaleSteps};
+use crate::settings::AppSettings;
+
+/// The seat every device is on unless a udev rule assigns it to another.
+pub const DEFAULT_SEAT: &str = "seat0";
+
+/// The seats logind knows, sorted. Empty without logind.
+pub fn list() -> Vec<String> {
+    let Ok(entries) = fs::read_dir("/run/systemd/seats") else {
+        return Vec::new();
+    };
+    let mut seats: Vec<String> = entries
+        .flatten()
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .filter(|name| !name.starts_with('.'))
+        .collect();
+    seats.sort();
+    seats
+}
+
+/// The seat of the session mdisplay runs in: `$XDG_SEAT`, or else what logind reports
+/// for the session. [`DEFAULT_SEAT`] for sessions without one, such as over SSH.
+pub fn current() -> String {
+    if let Ok(seat) = std::env::var("XDG_SEAT")
+        && !seat.is_empty()
+    {
+        return seat;
+    }
+    let session = std::env::var("XDG_SESSION_ID").unwrap_or_else(|_| "self".to_string());
+    crate::process::output(
+        Command::new("loginctl")
+            .args(["show-session", &session, "--property=Seat", "--value"])
+            .env("LC_ALL", "C"),
+    )
+    .ok()
+    .filter(|output| output.status.success())
+    .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+    .filter(|seat| !seat.is_empty())
+    .unwrap_or_else(|| DEFAULT_SEAT.to_string())
+}
+
+/// The seat the DRM device `card`, e.g. `card1`, is assigned to, as udev recorded it.
+pub fn of_card(card: &str) -> String {
+    fs::read_to_string(format!("/sys/class/drm/{}/dev", card))
+        .ok()
+        .and_then(|dev| fs::read_to_string(format!("/run/udev/data/c{}", dev.trim())).ok())
+        .and_then(|data| {
+            data.lines()
+                .find_map(|line| line.strip_prefix("E:ID_SEAT="))
+                .map(str::to_string)
+        })
+        .unwrap_or_else(|| DEFAULT_SEAT.to_string())
+}
+
+/// The seat the output called `name` is on. `None` for outputs without a DRM connector,
+/// such as virtual ones, which belong to the session that made them.
+pub fn of_output(name: &str) -> Option<String> {
+    crate::connector::card(name).map(|card| of_card(&card))
+}
+
+/// A backend that only sees the outputs of one seat, and leaves the others alone when
+/// applying.
+pub struct SeatBackend {
+    inner: Arc<dyn Backend>,
+    seat: String,
+}
+
+impl SeatBackend {
+    pub fn new(inner: Arc<dyn Backend>, seat: String) -> Self {
+        Self { inner, seat }
+    }
+
+    fn owns(&self, out: &Output) -> bool {
+        of_output(&out.name).is_none_or(|seat| seat == self.seat)
+    }
+
+    /// The outputs in `outputs` on this seat.
+    fn own(&self, outputs: &[Output]) -> Vec<Output> {
+        outputs
+            .iter()
+            .filter(|out| self.owns(out))
+            .cloned()
+            .collect()
+    }
+}
+
+impl Backend for SeatBackend {
+    fn fetch_outputs(&self) -> Result<Vec<Output>, String> {
+        let mut outputs = self.inner.fetch_outputs()?;
+        outputs.retain(|out| self.owns(out));
+        Ok(outputs)
+    }
+
+    fn apply_outputs(&self, outputs: &[Output]) -> Result<(), String> {
+        self.inner.apply_outputs(&self.own(outputs))
+    }
+
+    fn scale_steps(&self) -> ScaleSteps {
+        self.inner.scale_steps()
+    }
+
+    fn framebuffer_limit(&self) -> Option<(i32, i32)> {
+        self.inner.framebuffer_limit()
+    }
+
+    fn preview(&self, outputs: &[Output]) -> String {
+        self.inner.preview(&self.own(outputs))
+    }
+}
+
+/// `backend` kept to the seat chosen in `settings`, or to this session's seat when
+/// there is more than one. Single-seat machines get `backend` back as it is.
+pub fn confine(backend: Arc<dyn Backend>, settings: &AppSettings) -> Arc<dyn Backend> {
+    let seat = match &settings.seat {
+        Some(seat) => seat.clone(),
+        None if list().len() > 1 => current(),
+        None => return backend,
+    };
+    Arc::new(SeatBackend::new(backend, seat))
+}
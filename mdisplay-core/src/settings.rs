@@ -0,0 +1,554 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::backend::{Output, Subpixel};
+use crate::compositor::{BitDepth, ScaleFilter};
+use crate::cvt::Blanking;
+use crate::hooks::Hooks;
+use crate::paths;
+use crate::profile::Profile;
+
+/// Set once the settings belong to another user; see [`distrust_commands`].
+static DISTRUSTED: AtomicBool = AtomicBool::new(false);
+
+/// Stops this process from running the commands the settings name: apply hooks, profile
+/// wallpaper commands, custom tool paths and backend plugins. For acting on another
+/// user's session, whose settings only that user should get to run.
+pub fn distrust_commands() {
+    DISTRUSTED.store(true, Ordering::Relaxed);
+}
+
+/// Whether the commands in the settings may run; see [`distrust_commands`].
+pub fn commands_trusted() -> bool {
+    !DISTRUSTED.load(Ordering::Relaxed)
+}
+
+/// The contents of `~/.config/mdisplay/settings.json`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AppSettings {
+    pub monitors_conf_path: String,
+    pub config_conf_path: String,
+    #[serde(default)]
+    pub auto_append_source: bool,
+    #[serde(default)]
+    pub monitors_bak_path: String,
+    #[serde(default)]
+    pub outputs: HashMap<String, OutputPrefs>,
+    #[serde(default)]
+    pub hide_low_res_modes: bool,
+    #[serde(default)]
+    pub ui: UiState,
+    #[serde(default)]
+    pub profiles: Vec<Profile>,
+    #[serde(default)]
+    pub hooks: Hooks,
+    /// Minimum level logged: `error`, `warn`, `info`, `debug` or `trace`.
+    #[serde(default = "default_log_level")]
+    pub log_level: String,
+    /// Also write the log to `$XDG_STATE_HOME/mdisplay/mdisplay.log`.
+    #[serde(default)]
+    pub log_to_file: bool,
+    /// Send desktop notifications for failed applies, hotplug and profile changes.
+    #[serde(default = "default_true")]
+    pub notifications: bool,
+    /// UI language code; `None` follows the locale environment.
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Backend to use: `wayland`, a tool from [`crate::tools::Tool`] or a plugin (see
+    /// [`crate::plugin`]). Unset tries Wayland and then the tools in turn.
+    #[serde(default)]
+    pub backend: Option<String>,
+    /// Tool names mapped to the executable to run instead of the one on `PATH`.
+    #[serde(default)]
+    pub tool_paths: HashMap<String, String>,
+    #[serde(default)]
+    pub save_format: SaveFormat,
+    #[serde(default)]
+    pub snapping: Snapping,
+    /// Name of a built-in iced theme; `None` uses MDisplay's own dark theme.
+    #[serde(default)]
+    pub theme: Option<String>,
+    /// Size of the whole interface, text and canvas included, relative to the default.
+    #[serde(default = "default_ui_scale")]
+    pub ui_scale: f32,
+    /// Touchscreen and tablet names mapped to the connector they should follow.
+    #[serde(default)]
+    pub input_mappings: HashMap<String, String>,
+    #[serde(default)]
+    pub night_schedule: NightSchedule,
+    /// Move every output's brightness together, keeping their
+    /// [`OutputPrefs::brightness_balance`].
+    #[serde(default)]
+    pub brightness_linked: bool,
+    #[serde(default)]
+    pub auto_brightness: AutoBrightness,
+    /// Dock IDs (see [`crate::dock::Dock::id`]) mapped to the profile applied when that
+    /// dock is plugged in.
+    #[serde(default)]
+    pub dock_profiles: HashMap<String, String>,
+    /// Turn the built-in panel with the device, following the accelerometer.
+    #[serde(default)]
+    pub auto_rotate: bool,
+    /// Whether the Gaming quick action turns off every output but the one played on.
+    #[serde(default)]
+    pub gaming_disables_others: bool,
+    /// Profile the daemon applies while a game has Feral GameMode on, putting the layout
+    /// from before back once the last game exits.
+    #[serde(default)]
+    pub gamemode_profile: Option<String>,
+    /// Brightness in percent for outputs set to [`BatteryAction::Dim`].
+    #[serde(default = "default_battery_brightness")]
+    pub battery_brightness: u8,
+    /// Set by an administrator on kiosk and lab machines: the window shows the layout but
+    /// cannot apply or save it. The window offers no way to turn it off.
+    #[serde(default)]
+    pub read_only: bool,
+    /// logind seat whose outputs are read and changed; `None` is the seat of the session
+    /// mdisplay runs in. See [`crate::seat`].
+    #[serde(default)]
+    pub seat: Option<String>,
+}
+
+/// What **Save** writes to `monitors_conf_path`.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum SaveFormat {
+    /// mangowc `monitorrule` lines, sourced from the main config.
+    #[default]
+    Mangowc,
+    /// An executable `wlr-randr` script for other wlroots compositors.
+    WlrRandr,
+    /// `monitor=` lines to source from `hyprland.conf`.
+    Hyprland,
+    /// `output` commands to include from the sway config.
+    Sway,
+}
+
+impl SaveFormat {
+    pub const ALL: [SaveFormat; 4] = [
+        SaveFormat::Mangowc,
+        SaveFormat::WlrRandr,
+        SaveFormat::Hyprland,
+        SaveFormat::Sway,
+    ];
+
+    /// The name suggested for a file in this format.
+    pub fn file_name(self) -> &'static str {
+        match self {
+            SaveFormat::Mangowc => "monitors.conf",
+            SaveFormat::WlrRandr => "monitors.sh",
+            SaveFormat::Hyprland => "hyprland-monitors.conf",
+            SaveFormat::Sway => "sway-outputs",
+        }
+    }
+
+    /// The format a file is in going by its name: `.sh` for a script, `hypr` or `sway`
+    /// in the name for those compositors, and `.conf` for mangowc.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_str()?.to_lowercase();
+        if name.ends_with(".sh") {
+            Some(SaveFormat::WlrRandr)
+        } else if name.contains("hypr") {
+            Some(SaveFormat::Hyprland)
+        } else if name.contains("sway") {
+            Some(SaveFormat::Sway)
+        } else if name.ends_with(".conf") {
+            Some(SaveFormat::Mangowc)
+        } else {
+            None
+        }
+    }
+}
+
+impl std::fmt::Display for SaveFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            SaveFormat::Mangowc => "mangowc monitorrule",
+            SaveFormat::WlrRandr => "wlr-randr script",
+            SaveFormat::Hyprland => "Hyprland monitor",
+            SaveFormat::Sway => "sway output",
+        })
+    }
+}
+
+/// How dragged outputs settle in the layout editor.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Snapping {
+    /// Snap to the edges of other outputs and to the grid.
+    pub enabled: bool,
+    /// Distance in logical pixels within which an edge pulls the dragged output.
+    pub threshold: i32,
+    /// Grid step in logical pixels for positions away from other outputs.
+    pub grid: i32,
+}
+
+impl Default for Snapping {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            threshold: 40,
+            grid: 10,
+        }
+    }
+}
+
+/// Whether the night light follows the sun. When it does, outputs with a colour
+/// temperature reach it after sunset and return to neutral after sunrise; otherwise the
+/// temperature holds all day.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NightSchedule {
+    pub enabled: bool,
+    /// Degrees north.
+    pub latitude: f64,
+    /// Degrees east.
+    pub longitude: f64,
+    /// Minutes over which the temperature changes, centred on sunrise and sunset.
+    pub transition_minutes: u32,
+}
+
+impl Default for NightSchedule {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            latitude: 0.0,
+            longitude: 0.0,
+            transition_minutes: 60,
+        }
+    }
+}
+
+/// Brightness that follows the ambient light sensor.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AutoBrightness {
+    pub enabled: bool,
+    /// Adjust external monitors over DDC/CI too, not only built-in panels.
+    pub external: bool,
+    /// Brightness at each light level, by increasing lux; levels in between are
+    /// interpolated.
+    pub curve: Vec<CurvePoint>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CurvePoint {
+    pub lux: f64,
+    pub percent: u8,
+}
+
+impl Default for AutoBrightness {
+    fn default() -> Self {
+        let point = |lux, percent| CurvePoint { lux, percent };
+        Self {
+            enabled: false,
+            external: false,
+            curve: vec![
+                point(0.0, 10),
+                point(50.0, 35),
+                point(300.0, 65),
+                point(1000.0, 100),
+            ],
+        }
+    }
+}
+
+impl AutoBrightness {
+    /// The brightness the curve gives for `lux`.
+    pub fn percent_for(&self, lux: f64) -> u8 {
+        let Some(first) = self.curve.first() else {
+            return 100;
+        };
+        if lux <= first.lux {
+            return first.percent;
+        }
+        for pair in self.curve.windows(2) {
+            let (low, high) = (pair[0], pair[1]);
+            if lux <= high.lux {
+                let t = (lux - low.lux) / (high.lux - low.lux).max(f64::EPSILON);
+                let percent = low.percent as f64 + t * (high.percent as f64 - low.percent as f64);
+                return percent.round().clamp(0.0, 100.0) as u8;
+            }
+        }
+        self.curve.last().map_or(100, |p| p.percent)
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_battery_brightness() -> u8 {
+    30
+}
+
+fn default_ui_scale() -> f32 {
+    1.0
+}
+
+fn default_log_level() -> String {
+    "warn".to_string()
+}
+
+/// Window and view state restored on the next launch.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UiState {
+    pub window_width: f32,
+    pub window_height: f32,
+    pub sidebar_width: f32,
+    pub selected_output: Option<String>,
+    pub canvas_zoom: f32,
+    pub physical_view: bool,
+    /// Whether edits are applied as they are made; see the Live checkbox.
+    pub live_preview: bool,
+    /// Whether a faint grid is drawn behind the layout at the ruler marks.
+    pub show_grid: bool,
+    /// Output identities in the order their tabs were dragged into. Outputs missing
+    /// from it follow in the order the compositor lists them.
+    pub tab_order: Vec<String>,
+}
+
+impl Default for UiState {
+    fn default() -> Self {
+        Self {
+            window_width: 1000.0,
+            window_height: 700.0,
+            sidebar_width: 400.0,
+            selected_output: None,
+            canvas_zoom: 1.0,
+            physical_view: false,
+            live_preview: false,
+            show_grid: false,
+            tab_order: Vec::new(),
+        }
+    }
+}
+
+/// Per-monitor preferences that the compositor does not know about, keyed by
+/// [`crate::backend::Output::identity`] so they follow the panel across connectors.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OutputPrefs {
+    #[serde(default)]
+    pub bezels: Bezels,
+    /// Night-light colour temperature in kelvin; `None` leaves gamma neutral.
+    #[serde(default)]
+    pub color_temperature: Option<u32>,
+    /// Result of the calibration wizard, if it has been run on this panel.
+    #[serde(default)]
+    pub calibration: Option<Calibration>,
+    /// This output's brightness relative to the brightest one when brightness was
+    /// linked, from 0 to 1.
+    #[serde(default)]
+    pub brightness_balance: Option<f32>,
+    #[serde(default)]
+    pub scale_filter: ScaleFilter,
+    #[serde(default)]
+    pub bit_depth: BitDepth,
+    #[serde(default)]
+    pub overscan: Overscan,
+    /// Subpixel order sent instead of the reported one; [`Subpixel::Unknown`] keeps it.
+    #[serde(default)]
+    pub subpixel: Subpixel,
+    /// Timings generated for this output's custom modes.
+    #[serde(default)]
+    pub blanking: Blanking,
+    /// What the daemon does with this output while the machine runs on battery.
+    #[serde(default)]
+    pub on_battery: BatteryAction,
+    /// What the interface calls this output instead of its connector name, such as
+    /// "Left Dell", which changes with the port or dock it is plugged into.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nickname: Option<String>,
+}
+
+/// What happens to an external output when the machine switches to battery. It is put
+/// back when AC power returns.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BatteryAction {
+    #[default]
+    Keep,
+    /// Lower the brightness to [`AppSettings::battery_brightness`].
+    Dim,
+    Disable,
+}
+
+impl BatteryAction {
+    pub const ALL: [BatteryAction; 3] = [
+        BatteryAction::Keep,
+        BatteryAction::Dim,
+        BatteryAction::Disable,
+    ];
+}
+
+impl std::fmt::Display for BatteryAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            BatteryAction::Keep => "Keep",
+            BatteryAction::Dim => "Dim",
+            BatteryAction::Disable => "Disable",
+        })
+    }
+}
+
+/// Monitor settings found with the calibration wizard. Brightness and contrast are
+/// DDC/CI values that the monitor keeps itself; `None` means DDC was unavailable.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Calibration {
+    pub brightness: Option<u16>,
+    pub contrast: Option<u16>,
+    /// Gamma correction on top of the panel's own response; 1 leaves it untouched.
+    pub gamma: f32,
+}
+
+/// Bezel widths in millimetres for each edge of a panel.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Bezels {
+    pub left: f32,
+    pub right: f32,
+    pub top: f32,
+    pub bottom: f32,
+}
+
+/// Pixels a TV crops from each edge, at the output's native resolution.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Overscan {
+    pub top: u32,
+    pub right: u32,
+    pub bottom: u32,
+    pub left: u32,
+}
+
+impl std::fmt::Display for Overscan {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} {} {} px",
+            self.top, self.right, self.bottom, self.left
+        )
+    }
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        Self {
+            monitors_conf_path: "~/.config/mango/monitors.conf".to_string(),
+            config_conf_path: "~/.config/mango/config.conf".to_string(),
+            auto_append_source: true,
+            monitors_bak_path: paths::portable(&paths::backup_file()),
+            outputs: HashMap::new(),
+            hide_low_res_modes: false,
+            ui: UiState::default(),
+            profiles: Vec::new(),
+            notifications: true,
+            hooks: Hooks::default(),
+            log_level: default_log_level(),
+            log_to_file: false,
+            language: None,
+            backend: None,
+            save_format: SaveFormat::default(),
+            snapping: Snapping::default(),
+            theme: None,
+            ui_scale: default_ui_scale(),
+            input_mappings: HashMap::new(),
+            night_schedule: NightSchedule::default(),
+            brightness_linked: false,
+            auto_brightness: AutoBrightness::default(),
+            dock_profiles: HashMap::new(),
+            tool_paths: HashMap::new(),
+            auto_rotate: false,
+            gaming_disables_others: false,
+            gamemode_profile: None,
+            battery_brightness: default_battery_brightness(),
+            read_only: false,
+            seat: None,
+        }
+    }
+}
+
+impl AppSettings {
+    /// Where the settings are stored.
+    pub fn path() -> PathBuf {
+        paths::settings_file()
+    }
+
+    pub fn load() -> Self {
+        let path = Self::path();
+        if path.exists()
+            && let Ok(contents) = fs::read_to_string(&path)
+        {
+            match serde_json::from_str(&contents) {
+                Ok(settings) => return settings,
+                Err(e) => tracing::warn!(
+                    "Ignoring unreadable {}: {}; using defaults",
+                    path.display(),
+                    e
+                ),
+            }
+        }
+        Self::default()
+    }
+
+    pub fn profile(&self, name: &str) -> Option<&Profile> {
+        self.profiles.iter().find(|p| p.name == name)
+    }
+
+    /// Adds the profile, replacing any existing profile with the same name. The existing
+    /// profile's wallpaper, compositor sections and remembered monitors are kept unless
+    /// the new one brings its own.
+    pub fn store_profile(&mut self, mut profile: Profile) {
+        match self.profiles.iter_mut().find(|p| p.name == profile.name) {
+            Some(existing) => {
+                profile.wallpaper = profile.wallpaper.or(existing.wallpaper.take());
+                if profile.sections.is_empty() {
+                    profile.sections = std::mem::take(&mut existing.sections);
+                }
+                if profile.monitors.is_empty() {
+                    profile.monitors = std::mem::take(&mut existing.monitors);
+                }
+                *existing = profile;
+            }
+            None => self.profiles.push(profile),
+        }
+    }
+
+    pub fn output_prefs(&self, identity: &str) -> OutputPrefs {
+        self.outputs.get(identity).cloned().unwrap_or_default()
+    }
+
+    /// What to call `out` in the interface: its nickname, or its connector name without one.
+    pub fn display_name(&self, out: &Output) -> String {
+        self.outputs
+            .get(&out.identity())
+            .and_then(|prefs| prefs.nickname.as_deref())
+            .map(str::trim)
+            .filter(|nickname| !nickname.is_empty())
+            .unwrap_or(&out.name)
+            .to_string()
+    }
+
+    /// [`Self::monitors_conf_path`] with a leading `~/` expanded.
+    pub fn monitors_path(&self) -> PathBuf {
+        paths::expand(&self.monitors_conf_path)
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let path = Self::path();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create settings dir: {}", e))?;
+        }
+
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+
+        fs::write(&path, json).map_err(|e| format!("Failed to write settings.json: {}", e))?;
+        Ok(())
+    }
+}
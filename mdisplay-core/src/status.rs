@@ -0,0 +1,56 @@
+use serde::Serialize;
+
+use crate::backend::Output;
+
+/// Stable JSON shape shared by `get --json` and the D-Bus service; the field names are
+/// part of the public interface.
+#[derive(Serialize)]
+pub struct OutputStatus<'a> {
+    name: &'a str,
+    description: &'a str,
+    enabled: bool,
+    mode: Option<ModeStatus>,
+    position: Position,
+    scale: f32,
+    transform: &'a str,
+}
+
+#[derive(Serialize)]
+pub struct ModeStatus {
+    width: i32,
+    height: i32,
+    refresh_rate: f32,
+}
+
+#[derive(Serialize)]
+pub struct Position {
+    x: i32,
+    y: i32,
+}
+
+impl<'a> From<&'a Output> for OutputStatus<'a> {
+    fn from(out: &'a Output) -> Self {
+        Self {
+            name: &out.name,
+            description: &out.description,
+            enabled: out.enabled,
+            mode: out.modes.iter().find(|m| m.current).map(|m| ModeStatus {
+                width: m.width,
+                height: m.height,
+                refresh_rate: m.refresh_rate,
+            }),
+            position: Position {
+                x: out.position.0,
+                y: out.position.1,
+            },
+            scale: out.scale,
+            transform: &out.transform,
+        }
+    }
+}
+
+/// Encodes all outputs as a JSON array of [`OutputStatus`].
+pub fn outputs_json(outputs: &[Output]) -> Result<String, String> {
+    let statuses: Vec<OutputStatus> = outputs.iter().map(OutputStatus::from).collect();
+    serde_json::to_string(&statuses).map_err(|e| format!("Failed to serialize outputs: {}", e))
+}
@@ -0,0 +1,115 @@
+//! Sunrise and sunset, for running the night light on a schedule.
+//!
+//! Uses the sunrise equation with the usual refraction correction, which is within a
+//! minute or two of almanac times away from the poles. Times are Unix timestamps, so
+//! no time zone is needed.
+
+const SECONDS_PER_DAY: i64 = 86_400;
+const UNIX_EPOCH_JD: f64 = 2_440_587.5;
+const J2000: f64 = 2_451_545.0;
+
+/// The sun on one day at one place.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SunDay {
+    Normal { sunrise: i64, sunset: i64 },
+    PolarDay,
+    PolarNight,
+}
+
+fn sun_day(unix: i64, latitude: f64, longitude: f64) -> SunDay {
+    let julian_day = unix as f64 / SECONDS_PER_DAY as f64 + UNIX_EPOCH_JD;
+    let day = (julian_day - J2000 + 0.0008).floor();
+    let mean_solar_time = day - longitude / 360.0;
+    let anomaly = (357.5291 + 0.985_600_28 * mean_solar_time).rem_euclid(360.0);
+    let m = anomaly.to_radians();
+    let center = 1.9148 * m.sin() + 0.02 * (2.0 * m).sin() + 0.0003 * (3.0 * m).sin();
+    let ecliptic = (anomaly + center + 180.0 + 102.9372)
+        .rem_euclid(360.0)
+        .to_radians();
+    let transit = J2000 + mean_solar_time + 0.0053 * m.sin() - 0.0069 * (2.0 * ecliptic).sin();
+    let declination = (ecliptic.sin() * 23.4397f64.to_radians().sin()).asin();
+    let latitude = latitude.to_radians();
+    let cos_hour_angle = ((-0.833f64).to_radians().sin() - latitude.sin() * declination.sin())
+        / (latitude.cos() * declination.cos());
+    if cos_hour_angle > 1.0 {
+        return SunDay::PolarNight;
+    }
+    if cos_hour_angle < -1.0 {
+        return SunDay::PolarDay;
+    }
+    let half_day = cos_hour_angle.acos().to_degrees() / 360.0;
+    let to_unix = |jd: f64| ((jd - UNIX_EPOCH_JD) * SECONDS_PER_DAY as f64).round() as i64;
+    SunDay::Normal {
+        sunrise: to_unix(transit - half_day),
+        sunset: to_unix(transit + half_day),
+    }
+}
+
+/// How far into the night it is at `unix`: 0 by day, 1 by night, and in between for
+/// `transition` seconds centred on sunrise and sunset.
+pub fn night_factor(unix: i64, latitude: f64, longitude: f64, transition: i64) -> f32 {
+    // Neighbouring days too, since the local evening can fall on the next UTC day.
+    let mut events = Vec::new();
+    for offset in [-1, 0, 1] {
+        match sun_day(unix + offset * SECONDS_PER_DAY, latitude, longitude) {
+            SunDay::Normal { sunrise, sunset } => {
+                events.push((sunrise, false));
+                events.push((sunset, true));
+            }
+            SunDay::PolarDay if offset == 0 => return 0.0,
+            SunDay::PolarNight if offset == 0 => return 1.0,
+            _ => {}
+        }
+    }
+    events.sort_by_key(|(time, _)| *time);
+
+    let half = transition.max(1) as f32 / 2.0;
+    if let Some((time, sunset)) = events
+        .iter()
+        .min_by_key(|(time, _)| (unix - time).abs())
+        .filter(|(time, _)| ((unix - time).abs() as f32) < half)
+    {
+        let progress = (unix - time) as f32 / (2.0 * half);
+        return if *sunset {
+            0.5 + progress
+        } else {
+            0.5 - progress
+        };
+    }
+    let night = events
+        .iter()
+        .rev()
+        .find(|(time, _)| *time <= unix)
+        .is_some_and(|(_, sunset)| *sunset);
+    if night { 1.0 } else { 0.0 }
+}
+
+/// Sunrise and sunset on the UTC day containing `unix`, if the sun rises at all.
+pub fn sun_times(unix: i64, latitude: f64, longitude: f64) -> Option<(i64, i64)> {
+    match sun_day(unix, latitude, longitude) {
+        SunDay::Normal { sunrise, sunset } => Some((sunrise, sunset)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 2024-06-21 12:00 UTC.
+    const SOLSTICE_NOON: i64 = 1_718_971_200;
+    const LISBON: (f64, f64) = (38.72, -9.14);
+
+    #[test]
+    fn lisbon_solstice_matches_almanac() {
+        let (sunrise, sunset) = sun_times(SOLSTICE_NOON, LISBON.0, LISBON.1).unwrap();
+        // 05:12 and 20:05 UTC.
+        assert!((sunrise - (SOLSTICE_NOON - 6 * 3600 - 48 * 60)).abs() < 120);
+        assert!((sunset - (SOLSTICE_NOON + 8 * 3600 + 5 * 60)).abs() < 120);
+
+        let factor = |unix| night_factor(unix, LISBON.0, LISBON.1, 3600);
+        assert_eq!(factor(SOLSTICE_NOON), 0.0);
+        assert_eq!(factor(SOLSTICE_NOON + 12 * 3600), 1.0);
+        assert!((factor(sunset) - 0.5).abs() < 0.01);
+    }
+}
@@ -0,0 +1,727 @@
+//! Backends built on the compositors' own command-line tools, for sessions where
+//! wlr-output-management is unavailable.
+//!
+//! With no backend chosen, [`AutoBackend`] uses the Wayland protocol and falls back to
+//! the first of `wlr-randr`, `swaymsg`, `hyprctl` and `xrandr` that is installed and fits
+//! the session. Each tool's location can be overridden in
+//! [`AppSettings::tool_paths`], keyed by its name.
+
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::{Arc, OnceLock};
+
+use serde_json::Value;
+
+use crate::backend::{Backend, Output, OutputMode, ScaleSteps, Subpixel, shell_quote};
+use crate::process;
+use crate::settings::AppSettings;
+
+/// A configuration tool MDisplay can drive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tool {
+    WlrRandr,
+    Swaymsg,
+    Hyprctl,
+    Xrandr,
+}
+
+impl Tool {
+    /// Every tool, in the order [`AutoBackend`] tries them.
+    pub const ALL: [Tool; 4] = [Tool::WlrRandr, Tool::Swaymsg, Tool::Hyprctl, Tool::Xrandr];
+
+    /// The executable's usual name, which is also the backend name in the settings.
+    pub fn name(self) -> &'static str {
+        match self {
+            Tool::WlrRandr => "wlr-randr",
+            Tool::Swaymsg => "swaymsg",
+            Tool::Hyprctl => "hyprctl",
+            Tool::Xrandr => "xrandr",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Tool> {
+        Tool::ALL.into_iter().find(|t| t.name() == name)
+    }
+
+    /// The path configured for the tool, unless the settings are another user's.
+    fn configured(self, settings: &AppSettings) -> Option<&str> {
+        settings
+            .tool_paths
+            .get(self.name())
+            .map(String::as_str)
+            .filter(|p| !p.is_empty() && crate::settings::commands_trusted())
+    }
+
+    /// The executable to run: the configured path, or the tool's name looked up on
+    /// `PATH`. `None` when neither resolves to an executable.
+    pub fn program(self, settings: &AppSettings) -> Option<PathBuf> {
+        let configured = self.configured(settings);
+        let name = configured.unwrap_or(self.name());
+        if name.contains('/') {
+            let path = match (name.strip_prefix("~/"), dirs::home_dir()) {
+                (Some(rest), Some(home)) => home.join(rest),
+                _ => PathBuf::from(name),
+            };
+            return crate::plugin::is_executable(&path).then_some(path);
+        }
+        let path = std::env::var_os("PATH")?;
+        std::env::split_paths(&path)
+            .map(|dir| dir.join(name))
+            .find(|p| crate::plugin::is_executable(p))
+    }
+
+    /// Why the tool cannot configure this session's outputs, if it cannot.
+    fn session_problem(self) -> Option<&'static str> {
+        let set = |var: &str| std::env::var_os(var).is_some_and(|v| !v.is_empty());
+        match self {
+            Tool::WlrRandr if !set("WAYLAND_DISPLAY") => Some("not a Wayland session"),
+            Tool::Swaymsg if !set("SWAYSOCK") => Some("not a Sway session"),
+            Tool::Hyprctl if !set("HYPRLAND_INSTANCE_SIGNATURE") => Some("not a Hyprland session"),
+            // Under Wayland, xrandr only sees XWayland's copy of the layout.
+            Tool::Xrandr if set("WAYLAND_DISPLAY") => Some("only sees XWayland under Wayland"),
+            Tool::Xrandr if !set("DISPLAY") => Some("not an X11 session"),
+            _ => None,
+        }
+    }
+
+    /// The tool as a backend for this session, or why it cannot be one.
+    pub fn backend(self, settings: &AppSettings) -> Result<ToolBackend, String> {
+        if let Some(problem) = self.session_problem() {
+            return Err(problem.to_string());
+        }
+        let program = self
+            .program(settings)
+            .ok_or_else(|| match self.configured(settings) {
+                Some(path) => format!("{} is not executable", path),
+                None => "not installed".to_string(),
+            })?;
+        Ok(ToolBackend {
+            tool: self,
+            program,
+        })
+    }
+}
+
+impl std::fmt::Display for Tool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// [`Backend`] that reads and applies layouts by running a [`Tool`].
+#[derive(Debug, Clone)]
+pub struct ToolBackend {
+    pub tool: Tool,
+    pub program: PathBuf,
+}
+
+impl ToolBackend {
+    fn command(&self) -> Command {
+        Command::new(&self.program)
+    }
+
+    fn read(&self, args: &[&str]) -> Result<String, String> {
+        let output = process::output(self.command().args(args))
+            .map_err(|e| format!("Failed to run {}: {}", self.program.display(), e))?;
+        if !output.status.success() {
+            return Err(format!(
+                "{} failed: {}",
+                self.tool,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    /// The arguments that apply `outputs`, grouped per output where the tool takes them
+    /// that way.
+    fn apply_args(&self, outputs: &[Output]) -> Vec<Vec<String>> {
+        match self.tool {
+            Tool::WlrRandr => outputs.iter().map(crate::backend::wlr_randr_args).collect(),
+            Tool::Swaymsg => {
+                let commands: Vec<String> = outputs.iter().map(sway_command).collect();
+                vec![vec![commands.join("; ")]]
+            }
+            Tool::Hyprctl => {
+                let rules: Vec<String> = outputs
+                    .iter()
+                    .map(|out| format!("keyword monitor {}", hyprland_monitor(out)))
+                    .collect();
+                vec![vec!["--batch".to_string(), rules.join(" ; ")]]
+            }
+            Tool::Xrandr => outputs.iter().map(xrandr_args).collect(),
+        }
+    }
+}
+
+impl Backend for ToolBackend {
+    fn fetch_outputs(&self) -> Result<Vec<Output>, String> {
+        let invalid =
+            |e: serde_json::Error| format!("{} printed invalid outputs: {}", self.tool, e);
+        match self.tool {
+            Tool::WlrRandr => parse_wlr_randr(&self.read(&["--json"])?).map_err(invalid),
+            Tool::Swaymsg => parse_sway(&self.read(&["-t", "get_outputs", "-r"])?).map_err(invalid),
+            Tool::Hyprctl => {
+                parse_hyprland(&self.read(&["monitors", "all", "-j"])?).map_err(invalid)
+            }
+            Tool::Xrandr => Ok(parse_xrandr(&self.read(&["--query"])?)),
+        }
+    }
+
+    fn apply_outputs(&self, outputs: &[Output]) -> Result<(), String> {
+        let mut command = self.command();
+        command.args(self.apply_args(outputs).into_iter().flatten());
+        tracing::info!("Applying outputs with {}", self.tool);
+        crate::compositor::run(command, &format!("apply outputs with {}", self.tool))
+    }
+
+    fn scale_steps(&self) -> ScaleSteps {
+        match self.tool {
+            Tool::Hyprctl => ScaleSteps::WholeLogicalSize,
+            Tool::Xrandr => ScaleSteps::Unscaled,
+            Tool::WlrRandr | Tool::Swaymsg => ScaleSteps::Eighths,
+        }
+    }
+
+    fn framebuffer_limit(&self) -> Option<(i32, i32)> {
+        if self.tool != Tool::Xrandr {
+            return None;
+        }
+        let query = self.read(&["--query"]).ok()?;
+        parse_xrandr_maximum(&query)
+    }
+
+    fn preview(&self, outputs: &[Output]) -> String {
+        let mut command = shell_quote(&self.program.to_string_lossy());
+        for args in self.apply_args(outputs) {
+            let args: Vec<String> = args.iter().map(|a| shell_quote(a)).collect();
+            command.push_str(" \\\n  ");
+            command.push_str(&args.join(" "));
+        }
+        command
+    }
+}
+
+/// The default backend: wlr-output-management when the compositor offers it, otherwise
+/// the first [`Tool`] that can read the outputs. The choice is made on first use and
+/// kept for the backend's lifetime.
+pub struct AutoBackend {
+    settings: AppSettings,
+    chosen: OnceLock<Result<Arc<dyn Backend>, String>>,
+}
+
+impl AutoBackend {
+    pub fn new(settings: &AppSettings) -> Self {
+        AutoBackend {
+            settings: settings.clone(),
+            chosen: OnceLock::new(),
+        }
+    }
+
+    fn chosen(&self) -> Result<&Arc<dyn Backend>, String> {
+        self.chosen
+            .get_or_init(|| choose(&self.settings))
+            .as_ref()
+            .map_err(Clone::clone)
+    }
+}
+
+impl Backend for AutoBackend {
+    fn fetch_outputs(&self) -> Result<Vec<Output>, String> {
+        self.chosen()?.fetch_outputs()
+    }
+
+    fn apply_outputs(&self, outputs: &[Output]) -> Result<(), String> {
+        self.chosen()?.apply_outputs(outputs)
+    }
+
+    fn scale_steps(&self) -> ScaleSteps {
+        match self.chosen.get() {
+            Some(Ok(backend)) => backend.scale_steps(),
+            _ => ScaleSteps::detect(),
+        }
+    }
+
+    fn framebuffer_limit(&self) -> Option<(i32, i32)> {
+        self.chosen().ok()?.framebuffer_limit()
+    }
+
+    fn preview(&self, outputs: &[Output]) -> String {
+        match self.chosen() {
+            Ok(backend) => backend.preview(outputs),
+            Err(_) => crate::backend::wlr_randr_command(outputs),
+        }
+    }
+}
+
+/// Walks the fallback chain. The error lists why each candidate was passed over.
+fn choose(settings: &AppSettings) -> Result<Arc<dyn Backend>, String> {
+    let wayland = crate::wayland::WaylandBackend;
+    let mut reasons = match wayland.fetch_outputs() {
+        Ok(_) => return Ok(Arc::new(wayland)),
+        Err(e) => vec![format!("Wayland: {}", e)],
+    };
+    for tool in Tool::ALL {
+        let attempt = tool.backend(settings).and_then(|backend| {
+            backend.fetch_outputs()?;
+            Ok(backend)
+        });
+        match attempt {
+            Ok(backend) => {
+                tracing::warn!("{}; using {} instead", reasons[0], tool);
+                return Ok(Arc::new(backend));
+            }
+            Err(e) => reasons.push(format!("{}: {}", tool, e)),
+        }
+    }
+    Err(format!(
+        "No way to configure outputs found:\n{}",
+        reasons.join("\n")
+    ))
+}
+
+fn text(value: &Value, key: &str) -> String {
+    value[key].as_str().unwrap_or_default().to_string()
+}
+
+fn int(value: &Value, key: &str) -> i32 {
+    value[key].as_i64().unwrap_or_default() as i32
+}
+
+fn float(value: &Value, key: &str) -> f32 {
+    value[key].as_f64().unwrap_or_default() as f32
+}
+
+fn describe(make: &str, model: &str, serial: &str) -> String {
+    [make, model, serial]
+        .into_iter()
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn parse_wlr_randr(json: &str) -> Result<Vec<Output>, serde_json::Error> {
+    let heads: Vec<Value> = serde_json::from_str(json)?;
+    Ok(heads
+        .iter()
+        .map(|head| Output {
+            name: text(head, "name"),
+            description: text(head, "description"),
+            make: text(head, "make"),
+            model: text(head, "model"),
+            serial: text(head, "serial"),
+            physical_size: format!(
+                "{}x{} mm",
+                int(&head["physical_size"], "width"),
+                int(&head["physical_size"], "height")
+            ),
+            position: (int(&head["position"], "x"), int(&head["position"], "y")),
+            scale: head["scale"].as_f64().unwrap_or(1.0) as f32,
+            transform: head["transform"].as_str().unwrap_or("normal").to_string(),
+            modes: head["modes"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .map(|mode| OutputMode {
+                    width: int(mode, "width"),
+                    height: int(mode, "height"),
+                    refresh_rate: float(mode, "refresh"),
+                    current: mode["current"].as_bool().unwrap_or(false),
+                    preferred: mode["preferred"].as_bool().unwrap_or(false),
+                    custom: false,
+                })
+                .collect(),
+            enabled: head["enabled"].as_bool().unwrap_or(false),
+            adaptive_sync: head["adaptive_sync"].as_bool(),
+            rgb_range: None,
+            pixel_format: None,
+            subpixel: Subpixel::Unknown,
+        })
+        .collect())
+}
+
+fn parse_sway(json: &str) -> Result<Vec<Output>, serde_json::Error> {
+    let outputs: Vec<Value> = serde_json::from_str(json)?;
+    Ok(outputs
+        .iter()
+        .map(|out| {
+            let (make, model, serial) =
+                (text(out, "make"), text(out, "model"), text(out, "serial"));
+            let current = &out["current_mode"];
+            // Sway reports refresh rates in mHz.
+            let mut modes: Vec<OutputMode> = out["modes"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .map(|mode| OutputMode {
+                    width: int(mode, "width"),
+                    height: int(mode, "height"),
+                    refresh_rate: float(mode, "refresh") / 1000.0,
+                    current: false,
+                    preferred: false,
+                    custom: false,
+                })
+                .collect();
+            if let Some(mode) = modes.iter_mut().find(|m| {
+                m.width == int(current, "width")
+                    && m.height == int(current, "height")
+                    && (m.refresh_rate * 1000.0).round() as i32 == int(current, "refresh")
+            }) {
+                mode.current = true;
+            }
+            Output {
+                name: text(out, "name"),
+                description: describe(&make, &model, &serial),
+                make,
+                model,
+                serial,
+                physical_size: String::new(),
+                position: (int(&out["rect"], "x"), int(&out["rect"], "y")),
+                scale: out["scale"].as_f64().unwrap_or(1.0) as f32,
+                transform: out["transform"].as_str().unwrap_or("normal").to_string(),
+                modes,
+                enabled: out["active"].as_bool().unwrap_or(false),
+                adaptive_sync: out["adaptive_sync_status"].as_str().map(|s| s == "enabled"),
+                rgb_range: None,
+                pixel_format: None,
+                subpixel: match out["subpixel_hinting"].as_str() {
+                    Some("rgb") => Subpixel::Rgb,
+                    Some("bgr") => Subpixel::Bgr,
+                    Some("vrgb") => Subpixel::VerticalRgb,
+                    Some("vbgr") => Subpixel::VerticalBgr,
+                    Some("none") => Subpixel::None,
+                    _ => Subpixel::Unknown,
+                },
+            }
+        })
+        .collect())
+}
+
+/// The Hyprland `monitor` rule for `out`, as `hyprctl keyword monitor` takes it and as
+/// it is written after `monitor=` in `hyprland.conf`.
+pub(crate) fn hyprland_monitor(out: &Output) -> String {
+    if !out.enabled {
+        return format!("{},disable", out.name);
+    }
+    let vrr = match out.adaptive_sync {
+        Some(enabled) => format!(",vrr,{}", enabled as u8),
+        None => String::new(),
+    };
+    crate::compositor::hyprland_rule(out, &vrr)
+        .unwrap_or_else(|| format!("{},preferred,auto,1", out.name))
+}
+
+/// The sway `output` command for `out`, which is also valid in the sway config.
+pub(crate) fn sway_command(out: &Output) -> String {
+    let name = format!("output \"{}\"", out.name);
+    if !out.enabled {
+        return format!("{} disable", name);
+    }
+    let mut command = format!("{} enable", name);
+    if let Some(mode) = out.modes.iter().find(|m| m.current) {
+        command.push_str(&format!(
+            " mode {}{}x{}@{:.3}Hz",
+            if mode.custom { "--custom " } else { "" },
+            mode.width,
+            mode.height,
+            mode.refresh_rate
+        ));
+    }
+    command.push_str(&format!(
+        " position {} {} scale {} transform {}",
+        out.position.0, out.position.1, out.scale, out.transform
+    ));
+    if let Some(enabled) = out.adaptive_sync {
+        command.push_str(if enabled {
+            " adaptive_sync on"
+        } else {
+            " adaptive_sync off"
+        });
+    }
+    command
+}
+
+/// The wlr transform name for Hyprland's number.
+fn hyprland_transform_name(transform: i64) -> &'static str {
+    match transform {
+        1 => "90",
+        2 => "180",
+        3 => "270",
+        4 => "flipped",
+        5 => "flipped-90",
+        6 => "flipped-180",
+        7 => "flipped-270",
+        _ => "normal",
+    }
+}
+
+fn parse_hyprland(json: &str) -> Result<Vec<Output>, serde_json::Error> {
+    let monitors: Vec<Value> = serde_json::from_str(json)?;
+    Ok(monitors
+        .iter()
+        .map(|monitor| {
+            let (width, height) = (int(monitor, "width"), int(monitor, "height"));
+            let refresh_rate = float(monitor, "refreshRate");
+            // Available modes come as "1920x1080@60.00Hz".
+            let mut modes: Vec<OutputMode> = monitor["availableModes"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|mode| {
+                    let (size, rate) = mode.as_str()?.trim_end_matches("Hz").split_once('@')?;
+                    let (w, h) = size.split_once('x')?;
+                    Some(OutputMode {
+                        width: w.parse().ok()?,
+                        height: h.parse().ok()?,
+                        refresh_rate: rate.parse().ok()?,
+                        current: false,
+                        preferred: false,
+                        custom: false,
+                    })
+                })
+                .collect();
+            match modes.iter_mut().find(|m| {
+                m.width == width
+                    && m.height == height
+                    && (m.refresh_rate - refresh_rate).abs() < 0.5
+            }) {
+                Some(mode) => mode.current = true,
+                None if width > 0 => modes.push(OutputMode {
+                    width,
+                    height,
+                    refresh_rate,
+                    current: true,
+                    preferred: false,
+                    custom: true,
+                }),
+                None => {}
+            }
+            Output {
+                name: text(monitor, "name"),
+                description: text(monitor, "description"),
+                make: text(monitor, "make"),
+                model: text(monitor, "model"),
+                serial: text(monitor, "serial"),
+                physical_size: String::new(),
+                position: (int(monitor, "x"), int(monitor, "y")),
+                scale: monitor["scale"].as_f64().unwrap_or(1.0) as f32,
+                transform: hyprland_transform_name(monitor["transform"].as_i64().unwrap_or(0))
+                    .to_string(),
+                modes,
+                enabled: !monitor["disabled"].as_bool().unwrap_or(false),
+                adaptive_sync: monitor["vrr"].as_bool(),
+                rgb_range: None,
+                pixel_format: None,
+                subpixel: Subpixel::Unknown,
+            }
+        })
+        .collect())
+}
+
+/// Reads `xrandr --query`. X11 has no output scale, so every output gets 1, and reflected
+/// outputs are reported as unrotated.
+fn parse_xrandr(query: &str) -> Vec<Output> {
+    let mut outputs: Vec<Output> = Vec::new();
+    let mut connected = false;
+    for line in query.lines() {
+        if line.starts_with(char::is_whitespace) {
+            if connected && let Some(out) = outputs.last_mut() {
+                out.modes.extend(parse_xrandr_modes(line));
+            }
+            continue;
+        }
+        let mut words = line.split_whitespace();
+        let (Some(name), Some(state)) = (words.next(), words.next()) else {
+            continue;
+        };
+        connected = state == "connected";
+        if !connected {
+            continue;
+        }
+        let mut out = Output {
+            name: name.to_string(),
+            description: String::new(),
+            make: String::new(),
+            model: String::new(),
+            serial: String::new(),
+            physical_size: String::new(),
+            position: (0, 0),
+            scale: 1.0,
+            transform: "normal".to_string(),
+            modes: Vec::new(),
+            enabled: false,
+            adaptive_sync: None,
+            rgb_range: None,
+            pixel_format: None,
+            subpixel: Subpixel::Unknown,
+        };
+        // The rotation list in parentheses splits the line in two.
+        let (head, tail) = line.split_once('(').unwrap_or((line, ""));
+        for word in head.split_whitespace().skip(2) {
+            if let Some((_, x, y)) = parse_geometry(word) {
+                out.enabled = true;
+                out.position = (x, y);
+            }
+            out.transform = match word {
+                "left" => "270",
+                "inverted" => "180",
+                "right" => "90",
+                _ => continue,
+            }
+            .to_string();
+        }
+        let sizes: Vec<&str> = tail
+            .split_once(')')
+            .map(|(_, size)| size)
+            .unwrap_or_default()
+            .split_whitespace()
+            .filter_map(|w| w.strip_suffix("mm"))
+            .collect();
+        if let [width, height] = sizes[..] {
+            out.physical_size = format!("{}x{} mm", width, height);
+        }
+        outputs.push(out);
+    }
+    outputs
+}
+
+/// The largest screen from the `Screen 0: minimum 8 x 8, current …, maximum W x H` line.
+fn parse_xrandr_maximum(query: &str) -> Option<(i32, i32)> {
+    let line = query.lines().find(|l| l.starts_with("Screen "))?;
+    let (_, maximum) = line.split_once("maximum ")?;
+    let mut words = maximum.split_whitespace();
+    let w = words.next()?.parse().ok()?;
+    let h = words.nth(1)?.trim_end_matches(',').parse().ok()?;
+    Some((w, h))
+}
+
+/// `WxH+X+Y` as `((W, H), X, Y)`.
+fn parse_geometry(word: &str) -> Option<((i32, i32), i32, i32)> {
+    let (size, position) = word.split_once('+')?;
+    let (x, y) = position.split_once('+')?;
+    let (w, h) = size.split_once('x')?;
+    Some((
+        (w.parse().ok()?, h.parse().ok()?),
+        x.parse().ok()?,
+        y.parse().ok()?,
+    ))
+}
+
+/// One mode line, like `1920x1080     60.00*+  50.00`. `*` marks the current rate and
+/// `+` the preferred one, sometimes as a word of their own.
+fn parse_xrandr_modes(line: &str) -> Vec<OutputMode> {
+    let mut words = line.split_whitespace();
+    let Some((width, height)) = words.next().and_then(|size| {
+        let (w, h) = size.split_once('x')?;
+        Some((w.parse().ok()?, h.trim_end_matches('i').parse().ok()?))
+    }) else {
+        return Vec::new();
+    };
+    let mut modes: Vec<OutputMode> = Vec::new();
+    for word in words {
+        let rate = word.trim_end_matches(['*', '+']);
+        let mode = if rate.is_empty() {
+            match modes.last_mut() {
+                Some(mode) => mode,
+                None => continue,
+            }
+        } else {
+            let Ok(refresh_rate) = rate.parse() else {
+                continue;
+            };
+            modes.push(OutputMode {
+                width,
+                height,
+                refresh_rate,
+                current: false,
+                preferred: false,
+                custom: false,
+            });
+            modes.last_mut().expect("just pushed")
+        };
+        mode.current |= word.contains('*');
+        mode.preferred |= word.contains('+');
+    }
+    modes
+}
+
+fn xrandr_args(out: &Output) -> Vec<String> {
+    let mut args = vec!["--output".to_string(), out.name.clone()];
+    let Some(mode) = out.modes.iter().find(|m| m.current).filter(|_| out.enabled) else {
+        args.push("--off".to_string());
+        return args;
+    };
+    let rotation = match out.transform.trim_start_matches("flipped-") {
+        "90" => "right",
+        "180" => "inverted",
+        "270" => "left",
+        _ => "normal",
+    };
+    args.extend([
+        "--mode".to_string(),
+        format!("{}x{}", mode.width, mode.height),
+        "--rate".to_string(),
+        format!("{:.2}", mode.refresh_rate),
+        "--pos".to_string(),
+        format!("{}x{}", out.position.0, out.position.1),
+        "--rotate".to_string(),
+        rotation.to_string(),
+    ]);
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_xrandr_query() {
+        let query = "\
+Screen 0: minimum 8 x 8, current 3000 x 1920, maximum 32767 x 32767
+eDP-1 connected primary 1920x1080+0+0 (normal left inverted right x axis y axis) 344mm x 193mm
+   1920x1080     60.02*+  59.93    48.01
+   1280x720      60.00
+HDMI-1 connected 1080x1920+1920+0 left (normal left inverted right x axis y axis) 527mm x 296mm
+   1920x1080     60.00 +  50.00*
+DP-1 disconnected (normal left inverted right x axis y axis)
+VGA-1 connected (normal left inverted right x axis y axis)
+   1024x768      60.00
+";
+        let outputs = parse_xrandr(query);
+        let names: Vec<&str> = outputs.iter().map(|o| o.name.as_str()).collect();
+        assert_eq!(names, ["eDP-1", "HDMI-1", "VGA-1"]);
+
+        let edp = &outputs[0];
+        assert!(edp.enabled);
+        assert_eq!(edp.physical_size, "344x193 mm");
+        assert_eq!(edp.modes.len(), 4);
+        assert!(edp.modes[0].current && edp.modes[0].preferred);
+
+        let hdmi = &outputs[1];
+        assert_eq!((hdmi.position, hdmi.transform.as_str()), ((1920, 0), "270"));
+        assert!(hdmi.modes[0].preferred && !hdmi.modes[0].current);
+        assert!(hdmi.modes[1].current);
+
+        assert!(!outputs[2].enabled);
+        assert_eq!(parse_xrandr_maximum(query), Some((32767, 32767)));
+    }
+    #[test]
+    fn the_preview_is_the_command_the_tool_runs() {
+        let outputs =
+            crate::mock::MockBackend::from_json(include_str!("../fixtures/dual_monitor.json"))
+                .unwrap()
+                .fetch_outputs()
+                .unwrap();
+        let sway = ToolBackend {
+            tool: Tool::Swaymsg,
+            program: PathBuf::from("swaymsg"),
+        };
+        let preview = sway.preview(&outputs);
+        assert!(
+            preview.starts_with("swaymsg \\\n  'output \"DP-1\" enable "),
+            "{}",
+            preview
+        );
+        assert!(!preview.contains("wlr-randr"));
+    }
+}
@@ -0,0 +1,150 @@
+//! Whether the compositor did what an apply asked for.
+//!
+//! Compositors may accept a configuration and still change it: a mode the link cannot
+//! carry falls back to 60 Hz, a scale is rounded, adaptive sync stays off on a panel
+//! without it. Comparing the requested layout with a fresh read says exactly what.
+
+use crate::backend::Output;
+
+/// Refresh rates closer than this in Hz count as granted; custom modes come back a few
+/// mHz off the rate asked for, since their timings are rounded to the pixel clock.
+const REFRESH_SLACK: f32 = 0.05;
+/// Scales closer than this are what the compositor's fixed-point rounding leaves; the
+/// sidebar warns about those before applying.
+const SCALE_TOLERANCE: f32 = 0.01;
+
+/// One setting of one output that ended up different from the request.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mismatch {
+    pub output: String,
+    pub field: &'static str,
+    pub requested: String,
+    pub actual: String,
+}
+
+impl std::fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {}: {} instead of {}",
+            self.output, self.field, self.actual, self.requested
+        )
+    }
+}
+
+fn mode_text(out: &Output) -> Option<String> {
+    let mode = out.modes.iter().find(|m| m.current)?;
+    Some(format!("{}x{}", mode.width, mode.height))
+}
+
+fn refresh(out: &Output) -> Option<f32> {
+    out.modes.iter().find(|m| m.current).map(|m| m.refresh_rate)
+}
+
+/// Every difference between `requested` and `actual` that the compositor made. Outputs
+/// missing from `actual` were unplugged in the meantime and are skipped, as are the
+/// settings of outputs that were asked to be off.
+pub fn compare(requested: &[Output], actual: &[Output]) -> Vec<Mismatch> {
+    let mut mismatches = Vec::new();
+    for want in requested {
+        let Some(got) = actual.iter().find(|o| o.name == want.name) else {
+            continue;
+        };
+        let mut differs = |field: &'static str, requested: String, actual: String| {
+            mismatches.push(Mismatch {
+                output: want.name.clone(),
+                field,
+                requested,
+                actual,
+            })
+        };
+        let on_off = |enabled: bool| if enabled { "on" } else { "off" }.to_string();
+        if want.enabled != got.enabled {
+            differs("output", on_off(want.enabled), on_off(got.enabled));
+            continue;
+        }
+        if !want.enabled {
+            continue;
+        }
+        if let (Some(w), Some(g)) = (mode_text(want), mode_text(got))
+            && w != g
+        {
+            differs("resolution", w, g);
+        } else if let (Some(w), Some(g)) = (refresh(want), refresh(got))
+            && (w - g).abs() >= REFRESH_SLACK
+        {
+            differs("refresh rate", format!("{} Hz", w), format!("{} Hz", g));
+        }
+        if (want.scale - got.scale).abs() >= SCALE_TOLERANCE {
+            differs("scale", want.scale.to_string(), got.scale.to_string());
+        }
+        if want.position != got.position {
+            differs(
+                "position",
+                format!("{},{}", want.position.0, want.position.1),
+                format!("{},{}", got.position.0, got.position.1),
+            );
+        }
+        if want.transform != got.transform {
+            differs("rotation", want.transform.clone(), got.transform.clone());
+        }
+        if let (Some(w), Some(g)) = (want.adaptive_sync, got.adaptive_sync)
+            && w != g
+        {
+            differs("adaptive sync", on_off(w), on_off(g));
+        }
+        if let (Some(w), Some(g)) = (want.rgb_range, got.rgb_range)
+            && w != g
+        {
+            differs("RGB range", w.to_string(), g.to_string());
+        }
+        if let (Some(w), Some(g)) = (want.pixel_format, got.pixel_format)
+            && w != g
+        {
+            differs("pixel format", w.to_string(), g.to_string());
+        }
+    }
+    mismatches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::{Backend, PixelFormat};
+    use crate::mock::MockBackend;
+
+    #[test]
+    fn reports_a_refresh_fallback_and_nothing_else() {
+        let requested = MockBackend::from_json(include_str!("../fixtures/dual_monitor.json"))
+            .unwrap()
+            .fetch_outputs()
+            .unwrap();
+        let mut actual = requested.clone();
+        actual[0].modes[0].refresh_rate = 60.0;
+        actual[1].scale = 1.002;
+        let mismatches = compare(&requested, &actual);
+        assert_eq!(mismatches.len(), 1);
+        assert_eq!(
+            mismatches[0].to_string(),
+            "DP-1 refresh rate: 60 Hz instead of 143.998 Hz"
+        );
+    }
+
+    #[test]
+    fn a_pixel_format_the_backend_kept_is_reported() {
+        let mut requested = MockBackend::from_json(include_str!("../fixtures/dual_monitor.json"))
+            .unwrap()
+            .fetch_outputs()
+            .unwrap();
+        let mut actual = requested.clone();
+        actual[1].pixel_format = Some(PixelFormat::Rgb);
+        assert!(compare(&requested, &actual).is_empty());
+
+        requested[1].pixel_format = Some(PixelFormat::Ycbcr420);
+        let mismatches = compare(&requested, &actual);
+        assert_eq!(
+            mismatches[0].to_string(),
+            "HDMI-A-1 pixel format: RGB instead of YCbCr 4:2:0"
+        );
+    }
+}
@@ -1,13 +1,14 @@
 use std::collections::HashMap;
 
-use wayland_client::protocol::wl_registry;
+use wayland_client::protocol::{wl_output, wl_registry};
 use wayland_client::{Connection, Dispatch, Proxy, QueueHandle, delegate_noop};
 use wayland_protocols_wlr::output_management::v1::client::{
     zwlr_output_configuration_head_v1, zwlr_output_configuration_v1, zwlr_output_head_v1,
     zwlr_output_manager_v1, zwlr_output_mode_v1,
 };
+use zwlr_output_head_v1::AdaptiveSyncState;
 
-use crate::backend::{Output, OutputMode};
+use crate::backend::{Backend, Output, OutputMode, REFRESH_TOLERANCE, Subpixel};
 
 #[derive(Debug, Clone)]
 struct HeadBuilder {
@@ -21,6 +22,7 @@ struct HeadBuilder {
     scale: f32,
     transform: String,
     enabled: bool,
+    adaptive_sync: Option<bool>,
     modes: Vec<wayland_client::backend::ObjectId>,
     current_mode: Option<wayland_client::backend::ObjectId>,
     head_proxy: Option<zwlr_output_head_v1::ZwlrOutputHeadV1>,
@@ -39,6 +41,7 @@ impl Default for HeadBuilder {
             scale: 1.0,
             transform: "normal".to_string(),
             enabled: true,
+            adaptive_sync: None,
             modes: Vec::new(),
             current_mode: None,
             head_proxy: None,
@@ -73,6 +76,9 @@ struct WaylandState {
     modes: HashMap<wayland_client::backend::ObjectId, ModeBuilder>,
     serial: Option<u32>,
     apply_status: Option<Result<(), String>>,
+    /// Name and subpixel order of each `wl_output`, which is the only place the latter
+    /// is reported.
+    wl_outputs: HashMap<wayland_client::backend::ObjectId, (String, Subpixel)>,
 }
 
 impl Dispatch<wl_registry::WlRegistry, ()> for WaylandState {
@@ -84,21 +90,58 @@ impl Dispatch<wl_registry::WlRegistry, ()> for WaylandState {
         _: &Connection,
         qh: &QueueHandle<Self>,
     ) {
-        if let wl_registry::Event::Global {
+        let wl_registry::Event::Global {
             name,
             interface,
             version,
         } = event
-        {
-            if interface == "zwlr_output_manager_v1" {
-                let manager = registry.bind::<zwlr_output_manager_v1::ZwlrOutputManagerV1, _, _>(
-                    name,
-                    version.min(4),
-                    qh,
-                    (),
-                );
-                state.output_manager = Some(manager);
+        else {
+            return;
+        };
+        if interface == "zwlr_output_manager_v1" {
+            let manager = registry.bind::<zwlr_output_manager_v1::ZwlrOutputManagerV1, _, _>(
+                name,
+                version.min(4),
+                qh,
+                (),
+            );
+            state.output_manager = Some(manager);
+        } else if interface == "wl_output" && version >= 4 {
+            // Version 4 adds the name that ties the output to its head.
+            let output = registry.bind::<wl_output::WlOutput, _, _>(name, 4, qh, ());
+            state.wl_outputs.insert(output.id(), Default::default());
+        }
+    }
+}
+
+impl Dispatch<wl_output::WlOutput, ()> for WaylandState {
+    fn event(
+        state: &mut Self,
+        proxy: &wl_output::WlOutput,
+        event: wl_output::Event,
+        _: &(),
+        _: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        let Some((name, subpixel)) = state.wl_outputs.get_mut(&proxy.id()) else {
+            return;
+        };
+        match event {
+            wl_output::Event::Name { name: output_name } => *name = output_name,
+            wl_output::Event::Geometry {
+                subpixel: wayland_client::WEnum::Value(order),
+                ..
+            } => {
+                *subpixel = match order {
+                    wl_output::Subpixel::None => Subpixel::None,
+                    wl_output::Subpixel::HorizontalRgb => Subpixel::Rgb,
+                    wl_output::Subpixel::HorizontalBgr => Subpixel::Bgr,
+                    wl_output::Subpixel::VerticalRgb => Subpixel::VerticalRgb,
+                    wl_output::Subpixel::VerticalBgr => Subpixel::VerticalBgr,
+                    _ => Subpixel::Unknown,
+                };
             }
+            _ => {}
         }
     }
 }
@@ -115,8 +158,10 @@ impl Dispatch<zwlr_output_manager_v1::ZwlrOutputManagerV1, ()> for WaylandState
         match event {
             zwlr_output_manager_v1::Event::Head { head } => {
                 let id = head.id();
-                let mut builder = HeadBuilder::default();
-                builder.head_proxy = Some(head);
+                let builder = HeadBuilder {
+                    head_proxy: Some(head),
+                    ..Default::default()
+                };
                 state.heads.insert(id, builder);
             }
             zwlr_output_manager_v1::Event::Done { serial } => {
@@ -126,7 +171,6 @@ impl Dispatch<zwlr_output_manager_v1::ZwlrOutputManagerV1, ()> for WaylandState
         }
     }
 
-
     wayland_client::event_created_child!(WaylandState, zwlr_output_manager_v1::ZwlrOutputManagerV1, [
         0 => (zwlr_output_head_v1::ZwlrOutputHeadV1, ())
     ]);
@@ -173,9 +217,9 @@ impl Dispatch<zwlr_output_head_v1::ZwlrOutputHeadV1, ()> for WaylandState {
             }
             zwlr_output_head_v1::Event::Transform { transform } => {
                 builder.transform = transform_to_string(
-                    transform.into_result().unwrap_or(
-                        wayland_client::protocol::wl_output::Transform::Normal,
-                    ),
+                    transform
+                        .into_result()
+                        .unwrap_or(wayland_client::protocol::wl_output::Transform::Normal),
                 );
             }
             zwlr_output_head_v1::Event::Scale { scale } => {
@@ -190,11 +234,14 @@ impl Dispatch<zwlr_output_head_v1::ZwlrOutputHeadV1, ()> for WaylandState {
             zwlr_output_head_v1::Event::SerialNumber { serial_number } => {
                 builder.serial = serial_number;
             }
+            zwlr_output_head_v1::Event::AdaptiveSync { state } => {
+                builder.adaptive_sync =
+                    Some(state.into_result().ok() == Some(AdaptiveSyncState::Enabled));
+            }
             _ => {}
         }
     }
 
-
     wayland_client::event_created_child!(WaylandState, zwlr_output_head_v1::ZwlrOutputHeadV1, [
         3 => (zwlr_output_mode_v1::ZwlrOutputModeV1, ())
     ]);
@@ -246,8 +293,7 @@ impl Dispatch<zwlr_output_configuration_v1::ZwlrOutputConfigurationV1, ()> for W
                 state.apply_status = Some(Ok(()));
             }
             zwlr_output_configuration_v1::Event::Failed => {
-                state.apply_status =
-                    Some(Err("Configuration rejected by compositor".to_string()));
+                state.apply_status = Some(Err("Configuration rejected by compositor".to_string()));
             }
             zwlr_output_configuration_v1::Event::Cancelled => {
                 state.apply_status = Some(Err("Configuration cancelled".to_string()));
@@ -289,6 +335,20 @@ fn string_to_transform(s: &str) -> Transform {
     }
 }
 
+/// [`Backend`] for compositors implementing wlr-output-management.
+pub struct WaylandBackend;
+
+impl Backend for WaylandBackend {
+    fn fetch_outputs(&self) -> Result<Vec<Output>, String> {
+        fetch_outputs()
+    }
+
+    fn apply_outputs(&self, outputs: &[Output]) -> Result<(), String> {
+        apply_outputs(outputs)
+    }
+}
+
+/// Reads every head the compositor exposes, enabled or not.
 pub fn fetch_outputs() -> Result<Vec<Output>, String> {
     let conn =
         Connection::connect_to_env().map_err(|e| format!("Failed to connect to Wayland: {}", e))?;
@@ -305,6 +365,7 @@ pub fn fetch_outputs() -> Result<Vec<Output>, String> {
         modes: HashMap::new(),
         serial: None,
         apply_status: None,
+        wl_outputs: HashMap::new(),
     };
 
     event_queue
@@ -312,9 +373,7 @@ pub fn fetch_outputs() -> Result<Vec<Output>, String> {
         .map_err(|e| e.to_string())?;
 
     if state.output_manager.is_none() {
-        return Err(
-            "Compositor does not support wlr-output-management-unstable-v1".to_string(),
-        );
+        return Err("Compositor does not support wlr-output-management-unstable-v1".to_string());
     }
 
     event_queue
@@ -326,7 +385,8 @@ pub fn fetch_outputs() -> Result<Vec<Output>, String> {
 
     let mut outputs = Vec::new();
 
-    for (_, head_builder) in &state.heads {
+    tracing::debug!("Compositor reported {} heads", state.heads.len());
+    for head_builder in state.heads.values() {
         let mut modes = Vec::new();
         for mode_id in &head_builder.modes {
             if let Some(mode_builder) = state.modes.get(mode_id) {
@@ -337,6 +397,7 @@ pub fn fetch_outputs() -> Result<Vec<Output>, String> {
                     refresh_rate: mode_builder.refresh_rate,
                     current: is_current,
                     preferred: mode_builder.preferred,
+                    custom: false,
                 });
             }
         }
@@ -353,13 +414,86 @@ pub fn fetch_outputs() -> Result<Vec<Output>, String> {
             transform: head_builder.transform.clone(),
             modes,
             enabled: head_builder.enabled,
+            adaptive_sync: head_builder.adaptive_sync,
+            // wlr-output-management has no quantization range or pixel format.
+            rgb_range: None,
+            pixel_format: None,
+            subpixel: state
+                .wl_outputs
+                .values()
+                .find(|(name, _)| *name == head_builder.name)
+                .map_or(Subpixel::Unknown, |(_, subpixel)| *subpixel),
         });
     }
 
     Ok(outputs)
 }
 
+/// Blocks until the compositor announces a new output configuration, which happens on
+/// hotplug as well as after any client applies a change, and returns its serial.
+/// Compositors count serials from scratch when they start, so one lower than the last
+/// seen means the compositor was restarted.
+pub fn wait_for_output_change() -> Result<u32, String> {
+    let conn =
+        Connection::connect_to_env().map_err(|e| format!("Failed to connect to Wayland: {}", e))?;
+
+    let mut event_queue = conn.new_event_queue();
+    let qhandle = event_queue.handle();
+    let display = conn.display();
+    display.get_registry(&qhandle, ());
+
+    let mut state = WaylandState {
+        output_manager: None,
+        heads: HashMap::new(),
+        modes: HashMap::new(),
+        serial: None,
+        apply_status: None,
+        wl_outputs: HashMap::new(),
+    };
+
+    event_queue
+        .roundtrip(&mut state)
+        .map_err(|e| e.to_string())?;
+
+    if state.output_manager.is_none() {
+        return Err("Compositor does not support wlr-output-management-unstable-v1".to_string());
+    }
+
+    event_queue
+        .roundtrip(&mut state)
+        .map_err(|e| e.to_string())?;
+
+    let initial = state.serial;
+    loop {
+        event_queue
+            .blocking_dispatch(&mut state)
+            .map_err(|e| e.to_string())?;
+        if let Some(serial) = state.serial
+            && state.serial != initial
+        {
+            return Ok(serial);
+        }
+    }
+}
+
+/// Sends `outputs` to the compositor as one configuration and waits for it to be accepted
+/// or rejected. Heads not listed are left alone.
 pub fn apply_outputs(outputs: &[Output]) -> Result<(), String> {
+    for out in outputs {
+        tracing::info!(
+            "Applying {}: enabled={} position={:?} scale={} transform={} mode={:?}",
+            out.name,
+            out.enabled,
+            out.position,
+            out.scale,
+            out.transform,
+            out.modes
+                .iter()
+                .find(|m| m.current)
+                .map(|m| (m.width, m.height, m.refresh_rate))
+        );
+    }
+
     let conn =
         Connection::connect_to_env().map_err(|e| format!("Failed to connect to Wayland: {}", e))?;
 
@@ -374,6 +508,7 @@ pub fn apply_outputs(outputs: &[Output]) -> Result<(), String> {
         modes: HashMap::new(),
         serial: None,
         apply_status: None,
+        wl_outputs: HashMap::new(),
     };
 
     event_queue
@@ -387,8 +522,9 @@ pub fn apply_outputs(outputs: &[Output]) -> Result<(), String> {
         .roundtrip(&mut state)
         .map_err(|e| e.to_string())?;
 
-    let manager = state.output_manager.as_ref()
-        .ok_or_else(|| "Compositor does not support wlr-output-management-unstable-v1".to_string())?;
+    let manager = state.output_manager.as_ref().ok_or_else(|| {
+        "Compositor does not support wlr-output-management-unstable-v1".to_string()
+    })?;
     let serial = state.serial.unwrap_or(0);
     let config = manager.create_configuration(serial, &qhandle, ());
 
@@ -396,7 +532,7 @@ pub fn apply_outputs(outputs: &[Output]) -> Result<(), String> {
         let mut head_proxy = None;
         let mut head_modes = Vec::new();
 
-        for (_, hb) in &state.heads {
+        for hb in state.heads.values() {
             if hb.name == out.name {
                 head_proxy = hb.head_proxy.clone();
                 head_modes = hb.modes.clone();
@@ -411,20 +547,29 @@ pub fn apply_outputs(outputs: &[Output]) -> Result<(), String> {
                 head_config.set_position(out.position.0, out.position.1);
                 head_config.set_scale(out.scale as f64);
                 head_config.set_transform(string_to_transform(&out.transform));
+                // set_adaptive_sync arrived in version 4 of the protocol.
+                if let Some(enabled) = out.adaptive_sync
+                    && head_config.version() >= 4
+                {
+                    head_config.set_adaptive_sync(if enabled {
+                        AdaptiveSyncState::Enabled
+                    } else {
+                        AdaptiveSyncState::Disabled
+                    });
+                }
 
                 if let Some(active_mode) = out.modes.iter().find(|m| m.current) {
                     let mut found_proxy = None;
 
                     for mode_id in &head_modes {
-                        if let Some(mode_builder) = state.modes.get(mode_id) {
-                            if mode_builder.width == active_mode.width
-                                && mode_builder.height == active_mode.height
-                                && (mode_builder.refresh_rate - active_mode.refresh_rate).abs()
-                                    < 0.5
-                            {
-                                found_proxy = mode_builder.mode_proxy.clone();
-                                break;
-                            }
+                        if let Some(mode_builder) = state.modes.get(mode_id)
+                            && mode_builder.width == active_mode.width
+                            && mode_builder.height == active_mode.height
+                            && (mode_builder.refresh_rate - active_mode.refresh_rate).abs()
+                                < REFRESH_TOLERANCE
+                        {
+                            found_proxy = mode_builder.mode_proxy.clone();
+                            break;
                         }
                     }
 
@@ -455,5 +600,7 @@ pub fn apply_outputs(outputs: &[Output]) -> Result<(), String> {
         }
     }
 
-    state.apply_status.ok_or_else(|| "Compositor did not send apply status".to_string())?
+    state
+        .apply_status
+        .ok_or_else(|| "Compositor did not send apply status".to_string())?
 }
@@ -0,0 +1,341 @@
+use std::path::PathBuf;
+
+use clap::Subcommand;
+
+use crate::ipc;
+use mdisplay_core::backend::{self, Output, config_text, fetch_outputs};
+use mdisplay_core::plugin;
+use mdisplay_core::profile::{self, Profile};
+use mdisplay_core::report;
+use mdisplay_core::settings::{AppSettings, SaveFormat};
+use mdisplay_core::status::OutputStatus;
+use mdisplay_core::tools::Tool;
+use mdisplay_core::wayland::wait_for_output_change;
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// List the connected outputs and saved profiles
+    List,
+    /// Show the modes and settings of one output, or of all outputs
+    Get {
+        output: Option<String>,
+        /// Print the outputs as JSON instead of text
+        #[arg(long)]
+        json: bool,
+    },
+    /// Apply a saved profile to the connected outputs
+    Apply { profile: String },
+    /// Save the current layout as a profile
+    Save { profile: String },
+    /// Write a profile to a self-contained file, or to stdout
+    Export {
+        profile: String,
+        file: Option<PathBuf>,
+    },
+    /// Add a profile from an exported file, placing it on the same monitors
+    Import { file: PathBuf },
+    /// Write a Markdown report of the layout with an SVG diagram next to it, or print
+    /// the report without the diagram
+    Report { file: Option<PathBuf> },
+    /// Print a profile as a config for one compositor, with the profile's section for it
+    Config {
+        profile: String,
+        /// mangowc, wlr-randr, hyprland or sway; the save format from the settings if
+        /// left out
+        #[arg(long, value_parser = parse_format)]
+        format: Option<SaveFormat>,
+    },
+    /// Enable or disable an output
+    Toggle { output: String },
+    /// Serve the dev.mdisplay D-Bus interface and watch for layout changes
+    Daemon,
+    /// Stream the active profile and outputs as a waybar custom module
+    Waybar,
+    /// Apply the saved profile after the active one
+    Cycle {
+        /// Go to the previous profile instead
+        #[arg(long)]
+        reverse: bool,
+    },
+    /// List the available backends and show which one is in use
+    Backends,
+}
+
+/// A [`SaveFormat`] by the name it has in `settings.json`.
+fn parse_format(name: &str) -> Result<SaveFormat, String> {
+    SaveFormat::ALL
+        .into_iter()
+        .find(|f| serde_json::to_value(f).is_ok_and(|v| v == name))
+        .ok_or_else(|| format!("Unknown format '{}'", name))
+}
+
+pub fn run(command: Command, dry_run: bool) -> Result<(), String> {
+    match command {
+        Command::List => {
+            for out in sorted(fetch_outputs()?) {
+                println!("{}", summary(&out));
+            }
+            let settings = AppSettings::load();
+            if !settings.profiles.is_empty() {
+                println!();
+                println!("Profiles:");
+                for p in &settings.profiles {
+                    println!("  {}", p.name);
+                }
+            }
+        }
+        Command::Get { output, json } => {
+            let outputs = sorted(fetch_outputs()?);
+            let selected: Vec<&Output> = match &output {
+                Some(name) => vec![find(&outputs, name)?],
+                None => outputs.iter().collect(),
+            };
+            if json {
+                let statuses: Vec<OutputStatus> =
+                    selected.into_iter().map(OutputStatus::from).collect();
+                let encoded = if output.is_some() {
+                    serde_json::to_string_pretty(&statuses[0])
+                } else {
+                    serde_json::to_string_pretty(&statuses)
+                }
+                .map_err(|e| format!("Failed to serialize outputs: {}", e))?;
+                println!("{}", encoded);
+                return Ok(());
+            }
+            for (i, out) in selected.into_iter().enumerate() {
+                if i > 0 {
+                    println!();
+                }
+                print_details(out);
+            }
+        }
+        Command::Apply { profile: name } => {
+            let settings = AppSettings::load();
+            let profile = settings
+                .profile(&name)
+                .ok_or_else(|| format!("No profile named '{}'", name))?;
+            if dry_run {
+                let mut outputs = fetch_outputs()?;
+                profile.apply_to(&mut outputs);
+                println!("{}", backend::active().preview(&outputs));
+                return Ok(());
+            }
+            ipc::apply_profile(profile)?;
+            println!("Applied profile '{}'", name);
+        }
+        Command::Save { profile: name } => {
+            let outputs = fetch_outputs()?;
+            let mut settings = AppSettings::load();
+            settings.store_profile(Profile::from_outputs(&name, &outputs));
+            settings.save()?;
+            println!("Saved profile '{}'", name);
+        }
+        Command::Export {
+            profile: name,
+            file,
+        } => {
+            let settings = AppSettings::load();
+            let profile = settings
+                .profile(&name)
+                .ok_or_else(|| format!("No profile named '{}'", name))?;
+            let json = profile::export(profile, &fetch_outputs().unwrap_or_default())?;
+            match file {
+                Some(path) => std::fs::write(&path, json)
+                    .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?,
+                None => println!("{}", json),
+            }
+        }
+        Command::Import { file } => {
+            let json = std::fs::read_to_string(&file)
+                .map_err(|e| format!("Failed to read {}: {}", file.display(), e))?;
+            let profile = profile::import(&json, &fetch_outputs().unwrap_or_default())?;
+            let name = profile.name.clone();
+            let mut settings = AppSettings::load();
+            settings.store_profile(profile);
+            settings.save()?;
+            println!("Imported profile '{}'", name);
+        }
+        Command::Report { file } => {
+            let outputs = fetch_outputs()?;
+            match file {
+                Some(path) => {
+                    let diagram = report::write(&path, &outputs)?;
+                    println!("Wrote {} and {}", path.display(), diagram.display());
+                }
+                None => print!("{}", report::markdown(&outputs, None)),
+            }
+        }
+        Command::Config {
+            profile: name,
+            format,
+        } => {
+            let settings = AppSettings::load();
+            let profile = settings
+                .profile(&name)
+                .ok_or_else(|| format!("No profile named '{}'", name))?;
+            let format = format.unwrap_or(settings.save_format);
+            let mut outputs = fetch_outputs()?;
+            if profile.apply_to(&mut outputs) == 0 {
+                return Err(format!(
+                    "None of the outputs in profile '{}' are connected",
+                    name
+                ));
+            }
+            print!("{}", config_text(&outputs, format));
+            if let Some(section) = profile.section(format) {
+                println!("\n{}", section.trim_end());
+            }
+        }
+        Command::Toggle { output } => {
+            let mut outputs = fetch_outputs()?;
+            let enabled = !find(&outputs, &output)?.enabled;
+            if dry_run {
+                for out in outputs.iter_mut().filter(|o| o.name == output) {
+                    out.enabled = enabled;
+                }
+                println!("{}", backend::active().preview(&outputs));
+                return Ok(());
+            }
+            ipc::set_enabled(&output, enabled)?;
+            let state = if enabled { "enabled" } else { "disabled" };
+            println!("{} {}", output, state);
+        }
+        Command::Daemon => crate::daemon::run()?,
+        Command::Waybar => loop {
+            match fetch_outputs() {
+                Ok(outputs) => println!("{}", waybar_status(&outputs, &AppSettings::load())),
+                Err(e) => println!(
+                    "{}",
+                    serde_json::json!({ "text": "", "tooltip": e, "class": "error" })
+                ),
+            }
+            std::io::Write::flush(&mut std::io::stdout()).map_err(|e| e.to_string())?;
+            wait_for_output_change()?;
+        },
+        Command::Cycle { reverse } => {
+            let settings = AppSettings::load();
+            let count = settings.profiles.len();
+            if count == 0 {
+                return Err("No saved profiles".to_string());
+            }
+            let outputs = fetch_outputs()?;
+            let next = match settings.profiles.iter().position(|p| p.is_active(&outputs)) {
+                Some(i) if reverse => (i + count - 1) % count,
+                Some(i) => (i + 1) % count,
+                None => 0,
+            };
+            let profile = &settings.profiles[next];
+            if dry_run {
+                let mut outputs = outputs;
+                profile.apply_to(&mut outputs);
+                println!("{}", backend::active().preview(&outputs));
+                return Ok(());
+            }
+            ipc::apply_profile(profile)?;
+            println!("Applied profile '{}'", profile.name);
+        }
+        Command::Backends => {
+            let settings = AppSettings::load();
+            let selected = settings.backend.clone();
+            let marker = |active: bool| if active { "*" } else { " " };
+            println!(
+                "{} auto  (Wayland, then the first tool that works)",
+                marker(selected.is_none())
+            );
+            println!(
+                "{} wayland  (built-in)",
+                marker(selected.as_deref() == Some("wayland"))
+            );
+            for tool in Tool::ALL {
+                let location = match tool.program(&settings) {
+                    Some(path) => path.display().to_string(),
+                    None => "not found".to_string(),
+                };
+                println!(
+                    "{} {}  {}",
+                    marker(selected.as_deref() == Some(tool.name())),
+                    tool,
+                    location
+                );
+            }
+            for plugin in plugin::discover() {
+                println!(
+                    "{} {}  {}",
+                    marker(selected.as_deref() == Some(plugin.name.as_str())),
+                    plugin.name,
+                    plugin.path.display()
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+/// One line of waybar's custom module JSON: the active profile as the text and one line
+/// per output in the tooltip.
+fn waybar_status(outputs: &[Output], settings: &AppSettings) -> serde_json::Value {
+    let active = settings.profiles.iter().find(|p| p.is_active(outputs));
+    let tooltip = sorted(outputs.to_vec())
+        .iter()
+        .map(summary)
+        .collect::<Vec<_>>()
+        .join("\n");
+    serde_json::json!({
+        "text": active.map_or("custom", |p| p.name.as_str()),
+        "alt": active.map_or("custom", |_| "profile"),
+        "tooltip": tooltip,
+        "class": if active.is_some() { "profile" } else { "custom" },
+    })
+}
+
+fn sorted(mut outputs: Vec<Output>) -> Vec<Output> {
+    outputs.sort_by(|a, b| a.name.cmp(&b.name));
+    outputs
+}
+
+fn find<'a>(outputs: &'a [Output], name: &str) -> Result<&'a Output, String> {
+    outputs
+        .iter()
+        .find(|o| o.name == name)
+        .ok_or_else(|| format!("No output named '{}'", name))
+}
+
+fn summary(out: &Output) -> String {
+    if !out.enabled {
+        return format!("{}  disabled  {}", out.name, out.description);
+    }
+    let mode = out
+        .modes
+        .iter()
+        .find(|m| m.current)
+        .map(|m| format!("{}x{}@{:.3}", m.width, m.height, m.refresh_rate))
+        .unwrap_or_else(|| "unknown".to_string());
+    format!(
+        "{}  {}  {},{}  scale {}  {}  {}",
+        out.name, mode, out.position.0, out.position.1, out.scale, out.transform, out.description
+    )
+}
+
+fn print_details(out: &Output) {
+    println!("{}", out.name);
+    println!("  Description: {}", out.description);
+    println!("  Enabled: {}", out.enabled);
+    println!("  Position: {},{}", out.position.0, out.position.1);
+    println!("  Scale: {}", out.scale);
+    println!("  Transform: {}", out.transform);
+    println!("  Physical size: {}", out.physical_size);
+    println!("  Modes:");
+    for m in &out.modes {
+        let mut flags = String::new();
+        if m.current {
+            flags.push_str(" current");
+        }
+        if m.preferred {
+            flags.push_str(" preferred");
+        }
+        println!(
+            "    {}x{}@{:.3}{}",
+            m.width, m.height, m.refresh_rate, flags
+        );
+    }
+}
@@ -0,0 +1,1017 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::OnceLock;
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::dbus;
+use crate::gamemode;
+use crate::i18n::tr;
+use crate::ipc::{self, DaemonStatus};
+use crate::notify;
+use crate::power;
+use crate::sensors;
+use crate::watch;
+use mdisplay_core::backend::{self, Backend, Output, fetch_outputs};
+use mdisplay_core::brightness;
+use mdisplay_core::connector::ConnectorKind;
+use mdisplay_core::dock;
+use mdisplay_core::gamma::{self, GammaControl};
+use mdisplay_core::history::{self, EventKind};
+use mdisplay_core::hooks;
+use mdisplay_core::profile::{self, MachineState, Profile};
+use mdisplay_core::settings::{AppSettings, BatteryAction};
+use mdisplay_core::status;
+use mdisplay_core::wayland::wait_for_output_change;
+
+/// How often the night light schedule is re-evaluated.
+const GAMMA_INTERVAL: Duration = Duration::from_secs(60);
+/// How long a dock's profile waits for all of its outputs to show up after the dock is
+/// plugged in, before being applied to the ones that did.
+const DOCK_SETTLE: Duration = Duration::from_secs(30);
+/// How long after a resume or a compositor restart a scrambled layout is put back, as
+/// outputs come back one by one.
+const RESTORE_WINDOW: Duration = Duration::from_secs(30);
+/// How long a compositor that went away has to come back before the daemon gives up.
+const RECONNECT_WINDOW: Duration = Duration::from_secs(30);
+/// Auto-brightness changes smaller than this many percent are skipped, so sensor noise
+/// does not make the screen pulse.
+const MIN_BRIGHTNESS_STEP: u8 = 3;
+
+/// Logs `text` and records it in the history, where the window's History panel shows it.
+/// Applies, failures and hotplugs also show a desktop notification when those are on.
+fn note(settings: &AppSettings, kind: EventKind, text: impl Into<String>) {
+    let text = text.into();
+    if kind == EventKind::Failed {
+        tracing::warn!("{}", text);
+    } else {
+        tracing::info!("{}", text);
+    }
+    if let Some(summary) = notification(kind) {
+        notify::event(settings, summary, &text);
+    }
+    history::record(kind, text);
+}
+
+/// The summary of the desktop notification a note of `kind` gets, if any. Reverts and
+/// session events only go to the history.
+fn notification(kind: EventKind) -> Option<&'static str> {
+    match kind {
+        EventKind::Applied => Some(tr("Layout changed")),
+        EventKind::Failed => Some(tr("Apply failed")),
+        EventKind::Hotplug => Some(tr("Monitors changed")),
+        EventKind::Reverted | EventKind::Session => None,
+    }
+}
+
+/// Notes the outputs in `after` that were not in `before`, and the other way round.
+fn note_hotplugs(settings: &AppSettings, before: &[Output], after: &[Output]) {
+    for out in after {
+        if !before.iter().any(|o| o.name == out.name) {
+            note(
+                settings,
+                EventKind::Hotplug,
+                format!("{} connected ({})", out.name, out.description),
+            );
+        }
+    }
+    for out in before {
+        if !after.iter().any(|o| o.name == out.name) {
+            note(
+                settings,
+                EventKind::Hotplug,
+                format!("{} disconnected", out.name),
+            );
+        }
+    }
+}
+
+enum Event {
+    OutputsChanged,
+    SettingsChanged,
+    /// Let go of or take back an output's gamma, answering once done.
+    PauseGamma(String, bool, Sender<()>),
+    /// A window asking what the daemon is doing, and whether to hold off for a while.
+    Status(bool, Sender<DaemonStatus>),
+    /// A new reading from the ambient light sensor.
+    LightLevel(f64),
+    /// The machine switched to battery (`true`) or back to AC.
+    PowerChanged(bool),
+    /// The lid was closed (`true`) or opened.
+    LidChanged(bool),
+    /// The device was turned; carries the transform that keeps the built-in panel upright.
+    Turned(&'static str),
+    /// A USB device was plugged in or removed.
+    UsbChanged,
+    /// The first game turned GameMode on (`true`) or the last one exited.
+    GameModeChanged(bool),
+    /// The machine is about to suspend (`true`) or has resumed.
+    Sleeping(bool),
+    /// The compositor started over, dropping the layout and every gamma table.
+    CompositorRestarted,
+    Failed(String),
+}
+
+static EVENTS: OnceLock<Sender<Event>> = OnceLock::new();
+
+/// Gamma tables the daemon keeps applied, by output name. The compositor resets an
+/// output as soon as its [`GammaControl`] is dropped, so they live as long as the daemon.
+#[derive(Default)]
+struct GammaTables {
+    held: HashMap<String, (GammaControl, (f32, u32))>,
+    paused: HashSet<String>,
+}
+
+impl GammaTables {
+    /// Brings every enabled output to the gamma and temperature its preferences call for
+    /// right now, and releases the ones left neutral.
+    fn sync(&mut self, outputs: &[Output], settings: &AppSettings) {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs() as i64);
+        let neutral = (1.0, gamma::NEUTRAL_TEMPERATURE);
+        let wanted: HashMap<String, (f32, u32)> = outputs
+            .iter()
+            .filter(|o| o.enabled && !self.paused.contains(&o.name))
+            .map(|o| {
+                let prefs = settings.output_prefs(&o.identity());
+                let target = gamma::target(&prefs, &settings.night_schedule, now);
+                (o.name.clone(), target)
+            })
+            .filter(|(_, target)| *target != neutral)
+            .collect();
+        self.held.retain(|name, _| wanted.contains_key(name));
+
+        for (name, target) in wanted {
+            if self
+                .held
+                .get(&name)
+                .is_some_and(|(_, held)| *held == target)
+            {
+                continue;
+            }
+            let control = match self.held.remove(&name) {
+                Some((control, _)) => Ok(control),
+                None => GammaControl::new(&name),
+            };
+            match control.and_then(|mut c| c.set(target.0, target.1).map(|()| c)) {
+                Ok(control) => {
+                    tracing::debug!("Gamma of {} set to {:?}", name, target);
+                    self.held.insert(name, (control, target));
+                }
+                Err(e) => tracing::warn!("{}", e),
+            }
+        }
+    }
+}
+
+/// Brightness driven by the ambient light sensor.
+#[derive(Default)]
+struct AutoBrightness {
+    /// Looked up when first needed and again after the outputs change.
+    controls: Option<Vec<(String, brightness::Control)>>,
+    applied: HashMap<String, u8>,
+    lux: Option<f64>,
+}
+
+impl AutoBrightness {
+    /// Outputs in `dimmed` are left alone until they are restored.
+    fn update(
+        &mut self,
+        outputs: &[Output],
+        settings: &AppSettings,
+        dimmed: &HashMap<String, (brightness::Control, u8)>,
+    ) {
+        let auto = &settings.auto_brightness;
+        let Some(lux) = self.lux.filter(|_| auto.enabled) else {
+            return;
+        };
+        let controls = self.controls.get_or_insert_with(|| {
+            let names: Vec<String> = outputs
+                .iter()
+                .filter(|o| o.enabled)
+                .map(|o| o.name.clone())
+                .collect();
+            brightness::detect(&names)
+        });
+        let target = auto.percent_for(lux);
+        for (name, control) in controls.iter() {
+            if dimmed.contains_key(name)
+                || !auto.external && !ConnectorKind::from_name(name).is_builtin()
+            {
+                continue;
+            }
+            // Linked outputs keep their balance under auto-brightness as well.
+            let balance = outputs
+                .iter()
+                .find(|o| &o.name == name)
+                .filter(|_| settings.brightness_linked)
+                .and_then(|o| settings.output_prefs(&o.identity()).brightness_balance)
+                .unwrap_or(1.0);
+            let percent = (target as f32 * balance).round().clamp(0.0, 100.0) as u8;
+            if self
+                .applied
+                .get(name)
+                .is_some_and(|p| p.abs_diff(percent) < MIN_BRIGHTNESS_STEP)
+            {
+                continue;
+            }
+            match control.set(percent) {
+                Ok(()) => {
+                    tracing::debug!("{} lux: brightness of {} set to {}%", lux, name, percent);
+                    self.applied.insert(name.clone(), percent);
+                }
+                Err(e) => tracing::warn!("{}", e),
+            }
+        }
+    }
+}
+
+/// Docks seen on the last USB change, and the profile waiting for a newly plugged dock's
+/// outputs to come up.
+#[derive(Default)]
+struct DockRules {
+    connected: HashSet<String>,
+    pending: Option<(String, Instant)>,
+}
+
+impl DockRules {
+    fn update(&mut self, settings: &AppSettings) {
+        let docks = dock::list();
+        for dock in &docks {
+            if self.connected.contains(&dock.id) {
+                continue;
+            }
+            note(
+                settings,
+                EventKind::Hotplug,
+                format!("Dock {} ({}) plugged in", dock.name, dock.id),
+            );
+            if let Some(name) = settings.dock_profiles.get(&dock.id) {
+                self.pending = Some((name.clone(), Instant::now()));
+            }
+        }
+        let ids: HashSet<String> = docks.into_iter().map(|d| d.id).collect();
+        for id in self.connected.difference(&ids) {
+            note(
+                settings,
+                EventKind::Hotplug,
+                format!("Dock {} unplugged", id),
+            );
+        }
+        self.connected = ids;
+    }
+
+    /// Applies the pending profile once all of its outputs are connected, or to those
+    /// that are once [`DOCK_SETTLE`] has passed.
+    fn apply_pending(&mut self, backend: &dyn Backend, outputs: &[Output], settings: &AppSettings) {
+        let Some((name, since)) = &self.pending else {
+            return;
+        };
+        let Some(profile) = settings.profile(name) else {
+            note(
+                settings,
+                EventKind::Failed,
+                format!("The profile '{}' bound to a dock no longer exists", name),
+            );
+            self.pending = None;
+            return;
+        };
+        let ready = profile
+            .outputs
+            .iter()
+            .all(|c| outputs.iter().any(|o| o.name == c.name));
+        if !ready && since.elapsed() < DOCK_SETTLE {
+            return;
+        }
+        if !profile.is_active(outputs) {
+            match profile::activate_with(backend, profile) {
+                Ok(()) => note(
+                    settings,
+                    EventKind::Applied,
+                    format!("Docked: applied profile '{}'", name),
+                ),
+                Err(e) => note(settings, EventKind::Failed, e),
+            }
+        }
+        self.pending = None;
+    }
+}
+
+/// The machine state profile rules are checked against, and the profile they last chose.
+/// A profile is only applied when the choice changes, so a layout picked by hand stays
+/// until the power source, the lid or the connected outputs change.
+#[derive(Default)]
+struct ProfileRules {
+    state: MachineState,
+    chosen: Option<String>,
+}
+
+impl ProfileRules {
+    fn apply(&mut self, backend: &dyn Backend, outputs: &[Output], settings: &AppSettings) {
+        let Some(profile) = profile::pick(&settings.profiles, self.state, outputs) else {
+            self.chosen = None;
+            return;
+        };
+        if self.chosen.as_deref() == Some(profile.name.as_str()) {
+            return;
+        }
+        self.chosen = Some(profile.name.clone());
+        if profile.is_active(outputs) {
+            return;
+        }
+        match profile::activate_with(backend, profile) {
+            Ok(()) => note(
+                settings,
+                EventKind::Applied,
+                format!("Rules: applied profile '{}'", profile.name),
+            ),
+            Err(e) => note(settings, EventKind::Failed, e),
+        }
+    }
+}
+
+/// The layout to put back after a resume or a compositor restart. Some GPUs and
+/// compositors come back with outputs moved, at another mode or turned off.
+#[derive(Default)]
+struct RestoreRules {
+    before_sleep: Option<Profile>,
+    pending: Option<(Profile, Instant)>,
+}
+
+impl RestoreRules {
+    /// The saved profile `outputs` are in, or else the layout as it is.
+    fn snapshot(outputs: &[Output], settings: &AppSettings) -> Option<Profile> {
+        if outputs.is_empty() {
+            return None;
+        }
+        let profile = settings.profiles.iter().find(|p| p.is_active(outputs));
+        Some(
+            profile
+                .cloned()
+                .unwrap_or_else(|| Profile::from_outputs("", outputs)),
+        )
+    }
+
+    fn sleeping(&mut self, sleeping: bool, outputs: &[Output], settings: &AppSettings) {
+        if sleeping {
+            note(settings, EventKind::Session, "Suspending");
+            self.before_sleep = Self::snapshot(outputs, settings);
+        } else if let Some(profile) = self.before_sleep.take() {
+            note(settings, EventKind::Session, "Resumed from suspend");
+            self.pending = Some((profile, Instant::now()));
+        }
+    }
+
+    /// Remembers the layout the compositor had before it restarted, `outputs` being the
+    /// last read.
+    fn restarted(&mut self, outputs: &[Output], settings: &AppSettings) {
+        note(settings, EventKind::Session, "The compositor restarted");
+        if let Some(profile) = Self::snapshot(outputs, settings) {
+            self.pending = Some((profile, Instant::now()));
+        }
+    }
+
+    /// Puts the pending layout back once all of its outputs are connected, if they are not
+    /// laid out that way. Only the first difference within [`RESTORE_WINDOW`] is undone,
+    /// so later changes by hand stay.
+    fn apply_pending(&mut self, backend: &dyn Backend, outputs: &[Output], settings: &AppSettings) {
+        let Some((profile, since)) = &self.pending else {
+            return;
+        };
+        if since.elapsed() >= RESTORE_WINDOW {
+            self.pending = None;
+            return;
+        }
+        let ready = profile
+            .outputs
+            .iter()
+            .all(|c| outputs.iter().any(|o| o.name == c.name));
+        if !ready || profile.is_active(outputs) {
+            return;
+        }
+        match profile::activate_with(backend, profile) {
+            Ok(()) if profile.name.is_empty() => {
+                note(settings, EventKind::Reverted, "Put the layout back")
+            }
+            Ok(()) => note(
+                settings,
+                EventKind::Reverted,
+                format!("Applied profile '{}' again", profile.name),
+            ),
+            Err(e) => note(settings, EventKind::Failed, e),
+        }
+        self.pending = None;
+    }
+}
+
+/// The layout from before the GameMode profile was applied, kept to go back to once the
+/// last game exits.
+#[derive(Default)]
+struct GameModeRules {
+    previous: Option<Profile>,
+}
+
+impl GameModeRules {
+    fn update(
+        &mut self,
+        backend: &dyn Backend,
+        active: bool,
+        outputs: &[Output],
+        settings: &AppSettings,
+    ) {
+        if !active {
+            let Some(previous) = self.previous.take() else {
+                return;
+            };
+            match profile::activate_with(backend, &previous) {
+                Ok(()) => note(
+                    settings,
+                    EventKind::Reverted,
+                    "GameMode: put the layout from before back",
+                ),
+                Err(e) => note(settings, EventKind::Failed, e),
+            }
+            return;
+        }
+        if self.previous.is_some() {
+            return;
+        }
+        let Some(name) = &settings.gamemode_profile else {
+            return;
+        };
+        let Some(profile) = settings.profiles.iter().find(|p| &p.name == name) else {
+            note(
+                settings,
+                EventKind::Failed,
+                format!("GameMode: there is no profile '{}'", name),
+            );
+            return;
+        };
+        if profile.is_active(outputs) {
+            return;
+        }
+        match profile::activate_with(backend, profile) {
+            Ok(()) => {
+                note(
+                    settings,
+                    EventKind::Applied,
+                    format!("GameMode: applied profile '{}'", name),
+                );
+                self.previous = Some(Profile::from_outputs("", outputs));
+            }
+            Err(e) => note(settings, EventKind::Failed, e),
+        }
+    }
+}
+
+/// External outputs dimmed or turned off while on battery, kept so they can be put back
+/// on AC.
+#[derive(Default)]
+struct BatteryRules {
+    on_battery: bool,
+    disabled: Vec<String>,
+    /// Each dimmed output's control and its brightness before.
+    dimmed: HashMap<String, (brightness::Control, u8)>,
+}
+
+impl BatteryRules {
+    fn update(
+        &mut self,
+        backend: &dyn Backend,
+        on_battery: bool,
+        outputs: &[Output],
+        settings: &AppSettings,
+    ) {
+        if on_battery == self.on_battery {
+            return;
+        }
+        self.on_battery = on_battery;
+        if on_battery {
+            self.apply(backend, outputs, settings);
+        } else {
+            self.restore(backend, settings);
+        }
+    }
+
+    fn apply(&mut self, backend: &dyn Backend, outputs: &[Output], settings: &AppSettings) {
+        let rules: Vec<(&Output, BatteryAction)> = outputs
+            .iter()
+            .filter(|o| o.enabled && !ConnectorKind::from_name(&o.name).is_builtin())
+            .map(|o| (o, settings.output_prefs(&o.identity()).on_battery))
+            .collect();
+
+        let to_dim: Vec<String> = rules
+            .iter()
+            .filter(|(_, action)| *action == BatteryAction::Dim)
+            .map(|(o, _)| o.name.clone())
+            .collect();
+        if !to_dim.is_empty() {
+            for (name, control) in brightness::detect(&to_dim) {
+                let previous = match control.get() {
+                    Ok(percent) => percent,
+                    Err(e) => {
+                        tracing::warn!("{}", e);
+                        continue;
+                    }
+                };
+                match control.set(settings.battery_brightness) {
+                    Ok(()) => {
+                        tracing::info!("On battery: dimmed {}", name);
+                        self.dimmed.insert(name, (control, previous));
+                    }
+                    Err(e) => tracing::warn!("{}", e),
+                }
+            }
+        }
+
+        let mut enabled = outputs.iter().filter(|o| o.enabled).count();
+        let mut to_disable = Vec::new();
+        for (output, action) in rules {
+            if action != BatteryAction::Disable {
+                continue;
+            }
+            // Never leave the session without a screen, e.g. with the lid closed.
+            if enabled <= 1 {
+                tracing::info!("On battery: keeping {}, the last output on", output.name);
+                break;
+            }
+            to_disable.push(output.name.clone());
+            enabled -= 1;
+        }
+        if to_disable.is_empty() {
+            return;
+        }
+        let mut next = outputs.to_vec();
+        for out in next.iter_mut().filter(|o| to_disable.contains(&o.name)) {
+            out.enabled = false;
+        }
+        match hooks::apply_with(backend, &next, None) {
+            Ok(()) => {
+                note(
+                    settings,
+                    EventKind::Applied,
+                    format!("On battery: turned off {}", to_disable.join(", ")),
+                );
+                self.disabled = to_disable;
+            }
+            Err(e) => note(settings, EventKind::Failed, e),
+        }
+    }
+
+    fn restore(&mut self, backend: &dyn Backend, settings: &AppSettings) {
+        let disabled = std::mem::take(&mut self.disabled);
+        if !disabled.is_empty() {
+            let result = backend.fetch_outputs().and_then(|mut next| {
+                for out in next.iter_mut().filter(|o| disabled.contains(&o.name)) {
+                    out.enabled = true;
+                }
+                hooks::apply_with(backend, &next, None)
+            });
+            match result {
+                Ok(()) => note(
+                    settings,
+                    EventKind::Reverted,
+                    format!("On AC: turned {} back on", disabled.join(", ")),
+                ),
+                Err(e) => note(settings, EventKind::Failed, e),
+            }
+        }
+        for (name, (control, previous)) in self.dimmed.drain() {
+            match control.set(previous) {
+                Ok(()) => tracing::info!("On AC: restored the brightness of {}", name),
+                Err(e) => tracing::warn!("{}", e),
+            }
+        }
+    }
+}
+
+/// Waits up to [`RECONNECT_WINDOW`] for a compositor to answer `backend` again.
+fn reconnect(backend: &dyn Backend) -> Result<(), String> {
+    let deadline = Instant::now() + RECONNECT_WINDOW;
+    loop {
+        match backend.fetch_outputs() {
+            Ok(_) => return Ok(()),
+            Err(e) if Instant::now() >= deadline => return Err(e),
+            Err(_) => std::thread::sleep(Duration::from_secs(1)),
+        }
+    }
+}
+
+/// Turns the enabled built-in panel to `transform`, unless it is already there.
+fn rotate_builtin(
+    backend: &dyn Backend,
+    outputs: &[Output],
+    transform: &str,
+    settings: &AppSettings,
+) {
+    let mut next = outputs.to_vec();
+    let Some(panel) = next.iter_mut().find(|o| {
+        o.enabled && ConnectorKind::from_name(&o.name).is_builtin() && o.transform != transform
+    }) else {
+        return;
+    };
+    note(
+        settings,
+        EventKind::Applied,
+        format!("Rotating {} to {}", panel.name, transform),
+    );
+    panel.transform = transform.to_string();
+    if let Err(e) = hooks::apply_with(backend, &next, None) {
+        note(settings, EventKind::Failed, e);
+    }
+}
+
+/// Stops holding `output`'s gamma so another program can take it, or resumes. Only the
+/// daemon holds gamma, so elsewhere this fails.
+pub fn pause_gamma(output: &str, paused: bool) -> Result<(), String> {
+    let events = EVENTS
+        .get()
+        .ok_or_else(|| "Gamma is only managed by the daemon".to_string())?;
+    let (done, wait) = mpsc::channel();
+    events
+        .send(Event::PauseGamma(output.to_string(), paused, done))
+        .map_err(|_| "The daemon is shutting down".to_string())?;
+    wait.recv()
+        .map_err(|_| "The daemon is shutting down".to_string())
+}
+
+/// What the daemon is doing, for a window; with `hold`, automatic applies wait for
+/// [`ipc::HOLD_LEASE`]. Only the daemon answers, so elsewhere this fails.
+pub fn status(hold: bool) -> Result<DaemonStatus, String> {
+    let events = EVENTS
+        .get()
+        .ok_or_else(|| "Only the daemon reports its status".to_string())?;
+    let (reply, wait) = mpsc::channel();
+    events
+        .send(Event::Status(hold, reply))
+        .map_err(|_| "The daemon is shutting down".to_string())?;
+    wait.recv()
+        .map_err(|_| "The daemon is shutting down".to_string())
+}
+
+/// Runs the background service: the D-Bus interface, the IPC socket, a watch on the
+/// compositor's output configuration, the night light and calibrated gamma,
+/// auto-brightness, auto-rotation, the rules for external outputs on battery, the
+/// profiles bound to docks, profile rules, the GameMode profile, and putting the layout
+/// back after a resume or a compositor restart.
+pub fn run() -> Result<(), String> {
+    let connection = dbus::serve()?;
+    ipc::serve()?;
+
+    let (sender, events) = mpsc::channel();
+    let _ = EVENTS.set(sender.clone());
+    let output_events = sender.clone();
+    std::thread::spawn(move || {
+        let mut last = None;
+        loop {
+            let event = match wait_for_output_change() {
+                Ok(serial) if last.is_some_and(|last| serial < last) => {
+                    last = Some(serial);
+                    Event::CompositorRestarted
+                }
+                Ok(serial) => {
+                    last = Some(serial);
+                    Event::OutputsChanged
+                }
+                // Lost a compositor that was there before: wait for it to come back.
+                Err(e) if last.is_some() => {
+                    match reconnect(backend::from_settings(&AppSettings::load()).as_ref()) {
+                        Ok(()) => Event::CompositorRestarted,
+                        Err(_) => Event::Failed(e),
+                    }
+                }
+                Err(e) => Event::Failed(e),
+            };
+            if output_events.send(event).is_err() {
+                return;
+            }
+        }
+    });
+    let light_events = sender.clone();
+    std::thread::spawn(move || {
+        let result = sensors::watch_light(|lux| light_events.send(Event::LightLevel(lux)).is_ok());
+        if let Err(e) = result {
+            tracing::info!("No auto-brightness: {}", e);
+        }
+    });
+    let turn_events = sender.clone();
+    std::thread::spawn(move || {
+        let result = sensors::watch_orientation(|transform| {
+            turn_events.send(Event::Turned(transform)).is_ok()
+        });
+        if let Err(e) = result {
+            tracing::info!("No auto-rotation: {}", e);
+        }
+    });
+    let power_events = sender.clone();
+    std::thread::spawn(move || {
+        let result =
+            power::watch(|on_battery| power_events.send(Event::PowerChanged(on_battery)).is_ok());
+        if let Err(e) = result {
+            tracing::info!("Not following the power source: {}", e);
+        }
+    });
+    let lid_events = sender.clone();
+    std::thread::spawn(move || {
+        let result = power::watch_lid(|closed| lid_events.send(Event::LidChanged(closed)).is_ok());
+        if let Err(e) = result {
+            tracing::info!("Not following the lid: {}", e);
+        }
+    });
+    let usb_events = sender.clone();
+    std::thread::spawn(move || {
+        let result = watch::watch_usb(|| usb_events.send(Event::UsbChanged).is_ok());
+        if let Err(e) = result {
+            tracing::info!("Not following docks: {}", e);
+        }
+    });
+    let sleep_events = sender.clone();
+    std::thread::spawn(move || {
+        let result =
+            power::watch_sleep(|sleeping| sleep_events.send(Event::Sleeping(sleeping)).is_ok());
+        if let Err(e) = result {
+            tracing::info!("Not following suspend: {}", e);
+        }
+    });
+    let game_events = sender.clone();
+    std::thread::spawn(move || {
+        let result =
+            gamemode::watch(|active| game_events.send(Event::GameModeChanged(active)).is_ok());
+        if let Err(e) = result {
+            tracing::info!("Not following GameMode: {}", e);
+        }
+    });
+    std::thread::spawn(move || {
+        let result = watch::watch_files(&[AppSettings::path()], |_| {
+            sender.send(Event::SettingsChanged).is_ok()
+        });
+        if let Err(e) = result {
+            tracing::warn!("Not watching the settings: {}", e);
+        }
+    });
+
+    let mut settings = AppSettings::load();
+    let mut backend = backend::from_settings(&settings);
+    let mut outputs = fetch_outputs().unwrap_or_else(|e| {
+        tracing::error!("Error reading outputs: {}", e);
+        Vec::new()
+    });
+    let mut gamma = GammaTables::default();
+    gamma.sync(&outputs, &settings);
+    let mut auto_brightness = AutoBrightness::default();
+    let mut battery = BatteryRules::default();
+    let mut orientation = None;
+    let mut docks = DockRules::default();
+    docks.update(&settings);
+    let mut rules = ProfileRules::default();
+    let mut gamemode = GameModeRules::default();
+    let mut restore = RestoreRules::default();
+    // Until when a window with edits of its own asked the rules to leave the layout alone.
+    let mut held_until: Option<Instant> = None;
+
+    loop {
+        // Wake up when a hold runs out, to catch up on what it held back.
+        let timeout = held_until
+            .and_then(|until| until.checked_duration_since(Instant::now()))
+            .map_or(GAMMA_INTERVAL, |left| left.min(GAMMA_INTERVAL));
+        match events.recv_timeout(timeout) {
+            Ok(Event::OutputsChanged) => match fetch_outputs() {
+                Ok(current) => {
+                    note_hotplugs(&settings, &outputs, &current);
+                    outputs = current;
+                    auto_brightness.controls = None;
+                    let emitted = status::outputs_json(&outputs)
+                        .and_then(|json| dbus::emit_layout_changed(&connection, &json));
+                    if let Err(e) = emitted {
+                        tracing::warn!("{}", e);
+                    }
+                }
+                Err(e) => tracing::error!("Error reading outputs: {}", e),
+            },
+            Ok(Event::SettingsChanged) => {
+                settings = AppSettings::load();
+                backend = backend::from_settings(&settings);
+                auto_brightness.update(&outputs, &settings, &battery.dimmed);
+                if let Some(transform) = orientation.filter(|_| settings.auto_rotate) {
+                    rotate_builtin(backend.as_ref(), &outputs, transform, &settings);
+                }
+            }
+            Ok(Event::Turned(transform)) => {
+                orientation = Some(transform);
+                if settings.auto_rotate {
+                    rotate_builtin(backend.as_ref(), &outputs, transform, &settings);
+                }
+            }
+            Ok(Event::UsbChanged) => docks.update(&settings),
+            Ok(Event::LightLevel(lux)) => {
+                auto_brightness.lux = Some(lux);
+                auto_brightness.update(&outputs, &settings, &battery.dimmed);
+            }
+            Ok(Event::PowerChanged(on_battery)) => {
+                rules.state.on_battery = Some(on_battery);
+                battery.update(backend.as_ref(), on_battery, &outputs, &settings);
+                // Restored outputs go back under auto-brightness.
+                auto_brightness.applied.clear();
+                auto_brightness.update(&outputs, &settings, &battery.dimmed);
+            }
+            Ok(Event::LidChanged(closed)) => rules.state.lid_closed = Some(closed),
+            Ok(Event::GameModeChanged(active)) => {
+                gamemode.update(backend.as_ref(), active, &outputs, &settings)
+            }
+            Ok(Event::Sleeping(sleeping)) => restore.sleeping(sleeping, &outputs, &settings),
+            Ok(Event::CompositorRestarted) => {
+                restore.restarted(&outputs, &settings);
+                // Gamma controls belonged to the old compositor.
+                gamma.held.clear();
+                match fetch_outputs() {
+                    Ok(current) => {
+                        outputs = current;
+                        auto_brightness.controls = None;
+                    }
+                    Err(e) => tracing::error!("Error reading outputs: {}", e),
+                }
+            }
+            Ok(Event::PauseGamma(output, paused, done)) => {
+                if paused {
+                    gamma.held.remove(&output);
+                    gamma.paused.insert(output);
+                } else {
+                    gamma.paused.remove(&output);
+                }
+                gamma.sync(&outputs, &settings);
+                let _ = done.send(());
+                continue;
+            }
+            Ok(Event::Status(hold, reply)) => {
+                if hold {
+                    held_until = Some(Instant::now() + ipc::HOLD_LEASE);
+                }
+                let _ = reply.send(DaemonStatus {
+                    profile: settings
+                        .profiles
+                        .iter()
+                        .find(|p| p.is_active(&outputs))
+                        .map(|p| p.name.clone()),
+                    held: held_until.is_some_and(|until| Instant::now() < until),
+                });
+                continue;
+            }
+            Ok(Event::Failed(e)) => return Err(e),
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => return Ok(()),
+        }
+        if held_until.is_none_or(|until| Instant::now() >= until) {
+            docks.apply_pending(backend.as_ref(), &outputs, &settings);
+            restore.apply_pending(backend.as_ref(), &outputs, &settings);
+            rules.apply(backend.as_ref(), &outputs, &settings);
+        }
+        gamma.sync(&outputs, &settings);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mdisplay_core::backend::Backend;
+    use mdisplay_core::mock::MockBackend;
+    use mdisplay_core::profile::ProfileRule;
+
+    const FIXTURE: &str = include_str!("../mdisplay-core/fixtures/dual_monitor.json");
+
+    /// Settings that keep notifications off, since tests have no session bus to show them.
+    fn quiet(profiles: Vec<Profile>) -> AppSettings {
+        AppSettings {
+            profiles,
+            notifications: false,
+            ..Default::default()
+        }
+    }
+
+    /// Whether the history holds an event of `kind` with `text`. Tests share one history,
+    /// so it is searched rather than read from the end.
+    fn recorded(kind: EventKind, text: &str) -> bool {
+        history::load()
+            .iter()
+            .any(|e| e.kind == kind && e.text.starts_with(text))
+    }
+
+    #[test]
+    fn hotplugs_are_recorded_and_notified() {
+        crate::testing::isolate_dirs();
+        let after = MockBackend::from_json(FIXTURE)
+            .unwrap()
+            .fetch_outputs()
+            .unwrap();
+        note_hotplugs(&quiet(Vec::new()), &after[..2], &after);
+        assert!(recorded(EventKind::Hotplug, "eDP-1 connected"));
+        assert!(notification(EventKind::Hotplug).is_some());
+        assert!(notification(EventKind::Applied).is_some());
+        assert!(notification(EventKind::Failed).is_some());
+        assert!(notification(EventKind::Session).is_none());
+    }
+
+    #[test]
+    fn rules_and_docks_apply_through_the_backend_and_notify() {
+        crate::testing::isolate_dirs();
+        let backend = MockBackend::from_json(FIXTURE).unwrap();
+        let outputs = backend.fetch_outputs().unwrap();
+        let mut desk = Profile::from_outputs("desk", &outputs);
+        desk.outputs[1].position = (0, 1440);
+        desk.rule = Some(ProfileRule::default());
+        let settings = quiet(vec![desk]);
+
+        ProfileRules::default().apply(&backend, &outputs, &settings);
+        assert_eq!(backend.applied().len(), 1);
+        assert!(recorded(
+            EventKind::Applied,
+            "Rules: applied profile 'desk'"
+        ));
+
+        let mut docks = DockRules {
+            pending: Some(("desk".to_string(), Instant::now())),
+            ..Default::default()
+        };
+        docks.apply_pending(&backend, &outputs, &settings);
+        assert_eq!(backend.applied().len(), 2);
+        assert!(recorded(
+            EventKind::Applied,
+            "Docked: applied profile 'desk'"
+        ));
+        assert!(notification(EventKind::Applied).is_some());
+    }
+
+    #[test]
+    fn a_restored_layout_goes_through_the_backend() {
+        crate::testing::isolate_dirs();
+        let backend = MockBackend::from_json(FIXTURE).unwrap();
+        let outputs = backend.fetch_outputs().unwrap();
+        let mut restore = RestoreRules::default();
+        restore.sleeping(true, &outputs, &quiet(Vec::new()));
+
+        let mut scrambled = outputs.clone();
+        scrambled[1].position = (0, 1440);
+        let backend = MockBackend::new(scrambled.clone());
+        restore.sleeping(false, &scrambled, &quiet(Vec::new()));
+        restore.apply_pending(&backend, &scrambled, &quiet(Vec::new()));
+        assert_eq!(backend.applied().len(), 1);
+        assert_eq!(backend.applied()[0][1].position, outputs[1].position);
+        assert!(restore.pending.is_none());
+    }
+
+    #[test]
+    fn gamemode_applies_and_reverts_through_the_backend() {
+        crate::testing::isolate_dirs();
+        let backend = MockBackend::from_json(FIXTURE).unwrap();
+        let outputs = backend.fetch_outputs().unwrap();
+        let mut game = Profile::from_outputs("game", &outputs);
+        game.outputs[1].enabled = false;
+        let settings = AppSettings {
+            gamemode_profile: Some("game".to_string()),
+            ..quiet(vec![game])
+        };
+
+        let mut gamemode = GameModeRules::default();
+        gamemode.update(&backend, true, &outputs, &settings);
+        assert_eq!(backend.applied().len(), 1);
+        assert!(!backend.applied()[0][1].enabled);
+
+        gamemode.update(&backend, false, &backend.applied()[0], &settings);
+        assert_eq!(backend.applied().len(), 2);
+        assert!(backend.applied()[1][1].enabled);
+    }
+
+    #[test]
+    fn battery_rules_turn_outputs_off_and_on_in_one_apply() {
+        crate::testing::isolate_dirs();
+        let backend = MockBackend::from_json(FIXTURE).unwrap();
+        let outputs = backend.fetch_outputs().unwrap();
+        let mut settings = quiet(Vec::new());
+        let external: Vec<&Output> = outputs
+            .iter()
+            .filter(|o| o.enabled && !ConnectorKind::from_name(&o.name).is_builtin())
+            .collect();
+        for out in &external {
+            settings
+                .outputs
+                .entry(out.identity())
+                .or_default()
+                .on_battery = BatteryAction::Disable;
+        }
+
+        let mut battery = BatteryRules::default();
+        battery.update(&backend, true, &outputs, &settings);
+        assert_eq!(backend.applied().len(), 1);
+        let enabled = backend.applied()[0].iter().filter(|o| o.enabled).count();
+        assert!(enabled >= 1);
+        assert!(!battery.disabled.is_empty());
+
+        battery.update(&backend, false, &outputs, &settings);
+        assert_eq!(backend.applied().len(), 2);
+        assert!(battery.disabled.is_empty());
+        assert!(external.iter().all(|o| {
+            backend.applied()[1]
+                .iter()
+                .any(|a| a.name == o.name && a.enabled)
+        }));
+    }
+}
@@ -0,0 +1,71 @@
+use zbus::blocking::Connection;
+use zbus::blocking::connection::Builder;
+use zbus::object_server::SignalEmitter;
+use zbus::{fdo, interface};
+
+use mdisplay_core::backend::{fetch_outputs, set_output_enabled};
+use mdisplay_core::profile;
+use mdisplay_core::settings::AppSettings;
+use mdisplay_core::status;
+
+pub const BUS_NAME: &str = "dev.mdisplay";
+pub const OBJECT_PATH: &str = "/dev/mdisplay";
+
+/// `dev.mdisplay.Display`: profile and output control for desktop components.
+pub struct DisplayService;
+
+#[interface(name = "dev.mdisplay.Display")]
+impl DisplayService {
+    /// The connected outputs, in the same JSON shape as `mdisplay get --json`.
+    fn list_outputs(&self) -> fdo::Result<String> {
+        let outputs = fetch_outputs().map_err(fdo::Error::Failed)?;
+        status::outputs_json(&outputs).map_err(fdo::Error::Failed)
+    }
+
+    fn list_profiles(&self) -> Vec<String> {
+        AppSettings::load()
+            .profiles
+            .into_iter()
+            .map(|p| p.name)
+            .collect()
+    }
+
+    fn apply_profile(&self, name: &str) -> fdo::Result<()> {
+        let settings = AppSettings::load();
+        let profile = settings
+            .profile(name)
+            .ok_or_else(|| fdo::Error::InvalidArgs(format!("No profile named '{}'", name)))?;
+        profile::activate(profile).map_err(fdo::Error::Failed)
+    }
+
+    fn set_enabled(&self, output: &str, enabled: bool) -> fdo::Result<()> {
+        set_output_enabled(output, enabled).map_err(fdo::Error::Failed)
+    }
+
+    /// Emitted whenever the compositor reports a new output configuration.
+    #[zbus(signal)]
+    async fn layout_changed(emitter: &SignalEmitter<'_>, outputs: &str) -> zbus::Result<()>;
+}
+
+/// Claims [`BUS_NAME`] on the session bus and serves [`DisplayService`] from a background
+/// thread for as long as the returned connection lives.
+pub fn serve() -> Result<Connection, String> {
+    Builder::session()
+        .and_then(|b| b.name(BUS_NAME))
+        .and_then(|b| b.serve_at(OBJECT_PATH, DisplayService))
+        .and_then(|b| b.build())
+        .map_err(|e| format!("Failed to start D-Bus service: {}", e))
+}
+
+/// Broadcasts the current outputs through the `LayoutChanged` signal.
+pub fn emit_layout_changed(connection: &Connection, outputs_json: &str) -> Result<(), String> {
+    let iface = connection
+        .object_server()
+        .interface::<_, DisplayService>(OBJECT_PATH)
+        .map_err(|e| e.to_string())?;
+    zbus::block_on(DisplayService::layout_changed(
+        iface.signal_emitter(),
+        outputs_json,
+    ))
+    .map_err(|e| format!("Failed to emit LayoutChanged: {}", e))
+}
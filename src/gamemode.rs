@@ -0,0 +1,45 @@
+//! Whether a game is running, read from Feral GameMode on the session bus.
+
+use zbus::blocking::Connection;
+use zbus::proxy;
+
+#[proxy(
+    interface = "com.feralinteractive.GameMode",
+    default_service = "com.feralinteractive.GameMode",
+    default_path = "/com/feralinteractive/GameMode"
+)]
+trait GameMode {
+    /// How many games have registered and not yet left.
+    #[zbus(property)]
+    fn client_count(&self) -> zbus::Result<i32>;
+}
+
+/// Blocks, sending whether any game has GameMode on to `on_change` now and whenever that
+/// changes, until `on_change` returns `false`. Fails when GameMode is not installed.
+pub fn watch(mut on_change: impl FnMut(bool) -> bool) -> Result<(), String> {
+    let bus =
+        Connection::session().map_err(|e| format!("Failed to reach the session bus: {}", e))?;
+    let gamemode =
+        GameModeProxyBlocking::new(&bus).map_err(|e| format!("Failed to reach GameMode: {}", e))?;
+
+    let changes = gamemode.receive_client_count_changed();
+    let first = gamemode.client_count().map_err(|e| e.to_string())?;
+    let mut active = first > 0;
+    if !on_change(active) {
+        return Ok(());
+    }
+    for change in changes {
+        let Ok(count) = change.get() else {
+            continue;
+        };
+        // A second game joining or leaving changes nothing.
+        if (count > 0) == active {
+            continue;
+        }
+        active = count > 0;
+        if !on_change(active) {
+            return Ok(());
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,552 @@
+use std::fmt;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// UI languages with a built-in catalog. English strings double as the lookup keys, so
+/// anything missing from a catalog falls back to the English text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    English,
+    Portuguese,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::English, Language::Portuguese];
+
+    pub fn code(self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::Portuguese => "pt",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        let code = code.trim().to_lowercase();
+        Self::ALL
+            .into_iter()
+            .find(|lang| code == lang.code() || code.starts_with(&format!("{}_", lang.code())))
+    }
+
+    /// Picks the configured language, falling back to the POSIX locale variables.
+    pub fn detect(configured: Option<&str>) -> Self {
+        if let Some(lang) = configured.and_then(Self::from_code) {
+            return lang;
+        }
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .find(|v| !v.is_empty())
+            .and_then(|v| Self::from_code(v.split('.').next().unwrap_or("")))
+            .unwrap_or(Language::English)
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Language::English => "English",
+            Language::Portuguese => "Português",
+        })
+    }
+}
+
+static CURRENT: AtomicU8 = AtomicU8::new(0);
+
+pub fn set_language(lang: Language) {
+    CURRENT.store(lang as u8, Ordering::Relaxed);
+}
+
+pub fn language() -> Language {
+    Language::ALL[CURRENT.load(Ordering::Relaxed) as usize]
+}
+
+/// `number` written with the current language's decimal separator.
+pub fn localized(number: impl fmt::Display) -> String {
+    let text = number.to_string();
+    match language() {
+        Language::English => text,
+        Language::Portuguese => text.replace('.', ","),
+    }
+}
+
+/// Formats `value` with `decimals` digits after the current language's decimal separator.
+pub fn decimal(value: impl Into<f64>, decimals: usize) -> String {
+    localized(format!("{:.*}", decimals, value.into()))
+}
+
+/// Reads a number typed with either a decimal point or a decimal comma, whatever the
+/// language, since keyboards and habits do not always match it.
+pub fn parse_decimal<T: FromStr>(text: &str) -> Option<T> {
+    text.trim().replace(',', ".").parse().ok()
+}
+
+/// Translates a UI string into the current language.
+pub fn tr(text: &'static str) -> &'static str {
+    match language() {
+        Language::English => text,
+        Language::Portuguese => portuguese(text).unwrap_or(text),
+    }
+}
+
+/// Translates a template and substitutes each `{}` with the next argument.
+pub fn trf(template: &'static str, args: &[&dyn fmt::Display]) -> String {
+    let mut out = String::new();
+    let mut args = args.iter();
+    let mut parts = tr(template).split("{}").peekable();
+    while let Some(part) = parts.next() {
+        out.push_str(part);
+        if parts.peek().is_some()
+            && let Some(arg) = args.next()
+        {
+            out.push_str(&arg.to_string());
+        }
+    }
+    out
+}
+
+fn portuguese(text: &str) -> Option<&'static str> {
+    Some(match text {
+        "Enabled" => "Ativo",
+        "Description" => "Descrição",
+        "Physical Size" => "Tamanho físico",
+        "Unknown" => "Desconhecido",
+        "DPI Scale" => "Escala DPI",
+        "Scale" => "Escala",
+        "Scale Preset" => "Escala padrão",
+        "Custom" => "Personalizada",
+        "Logical {}x{}" => "Lógica {}x{}",
+        "Density" => "Densidade",
+        "{} PPI, {} effective" => "{} PPI, {} efetivos",
+        "Use {}" => "Usar {}",
+        "Scale {} will be rounded by the compositor and may look blurry" => {
+            "A escala {} será arredondada pelo compositor e pode ficar desfocada"
+        }
+        "Scale {} gives a fractional logical size and may look blurry" => {
+            "A escala {} dá um tamanho lógico fracionário e pode ficar desfocada"
+        }
+        "Position" => "Posição",
+        "Bezels (mm)" => "Molduras (mm)",
+        "Resolution" => "Resolução",
+        "Refresh Rate" => "Taxa de atualização",
+        "Transform" => "Rotação",
+        "Apply" => "Aplicar",
+        "Save" => "Guardar",
+        "Restore Default" => "Repor predefinição",
+        "Fit" => "Ajustar",
+        "Language" => "Idioma",
+        "Applied successfully!" => "Aplicado com sucesso!",
+        "Apply error: {}" => "Erro ao aplicar: {}",
+        "Saved to {}" => "Guardado em {}",
+        "Save error: {}" => "Erro ao guardar: {}",
+        "Restored to default config!" => "Configuração predefinida reposta!",
+        "Restore error: {}" => "Erro ao repor: {}",
+        "Settings error: {}" => "Erro nas definições: {}",
+        "Enable or disable this monitor (E)" => "Ativar ou desativar este monitor (E)",
+        "Decrease scale (-)" => "Diminuir escala (-)",
+        "Increase scale (+)" => "Aumentar escala (+)",
+        "Move left (Left arrow)" => "Mover para a esquerda (seta esquerda)",
+        "Move right (Right arrow)" => "Mover para a direita (seta direita)",
+        "Move up (Up arrow)" => "Mover para cima (seta cima)",
+        "Move down (Down arrow)" => "Mover para baixo (seta baixo)",
+        "Apply the layout (Ctrl+Enter)" => "Aplicar a disposição (Ctrl+Enter)",
+        "Save the layout to the config (Ctrl+S)" => "Guardar a disposição na configuração (Ctrl+S)",
+        "Restore the monitor rules from before the first save" => {
+            "Repor as regras de monitor anteriores ao primeiro guardar"
+        }
+        "No enabled outputs" => "Nenhuma saída ativa",
+        "Desktop {}x{}" => "Ambiente de trabalho {}x{}",
+        "No unapplied changes" => "Sem alterações por aplicar",
+        "{} output(s) with unapplied changes" => "{} saída(s) com alterações por aplicar",
+        "{} of {} enabled" => "{} de {} ativas",
+        "Night Light" => "Luz noturna",
+        "Mode" => "Modo",
+        "Type to filter, e.g. 144" => "Escreva para filtrar, ex. 144",
+        "Hide low-res" => "Ocultar baixa res.",
+        "Preferred mode is {}x{} @ {} Hz" => "O modo preferido é {}x{} @ {} Hz",
+        "Reset to preferred" => "Repor preferido",
+        "Disabled outputs: drag onto the layout to enable" => {
+            "Saídas desativadas: arraste para a disposição para ativar"
+        }
+        "At least one output must stay enabled" => "Pelo menos uma saída tem de ficar ativa",
+        "Profiles" => "Perfis",
+        "Identify" => "Identificar",
+        "Open MDisplay" => "Abrir MDisplay",
+        "Quit" => "Sair",
+        "{}\n{} at {},{}" => "{}\n{} em {},{}",
+        "Load profile" => "Carregar perfil",
+        "Profile name" => "Nome do perfil",
+        "Save Profile" => "Guardar perfil",
+        "Store the current layout as a named profile for the tray menu" => {
+            "Guardar a disposição atual como um perfil para o menu da bandeja"
+        }
+        "Saved profile {}" => "Perfil {} guardado",
+        "Loaded profile {}" => "Perfil {} carregado",
+        "Apply failed" => "Falha ao aplicar",
+        "Applied profile {}" => "Perfil {} aplicado",
+        "New monitor detected" => "Novo monitor detetado",
+        "Layout" => "Disposição",
+        "Settings" => "Definições",
+        "Draw monitors at their real-world size" => "Desenhar os monitores no tamanho real",
+        "Preview" => "Pré-visualizar",
+        "Show the command the active backend would run, without applying" => {
+            "Mostrar o comando que o backend ativo executaria, sem aplicar"
+        }
+        "Command the backend would run" => "Comando que o backend executaria",
+        "Close" => "Fechar",
+        "Reading outputs…" => "A ler saídas…",
+        "Applying…" => "A aplicar…",
+        "Error reading outputs: {}" => "Erro ao ler saídas: {}",
+        "Saving" => "Gravação",
+        "Format" => "Formato",
+        "Monitors file" => "Ficheiro de monitores",
+        "Main config" => "Configuração principal",
+        "Add a source line for the monitors file to the main config" => {
+            "Adicionar uma linha source do ficheiro de monitores à configuração principal"
+        }
+        "Backend" => "Backend",
+        "Layout editor" => "Editor de disposição",
+        "Snap to other outputs and the grid" => "Alinhar a outros ecrãs e à grelha",
+        "Snap distance" => "Distância de alinhamento",
+        "Appearance" => "Aparência",
+        "Theme" => "Tema",
+        "Daemon and hooks" => "Daemon e hooks",
+        "Desktop notifications" => "Notificações do ambiente de trabalho",
+        "Pre-apply hook" => "Hook antes de aplicar",
+        "Post-apply hook" => "Hook depois de aplicar",
+        "Shell command" => "Comando da shell",
+        "Log level" => "Nível de registo",
+        "Write the log to a file" => "Escrever o registo num ficheiro",
+        "Logging changes take effect on the next start." => {
+            "As alterações ao registo têm efeito no próximo arranque."
+        }
+        "Settings reloaded from disk" => "Definições recarregadas do disco",
+        "Settings reloaded" => "Definições recarregadas",
+        "{} changed on disk" => "{} foi alterado no disco",
+        "Monitors config changed" => "Configuração de monitores alterada",
+        "Input devices" => "Dispositivos de entrada",
+        "No touchscreens or tablets found" => "Nenhum ecrã tátil ou mesa digitalizadora encontrado",
+        "Not mapped" => "Sem associação",
+        "Touchscreen" => "Ecrã tátil",
+        "Tablet" => "Mesa digitalizadora",
+        "This compositor has no command for input mapping; mappings are only saved" => {
+            "Este compositor não tem comando para associar entradas; as associações só são guardadas"
+        }
+        "Connector" => "Conector",
+        "built-in panel" => "painel integrado",
+        "DisplayPort Alt Mode on {}" => "DisplayPort Alt Mode em {}",
+        "Extend" => "Estender",
+        "Mirror" => "Espelhar",
+        "Show the same picture as another output, or drop it onto that output's corner on the canvas" => {
+            "Mostrar a mesma imagem que outra saída, ou largá-la no canto dessa saída na área de disposição"
+        }
+        "{} has a different logical size, so only part of the desktop is mirrored" => {
+            "{} tem um tamanho lógico diferente, por isso só parte do ambiente de trabalho é espelhada"
+        }
+        "{} (adaptive sync capable)" => "{} (suporta sincronização adaptativa)",
+        "Refresh range" => "Intervalo de atualização",
+        "Metadata only" => "Apenas metadados",
+        ", up to {} nits" => ", até {} nits",
+        "Max pixel clock" => "Relógio de píxeis máximo",
+        "Tools" => "Ferramentas",
+        "Test pattern" => "Padrão de teste",
+        "Color bars, gradients and an alignment grid on this output. Click to go on, Esc to close" => {
+            "Barras de cor, gradientes e uma grelha de alinhamento nesta saída. Clique para avançar, Esc para fechar"
+        }
+        "Test pattern error: {}" => "Erro no padrão de teste: {}",
+        "Dead pixel test" => "Teste de píxeis mortos",
+        "Disable" => "Desativar",
+        "Motion test" => "Teste de movimento",
+        "Calibrate" => "Calibrar",
+        "Calibrate…" => "Calibrar…",
+        "Calibrate {}" => "Calibrar {}",
+        "Calibration saved" => "Calibração guardada",
+        "Calibration cancelled" => "Calibração cancelada",
+        "Calibration error: {}" => "Erro na calibração: {}",
+        "Calibrated: brightness {}, contrast {}, gamma {}" => {
+            "Calibrado: brilho {}, contraste {}, gama {}"
+        }
+        "1. Brightness: lower it until the background is black, then raise it until every dark patch is just visible." => {
+            "1. Brilho: baixe-o até o fundo ficar preto e depois suba-o até todos os quadrados escuros ficarem apenas visíveis."
+        }
+        "2. Contrast: raise it as far as every light patch stays distinct from the white background." => {
+            "2. Contraste: suba-o enquanto todos os quadrados claros se distinguirem do fundo branco."
+        }
+        "3. Gamma: change it until the gray square blends into the striped background when seen from a few steps away." => {
+            "3. Gama: ajuste-a até o quadrado cinzento se confundir com o fundo às riscas, visto a alguns passos de distância."
+        }
+        "Up and Down change the value, Enter goes to the next step and Esc cancels. Brightness and contrast need ddcutil and a monitor with DDC/CI; steps the output does not support are skipped." => {
+            "As setas para cima e para baixo alteram o valor, Enter passa ao passo seguinte e Esc cancela. O brilho e o contraste precisam do ddcutil e de um monitor com DDC/CI; os passos que a saída não suporta são ignorados."
+        }
+        "Start" => "Iniciar",
+        "Cancel" => "Cancelar",
+        "Follow sunset and sunrise" => "Seguir o pôr e o nascer do sol",
+        "Latitude" => "Latitude",
+        "Longitude" => "Longitude",
+        "Transition" => "Transição",
+        "{} min" => "{} min",
+        "Outputs with Night Light on warm up to their temperature after sunset and return to neutral after sunrise. The daemon applies the night light, so run mdisplay daemon." => {
+            "As saídas com a luz noturna ligada aquecem até à sua temperatura depois do pôr do sol e voltam ao neutro depois do nascer do sol. É o daemon que aplica a luz noturna, por isso execute mdisplay daemon."
+        }
+        "Brightness" => "Brilho",
+        "Link" => "Sincronizar",
+        "Move the brightness of every output together, keeping how they compare now" => {
+            "Mover o brilho de todas as saídas em conjunto, mantendo a relação atual entre elas"
+        }
+        "Brightness error: {}" => "Erro no brilho: {}",
+        "Auto-brightness" => "Brilho automático",
+        "Follow the ambient light sensor" => "Seguir o sensor de luz ambiente",
+        "Adjust external monitors too" => "Ajustar também os monitores externos",
+        "At {} lux" => "A {} lux",
+        "Needs iio-sensor-proxy and a running mdisplay daemon, which sets the brightness as the light changes." => {
+            "Precisa do iio-sensor-proxy e do mdisplay daemon em execução, que ajusta o brilho conforme a luz muda."
+        }
+        "On battery" => "Na bateria",
+        "What the daemon does with this output while the laptop runs on battery" => {
+            "O que o daemon faz com esta saída enquanto o portátil funciona na bateria"
+        }
+        "Dim to" => "Reduzir para",
+        "External outputs set to Dim or Disable under On battery change when the laptop unplugs and come back on AC. The daemon watches the power source through UPower, so run mdisplay daemon." => {
+            "As saídas externas definidas como Dim ou Disable em Na bateria mudam quando o portátil é desligado da corrente e voltam ao normal com a corrente. O daemon acompanha a fonte de energia através do UPower, por isso execute mdisplay daemon."
+        }
+        "Adaptive sync" => "Sincronização adaptativa",
+        "Gaming" => "Jogos",
+        "Revert gaming" => "Reverter modo de jogo",
+        "Go back to the layout from before Gaming" => {
+            "Voltar à disposição anterior ao modo de jogo"
+        }
+        "Switch this output to its highest refresh rate with adaptive sync and apply" => {
+            "Mudar esta saída para a taxa de atualização mais alta com sincronização adaptativa e aplicar"
+        }
+        "Gaming turns off the other outputs" => "O modo de jogo desliga as outras saídas",
+        "Quick actions" => "Ações rápidas",
+        "Stop presenting" => "Parar apresentação",
+        "Go back to the layout from before the quick action" => {
+            "Voltar à disposição anterior à ação rápida"
+        }
+        "Present" => "Apresentar",
+        "Mirror the built-in panel on this output at the best resolution both share, and apply" => {
+            "Espelhar o ecrã integrado nesta saída na melhor resolução comum aos dois, e aplicar"
+        }
+        "The outputs have no resolution in common" => {
+            "As saídas não têm nenhuma resolução em comum"
+        }
+        "'{}' is not a refresh rate" => "'{}' não é uma taxa de atualização",
+        "Custom mode added; the compositor may still reject it" => {
+            "Modo personalizado adicionado; o compositor ainda o pode rejeitar"
+        }
+        "Type any refresh rate, e.g. 23.976, and press Enter to add it as a custom mode" => {
+            "Escreva qualquer taxa de atualização, p. ex. 23.976, e prima Enter para a adicionar como modo personalizado"
+        }
+        "Blanking" => "Apagamento",
+        "Reduced blanking lowers the pixel clock so high refresh rates fit the cable" => {
+            "O apagamento reduzido baixa o relógio de píxeis para que taxas de atualização altas caibam no cabo"
+        }
+        "{} MHz pixel clock" => "Relógio de píxeis de {} MHz",
+        "Copy" => "Copiar",
+        "Above the monitor's {} MHz limit; try reduced blanking" => {
+            "Acima do limite de {} MHz do monitor; experimente o apagamento reduzido"
+        }
+        "Scaling filter" => "Filtro de escala",
+        "Nearest keeps pixel art sharp; Linear smooths text and photos" => {
+            "Nearest mantém a pixel art nítida; Linear suaviza texto e fotografias"
+        }
+        "This compositor has no scale filter setting; the choice is only saved" => {
+            "Este compositor não tem definição de filtro de escala; a escolha só é guardada"
+        }
+        "Rotation" => "Rotação",
+        "Rotate the built-in panel with the device" => "Rodar o ecrã integrado com o dispositivo",
+        "For convertibles and tablets. Needs iio-sensor-proxy and a running mdisplay daemon." => {
+            "Para convertíveis e tablets. Precisa do iio-sensor-proxy e do mdisplay daemon em execução."
+        }
+        "Docks" => "Docks",
+        "Not connected" => "Não ligada",
+        "No docks found" => "Nenhuma dock encontrada",
+        "No profile" => "Sem perfil",
+        "The daemon applies a dock's profile when it is plugged in, once the outputs on it are connected." => {
+            "O daemon aplica o perfil de uma dock quando é ligada, assim que as saídas nela estiverem ligadas."
+        }
+        "HDMI 2.0 carries this mode only with 4:2:0 chroma, which blurs colored text" => {
+            "O HDMI 2.0 só transporta este modo com croma 4:2:0, que desfoca texto colorido"
+        }
+        "This mode needs a dual-link DVI cable" => "Este modo precisa de um cabo DVI dual-link",
+        "This mode needs all four DisplayPort lanes; a dock that also carries USB 3 may not manage it" => {
+            "Este modo precisa das quatro vias DisplayPort; uma dock que também transporte USB 3 pode não conseguir"
+        }
+        "This mode is likely more than {} can carry; try a lower refresh rate or reduced blanking" => {
+            "Este modo é provavelmente mais do que o {} consegue transportar; experimente uma frequência mais baixa ou blanking reduzido"
+        }
+        "Virtual output" => "Saída virtual",
+        "Remove" => "Remover",
+        "Destroy this virtual output" => "Destruir esta saída virtual",
+        "Add Virtual Output" => "Adicionar saída virtual",
+        "Create a headless output, e.g. to use a tablet as a second screen over VNC" => {
+            "Criar uma saída headless, por exemplo para usar um tablet como segundo ecrã por VNC"
+        }
+        "Bit depth" => "Profundidade de cor",
+        "Use 8-bit for TVs that show wrong colors, 10-bit for HDR and wide-gamut panels" => {
+            "Use 8 bits em televisores com cores erradas, 10 bits em painéis HDR e de gama alargada"
+        }
+        "RGB range" => "Gama RGB",
+        "Pick Full if a TV looks washed out, Limited if blacks look crushed" => {
+            "Escolha Completa se um televisor parecer desbotado, Limitada se os pretos parecerem esmagados"
+        }
+        "Overscan" => "Overscan",
+        "Adjust" => "Ajustar",
+        "Reset" => "Repor",
+        "Line the green frame up with the visible edges: Space picks an edge, Up and Down move it, Esc saves" => {
+            "Alinhe a moldura verde com as margens visíveis: Espaço escolhe uma margem, Cima e Baixo movem-na, Esc guarda"
+        }
+        "Measure how much a TV crops: Space picks an edge, Up and Down move it, Esc saves. This compositor cannot keep windows clear of the margins" => {
+            "Meça quanto um televisor corta: Espaço escolhe uma margem, Cima e Baixo movem-na, Esc guarda. Este compositor não consegue afastar as janelas das margens"
+        }
+        "Subpixels" => "Subpíxeis",
+        "{} (reported)" => "{} (indicado)",
+        "Fonts look fringed when this is wrong; rotated panels usually need a vertical order" => {
+            "O texto fica com franjas coloridas quando isto está errado; ecrãs rodados costumam precisar de uma ordem vertical"
+        }
+        "This compositor has no subpixel setting; the choice is only saved" => {
+            "Este compositor não tem definição de subpíxeis; a escolha é apenas guardada"
+        }
+        "Not found" => "Não encontrado",
+        "No outputs found" => "Nenhuma saída encontrada",
+        "Choose another backend or set the tool paths in Settings." => {
+            "Escolha outro backend ou defina os caminhos das ferramentas nas Definições."
+        }
+        "Wallpapers" => "Papéis de parede",
+        "Save a profile to give it a wallpaper" => {
+            "Guarde um perfil para lhe atribuir um papel de parede"
+        }
+        "Profile rules" => "Regras de perfis",
+        "Automatic" => "Automático",
+        "Priority" => "Prioridade",
+        "Requires" => "Requer",
+        "Save a profile to apply it automatically" => {
+            "Guarde um perfil para o aplicar automaticamente"
+        }
+        "Automatic profiles are applied by mdisplay daemon when their conditions start to hold; the highest priority wins." => {
+            "Os perfis automáticos são aplicados pelo mdisplay daemon quando as suas condições passam a verificar-se; vence a prioridade mais alta."
+        }
+        "Export profile" => "Exportar perfil",
+        "Import profile" => "Importar perfil",
+        "Export…" => "Exportar…",
+        "Import…" => "Importar…",
+        "Exported to {}" => "Exportado para {}",
+        "Imported profile {}" => "Perfil {} importado",
+        "Save the named profile to a file to share it or attach it to a bug report" => {
+            "Guardar o perfil indicado num ficheiro para o partilhar ou anexar a um relatório de erro"
+        }
+        "Add a profile from an exported file" => "Adicionar um perfil de um ficheiro exportado",
+        "Scale {} does not divide the mode evenly and will be adjusted when applied" => {
+            "A escala {} não divide o modo de forma exata e será ajustada ao aplicar"
+        }
+        "This backend cannot scale outputs; scale {} will be reset to 1" => {
+            "Este backend não consegue escalar saídas; a escala {} voltará a 1"
+        }
+        "The layout spans {}x{} pixels; X11 programs cannot reach past {}. Move the outputs closer together." => {
+            "O esquema ocupa {}x{} píxeis; os programas X11 não chegam além de {}. Aproxime as saídas."
+        }
+        "The layout needs a {}x{} framebuffer but the GPU supports at most {}x{}" => {
+            "O esquema precisa de um framebuffer de {}x{}, mas a GPU suporta no máximo {}x{}"
+        }
+        "Positions cannot be negative" => "As posições não podem ser negativas",
+        "Enter a whole number of pixels" => "Introduza um número inteiro de píxeis",
+        "The scale must be above 0.1" => "A escala tem de ser superior a 0,1",
+        "Enter a scale such as 1.5" => "Introduza uma escala como 1,5",
+        "Scale factor, such as 1.5 for 150%" => "Fator de escala, como 1,5 para 150%",
+        "Horizontal position in logical pixels" => "Posição horizontal em píxeis lógicos",
+        "Vertical position in logical pixels" => "Posição vertical em píxeis lógicos",
+        "Reverting the live preview in {} s" => "A reverter a pré-visualização em direto em {} s",
+        "Keep" => "Manter",
+        "Revert" => "Reverter",
+        "Live" => "Em direto",
+        "Apply edits to the screens as you make them; they revert unless kept" => {
+            "Aplicar as alterações aos ecrãs à medida que são feitas; são revertidas se não forem mantidas"
+        }
+        "Applied, but the compositor changed: {}" => "Aplicado, mas o compositor alterou: {}",
+        "Saving…" => "A guardar…",
+        "While a game runs" => "Durante um jogo",
+        "The daemon applies this profile while a game has Feral GameMode on, and puts the layout from before back once the last game exits." => {
+            "O serviço aplica este perfil enquanto um jogo tiver o Feral GameMode ativo e repõe a disposição anterior quando o último jogo termina."
+        }
+        "Export report" => "Exportar relatório",
+        "Export report…" => "Exportar relatório…",
+        "Save a table of the outputs and a diagram of the layout, e.g. for a support thread" => {
+            "Guardar uma tabela das saídas e um diagrama da disposição, por exemplo para um pedido de ajuda"
+        }
+        "Save As" => "Guardar como",
+        "Save As…" => "Guardar como…",
+        "Write the layout to a file of your choice; the name picks the format: .sh for wlr-randr, hypr or sway in it for those, .conf for mangowc" => {
+            "Escrever a disposição num ficheiro à escolha; o nome escolhe o formato: .sh para wlr-randr, hypr ou sway no nome para esses, .conf para o mangowc"
+        }
+        "Files" => "Ficheiros",
+        "Log" => "Registo",
+        "Settings and profiles" => "Definições e perfis",
+        "Original monitor rules" => "Regras de monitor originais",
+        "Daemon: profile {}" => "Serviço: perfil {}",
+        "Daemon: custom layout" => "Serviço: disposição personalizada",
+        "{}, waiting for your changes" => "{}, à espera das suas alterações",
+        "History" => "Histórico",
+        "Hotplugs, applies, failures and reverts, newest first" => {
+            "Ligações, aplicações, falhas e reversões, as mais recentes primeiro"
+        }
+        "Refresh" => "Atualizar",
+        "Nothing recorded yet. The daemon records hotplugs and the rules it applies while it runs." => {
+            "Ainda nada registado. O serviço regista as ligações e as regras que aplica enquanto corre."
+        }
+        "Hotplug" => "Ligação",
+        "Applied" => "Aplicado",
+        "Reverted" => "Revertido",
+        "Failed" => "Falhou",
+        "Session" => "Sessão",
+        "Read-only" => "Só de leitura",
+        "Seat" => "Posto",
+        "Edit offline" => "Editar sem ligação",
+        "Edit a profile without its monitors connected, e.g. to prepare the office layout from home" => {
+            "Editar um perfil sem os monitores ligados, p. ex. para preparar em casa a disposição do escritório"
+        }
+        "Editing profile {} offline" => "A editar o perfil {} sem ligação",
+        "Done" => "Concluído",
+        "Go back to the connected outputs; save the profile first to keep the edits" => {
+            "Voltar às saídas ligadas; guarde o perfil antes para manter as alterações"
+        }
+        "Editing {} offline: save it as a profile, or finish editing to apply" => {
+            "A editar {} sem ligação: guarde-o como perfil, ou conclua a edição para aplicar"
+        }
+        "Only the outputs of this seat are shown and changed; auto is the seat of this session" => {
+            "Só as saídas deste posto são mostradas e alteradas; auto é o posto desta sessão"
+        }
+        "Read-only: this window cannot apply or save" => {
+            "Só de leitura: esta janela não pode aplicar nem guardar"
+        }
+        "Nickname" => "Alcunha",
+        "e.g. Left Dell" => "p. ex. Dell da esquerda",
+        "Shown instead of the connector name, and kept for this monitor on any port" => {
+            "Mostrada em vez do nome do conector e mantida para este monitor em qualquer porta"
+        }
+        "Grid" => "Grelha",
+        "Draw a faint grid behind the layout at the ruler marks" => {
+            "Desenhar uma grelha ténue por trás do esquema, nas marcas das réguas"
+        }
+        "Interface size" => "Tamanho da interface",
+        "Layout changed" => "Disposição alterada",
+        "Monitors changed" => "Monitores alterados",
+        "{} {}: {} instead of {}" => "{} {}: {} em vez de {}",
+        "output" => "saída",
+        "resolution" => "resolução",
+        "refresh rate" => "taxa de atualização",
+        "scale" => "escala",
+        "position" => "posição",
+        "rotation" => "rotação",
+        "adaptive sync" => "sincronização adaptativa",
+        "on" => "ligado",
+        "off" => "desligado",
+        "Failed to write {}: {}" => "Falha ao gravar {}: {}",
+        "Failed to read {}: {}" => "Falha ao ler {}: {}",
+        "Pixel format" => "Formato de píxel",
+        "YCbCr 4:2:0 fits 4K at 60 Hz through HDMI 2.0 but blurs colored text" => {
+            "YCbCr 4:2:0 permite 4K a 60 Hz por HDMI 2.0, mas desfoca o texto colorido"
+        }
+        "pixel format" => "formato de píxel",
+        _ => return None,
+    })
+}
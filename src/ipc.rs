@@ -0,0 +1,357 @@
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::fs::PermissionsExt;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use mdisplay_core::backend::{self, Backend, Output};
+use mdisplay_core::history::{self, EventKind};
+use mdisplay_core::hooks;
+use mdisplay_core::profile::{self, CUSTOM_LAYOUT, OutputConfig, Profile};
+use mdisplay_core::settings::AppSettings;
+use mdisplay_core::status;
+
+/// One request per connection, sent as a single line of JSON.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum Request {
+    Outputs,
+    ApplyProfile {
+        name: String,
+    },
+    ApplyLayout {
+        outputs: Vec<OutputConfig>,
+        /// The backend the client has selected, as in [`AppSettings::backend`].
+        #[serde(default)]
+        backend: Option<String>,
+    },
+    SetEnabled {
+        output: String,
+        enabled: bool,
+    },
+    /// Makes the daemon let go of an output's gamma table, or take it back.
+    PauseGamma {
+        output: String,
+        paused: bool,
+    },
+    /// Asks the daemon what it is doing. With `hold`, it also leaves the layout alone
+    /// for [`HOLD_LEASE`], so an open window's edits are not undone by profile rules.
+    Status {
+        #[serde(default)]
+        hold: bool,
+    },
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Response {
+    pub ok: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// The outputs in the `get --json` format, for [`Request::Outputs`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub outputs: Option<serde_json::Value>,
+    /// For [`Request::Status`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<DaemonStatus>,
+}
+
+impl Response {
+    fn from_result(result: Result<Response, String>) -> Self {
+        match result {
+            Ok(response) => Self {
+                ok: true,
+                ..response
+            },
+            Err(e) => Self {
+                error: Some(e),
+                ..Default::default()
+            },
+        }
+    }
+}
+
+/// What the daemon reports about itself.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DaemonStatus {
+    /// The saved profile the outputs are laid out as, if any.
+    pub profile: Option<String>,
+    /// Whether automatic applies are held off for a window.
+    pub held: bool,
+}
+
+/// How long a [`Request::Status`] with `hold` keeps the daemon's profile rules, docks and
+/// restores off the layout. Windows renew it while they have edits of their own.
+pub const HOLD_LEASE: Duration = Duration::from_secs(10);
+
+/// How long a client may take to send its request line.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+/// How long to wait for a running instance to answer. Applies run hooks and helper
+/// programs, so this is well above [`mdisplay_core::process::TIMEOUT`].
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// The socket in the session's runtime directory, which only its user can enter. There is
+/// none without one: a shared directory would let other users squat on it or send requests.
+pub fn socket_path() -> Option<PathBuf> {
+    dirs::runtime_dir().map(|dir| dir.join("mdisplay.sock"))
+}
+
+/// Listens on [`socket_path`] from a background thread, answering requests against the
+/// compositor. Fails if another instance is already listening or there is no runtime
+/// directory.
+pub fn serve() -> Result<(), String> {
+    let path = socket_path().ok_or_else(|| {
+        "XDG_RUNTIME_DIR is not set, so there is nowhere private to listen".to_string()
+    })?;
+    if UnixStream::connect(&path).is_ok() {
+        return Err(format!(
+            "Another instance is listening on {}",
+            path.display()
+        ));
+    }
+    let _ = std::fs::remove_file(&path);
+    let listener = UnixListener::bind(&path)
+        .map_err(|e| format!("Failed to bind {}: {}", path.display(), e))?;
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))
+        .map_err(|e| format!("Failed to restrict {}: {}", path.display(), e))?;
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            if let Err(e) = handle_connection(stream) {
+                tracing::warn!("IPC error: {}", e);
+            }
+        }
+    });
+    Ok(())
+}
+
+fn handle_connection(stream: UnixStream) -> Result<(), String> {
+    // Requests are answered one at a time, so a client that never finishes its line
+    // must not hold up the rest.
+    stream
+        .set_read_timeout(Some(REQUEST_TIMEOUT))
+        .map_err(|e| e.to_string())?;
+    let mut line = String::new();
+    BufReader::new(&stream)
+        .read_line(&mut line)
+        .map_err(|e| e.to_string())?;
+    tracing::debug!("IPC request: {}", line.trim_end());
+    let response = match serde_json::from_str::<Request>(&line) {
+        Ok(request) => Response::from_result(handle(request)),
+        Err(e) => Response::from_result(Err(format!("Invalid request: {}", e))),
+    };
+    let mut encoded = serde_json::to_string(&response).map_err(|e| e.to_string())?;
+    encoded.push('\n');
+    (&stream)
+        .write_all(encoded.as_bytes())
+        .map_err(|e| e.to_string())
+}
+
+fn handle(request: Request) -> Result<Response, String> {
+    match request {
+        Request::Outputs => {
+            let outputs = backend::fetch_outputs()?;
+            let json = status::outputs_json(&outputs)?;
+            let outputs = serde_json::from_str(&json).map_err(|e| e.to_string())?;
+            Ok(Response {
+                outputs: Some(outputs),
+                ..Default::default()
+            })
+        }
+        Request::ApplyProfile { name } => {
+            let settings = AppSettings::load();
+            let profile = settings
+                .profile(&name)
+                .ok_or_else(|| format!("No profile named '{}'", name))?;
+            recorded(
+                format!("Applied profile '{}'", name),
+                profile::activate(profile),
+            )
+            .map(|()| Response::default())
+        }
+        Request::ApplyLayout { outputs, backend } => {
+            let settings = AppSettings {
+                backend,
+                ..AppSettings::load()
+            };
+            let layout = Profile {
+                name: String::new(),
+                outputs,
+                wallpaper: None,
+                rule: None,
+                sections: Default::default(),
+                monitors: Vec::new(),
+            };
+            let backend = backend::from_settings(&settings);
+            recorded(
+                format!("Applied {}", layout.label()),
+                profile::activate_with(backend.as_ref(), &layout),
+            )
+            .map(|()| Response::default())
+        }
+        Request::SetEnabled { output, enabled } => {
+            backend::set_output_enabled(&output, enabled).map(|()| Response::default())
+        }
+        Request::PauseGamma { output, paused } => {
+            crate::daemon::pause_gamma(&output, paused).map(|()| Response::default())
+        }
+        Request::Status { hold } => crate::daemon::status(hold).map(|status| Response {
+            status: Some(status),
+            ..Default::default()
+        }),
+    }
+}
+
+/// Sends a request to a running instance. Returns `Ok(None)` when nothing is listening,
+/// so callers can fall back to talking to the compositor themselves.
+pub fn request(request: &Request) -> Result<Option<Response>, String> {
+    let Some(Ok(mut stream)) = socket_path().map(UnixStream::connect) else {
+        return Ok(None);
+    };
+    stream
+        .set_read_timeout(Some(RESPONSE_TIMEOUT))
+        .and_then(|()| stream.set_write_timeout(Some(REQUEST_TIMEOUT)))
+        .map_err(|e| format!("Failed to set up the IPC socket: {}", e))?;
+    let mut encoded = serde_json::to_string(request).map_err(|e| e.to_string())?;
+    encoded.push('\n');
+    stream
+        .write_all(encoded.as_bytes())
+        .map_err(|e| format!("Failed to send IPC request: {}", e))?;
+
+    let mut line = String::new();
+    BufReader::new(&stream)
+        .read_line(&mut line)
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut => format!(
+                "The running instance did not answer within {} seconds",
+                RESPONSE_TIMEOUT.as_secs()
+            ),
+            _ => format!("Failed to read IPC response: {}", e),
+        })?;
+    serde_json::from_str(&line)
+        .map(Some)
+        .map_err(|e| format!("Invalid IPC response: {}", e))
+}
+
+/// Records an apply in the history, whoever ends up doing it: the instance a request
+/// reaches or the process that found none running.
+fn recorded(what: String, result: Result<(), String>) -> Result<(), String> {
+    match &result {
+        Ok(()) => history::record(EventKind::Applied, what),
+        Err(e) => history::record(EventKind::Failed, e.clone()),
+    }
+    result
+}
+
+/// Runs `request` through a running instance, or `local` when there is none.
+fn routed(request: Request, local: impl FnOnce() -> Result<(), String>) -> Result<(), String> {
+    match self::request(&request)? {
+        Some(Response { ok: true, .. }) => Ok(()),
+        Some(Response { error, .. }) => Err(error.unwrap_or_else(|| "Unknown IPC error".into())),
+        None => local(),
+    }
+}
+
+/// Applies `outputs` through a running instance, or through `backend` when there is none.
+/// `choice` is the setting `backend` was made from, so the instance uses the same one.
+pub fn apply_layout(
+    backend: &dyn Backend,
+    choice: Option<&str>,
+    outputs: &[Output],
+) -> Result<(), String> {
+    let request = Request::ApplyLayout {
+        outputs: outputs.iter().map(OutputConfig::from_output).collect(),
+        backend: choice.map(str::to_string),
+    };
+    routed(request, || {
+        recorded(
+            format!("Applied {}", CUSTOM_LAYOUT),
+            hooks::apply_with(backend, outputs, None),
+        )
+    })
+}
+
+pub fn apply_profile(profile: &Profile) -> Result<(), String> {
+    routed(
+        Request::ApplyProfile {
+            name: profile.name.clone(),
+        },
+        || {
+            recorded(
+                format!("Applied profile '{}'", profile.name),
+                profile::activate(profile),
+            )
+        },
+    )
+}
+
+pub fn set_enabled(output: &str, enabled: bool) -> Result<(), String> {
+    routed(
+        Request::SetEnabled {
+            output: output.to_string(),
+            enabled,
+        },
+        || backend::set_output_enabled(output, enabled),
+    )
+}
+
+/// Asks a running daemon to stop holding `output`'s gamma so another program can set it,
+/// or to take it back. Does nothing when no daemon is running.
+pub fn pause_gamma(output: &str, paused: bool) -> Result<(), String> {
+    routed(
+        Request::PauseGamma {
+            output: output.to_string(),
+            paused,
+        },
+        || Ok(()),
+    )
+}
+
+/// What a running daemon is doing, asking it to hold off when `hold` is set. `None` when
+/// no daemon is running.
+pub fn daemon_status(hold: bool) -> Result<Option<DaemonStatus>, String> {
+    match request(&Request::Status { hold })? {
+        Some(Response {
+            ok: true, status, ..
+        }) => Ok(status),
+        Some(Response { error, .. }) => Err(error.unwrap_or_else(|| "Unknown IPC error".into())),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mdisplay_core::mock::MockBackend;
+
+    #[test]
+    fn an_applied_layout_keeps_adaptive_sync_over_ipc() {
+        let mut outputs =
+            MockBackend::from_json(include_str!("../mdisplay-core/fixtures/dual_monitor.json"))
+                .unwrap()
+                .fetch_outputs()
+                .unwrap();
+        outputs[0].adaptive_sync = Some(true);
+        let request = Request::ApplyLayout {
+            outputs: outputs.iter().map(OutputConfig::from_output).collect(),
+            backend: Some("swaymsg".to_string()),
+        };
+        let line = serde_json::to_string(&request).unwrap();
+        let Request::ApplyLayout {
+            outputs: configs,
+            backend,
+        } = serde_json::from_str(&line).unwrap()
+        else {
+            panic!("not an ApplyLayout: {}", line);
+        };
+        assert_eq!(backend.as_deref(), Some("swaymsg"));
+
+        let mut current = outputs.clone();
+        current[0].adaptive_sync = Some(false);
+        assert!(!configs[0].matches(&current[0]));
+        configs[0].apply_to(&mut current[0]);
+        assert_eq!(current[0].adaptive_sync, Some(true));
+        assert!(configs[0].matches(&current[0]));
+    }
+}
@@ -0,0 +1,81 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::str::FromStr;
+use std::sync::{Mutex, OnceLock};
+
+use mdisplay_core::paths;
+use mdisplay_core::settings::AppSettings;
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{Registry, fmt, reload};
+
+/// Overrides the configured level for a single run, e.g. `MDISPLAY_LOG=debug`.
+const LEVEL_ENV: &str = "MDISPLAY_LOG";
+
+/// Changes the level once [`configure`] knows the one in the settings.
+static LEVEL: OnceLock<reload::Handle<LevelFilter, Registry>> = OnceLock::new();
+/// The log file, once [`configure`] has opened it.
+static LOG_FILE: Mutex<Option<File>> = Mutex::new(None);
+
+/// Writes to [`LOG_FILE`], or nowhere while there is none.
+struct LogFile;
+
+impl Write for LogFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match LOG_FILE.lock().unwrap().as_mut() {
+            Some(file) => file.write(buf),
+            None => Ok(buf.len()),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match LOG_FILE.lock().unwrap().as_mut() {
+            Some(file) => file.flush(),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Installs the global subscriber, logging to stderr at `MDISPLAY_LOG`, else `level`
+/// from the command line, else warnings. It runs before the settings are read so that
+/// problems reading them are logged; [`configure`] then applies what they say.
+pub fn init(level: Option<&str>) {
+    let level = std::env::var(LEVEL_ENV)
+        .ok()
+        .or(level.map(str::to_string))
+        .and_then(|level| LevelFilter::from_str(&level).ok())
+        .unwrap_or(LevelFilter::WARN);
+    let (filter, handle) = reload::Layer::new(level);
+    let _ = LEVEL.set(handle);
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(fmt::layer().with_writer(std::io::stderr))
+        .with(fmt::layer().with_ansi(false).with_writer(|| LogFile))
+        .init();
+}
+
+/// Switches to the level in `settings` unless `MDISPLAY_LOG` is set, and starts writing
+/// the log file when enabled there. Problems opening the file are reported on stderr and
+/// otherwise ignored.
+pub fn configure(settings: &AppSettings) {
+    if std::env::var_os(LEVEL_ENV).is_none()
+        && let Some(handle) = LEVEL.get()
+    {
+        let level = LevelFilter::from_str(&settings.log_level).unwrap_or(LevelFilter::WARN);
+        let _ = handle.modify(|filter| *filter = level);
+    }
+
+    if !settings.log_to_file {
+        return;
+    }
+    let path = paths::log_file();
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    match OpenOptions::new().create(true).append(true).open(&path) {
+        Ok(file) => *LOG_FILE.lock().unwrap() = Some(file),
+        Err(e) => eprintln!("Failed to open log file {}: {}", path.display(), e),
+    }
+}
@@ -1,13 +1,58 @@
-mod backend;
-mod settings;
+mod cli;
+mod daemon;
+mod dbus;
+mod gamemode;
+mod i18n;
+mod ipc;
+mod logging;
+mod notify;
+mod portal;
+mod power;
+mod remote;
+mod sensors;
+#[cfg(test)]
+mod testing;
+#[cfg(feature = "tray")]
+mod tray;
 mod ui;
-mod wayland;
+mod watch;
 
 use clap::Parser;
 
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<cli::Command>,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Print the command the active backend would run instead of applying"
+    )]
+    dry_run: bool,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Act on this user's session: their settings, daemon and compositor, e.g. over SSH"
+    )]
+    user: Option<String>,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Runtime directory of the session to act on; /run/user/<uid> with --user"
+    )]
+    runtime_dir: Option<std::path::PathBuf>,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Wayland socket to connect to; the first one in the runtime directory by default"
+    )]
+    wayland_display: Option<String>,
+
     #[arg(long, help = "Path to save the monitors.conf file")]
     set_monitors_path: Option<String>,
 
@@ -23,18 +68,98 @@ struct Args {
     )]
     auto_append_source: Option<bool>,
 
+    #[arg(
+        long,
+        help = "Whether to send desktop notifications for failures and hotplug (true/false)"
+    )]
+    notifications: Option<bool>,
+
+    #[arg(
+        long,
+        help = "Shell command to run before every apply; an empty string removes it"
+    )]
+    set_pre_apply_hook: Option<String>,
+
+    #[arg(
+        long,
+        help = "Shell command to run after every successful apply; an empty string removes it"
+    )]
+    set_post_apply_hook: Option<String>,
+
+    #[arg(
+        long,
+        help = "UI language code (en, pt), or 'auto' to follow the locale"
+    )]
+    set_language: Option<String>,
+
+    #[arg(
+        long,
+        help = "Backend to use (see the 'backends' command): a plugin, a tool, 'wayland', or 'auto'"
+    )]
+    set_backend: Option<String>,
+
+    #[arg(
+        long,
+        help = "logind seat whose outputs to manage on multi-seat machines, or 'auto' for this session's"
+    )]
+    set_seat: Option<String>,
+
+    #[arg(
+        long,
+        help = "Minimum log level (error, warn, info, debug, trace); MDISPLAY_LOG overrides it"
+    )]
+    set_log_level: Option<String>,
+
+    #[arg(
+        long,
+        help = "Whether to also write the log to the XDG state directory (true/false)"
+    )]
+    log_to_file: Option<bool>,
+
+    #[arg(
+        long,
+        help = "Open the window read-only: the layout can be viewed but not applied or saved"
+    )]
+    read_only: bool,
+
     #[arg(long, help = "Reset all settings to their defaults")]
     reset_settings: bool,
+
+    #[cfg(feature = "tray")]
+    #[arg(
+        long,
+        help = "Run as a tray icon with profile quick-switch instead of the window"
+    )]
+    tray: bool,
 }
 
 fn main() -> iced::Result {
     let args = Args::parse();
+    if let Err(e) = remote::enter(
+        args.user.as_deref(),
+        args.runtime_dir.as_deref(),
+        args.wayland_display.as_deref(),
+    ) {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
 
     let mut exit_after_args = false;
-    let mut app_settings = settings::AppSettings::load();
+    logging::init(args.set_log_level.as_deref());
+    let mut app_settings = mdisplay_core::settings::AppSettings::load();
+    logging::configure(&app_settings);
+    match mdisplay_core::paths::migrate(&mut app_settings) {
+        Ok(true) => {
+            if let Err(e) = app_settings.save() {
+                tracing::warn!("{}", e);
+            }
+        }
+        Ok(false) => {}
+        Err(e) => tracing::warn!("{}", e),
+    }
 
     if args.reset_settings {
-        let default_settings = settings::AppSettings::default();
+        let default_settings = mdisplay_core::settings::AppSettings::default();
         if let Err(e) = default_settings.save() {
             eprintln!("Error resetting settings: {}", e);
             std::process::exit(1);
@@ -55,6 +180,75 @@ fn main() -> iced::Result {
         exit_after_args = true;
     }
 
+    if let Some(enabled) = args.notifications {
+        app_settings.notifications = enabled;
+        exit_after_args = true;
+    }
+
+    if let Some(command) = args.set_pre_apply_hook {
+        app_settings.hooks.pre_apply = (!command.is_empty()).then_some(command);
+        exit_after_args = true;
+    }
+    if let Some(command) = args.set_post_apply_hook {
+        app_settings.hooks.post_apply = (!command.is_empty()).then_some(command);
+        exit_after_args = true;
+    }
+
+    if let Some(level) = args.set_log_level {
+        if level
+            .parse::<tracing_subscriber::filter::LevelFilter>()
+            .is_err()
+        {
+            eprintln!("Unknown log level '{}'", level);
+            std::process::exit(1);
+        }
+        app_settings.log_level = level;
+        exit_after_args = true;
+    }
+    if let Some(enabled) = args.log_to_file {
+        app_settings.log_to_file = enabled;
+        exit_after_args = true;
+    }
+
+    if let Some(code) = args.set_language {
+        if code == "auto" {
+            app_settings.language = None;
+        } else if i18n::Language::from_code(&code).is_some() {
+            app_settings.language = Some(code);
+        } else {
+            eprintln!("Unknown language '{}'", code);
+            std::process::exit(1);
+        }
+        exit_after_args = true;
+    }
+
+    if let Some(name) = args.set_backend {
+        if name == "auto" {
+            app_settings.backend = None;
+        } else if name == "wayland"
+            || mdisplay_core::tools::Tool::from_name(&name).is_some()
+            || mdisplay_core::plugin::find(&name).is_some()
+        {
+            app_settings.backend = Some(name);
+        } else {
+            eprintln!("No backend named '{}'", name);
+            std::process::exit(1);
+        }
+        exit_after_args = true;
+    }
+
+    if let Some(seat) = args.set_seat {
+        if seat == "auto" {
+            app_settings.seat = None;
+        } else if mdisplay_core::seat::list().contains(&seat) {
+            app_settings.seat = Some(seat);
+        } else {
+            eprintln!("No seat named '{}'", seat);
+            std::process::exit(1);
+        }
+        exit_after_args = true;
+    }
+
     if exit_after_args {
         if let Err(e) = app_settings.save() {
             eprintln!("Error saving settings: {}", e);
@@ -63,27 +257,39 @@ fn main() -> iced::Result {
         println!("Settings updated successfully.");
         return Ok(());
     }
-    let custom_palette = iced::theme::Palette {
-        background: iced::Color::from_rgb8(20, 20, 20),
-        text: iced::Color::from_rgb8(230, 230, 230),
-        primary: iced::Color::from_rgb8(100, 100, 100),
-        success: iced::Color::from_rgb8(60, 60, 60),
-        danger: iced::Color::from_rgb8(80, 80, 80),
-        warning: iced::Color::from_rgb8(120, 120, 120),
-    };
+    i18n::set_language(i18n::Language::detect(app_settings.language.as_deref()));
+
+    if let Some(command) = args.command {
+        if let Err(e) = cli::run(command, args.dry_run) {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
 
-    let custom_theme = iced::Theme::Custom(std::sync::Arc::new(iced::theme::Custom::new(
-        "MonoDark".to_string(),
-        custom_palette,
-    )));
+    #[cfg(feature = "tray")]
+    if args.tray {
+        if let Err(e) = tray::run() {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
 
+    let read_only = args.read_only;
     iced::application(
-        ui::MangoDisplay::default,
+        move || ui::MangoDisplay::new(read_only),
         ui::MangoDisplay::update,
         ui::MangoDisplay::view,
     )
     .title("MDisplay")
-    .theme(move |_app: &ui::MangoDisplay| custom_theme.clone())
-    .window_size(iced::Size::new(1000.0, 700.0))
+    .subscription(ui::MangoDisplay::subscription)
+    .theme(ui::MangoDisplay::theme)
+    .scale_factor(ui::MangoDisplay::scale_factor)
+    .window_size(iced::Size::new(
+        app_settings.ui.window_width,
+        app_settings.ui.window_height,
+    ))
+    .exit_on_close_request(false)
     .run()
 }
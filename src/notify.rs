@@ -0,0 +1,42 @@
+use std::collections::HashMap;
+
+use zbus::blocking::Connection;
+use zbus::zvariant::Value;
+
+use mdisplay_core::settings::AppSettings;
+
+/// Shows a desktop notification through `org.freedesktop.Notifications`.
+pub fn send(summary: &str, body: &str) -> Result<(), String> {
+    let connection =
+        Connection::session().map_err(|e| format!("Failed to connect to session bus: {}", e))?;
+    let hints: HashMap<&str, Value<'_>> = HashMap::new();
+    connection
+        .call_method(
+            Some("org.freedesktop.Notifications"),
+            "/org/freedesktop/Notifications",
+            Some("org.freedesktop.Notifications"),
+            "Notify",
+            &(
+                "MDisplay",
+                0u32,
+                "video-display",
+                summary,
+                body,
+                Vec::<&str>::new(),
+                hints,
+                -1i32,
+            ),
+        )
+        .map_err(|e| format!("Failed to send notification: {}", e))?;
+    Ok(())
+}
+
+/// Reports a background event when notifications are enabled, logging delivery failures
+/// rather than surfacing them.
+pub fn event(settings: &AppSettings, summary: &str, body: &str) {
+    if settings.notifications
+        && let Err(e) = send(summary, body)
+    {
+        tracing::warn!("{}", e);
+    }
+}
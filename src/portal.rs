@@ -0,0 +1,111 @@
+//! File choosers through xdg-desktop-portal, so each desktop shows its own dialog.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use zbus::MatchRule;
+use zbus::blocking::{Connection, MessageIterator};
+use zbus::message::Type;
+use zbus::zvariant::{OwnedValue, Value};
+
+/// Asks where to save a file, suggesting `name`. `None` when the user cancelled.
+pub fn save_file(title: &str, name: &str) -> Result<Option<PathBuf>, String> {
+    let mut options = HashMap::new();
+    options.insert("current_name", Value::from(name));
+    choose("SaveFile", title, options)
+}
+
+/// Asks for a file to open. `None` when the user cancelled.
+pub fn open_file(title: &str) -> Result<Option<PathBuf>, String> {
+    choose("OpenFile", title, HashMap::new())
+}
+
+fn choose(
+    method: &str,
+    title: &str,
+    mut options: HashMap<&str, Value<'_>>,
+) -> Result<Option<PathBuf>, String> {
+    static REQUESTS: AtomicU32 = AtomicU32::new(0);
+    let failed = |e: zbus::Error| format!("File chooser failed: {}", e);
+
+    let bus =
+        Connection::session().map_err(|e| format!("Failed to connect to session bus: {}", e))?;
+    // The portal answers on a request object named after our bus name and a token of our
+    // choosing, so the reply can be listened for before asking.
+    let token = format!(
+        "mdisplay{}_{}",
+        std::process::id(),
+        REQUESTS.fetch_add(1, Ordering::Relaxed)
+    );
+    let sender = bus
+        .unique_name()
+        .ok_or("No name on the session bus")?
+        .trim_start_matches(':')
+        .replace('.', "_");
+    let path = format!(
+        "/org/freedesktop/portal/desktop/request/{}/{}",
+        sender, token
+    );
+    let rule = MatchRule::builder()
+        .msg_type(Type::Signal)
+        .interface("org.freedesktop.portal.Request")
+        .and_then(|b| b.member("Response"))
+        .and_then(|b| b.path(path.as_str()))
+        .map_err(failed)?
+        .build();
+    let mut responses = MessageIterator::for_match_rule(rule, &bus, None).map_err(failed)?;
+
+    options.insert("handle_token", Value::from(token.clone()));
+    bus.call_method(
+        Some("org.freedesktop.portal.Desktop"),
+        "/org/freedesktop/portal/desktop",
+        Some("org.freedesktop.portal.FileChooser"),
+        method,
+        &("", title, options),
+    )
+    .map_err(|e| format!("No file chooser portal: {}", e))?;
+
+    let message = responses
+        .next()
+        .ok_or("The file chooser closed without answering")?
+        .map_err(failed)?;
+    let (response, results): (u32, HashMap<String, OwnedValue>) =
+        message.body().deserialize().map_err(failed)?;
+    // 1 is a cancel, 2 the dialog going away some other way.
+    if response != 0 {
+        return Ok(None);
+    }
+    let uris: Vec<String> = results
+        .get("uris")
+        .and_then(|v| v.try_clone().ok())
+        .and_then(|v| v.try_into().ok())
+        .unwrap_or_default();
+    Ok(uris
+        .first()
+        .and_then(|uri| uri.strip_prefix("file://"))
+        .map(|path| PathBuf::from(percent_decode(path))))
+}
+
+/// Undoes the `%XX` escapes of a file URI.
+fn percent_decode(text: &str) -> String {
+    let mut bytes = Vec::with_capacity(text.len());
+    let mut rest = text.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        let hex = tail
+            .get(..2)
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match hex {
+            Some(decoded) if byte == b'%' => {
+                bytes.push(decoded);
+                rest = &tail[2..];
+            }
+            _ => {
+                bytes.push(byte);
+                rest = tail;
+            }
+        }
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
@@ -0,0 +1,99 @@
+//! Whether the machine runs on battery and whether its lid is closed, read through UPower
+//! on the system bus, and when it suspends and resumes, read through logind.
+
+use zbus::blocking::Connection;
+use zbus::proxy;
+
+#[proxy(
+    interface = "org.freedesktop.UPower",
+    default_service = "org.freedesktop.UPower",
+    default_path = "/org/freedesktop/UPower"
+)]
+trait UPower {
+    #[zbus(property)]
+    fn on_battery(&self) -> zbus::Result<bool>;
+    #[zbus(property)]
+    fn lid_is_present(&self) -> zbus::Result<bool>;
+    #[zbus(property)]
+    fn lid_is_closed(&self) -> zbus::Result<bool>;
+}
+
+#[proxy(
+    interface = "org.freedesktop.login1.Manager",
+    default_service = "org.freedesktop.login1",
+    default_path = "/org/freedesktop/login1"
+)]
+trait Login {
+    #[zbus(signal)]
+    fn prepare_for_sleep(&self, start: bool) -> zbus::Result<()>;
+}
+
+fn upower(bus: &Connection) -> Result<UPowerProxyBlocking<'_>, String> {
+    UPowerProxyBlocking::new(bus).map_err(|e| format!("Failed to reach UPower: {}", e))
+}
+
+/// Blocks, sending whether the machine is on battery to `on_change` now and whenever it
+/// switches, until `on_change` returns `false`. Fails when UPower is not running.
+pub fn watch(mut on_change: impl FnMut(bool) -> bool) -> Result<(), String> {
+    let bus = Connection::system().map_err(|e| format!("Failed to reach the system bus: {}", e))?;
+    let upower = upower(&bus)?;
+
+    let changes = upower.receive_on_battery_changed();
+    let first = upower.on_battery().map_err(|e| e.to_string())?;
+    if !on_change(first) {
+        return Ok(());
+    }
+    for change in changes {
+        let Ok(on_battery) = change.get() else {
+            continue;
+        };
+        if !on_change(on_battery) {
+            return Ok(());
+        }
+    }
+    Ok(())
+}
+
+/// Like [`watch`], for whether the lid is closed. Fails on machines without a lid.
+pub fn watch_lid(mut on_change: impl FnMut(bool) -> bool) -> Result<(), String> {
+    let bus = Connection::system().map_err(|e| format!("Failed to reach the system bus: {}", e))?;
+    let upower = upower(&bus)?;
+    if !upower.lid_is_present().map_err(|e| e.to_string())? {
+        return Err("No lid".to_string());
+    }
+
+    let changes = upower.receive_lid_is_closed_changed();
+    let first = upower.lid_is_closed().map_err(|e| e.to_string())?;
+    if !on_change(first) {
+        return Ok(());
+    }
+    for change in changes {
+        let Ok(closed) = change.get() else {
+            continue;
+        };
+        if !on_change(closed) {
+            return Ok(());
+        }
+    }
+    Ok(())
+}
+
+/// Blocks, sending `true` to `on_sleep` when the machine is about to suspend or hibernate
+/// and `false` once it has resumed, until `on_sleep` returns `false`. Fails without logind.
+pub fn watch_sleep(mut on_sleep: impl FnMut(bool) -> bool) -> Result<(), String> {
+    let bus = Connection::system().map_err(|e| format!("Failed to reach the system bus: {}", e))?;
+    let login =
+        LoginProxyBlocking::new(&bus).map_err(|e| format!("Failed to reach logind: {}", e))?;
+    let signals = login
+        .receive_prepare_for_sleep()
+        .map_err(|e| format!("Failed to reach logind: {}", e))?;
+    for signal in signals {
+        let Ok(args) = signal.args() else {
+            continue;
+        };
+        if !on_sleep(args.start) {
+            return Ok(());
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,150 @@
+//! Running the command line against another session, e.g. by an administrator logged in
+//! over SSH. Wayland clients find the compositor through `XDG_RUNTIME_DIR` and
+//! `WAYLAND_DISPLAY`, and mdisplay finds the settings and a running daemon through `HOME`
+//! and `XDG_RUNTIME_DIR`, so pointing those at the session is all it takes.
+//!
+//! With `--user`, the process first becomes that user, so their runtime directory can be
+//! entered and files written to their home stay theirs. Their settings still name
+//! commands of their choosing, so those are not run at all; see
+//! [`mdisplay_core::settings::distrust_commands`].
+
+use std::ffi::{CString, OsString};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// An entry of `/etc/passwd`.
+struct Account {
+    uid: libc::uid_t,
+    gid: libc::gid_t,
+    home: PathBuf,
+    runtime_dir: PathBuf,
+}
+
+/// The account called `user`, read from `/etc/passwd`.
+fn account(user: &str) -> Result<Account, String> {
+    let passwd = fs::read_to_string("/etc/passwd")
+        .map_err(|e| format!("Failed to read /etc/passwd: {}", e))?;
+    passwd
+        .lines()
+        .map(|line| line.split(':').collect::<Vec<_>>())
+        .find(|fields| fields.first() == Some(&user) && fields.len() >= 6)
+        .and_then(|fields| {
+            Some(Account {
+                uid: fields[2].parse().ok()?,
+                gid: fields[3].parse().ok()?,
+                home: PathBuf::from(fields[5]),
+                runtime_dir: PathBuf::from(format!("/run/user/{}", fields[2])),
+            })
+        })
+        .ok_or_else(|| format!("No user named '{}'", user))
+}
+
+/// Turns this process into `user`, with their groups. Only root can do that for someone
+/// else.
+fn become_user(user: &str, account: &Account) -> Result<(), String> {
+    // SAFETY: plain system calls; called first thing in `main`, while this is the only
+    // thread, so no other thread keeps running with the old credentials.
+    unsafe {
+        if libc::geteuid() == account.uid {
+            return Ok(());
+        }
+        if libc::geteuid() != 0 {
+            return Err(format!(
+                "Only root can act on the session of '{}'; run this as root or as that user",
+                user
+            ));
+        }
+        let name = CString::new(user).map_err(|e| e.to_string())?;
+        if libc::initgroups(name.as_ptr(), account.gid) != 0
+            || libc::setgid(account.gid) != 0
+            || libc::setuid(account.uid) != 0
+        {
+            return Err(format!(
+                "Failed to switch to user '{}': {}",
+                user,
+                io::Error::last_os_error()
+            ));
+        }
+    }
+    mdisplay_core::settings::distrust_commands();
+    Ok(())
+}
+
+/// The first Wayland socket in `runtime_dir`, usually `wayland-0` or `wayland-1`.
+fn find_display(runtime_dir: &Path) -> Result<String, String> {
+    let mut sockets: Vec<String> = fs::read_dir(runtime_dir)
+        .map_err(|e| format!("Failed to read {}: {}", runtime_dir.display(), e))?
+        .flatten()
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .filter(|name| name.starts_with("wayland-") && !name.ends_with(".lock"))
+        .collect();
+    sockets.sort();
+    sockets.into_iter().next().ok_or_else(|| {
+        format!(
+            "No Wayland compositor is running in {}",
+            runtime_dir.display()
+        )
+    })
+}
+
+/// The environment variables to set, or to remove when `None`, to reach the session of
+/// `account` or the one given by `runtime_dir` and `display`. Whatever is left out is
+/// worked out from the rest.
+fn environment(
+    account: Option<Account>,
+    runtime_dir: Option<&Path>,
+    display: Option<&str>,
+) -> Result<Vec<(&'static str, Option<OsString>)>, String> {
+    let mut vars = Vec::new();
+    if let Some(account) = &account {
+        vars.push(("HOME", Some(account.home.clone().into_os_string())));
+        // Those of whoever runs the command would win over the user's home.
+        for var in ["XDG_CONFIG_HOME", "XDG_STATE_HOME", "XDG_CACHE_HOME"] {
+            vars.push((var, None));
+        }
+    }
+    let runtime_dir = runtime_dir
+        .map(Path::to_path_buf)
+        .or_else(|| account.map(|account| account.runtime_dir));
+    if let Some(dir) = &runtime_dir {
+        if !dir.is_dir() {
+            return Err(format!(
+                "{} does not exist; is the user logged in?",
+                dir.display()
+            ));
+        }
+        vars.push(("XDG_RUNTIME_DIR", Some(dir.clone().into_os_string())));
+    }
+    let display = match (display, &runtime_dir) {
+        (Some(display), _) => Some(display.to_string()),
+        (None, Some(dir)) => Some(find_display(dir)?),
+        (None, None) => None,
+    };
+    if let Some(display) = display {
+        vars.push(("WAYLAND_DISPLAY", Some(display.into())));
+    }
+    Ok(vars)
+}
+
+/// Points this process at another session. Must run before any other thread starts.
+pub fn enter(
+    user: Option<&str>,
+    runtime_dir: Option<&Path>,
+    display: Option<&str>,
+) -> Result<(), String> {
+    let account = user.map(account).transpose()?;
+    if let (Some(user), Some(account)) = (user, &account) {
+        become_user(user, account)?;
+    }
+    for (var, value) in environment(account, runtime_dir, display)? {
+        // SAFETY: called first thing in `main`, while this is the only thread.
+        unsafe {
+            match value {
+                Some(value) => std::env::set_var(var, value),
+                None => std::env::remove_var(var),
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,112 @@
+//! The ambient light sensor and the accelerometer, read through iio-sensor-proxy on the
+//! system bus.
+
+use zbus::blocking::Connection;
+use zbus::proxy;
+
+#[proxy(
+    interface = "net.hadess.SensorProxy",
+    default_service = "net.hadess.SensorProxy",
+    default_path = "/net/hadess/SensorProxy"
+)]
+trait Sensor {
+    fn claim_light(&self) -> zbus::Result<()>;
+
+    #[zbus(property)]
+    fn has_ambient_light(&self) -> zbus::Result<bool>;
+
+    #[zbus(property)]
+    fn light_level(&self) -> zbus::Result<f64>;
+
+    /// `lux`, or `vendor` for sensors that only report a relative level.
+    #[zbus(property)]
+    fn light_level_unit(&self) -> zbus::Result<String>;
+
+    fn claim_accelerometer(&self) -> zbus::Result<()>;
+
+    #[zbus(property)]
+    fn has_accelerometer(&self) -> zbus::Result<bool>;
+
+    /// `normal`, `bottom-up`, `left-up`, `right-up` or `undefined`.
+    #[zbus(property)]
+    fn accelerometer_orientation(&self) -> zbus::Result<String>;
+}
+
+fn connect() -> Result<(Connection, SensorProxyBlocking<'static>), String> {
+    let bus = Connection::system().map_err(|e| format!("Failed to reach the system bus: {}", e))?;
+    let sensor = SensorProxyBlocking::new(&bus)
+        .map_err(|e| format!("Failed to reach iio-sensor-proxy: {}", e))?;
+    Ok((bus, sensor))
+}
+
+/// Blocks, sending each new light level to `on_level` until it returns `false`. Fails
+/// when iio-sensor-proxy is not running or the machine has no light sensor.
+pub fn watch_light(mut on_level: impl FnMut(f64) -> bool) -> Result<(), String> {
+    let (_bus, sensor) = connect()?;
+    if !sensor.has_ambient_light().unwrap_or(false) {
+        return Err("No ambient light sensor".to_string());
+    }
+    sensor
+        .claim_light()
+        .map_err(|e| format!("Failed to claim the light sensor: {}", e))?;
+    if let Ok(unit) = sensor.light_level_unit()
+        && unit != "lux"
+    {
+        tracing::info!("The light sensor reports {} units rather than lux", unit);
+    }
+
+    let changes = sensor.receive_light_level_changed();
+    let first = sensor.light_level().map_err(|e| e.to_string())?;
+    if !on_level(first) {
+        return Ok(());
+    }
+    for change in changes {
+        let Ok(level) = change.get() else {
+            continue;
+        };
+        if !on_level(level) {
+            return Ok(());
+        }
+    }
+    Ok(())
+}
+
+/// Blocks, sending the transform that keeps the built-in panel upright, in wlr-randr
+/// names, whenever the device is turned, until `on_turn` returns `false`. Fails without
+/// iio-sensor-proxy or an accelerometer.
+pub fn watch_orientation(mut on_turn: impl FnMut(&'static str) -> bool) -> Result<(), String> {
+    let (_bus, sensor) = connect()?;
+    if !sensor.has_accelerometer().unwrap_or(false) {
+        return Err("No accelerometer".to_string());
+    }
+    sensor
+        .claim_accelerometer()
+        .map_err(|e| format!("Failed to claim the accelerometer: {}", e))?;
+
+    let transform = |orientation: &str| match orientation {
+        "normal" => Some("normal"),
+        "bottom-up" => Some("180"),
+        "left-up" => Some("90"),
+        "right-up" => Some("270"),
+        // Lying flat.
+        _ => None,
+    };
+    let changes = sensor.receive_accelerometer_orientation_changed();
+    let first = sensor
+        .accelerometer_orientation()
+        .map_err(|e| e.to_string())?;
+    if let Some(t) = transform(&first)
+        && !on_turn(t)
+    {
+        return Ok(());
+    }
+    for change in changes {
+        let Some(t) = change.get().ok().as_deref().and_then(transform) else {
+            continue;
+        };
+        if !on_turn(t) {
+            return Ok(());
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,18 @@
+//! Helpers shared by the tests.
+
+use std::sync::Once;
+
+/// Points the XDG config and state directories at a directory of this test run's own, so
+/// the settings and history that tests read and write are never the user's.
+pub fn isolate_dirs() {
+    static ONCE: Once = Once::new();
+    ONCE.call_once(|| {
+        let root = std::env::temp_dir().join(format!("mdisplay-test-{}", std::process::id()));
+        // SAFETY: nothing else in the test binary touches the environment, and these two
+        // only ever get this one value.
+        unsafe {
+            std::env::set_var("XDG_CONFIG_HOME", root.join("config"));
+            std::env::set_var("XDG_STATE_HOME", root.join("state"));
+        }
+    });
+}
@@ -0,0 +1,151 @@
+use ksni::blocking::TrayMethods;
+use ksni::menu::{StandardItem, SubMenu};
+use ksni::{MenuItem, Tray};
+
+use crate::i18n::{tr, trf};
+use crate::ipc;
+use crate::notify;
+use crate::watch;
+use mdisplay_core::profile::Profile;
+use mdisplay_core::settings::AppSettings;
+
+struct DisplayTray {
+    profiles: Vec<Profile>,
+}
+
+impl DisplayTray {
+    fn activate_profile(profile: &Profile) {
+        let settings = AppSettings::load();
+        match ipc::apply_profile(profile) {
+            Ok(()) => notify::event(&settings, &trf("Applied profile {}", &[&profile.name]), ""),
+            Err(e) => {
+                tracing::error!("Error applying profile '{}': {}", profile.name, e);
+                notify::event(&settings, tr("Apply failed"), &e);
+            }
+        }
+    }
+
+    /// Lists each connected output in a notification so the user can match connector
+    /// names to panels.
+    fn identify() {
+        let outputs = match mdisplay_core::backend::fetch_outputs() {
+            Ok(outputs) => outputs,
+            Err(e) => {
+                tracing::error!("Error reading outputs: {}", e);
+                return;
+            }
+        };
+        for out in outputs.iter().filter(|o| o.enabled) {
+            let mode = out
+                .modes
+                .iter()
+                .find(|m| m.current)
+                .map(|m| format!("{}x{} @ {:.2} Hz", m.width, m.height, m.refresh_rate))
+                .unwrap_or_default();
+            let body = trf(
+                "{}\n{} at {},{}",
+                &[&out.description, &mode, &out.position.0, &out.position.1],
+            );
+            if let Err(e) = notify::send(&out.name, &body) {
+                tracing::warn!("{}", e);
+            }
+        }
+    }
+
+    fn open_window() {
+        let spawned =
+            std::env::current_exe().and_then(|exe| std::process::Command::new(exe).spawn());
+        if let Err(e) = spawned {
+            tracing::error!("Error launching mdisplay: {}", e);
+        }
+    }
+}
+
+impl Tray for DisplayTray {
+    fn id(&self) -> String {
+        "mdisplay".into()
+    }
+
+    fn title(&self) -> String {
+        "MDisplay".into()
+    }
+
+    fn icon_name(&self) -> String {
+        "video-display".into()
+    }
+
+    fn activate(&mut self, _x: i32, _y: i32) {
+        Self::open_window();
+    }
+
+    fn menu(&self) -> Vec<MenuItem<Self>> {
+        let profiles: Vec<MenuItem<Self>> = self
+            .profiles
+            .iter()
+            .enumerate()
+            .map(|(idx, p)| {
+                StandardItem {
+                    label: p.name.clone(),
+                    activate: Box::new(move |this: &mut Self| {
+                        if let Some(profile) = this.profiles.get(idx) {
+                            Self::activate_profile(profile);
+                        }
+                    }),
+                    ..Default::default()
+                }
+                .into()
+            })
+            .collect();
+
+        vec![
+            SubMenu {
+                label: tr("Profiles").into(),
+                enabled: !profiles.is_empty(),
+                submenu: profiles,
+                ..Default::default()
+            }
+            .into(),
+            StandardItem {
+                label: tr("Identify").into(),
+                activate: Box::new(|_: &mut Self| Self::identify()),
+                ..Default::default()
+            }
+            .into(),
+            StandardItem {
+                label: tr("Open MDisplay").into(),
+                activate: Box::new(|_: &mut Self| Self::open_window()),
+                ..Default::default()
+            }
+            .into(),
+            MenuItem::Separator,
+            StandardItem {
+                label: tr("Quit").into(),
+                icon_name: "application-exit".into(),
+                activate: Box::new(|_: &mut Self| std::process::exit(0)),
+                ..Default::default()
+            }
+            .into(),
+        ]
+    }
+}
+
+/// Runs the StatusNotifierItem until the user picks "Quit".
+pub fn run() -> Result<(), String> {
+    let mut known = AppSettings::load().profiles;
+    let tray = DisplayTray {
+        profiles: known.clone(),
+    };
+    let handle = tray
+        .spawn()
+        .map_err(|e| format!("Failed to register tray icon: {}", e))?;
+
+    // Profiles saved in the GUI or by a dotfile manager show up in the menu right away.
+    watch::watch_files(&[AppSettings::path()], |_| {
+        let profiles = AppSettings::load().profiles;
+        if profiles != known {
+            known = profiles.clone();
+            handle.update(move |tray: &mut DisplayTray| tray.profiles = profiles);
+        }
+        true
+    })
+}
@@ -1,20 +1,232 @@
 use iced::widget::canvas::{self, Action, Cache, Canvas, Event, Geometry, Path, Program};
 use iced::widget::{
-    Container, Scrollable, Space, button, checkbox, column, container, pick_list, row, text,
-    text_input,
+    Container, Scrollable, Space, button, center, checkbox, column, combo_box, container,
+    mouse_area, opaque, operation, pick_list, row, slider, stack, text, text_input, tooltip,
 };
 use iced::{
-    Color, Element, Length, Point, Rectangle, Renderer, Size, Task, Theme, alignment, mouse,
+    Color, Element, Length, Padding, Point, Rectangle, Renderer, Size, Subscription, Task, Theme,
+    alignment, event, keyboard, mouse, window,
 };
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
 use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use crate::backend::{Output, OutputMode, restore_default_config, save_config};
-use crate::wayland::{apply_outputs, fetch_outputs};
+use crate::i18n::{Language, decimal, localized, parse_decimal, tr, trf};
+use crate::ipc::{self, DaemonStatus};
+use crate::notify;
+use crate::portal;
+use crate::watch;
+use mdisplay_core::backend::{
+    self, Backend, Output, OutputMode, PixelFormat, RgbRange, ScaleSteps, Subpixel, clone_groups,
+    fetch_outputs, has_whole_logical_size, restore_default_config, save_config, save_config_to,
+};
+use mdisplay_core::bandwidth::{self, LinkWarning};
+use mdisplay_core::brightness;
+use mdisplay_core::calibration::CalibrationWizard;
+use mdisplay_core::compositor::{self, BitDepth, Compositor, ScaleFilter};
+use mdisplay_core::connector::{self, Connector, ConnectorKind};
+use mdisplay_core::cvt::{self, Blanking};
+use mdisplay_core::dock::{self, Dock};
+use mdisplay_core::edid::EdidInfo;
+use mdisplay_core::gamma;
+use mdisplay_core::history::{self, EventKind};
+use mdisplay_core::input::{self, InputDevice, InputKind};
+use mdisplay_core::limits::{self, LimitProblem};
+use mdisplay_core::overlay;
+use mdisplay_core::paths;
+use mdisplay_core::pattern::{MotionTest, OverscanFrame, PixelTest, TestPattern};
+use mdisplay_core::plugin;
+use mdisplay_core::profile::{self, LidCondition, PowerCondition, Profile, ProfileRule};
+use mdisplay_core::report;
+use mdisplay_core::seat;
+use mdisplay_core::settings::{
+    AppSettings, BatteryAction, Bezels, Calibration, Overscan, SaveFormat, Snapping,
+};
+use mdisplay_core::tools::Tool;
+use mdisplay_core::verify::{self, Mismatch};
+
+const SCALE_PRESETS: [f32; 5] = [1.0, 1.25, 1.5, 1.75, 2.0];
+/// Sizes offered for the interface, which can be too small to read on a high-density
+/// panel until its own scale is set.
+const UI_SCALES: [f32; 5] = [1.0, 1.25, 1.5, 1.75, 2.0];
+
+/// Settings-page name of the default backend, which tries Wayland and then the tools.
+const AUTO_BACKEND: &str = "auto";
+/// Settings-page name of the seat of the session the window runs in.
+const AUTO_SEAT: &str = "auto";
+/// Settings-page name of the built-in wlr-output-management backend.
+const WAYLAND_BACKEND: &str = "wayland";
+/// Settings-page name of [`mdisplay_theme`].
+const DEFAULT_THEME: &str = "MDisplay";
+/// Settings-page name of [`high_contrast_theme`].
+const HIGH_CONTRAST_THEME: &str = "High contrast";
+/// The colour the high-contrast theme marks the selection with.
+const HIGH_CONTRAST_ACCENT: Color = Color::from_rgb8(255, 214, 0);
+/// How much larger the canvas text is with the high-contrast theme.
+const HIGH_CONTRAST_TEXT_SCALE: f32 = 1.4;
+const LOG_LEVELS: [&str; 5] = ["error", "warn", "info", "debug", "trace"];
+
+/// How long live preview waits after the last edit before applying.
+const LIVE_PREVIEW_DELAY: Duration = Duration::from_millis(500);
+/// Seconds a live-previewed layout stays before reverting unless kept.
+const REVERT_SECONDS: u32 = 15;
+
+/// MDisplay's own grey-on-black theme.
+fn mdisplay_theme() -> Theme {
+    let palette = iced::theme::Palette {
+        background: Color::from_rgb8(20, 20, 20),
+        text: Color::from_rgb8(230, 230, 230),
+        primary: Color::from_rgb8(100, 100, 100),
+        success: Color::from_rgb8(60, 60, 60),
+        danger: Color::from_rgb8(80, 80, 80),
+        warning: Color::from_rgb8(120, 120, 120),
+    };
+    Theme::Custom(Arc::new(iced::theme::Custom::new(
+        "MonoDark".to_string(),
+        palette,
+    )))
+}
+
+/// White on black with a yellow accent, for low vision. The canvas also draws thicker
+/// outlines and larger text with it.
+fn high_contrast_theme() -> Theme {
+    let palette = iced::theme::Palette {
+        background: Color::BLACK,
+        text: Color::WHITE,
+        primary: HIGH_CONTRAST_ACCENT,
+        success: Color::from_rgb8(0, 230, 118),
+        danger: Color::from_rgb8(255, 82, 82),
+        warning: Color::from_rgb8(255, 171, 64),
+    };
+    Theme::Custom(Arc::new(iced::theme::Custom::new(
+        HIGH_CONTRAST_THEME.to_string(),
+        palette,
+    )))
+}
+
+fn scale_preset_label(scale: f32) -> String {
+    format!("{}%", (scale * 100.0).round() as i32)
+}
+
+/// Reads an X or Y field; the error explains the refusal in the field's tooltip.
+fn parse_position(text: &str) -> Result<i32, &'static str> {
+    match i32::from_str(text.trim()) {
+        Ok(v) if v >= 0 => Ok(v),
+        Ok(_) => Err(tr("Positions cannot be negative")),
+        Err(_) => Err(tr("Enter a whole number of pixels")),
+    }
+}
+
+/// Reads the scale field; the error explains the refusal in the field's tooltip.
+fn parse_scale(text: &str) -> Result<f32, &'static str> {
+    match parse_decimal::<f32>(text) {
+        Some(v) if v.is_finite() && v > 0.1 => Ok(v),
+        Some(_) => Err(tr("The scale must be above 0.1")),
+        None => Err(tr("Enter a scale such as 1.5")),
+    }
+}
+
+/// The usual text input look, outlined in the theme's danger colour when `invalid`.
+fn input_style(invalid: bool) -> impl Fn(&Theme, text_input::Status) -> text_input::Style {
+    move |theme, status| {
+        let style = text_input::default(theme, status);
+        if !invalid {
+            return style;
+        }
+        text_input::Style {
+            border: style.border.color(theme.palette().danger).width(2.0),
+            ..style
+        }
+    }
+}
+
+fn scale_warning(steps: ScaleSteps, mode: &OutputMode, scale: f32) -> Option<String> {
+    let template = if !steps.accepts(mode, scale) {
+        match steps {
+            ScaleSteps::Eighths => "Scale {} will be rounded by the compositor and may look blurry",
+            ScaleSteps::WholeLogicalSize => {
+                "Scale {} does not divide the mode evenly and will be adjusted when applied"
+            }
+            ScaleSteps::Unscaled => {
+                "This backend cannot scale outputs; scale {} will be reset to 1"
+            }
+        }
+    } else if !has_whole_logical_size(mode, scale) {
+        "Scale {} gives a fractional logical size and may look blurry"
+    } else {
+        return None;
+    };
+    Some(trf(template, &[&decimal(scale, 3)]))
+}
+
+/// A setting the compositor did not take, e.g. "DP-1 scale: 1 instead of 1.5".
+fn mismatch_text(mismatch: &Mismatch) -> String {
+    let value = |value: &str| match value {
+        "on" => tr("on").to_string(),
+        "off" => tr("off").to_string(),
+        _ => value.to_string(),
+    };
+    trf(
+        "{} {}: {} instead of {}",
+        &[
+            &mismatch.output,
+            &tr(mismatch.field),
+            &value(&mismatch.actual),
+            &value(&mismatch.requested),
+        ],
+    )
+}
+
+fn link_warning_text(warning: LinkWarning) -> String {
+    match warning {
+        LinkWarning::Chroma420 => {
+            tr("HDMI 2.0 carries this mode only with 4:2:0 chroma, which blurs colored text")
+                .to_string()
+        }
+        LinkWarning::DualLinkDvi => tr("This mode needs a dual-link DVI cable").to_string(),
+        LinkWarning::UsbCLanes => tr(
+            "This mode needs all four DisplayPort lanes; a dock that also carries USB 3 may not manage it",
+        )
+        .to_string(),
+        LinkWarning::ExceedsLink(kind) => trf(
+            "This mode is likely more than {} can carry; try a lower refresh rate or reduced blanking",
+            &[&kind.label()],
+        ),
+        LinkWarning::ExceedsDisplay(max) => trf(
+            "Above the monitor's {} MHz limit; try reduced blanking",
+            &[&max],
+        ),
+    }
+}
+
+fn limit_problem_text(problem: LimitProblem) -> String {
+    match problem {
+        LimitProblem::Coordinates(w, h) => trf(
+            "The layout spans {}x{} pixels; X11 programs cannot reach past {}. Move the outputs closer together.",
+            &[&w, &h, &limits::COORDINATE_LIMIT],
+        ),
+        LimitProblem::Framebuffer((w, h), (max_w, max_h)) => trf(
+            "The layout needs a {}x{} framebuffer but the GPU supports at most {}x{}",
+            &[&w, &h, &max_w, &max_h],
+        ),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Edge {
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
 
 #[derive(Debug, Clone)]
 pub enum Message {
-    MonitorClicked(usize),
-    MonitorPositioned(usize, i32, i32),
+    MonitorClicked(String),
+    MonitorPositioned(String, i32, i32),
+    MonitorUndocked(String, i32, i32),
     XChanged(String),
     YChanged(String),
     XInc,
@@ -24,420 +236,3566 @@ pub enum Message {
     ScaleChanged(String),
     ScaleInc,
     ScaleDec,
+    ScalePresetSelected(String),
+    BezelChanged(Edge, String),
+    NightLightToggled(bool),
+    HideLowResToggled(bool),
+    ColorTemperatureChanged(u32),
     EnabledToggled(bool),
     ResolutionSelected(usize),
     TransformSelected(String),
     ApplyClicked,
+    ApplyFinished(Result<(Vec<Output>, Vec<Mismatch>), String>),
+    OutputsLoaded(Result<Vec<Output>, String>),
     SaveClicked,
+    SaveFinished(Result<(), String>),
+    SaveAsClicked,
+    SaveAsFinished(Result<Option<PathBuf>, String>),
+    SpinnerTick,
+    /// A frame of a [`Transition`] was drawn.
+    TransitionTick(Instant),
+    DaemonPolled,
+    DaemonStatusReceived(Result<Option<DaemonStatus>, String>),
+    PreviewClicked,
+    PreviewClosed,
     RestoreDefaultClicked,
     ResolutionSizeSelected(String),
+    RefreshRateTyped(String),
+    CustomRefreshSubmitted,
+    BlankingSelected(Blanking),
+    ScaleFilterSelected(ScaleFilter),
+    BitDepthSelected(BitDepth),
+    SubpixelSelected(Subpixel),
+    ModelineCopied(String),
+    FitView,
+    DragFinished,
+    Undo,
+    SelectNext,
+    SelectPrevious,
+    DisableSelected,
+    ToggleSelectedEnabled,
+    NudgeSelected(i32, i32),
+    CycleMode(isize),
+    CycleTransform,
+    ResetToPreferred,
+    FocusNext,
+    FocusPrevious,
+    LanguageSelected(Language),
+    OutputsChanged(Vec<Output>),
+    PaneSelected(Pane),
+    PhysicalViewToggled,
+    GridToggled,
+    ProfileNameChanged(String),
+    ProfileSaved,
+    ProfileSelected(String),
+    OfflineEditStarted(String),
+    OfflineEditFinished,
+    ProfileExported,
+    ProfileExportFinished(Result<Option<PathBuf>, String>),
+    ReportExported,
+    ReportExportFinished(Result<Option<PathBuf>, String>),
+    ProfileImported,
+    ProfileImportFinished(Result<Option<Profile>, String>),
+    CanvasZoomed(f32),
+    WindowResized(Size),
+    SidebarResizeStarted,
+    SidebarResized(f32),
+    SidebarResizeFinished,
+    NicknameChanged(String),
+    /// A tab was pressed, which selects its output and starts dragging it.
+    TabPressed(String),
+    /// The cursor moved onto another output's tab.
+    TabEntered(String),
+    TabDragFinished,
+    CloseRequested,
+    SettingsToggled,
+    HistoryToggled,
+    HistoryRefreshed,
+    MonitorsPathChanged(String),
+    ConfigPathChanged(String),
+    AutoAppendToggled(bool),
+    SaveFormatSelected(SaveFormat),
+    BackendSelected(String),
+    SeatSelected(String),
+    ToolPathChanged(Tool, String),
+    SnappingToggled(bool),
+    SnapThresholdChanged(i32),
+    NightScheduleToggled(bool),
+    BrightnessLoaded(Vec<(String, brightness::Control, u8)>),
+    BrightnessChanged(u8),
+    BrightnessReleased,
+    BrightnessApplied(Result<(), String>),
+    BrightnessLinkToggled(bool),
+    AutoBrightnessToggled(bool),
+    AutoBrightnessExternalToggled(bool),
+    CurvePointChanged(usize, u8),
+    BatteryActionSelected(BatteryAction),
+    BatteryBrightnessChanged(u8),
+    AdaptiveSyncToggled(bool),
+    RgbRangeSelected(RgbRange),
+    PixelFormatSelected(PixelFormat),
+    QuickActionClicked(QuickAction),
+    QuickActionReverted,
+    LivePreviewToggled(bool),
+    LivePreviewDue(u64),
+    RevertTick(u64),
+    LiveChangesKept,
+    LiveChangesReverted,
+    VirtualOutputAdded,
+    VirtualOutputRemoved(String),
+    VirtualOutputFinished(Result<(), String>),
+    GamingDisablesOthersToggled(bool),
+    GameModeProfileSelected(Option<String>),
+    AutoRotateToggled(bool),
+    LatitudeChanged(String),
+    LongitudeChanged(String),
+    TransitionChanged(u32),
+    ThemeSelected(String),
+    UiScaleSelected(String),
+    NotificationsToggled(bool),
+    PreApplyHookChanged(String),
+    PostApplyHookChanged(String),
+    LogLevelSelected(String),
+    LogToFileToggled(bool),
+    SettingsFileChanged,
+    MonitorsConfigChanged,
+    InputMapped(String, Option<String>),
+    DockProfileSelected(String, Option<String>),
+    WallpaperChanged(String, String),
+    ProfileRuleChanged(String, Option<ProfileRule>),
+    MirrorSelected(Option<String>),
+    PatternOpened(PatternKind),
+    OverscanOpened,
+    OverscanMeasured(String, Result<Overscan, String>),
+    OverscanReset,
+    ContextMenuOpened(String, Point),
+    ContextMenuClosed,
+    /// An output was double-clicked on the canvas.
+    OutputDialogOpened(String),
+    OutputDialogClosed,
+    PatternClosed(Result<(), String>),
+    CalibrationOpened,
+    CalibrationClosed,
+    CalibrationStarted,
+    CalibrationFinished(String, Result<Option<Calibration>, String>),
+}
+
+/// Re-reads the outputs each time the compositor reports a configuration change. The
+/// Wayland wait blocks, so it runs on its own thread.
+fn output_changes() -> iced::futures::channel::mpsc::Receiver<Message> {
+    let (mut sender, receiver) = iced::futures::channel::mpsc::channel(4);
+    std::thread::spawn(move || {
+        loop {
+            if let Err(e) = mdisplay_core::wayland::wait_for_output_change() {
+                tracing::warn!("Stopped watching outputs: {}", e);
+                return;
+            }
+            if let Ok(outputs) = fetch_outputs()
+                && sender.try_send(Message::OutputsChanged(outputs)).is_err()
+                && sender.is_closed()
+            {
+                return;
+            }
+        }
+    });
+    receiver
+}
+
+/// Ticks the busy spinner along while a backend job runs.
+fn spinner_ticks() -> iced::futures::channel::mpsc::Receiver<Message> {
+    let (mut sender, receiver) = iced::futures::channel::mpsc::channel(1);
+    std::thread::spawn(move || {
+        loop {
+            std::thread::sleep(Duration::from_millis(80));
+            if sender.try_send(Message::SpinnerTick).is_err() && sender.is_closed() {
+                return;
+            }
+        }
+    });
+    receiver
+}
+
+/// How often the window asks a running daemon what it is doing.
+const DAEMON_POLL: Duration = Duration::from_secs(3);
+
+/// Asks for the daemon's status every [`DAEMON_POLL`].
+fn daemon_polls() -> iced::futures::channel::mpsc::Receiver<Message> {
+    let (mut sender, receiver) = iced::futures::channel::mpsc::channel(1);
+    std::thread::spawn(move || {
+        loop {
+            if sender.try_send(Message::DaemonPolled).is_err() && sender.is_closed() {
+                return;
+            }
+            std::thread::sleep(DAEMON_POLL);
+        }
+    });
+    receiver
+}
+
+/// Reports edits to `files`, the settings file followed by the monitors config, e.g. by a
+/// dotfile manager, from a watcher thread.
+fn config_changes(files: &[PathBuf; 2]) -> iced::futures::channel::mpsc::Receiver<Message> {
+    let files = files.clone();
+    let (mut sender, receiver) = iced::futures::channel::mpsc::channel(4);
+    std::thread::spawn(move || {
+        let result = watch::watch_files(&files, |file| {
+            let message = if file == files[0] {
+                Message::SettingsFileChanged
+            } else {
+                Message::MonitorsConfigChanged
+            };
+            sender.try_send(message).is_ok() || !sender.is_closed()
+        });
+        if let Err(e) = result {
+            tracing::warn!("Stopped watching the config files: {}", e);
+        }
+    });
+    receiver
+}
+
+/// Below this window width the canvas and sidebar share the window as switchable panes.
+const COMPACT_WIDTH: f32 = 760.0;
+
+/// The half of the window shown in compact mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pane {
+    Layout,
+    Settings,
+}
+
+const MIN_SIDEBAR_WIDTH: f32 = 300.0;
+const MIN_CANVAS_WIDTH: f32 = 300.0;
+const SIDEBAR_PADDING: f32 = 20.0;
+
+fn tab_drag_event(
+    event: iced::Event,
+    _status: iced::event::Status,
+    _window: window::Id,
+) -> Option<Message> {
+    match event {
+        iced::Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
+            Some(Message::TabDragFinished)
+        }
+        _ => None,
+    }
+}
+
+fn sidebar_resize_event(
+    event: iced::Event,
+    _status: iced::event::Status,
+    _window: window::Id,
+) -> Option<Message> {
+    match event {
+        iced::Event::Mouse(mouse::Event::CursorMoved { position }) => {
+            Some(Message::SidebarResized(position.x))
+        }
+        iced::Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
+            Some(Message::SidebarResizeFinished)
+        }
+        _ => None,
+    }
+}
+
+const TRANSFORMS: [&str; 8] = [
+    "normal",
+    "90",
+    "180",
+    "270",
+    "flipped",
+    "flipped-90",
+    "flipped-180",
+    "flipped-270",
+];
+
+/// Width of a tab at the top of the sidebar, and how many characters of the output's
+/// name fit in it beside the connector icon.
+const TAB_WIDTH: f32 = 110.0;
+const TAB_LABEL_CHARS: usize = 9;
+
+/// `text` cut to `max` characters, ending in an ellipsis when it was longer.
+fn ellipsize(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+    let mut cut: String = text.chars().take(max.saturating_sub(1)).collect();
+    cut.push('…');
+    cut
+}
+
+/// Pixels moved per arrow key press when nudging the selected monitor, and the grid a
+/// drag with Shift held rounds to whatever the snapping grid is.
+const NUDGE_STEP: i32 = 10;
+
+/// Pixels moved per arrow key press with Ctrl held, for exact placement.
+const FINE_NUDGE_STEP: i32 = 1;
+
+/// An entry in the searchable mode list, pointing back into `Output::modes`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModeChoice {
+    index: usize,
+    width: i32,
+    height: i32,
+    refresh_rate: f32,
+    preferred: bool,
+    current: bool,
+}
+
+impl ModeChoice {
+    fn from_modes(modes: &[OutputMode], hide_low_res: bool) -> Vec<Self> {
+        modes
+            .iter()
+            .enumerate()
+            .filter(|(_, m)| !hide_low_res || m.current || !is_low_res(m))
+            .map(|(index, m)| ModeChoice {
+                index,
+                width: m.width,
+                height: m.height,
+                refresh_rate: m.refresh_rate,
+                preferred: m.preferred,
+                current: m.current,
+            })
+            .collect()
+    }
+}
+
+impl std::fmt::Display for ModeChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}x{} @ {} Hz",
+            self.width,
+            self.height,
+            decimal(self.refresh_rate, 3)
+        )?;
+        if self.preferred {
+            f.write_str(PREFERRED_MARK)?;
+        }
+        Ok(())
+    }
+}
+
+/// The fullscreen checks that can be shown on an output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PatternKind {
+    TestPattern,
+    PixelTest,
+    MotionTest,
+}
+
+/// How long outputs take to glide to where a layout change put them.
+const TRANSITION: Duration = Duration::from_millis(250);
+
+/// Outputs gliding on the canvas from where they were to where a loaded profile, a quick
+/// action, an undo or normalization put them, so the change can be followed.
+struct Transition {
+    /// Where each output started, by name.
+    from: HashMap<String, (i32, i32)>,
+    start: Instant,
+    /// How far along the outputs are, from 0 to 1, eased.
+    progress: f32,
+}
+
+impl Transition {
+    /// `rects` of `outputs`, as drawn on the canvas, moved back towards where they
+    /// started.
+    fn apply(&self, outputs: &[Output], rects: &mut [Rectangle]) {
+        for (rect, out) in rects.iter_mut().zip(outputs) {
+            if let Some(&(x, y)) = self.from.get(&out.name) {
+                rect.x = x as f32 + (rect.x - x as f32) * self.progress;
+                rect.y = y as f32 + (rect.y - y as f32) * self.progress;
+            }
+        }
+    }
+}
+
+/// Layout changes made by live preview that revert unless kept.
+struct PendingRevert {
+    /// The layout from before the first live change.
+    layout: Vec<Output>,
+    seconds: u32,
+    /// The edit that started the countdown, so ticks of an earlier one are ignored.
+    id: u64,
+}
+
+/// One-click layout changes that can be reverted with one click.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuickAction {
+    /// See [`gaming_layout`].
+    Gaming,
+    /// See [`presentation_layout`].
+    Present,
+}
+
+/// `outputs` set up for playing on `idx`: its fastest mode at the current resolution,
+/// adaptive sync on where the compositor supports it, and optionally every other output
+/// off.
+fn gaming_layout(outputs: &[Output], idx: usize, disable_others: bool) -> Vec<Output> {
+    let mut outputs = outputs.to_vec();
+    for (i, out) in outputs.iter_mut().enumerate() {
+        if i != idx {
+            if disable_others {
+                out.enabled = false;
+            }
+            continue;
+        }
+        out.enabled = true;
+        let size = out
+            .modes
+            .iter()
+            .find(|m| m.current)
+            .map(|m| (m.width, m.height));
+        let fastest = out
+            .modes
+            .iter()
+            .enumerate()
+            .filter(|(_, m)| size.is_none_or(|s| s == (m.width, m.height)))
+            .max_by(|(_, a), (_, b)| a.refresh_rate.total_cmp(&b.refresh_rate))
+            .map(|(i, _)| i);
+        if let Some(fastest) = fastest {
+            for (i, m) in out.modes.iter_mut().enumerate() {
+                m.current = i == fastest;
+            }
+        }
+        if out.adaptive_sync.is_some() {
+            out.adaptive_sync = Some(true);
+        }
+    }
+    outputs
+}
+
+/// `outputs` with `target` (a projector, usually) mirroring `source` at the largest
+/// resolution both support, each at its fastest rate for it. `None` when they share no
+/// resolution.
+fn presentation_layout(outputs: &[Output], source: usize, target: usize) -> Option<Vec<Output>> {
+    let sizes = |out: &Output| -> Vec<(i32, i32)> {
+        out.modes.iter().map(|m| (m.width, m.height)).collect()
+    };
+    let target_sizes = sizes(&outputs[target]);
+    let size = sizes(&outputs[source])
+        .into_iter()
+        .filter(|s| target_sizes.contains(s))
+        .max_by_key(|(w, h)| w * h)?;
+
+    let mut outputs = outputs.to_vec();
+    for idx in [source, target] {
+        let modes = &mut outputs[idx].modes;
+        let fastest = modes
+            .iter()
+            .enumerate()
+            .filter(|(_, m)| (m.width, m.height) == size)
+            .max_by(|(_, a), (_, b)| a.refresh_rate.total_cmp(&b.refresh_rate))
+            .map(|(i, _)| i)?;
+        for (i, m) in modes.iter_mut().enumerate() {
+            m.current = i == fastest;
+        }
+    }
+    let (position, scale, transform) = {
+        let src = &outputs[source];
+        (src.position, src.scale, src.transform.clone())
+    };
+    let out = &mut outputs[target];
+    out.enabled = true;
+    out.position = position;
+    out.scale = scale;
+    out.transform = transform;
+    Some(outputs)
+}
+
+/// The calibration wizard's steps, shown before it covers the output.
+fn calibration_intro<'a>(output: &str) -> Element<'a, Message> {
+    container(
+        column![
+            text(trf("Calibrate {}", &[&output])).size(15),
+            text(tr("1. Brightness: lower it until the background is black, then raise it until every dark patch is just visible.")).size(13),
+            text(tr("2. Contrast: raise it as far as every light patch stays distinct from the white background.")).size(13),
+            text(tr("3. Gamma: change it until the gray square blends into the striped background when seen from a few steps away.")).size(13),
+            text(tr("Up and Down change the value, Enter goes to the next step and Esc cancels. Brightness and contrast need ddcutil and a monitor with DDC/CI; steps the output does not support are skipped.")).size(13),
+            row![
+                button(text(tr("Start")).size(13)).on_press(Message::CalibrationStarted),
+                button(text(tr("Cancel")).size(13))
+                    .style(button::secondary)
+                    .on_press(Message::CalibrationClosed),
+            ]
+            .spacing(10),
+        ]
+        .spacing(8),
+    )
+    .padding(10)
+    .width(Length::Fill)
+    .style(container::bordered_box)
+    .into()
+}
+
+/// Appended to mode labels for the EDID-preferred mode.
+const PREFERRED_MARK: &str = " ★";
+
+/// Modes below XGA are rarely wanted on a desktop. wlr-output-management does not
+/// report interlacing, so resolution is the only signal available for filtering.
+fn is_low_res(mode: &OutputMode) -> bool {
+    mode.width * mode.height < 1024 * 768
+}
+
+/// Maximum number of layout snapshots kept for undo.
+const HISTORY_LIMIT: usize = 100;
+
+fn shortcut(event: keyboard::Event) -> Option<Message> {
+    let keyboard::Event::KeyPressed { key, modifiers, .. } = event else {
+        return None;
+    };
+    match key.as_ref() {
+        keyboard::Key::Character("s") if modifiers.command() => Some(Message::SaveClicked),
+        keyboard::Key::Character("z") if modifiers.command() => Some(Message::Undo),
+        keyboard::Key::Named(keyboard::key::Named::Enter) if modifiers.command() => {
+            Some(Message::ApplyClicked)
+        }
+        keyboard::Key::Named(keyboard::key::Named::Tab) if modifiers.shift() => {
+            Some(Message::SelectPrevious)
+        }
+        keyboard::Key::Named(keyboard::key::Named::Tab) => Some(Message::SelectNext),
+        keyboard::Key::Named(keyboard::key::Named::Delete) => Some(Message::DisableSelected),
+        keyboard::Key::Named(keyboard::key::Named::Escape) => Some(Message::ContextMenuClosed),
+        keyboard::Key::Named(keyboard::key::Named::F6) if modifiers.shift() => {
+            Some(Message::FocusPrevious)
+        }
+        keyboard::Key::Named(keyboard::key::Named::F6) => Some(Message::FocusNext),
+        keyboard::Key::Named(
+            arrow @ (keyboard::key::Named::ArrowLeft
+            | keyboard::key::Named::ArrowRight
+            | keyboard::key::Named::ArrowUp
+            | keyboard::key::Named::ArrowDown),
+        ) if !modifiers.alt() => {
+            let step = if modifiers.control() {
+                FINE_NUDGE_STEP
+            } else {
+                NUDGE_STEP
+            };
+            Some(match arrow {
+                keyboard::key::Named::ArrowLeft => Message::NudgeSelected(-step, 0),
+                keyboard::key::Named::ArrowRight => Message::NudgeSelected(step, 0),
+                keyboard::key::Named::ArrowUp => Message::NudgeSelected(0, -step),
+                _ => Message::NudgeSelected(0, step),
+            })
+        }
+        _ if modifiers.command() || modifiers.alt() => None,
+        keyboard::Key::Named(keyboard::key::Named::PageUp) => Some(Message::CycleMode(-1)),
+        keyboard::Key::Named(keyboard::key::Named::PageDown) => Some(Message::CycleMode(1)),
+        keyboard::Key::Character("+" | "=") => Some(Message::ScaleInc),
+        keyboard::Key::Character("-") => Some(Message::ScaleDec),
+        keyboard::Key::Character("r") => Some(Message::CycleTransform),
+        keyboard::Key::Character("e") => Some(Message::ToggleSelectedEnabled),
+        _ => None,
+    }
 }
 
 pub struct MangoDisplay {
     outputs: Vec<Output>,
-    selected_output_idx: Option<usize>,
-    layout_cache: Cache,
+    /// Name of the selected output. Outputs are kept by name rather than position since
+    /// the list is replaced whenever the compositor reports a change.
+    selected_output: Option<String>,
+    layout_cache: CanvasCaches,
+    history: Vec<Vec<Output>>,
+    applied_outputs: Vec<Output>,
+    view_frame: Rectangle,
     x_input: String,
     y_input: String,
     scale_input: String,
-    pub settings: crate::settings::AppSettings,
+    /// A refresh rate being typed in, for modes the output does not advertise.
+    refresh_input: String,
+    bezel_inputs: [String; 4],
+    mode_search: combo_box::State<ModeChoice>,
+    window_size: Size,
+    resizing_sidebar: bool,
+    /// The output whose tab is being dragged to another place in the tab row.
+    tab_drag: Option<String>,
+    profile_name: String,
+    command_preview: Option<String>,
+    compact_pane: Pane,
+    /// Whether the sidebar shows the application settings instead of the selected output.
+    settings_open: bool,
+    /// The profile being edited without its monitors connected; the canvas shows its
+    /// outputs instead of the live layout until the edit is finished.
+    offline: Option<String>,
+    /// Whether the sidebar shows the event history, and the events last read from it.
+    history_open: bool,
+    events: Vec<history::Event>,
+    /// Where on the canvas the selected output's context menu was opened.
+    context_menu: Option<Point>,
+    /// Whether the selected output's controls are shown in a dialog over the canvas.
+    output_dialog: bool,
+    /// Whether the calibration wizard's instructions are shown for the selected output.
+    calibration_intro: bool,
+    /// The quick action in effect and the layout from before it, for reverting it.
+    quick_action: Option<(QuickAction, Vec<Output>)>,
+    transition: Option<Transition>,
+    /// Counts edits made while live preview is on; only the latest one's timer applies.
+    live_edits: u64,
+    live_revert: Option<PendingRevert>,
+    /// Brightness control and current percentage of each output that has one.
+    brightness: HashMap<String, (brightness::Control, u8)>,
+    /// Latitude and longitude as typed on the settings page.
+    location_inputs: [String; 2],
+    /// Backend names offered on the settings page, discovered when it opens.
+    backend_choices: Vec<String>,
+    /// logind seats, listed when the settings open.
+    seats: Vec<String>,
+    /// Touchscreens and tablets offered for mapping, discovered when the settings open.
+    input_devices: Vec<InputDevice>,
+    docks: Vec<Dock>,
+    /// Connector details per output name, read from sysfs when the outputs change.
+    connectors: HashMap<String, Connector>,
+    /// The monitors config as this window last wrote or saw it, so only outside edits
+    /// are reported.
+    monitors_conf_snapshot: Option<String>,
+    backend: Arc<dyn Backend>,
+    /// Why the outputs could not be read, shown in place of the output list.
+    load_error: Option<String>,
+    /// Label of the backend job in flight, if any.
+    busy: Option<&'static str>,
+    /// Animation step of the spinner shown while [`Self::busy`].
+    spinner_frame: u32,
+    /// What the running daemon reported last; `None` when there is none.
+    daemon: Option<DaemonStatus>,
+    /// Started with `--read-only`; [`AppSettings::read_only`] locks the window too.
+    locked: bool,
+    pub settings: AppSettings,
     status_message: Option<String>,
 }
 
 impl Default for MangoDisplay {
     fn default() -> Self {
-        let outputs = fetch_outputs().unwrap_or_default();
-        let selected_output_idx = if !outputs.is_empty() { Some(0) } else { None };
-        let mut app = Self {
-            outputs,
-            selected_output_idx,
-            layout_cache: Cache::default(),
+        let settings = AppSettings::load();
+        Self {
+            applied_outputs: Vec::new(),
+            outputs: Vec::new(),
+            selected_output: None,
+            layout_cache: CanvasCaches::default(),
+            history: Vec::new(),
+            view_frame: Rectangle::default(),
             x_input: String::new(),
             y_input: String::new(),
             scale_input: String::new(),
-            settings: crate::settings::AppSettings::load(),
+            refresh_input: String::new(),
+            bezel_inputs: Default::default(),
+            mode_search: combo_box::State::new(Vec::new()),
+            window_size: Size::new(settings.ui.window_width, settings.ui.window_height),
+            resizing_sidebar: false,
+            tab_drag: None,
+            profile_name: String::new(),
+            command_preview: None,
+            compact_pane: Pane::Layout,
+            settings_open: false,
+            offline: None,
+            history_open: false,
+            events: Vec::new(),
+            context_menu: None,
+            output_dialog: false,
+            calibration_intro: false,
+            quick_action: None,
+            transition: None,
+            live_edits: 0,
+            live_revert: None,
+            brightness: HashMap::new(),
+            location_inputs: [
+                localized(settings.night_schedule.latitude),
+                localized(settings.night_schedule.longitude),
+            ],
+            backend_choices: Vec::new(),
+            seats: Vec::new(),
+            input_devices: Vec::new(),
+            docks: Vec::new(),
+            connectors: HashMap::new(),
+            monitors_conf_snapshot: std::fs::read_to_string(settings.monitors_path()).ok(),
+            backend: backend::from_settings(&settings),
+            load_error: None,
+            busy: None,
+            spinner_frame: 0,
+            daemon: None,
+            locked: false,
+            settings,
             status_message: None,
-        };
-        app.update_inputs_for_selection();
-        app
+        }
     }
 }
 
+/// Sends `message` after `delay`.
+fn after(delay: Duration, message: Message) -> Task<Message> {
+    Task::perform(blocking(move || std::thread::sleep(delay)), move |()| {
+        message
+    })
+}
+
+/// Runs a blocking backend call on its own thread so a slow or hung compositor cannot
+/// freeze the window.
+fn blocking<T: Send + 'static>(
+    job: impl FnOnce() -> T + Send + 'static,
+) -> impl std::future::Future<Output = T> {
+    let (sender, receiver) = iced::futures::channel::oneshot::channel();
+    std::thread::spawn(move || {
+        let _ = sender.send(job());
+    });
+    async move { receiver.await.expect("backend thread panicked") }
+}
+
 impl MangoDisplay {
+    /// Starts with an empty layout and reads the outputs in the background. A `locked`
+    /// window only shows the layout.
+    pub fn new(locked: bool) -> (Self, Task<Message>) {
+        let mut app = Self {
+            locked,
+            ..Self::default()
+        };
+        let task = app.reload_outputs();
+        (app, task)
+    }
+
+    /// Reads the outputs from the current backend in the background.
+    fn reload_outputs(&mut self) -> Task<Message> {
+        self.busy = Some(tr("Reading outputs…"));
+        let backend = self.backend.clone();
+        Task::perform(
+            blocking(move || backend.fetch_outputs()),
+            Message::OutputsLoaded,
+        )
+    }
+
+    /// Whether applying and saving are turned off, for kiosk and lab machines.
+    fn read_only(&self) -> bool {
+        self.locked || self.settings.read_only
+    }
+
+    fn save_settings(&mut self) {
+        // The settings of a read-only window are someone else's to change.
+        if self.read_only() {
+            self.status_message =
+                Some(tr("Read-only: this window cannot apply or save").to_string());
+            return;
+        }
+        if let Err(e) = self.settings.save() {
+            self.status_message = Some(trf("Settings error: {}", &[&e]));
+        }
+    }
+
+    /// Per-output actions shown on right click in the layout canvas.
+    fn context_menu_view(&self) -> Element<'_, Message> {
+        let item = |label: &'static str, message: Message| {
+            button(text(tr(label)).size(13))
+                .width(Length::Fill)
+                .style(button::text)
+                .on_press(message)
+        };
+        container(
+            column![
+                item(
+                    "Test pattern",
+                    Message::PatternOpened(PatternKind::TestPattern)
+                ),
+                item(
+                    "Dead pixel test",
+                    Message::PatternOpened(PatternKind::PixelTest)
+                ),
+                item(
+                    "Motion test",
+                    Message::PatternOpened(PatternKind::MotionTest)
+                ),
+                item("Calibrate…", Message::CalibrationOpened),
+                item("Disable", Message::DisableSelected),
+            ]
+            .width(Length::Fixed(180.0)),
+        )
+        .padding(4)
+        .style(container::bordered_box)
+        .into()
+    }
+
+    /// The main controls of the selected output, for the dialog a double-click on the
+    /// canvas opens. Handier than the sidebar when there are many outputs.
+    fn output_dialog_view(&self) -> Option<Element<'_, Message>> {
+        let idx = self.selected_idx()?;
+        let out = &self.outputs[idx];
+        let field = |label: &'static str, control: Element<'static, Message>| {
+            row![container(text(tr(label)).size(14)).width(90.0), control]
+                .spacing(10)
+                .align_y(alignment::Vertical::Center)
+        };
+
+        let enabled = if out.enabled && self.is_last_enabled(idx) {
+            described(
+                checkbox(out.enabled),
+                tr("At least one output must stay enabled"),
+            )
+        } else {
+            described(
+                checkbox(out.enabled).on_toggle(Message::EnabledToggled),
+                tr("Enable or disable this monitor (E)"),
+            )
+        };
+        let modes = ModeChoice::from_modes(&out.modes, self.settings.hide_low_res_modes);
+        let current = modes.iter().find(|c| c.current).cloned();
+        let scale_error = parse_scale(&self.scale_input).err();
+        let scale = row![
+            described(
+                text_input(tr("Scale"), &self.scale_input)
+                    .on_input(Message::ScaleChanged)
+                    .style(input_style(scale_error.is_some()))
+                    .width(Length::Fixed(60.0)),
+                scale_error.unwrap_or(tr("Scale factor, such as 1.5 for 150%")),
+            ),
+            described(
+                button("-").on_press(Message::ScaleDec),
+                tr("Decrease scale (-)")
+            ),
+            described(
+                button("+").on_press(Message::ScaleInc),
+                tr("Increase scale (+)")
+            ),
+        ]
+        .spacing(5)
+        .align_y(alignment::Vertical::Center);
+        let transforms: Vec<String> = TRANSFORMS.iter().map(|t| t.to_string()).collect();
+
+        let dialog = column![
+            row![
+                text(self.settings.display_name(out)).size(20),
+                Space::new().width(Length::Fill),
+                button(text(tr("Close")).size(13))
+                    .style(button::secondary)
+                    .on_press(Message::OutputDialogClosed)
+            ]
+            .spacing(10)
+            .align_y(alignment::Vertical::Center),
+            text(out.description.clone()).size(13),
+            field("Enabled", enabled),
+            field(
+                "Mode",
+                pick_list(modes, current, |choice: ModeChoice| {
+                    Message::ResolutionSelected(choice.index)
+                })
+                .width(Length::Fixed(220.0))
+                .into()
+            ),
+            field("Scale", scale.into()),
+            field(
+                "Transform",
+                pick_list(
+                    transforms,
+                    Some(out.transform.clone()),
+                    Message::TransformSelected
+                )
+                .width(Length::Fixed(220.0))
+                .into()
+            ),
+        ]
+        .spacing(12);
+        Some(
+            container(dialog)
+                .padding(16)
+                .width(Length::Fixed(400.0))
+                .style(container::bordered_box)
+                .into(),
+        )
+    }
+
+    /// The theme picked on the settings page, or MDisplay's own dark theme.
+    pub fn theme(&self) -> Theme {
+        if self.high_contrast() {
+            return high_contrast_theme();
+        }
+        self.settings
+            .theme
+            .as_deref()
+            .and_then(|name| Theme::ALL.iter().find(|t| t.to_string() == name))
+            .cloned()
+            .unwrap_or_else(mdisplay_theme)
+    }
+
+    /// How much larger than the default everything in the window is drawn, text and
+    /// canvas included.
+    pub fn scale_factor(&self) -> f32 {
+        self.settings.ui_scale.clamp(0.5, 3.0)
+    }
+
+    fn high_contrast(&self) -> bool {
+        self.settings.theme.as_deref() == Some(HIGH_CONTRAST_THEME)
+    }
+
+    /// Where the selected output sits in `outputs`, if it is still connected.
+    fn selected_idx(&self) -> Option<usize> {
+        let name = self.selected_output.as_deref()?;
+        self.outputs.iter().position(|o| o.name == name)
+    }
+
+    fn selected(&self) -> Option<&Output> {
+        self.selected_idx().map(|idx| &self.outputs[idx])
+    }
+
+    fn selected_mut(&mut self) -> Option<&mut Output> {
+        let idx = self.selected_idx()?;
+        self.outputs.get_mut(idx)
+    }
+
+    /// Indices into `outputs` in the order the tabs show them: as the user arranged them,
+    /// then any output never arranged in the order the compositor lists them.
+    fn tab_order(&self) -> Vec<usize> {
+        let order = &self.settings.ui.tab_order;
+        let mut indices: Vec<usize> = (0..self.outputs.len()).collect();
+        indices.sort_by_key(|&i| {
+            let identity = self.outputs[i].identity();
+            order
+                .iter()
+                .position(|o| *o == identity)
+                .unwrap_or(order.len())
+        });
+        indices
+    }
+
+    /// Moves the tab of the output called `dragged` to where the tab of `target` is.
+    fn move_tab(&mut self, dragged: &str, target: &str) {
+        let mut order = self.tab_order();
+        let (Some(from), Some(to)) = (
+            order.iter().position(|&i| self.outputs[i].name == dragged),
+            order.iter().position(|&i| self.outputs[i].name == target),
+        ) else {
+            return;
+        };
+        let idx = order.remove(from);
+        order.insert(to, idx);
+        let mut arranged: Vec<String> = order.iter().map(|&i| self.outputs[i].identity()).collect();
+        // Keep the places of outputs that are not connected now.
+        arranged.extend(
+            self.settings
+                .ui
+                .tab_order
+                .iter()
+                .filter(|identity| !arranged.contains(identity))
+                .cloned()
+                .collect::<Vec<_>>(),
+        );
+        self.settings.ui.tab_order = arranged;
+    }
+
+    /// Selects the first output when the selected one is gone.
+    fn ensure_selection(&mut self) {
+        if self.selected_idx().is_none() {
+            self.selected_output = self.outputs.first().map(|o| o.name.clone());
+        }
+    }
+
     fn update_inputs_for_selection(&mut self) {
-        if let Some(idx) = self.selected_output_idx {
-            if let Some(out) = self.outputs.get(idx) {
+        if let Some(idx) = self.selected_idx() {
+            let out = &self.outputs[idx];
+            // Fields that already read as the output's value are left as typed, so "1,"
+            // on the way to "1,25" is not reformatted under the cursor.
+            if parse_position(&self.x_input) != Ok(out.position.0) {
                 self.x_input = out.position.0.to_string();
+            }
+            if parse_position(&self.y_input) != Ok(out.position.1) {
                 self.y_input = out.position.1.to_string();
-                self.scale_input = format!("{:.2}", out.scale);
             }
+            if parse_scale(&self.scale_input) != Ok(out.scale) {
+                self.scale_input = decimal(out.scale, 2);
+            }
+            self.refresh_input.clear();
+            let bezels = self.settings.output_prefs(&out.identity()).bezels;
+            self.bezel_inputs =
+                [bezels.left, bezels.right, bezels.top, bezels.bottom].map(localized);
         }
+        self.refresh_mode_search();
     }
 
-    fn normalize_positions(&mut self) {
-        let min_x = self.outputs.iter().map(|o| o.position.0).min().unwrap_or(0);
-        let min_y = self.outputs.iter().map(|o| o.position.1).min().unwrap_or(0);
-
-        let mut changed = false;
-        let offset_x = if min_x < 0 { -min_x } else { 0 };
-        let offset_y = if min_y < 0 { -min_y } else { 0 };
+    fn refresh_mode_search(&mut self) {
+        let choices = self
+            .selected()
+            .map(|out| ModeChoice::from_modes(&out.modes, self.settings.hide_low_res_modes))
+            .unwrap_or_default();
+        self.mode_search = combo_box::State::new(choices);
+    }
 
-        if offset_x > 0 || offset_y > 0 {
-            for out in &mut self.outputs {
-                out.position.0 += offset_x;
-                out.position.1 += offset_y;
+    /// Records the current layout for undo, skipping duplicates of the last snapshot.
+    fn checkpoint(&mut self) {
+        if self.history.last() != Some(&self.outputs) {
+            self.history.push(self.outputs.clone());
+            if self.history.len() > HISTORY_LIMIT {
+                self.history.remove(0);
             }
-            changed = true;
         }
+    }
 
-        if changed {
-            self.update_inputs_for_selection();
-            self.layout_cache.clear();
+    fn is_last_enabled(&self, idx: usize) -> bool {
+        self.outputs
+            .iter()
+            .enumerate()
+            .all(|(i, o)| i == idx || !o.enabled)
+    }
+
+    /// Enables or disables an output, refusing to turn off the last enabled one since
+    /// applying that would leave the session without a screen.
+    fn set_enabled(&mut self, idx: usize, enabled: bool) {
+        if !enabled && self.is_last_enabled(idx) {
+            self.status_message = Some(tr("At least one output must stay enabled").to_string());
+            return;
+        }
+        if let Some(out) = self.outputs.get_mut(idx) {
+            out.enabled = enabled;
         }
     }
 
-    pub fn update(&mut self, message: Message) -> Task<Message> {
-        match message {
-            Message::MonitorClicked(idx) => {
-                self.selected_output_idx = Some(idx);
-                self.update_inputs_for_selection();
-                self.layout_cache.clear();
-            }
-            Message::MonitorPositioned(idx, x, y) => {
-                if let Some(out) = self.outputs.get_mut(idx) {
-                    out.position = (x, y);
-                }
-                if Some(idx) == self.selected_output_idx {
-                    self.update_inputs_for_selection();
-                }
-                self.layout_cache.clear();
-            }
-            Message::XChanged(val) => {
-                self.x_input = val.clone();
-                if let (Some(idx), Ok(mut v)) = (self.selected_output_idx, i32::from_str(&val)) {
-                    if v < 0 {
-                        v = 0;
-                    }
-                    self.outputs[idx].position.0 = v;
-                    self.layout_cache.clear();
-                }
+    /// Moves scales the compositor would refuse to the nearest one it takes. Scales it
+    /// merely rounds are left for the sidebar warning.
+    fn snap_scales(&mut self) {
+        let steps = self.backend.scale_steps();
+        if steps == ScaleSteps::Eighths {
+            return;
+        }
+        for out in self.outputs.iter_mut().filter(|o| o.enabled) {
+            let Some(mode) = out.modes.iter().find(|m| m.current) else {
+                continue;
+            };
+            if !steps.accepts(mode, out.scale)
+                && let Some(scale) = steps.nearest(mode, out.scale)
+            {
+                tracing::info!(
+                    "Adjusting the scale of {} from {} to {}",
+                    out.name,
+                    out.scale,
+                    scale
+                );
+                out.scale = scale;
             }
-            Message::YChanged(val) => {
-                self.y_input = val.clone();
-                if let (Some(idx), Ok(mut v)) = (self.selected_output_idx, i32::from_str(&val)) {
-                    if v < 0 {
-                        v = 0;
-                    }
-                    self.outputs[idx].position.1 = v;
-                    self.layout_cache.clear();
-                }
+        }
+        self.update_inputs_for_selection();
+        self.layout_cache.clear();
+    }
+
+    fn select_relative(&mut self, step: isize) {
+        if self.outputs.is_empty() {
+            return;
+        }
+        let order = self.tab_order();
+        let len = order.len() as isize;
+        let current = self
+            .selected_idx()
+            .and_then(|idx| order.iter().position(|&i| i == idx))
+            .map_or(-step.signum(), |i| i as isize);
+        let next = order[(current + step).rem_euclid(len) as usize];
+        self.selected_output = Some(self.outputs[next].name.clone());
+        self.update_inputs_for_selection();
+        self.layout_cache.clear();
+    }
+
+    pub fn subscription(&self) -> Subscription<Message> {
+        let mut subscriptions = vec![
+            keyboard::listen().filter_map(shortcut),
+            window::resize_events().map(|(_, size)| Message::WindowResized(size)),
+            window::close_requests().map(|_| Message::CloseRequested),
+            Subscription::run(output_changes),
+            Subscription::run(daemon_polls),
+            Subscription::run_with(
+                [AppSettings::path(), self.settings.monitors_path()],
+                config_changes,
+            ),
+        ];
+        if self.resizing_sidebar {
+            subscriptions.push(event::listen_with(sidebar_resize_event));
+        }
+        if self.tab_drag.is_some() {
+            subscriptions.push(event::listen_with(tab_drag_event));
+        }
+        if self.busy.is_some() {
+            subscriptions.push(Subscription::run(spinner_ticks));
+        }
+        if self.transition.is_some() {
+            subscriptions.push(window::frames().map(Message::TransitionTick));
+        }
+        Subscription::batch(subscriptions)
+    }
+
+    /// Picks up outputs that were plugged in or removed while the window was open. Edits
+    /// to outputs that are still connected are kept.
+    fn merge_connected_outputs(&mut self, current: Vec<Output>) {
+        self.outputs
+            .retain(|out| current.iter().any(|c| c.name == out.name));
+        self.applied_outputs
+            .retain(|out| current.iter().any(|c| c.name == out.name));
+        for mut out in current {
+            if self.outputs.iter().any(|o| o.name == out.name) {
+                continue;
             }
-            Message::XInc => {
-                if let Some(idx) = self.selected_output_idx {
-                    self.outputs[idx].position.0 += 1;
-                    self.update_inputs_for_selection();
-                    self.layout_cache.clear();
+            out.tidy_modes();
+            notify::event(
+                &self.settings,
+                tr("New monitor detected"),
+                &format!("{}: {}", out.name, out.description),
+            );
+            self.applied_outputs.push(out.clone());
+            self.outputs.push(out);
+        }
+
+        self.ensure_selection();
+        self.refresh_connectors();
+        self.update_inputs_for_selection();
+        self.layout_cache.clear();
+    }
+
+    /// Whether `out`'s current mode is likely to fail or be degraded on its connector.
+    fn link_warning(&self, out: &Output) -> Option<LinkWarning> {
+        let mode = out.modes.iter().find(|m| m.current)?;
+        let connector = self.connectors.get(&out.name)?;
+        let prefs = self.settings.output_prefs(&out.identity());
+        let clock = bandwidth::pixel_clock_khz(mode, prefs.blanking);
+        let bpc = prefs.bit_depth.bits().unwrap_or(bandwidth::DEFAULT_BPC);
+        // Already sent as 4:2:0, so there is nothing to warn about.
+        bandwidth::check(connector, clock, bpc).filter(|w| {
+            *w != LinkWarning::Chroma420 || out.pixel_format != Some(PixelFormat::Ycbcr420)
+        })
+    }
+
+    fn refresh_connectors(&mut self) {
+        self.connectors = self
+            .outputs
+            .iter()
+            .map(|out| (out.name.clone(), connector::detect(&out.name)))
+            .collect();
+    }
+
+    /// Looks up brightness controls in the background; DDC/CI detection is slow.
+    fn detect_brightness(&self) -> Task<Message> {
+        let names: Vec<String> = self.outputs.iter().map(|o| o.name.clone()).collect();
+        Task::perform(
+            blocking(move || {
+                brightness::detect(&names)
+                    .into_iter()
+                    .filter_map(|(name, control)| match control.get() {
+                        Ok(percent) => Some((name, control, percent)),
+                        Err(e) => {
+                            tracing::debug!("{}", e);
+                            None
+                        }
+                    })
+                    .collect()
+            }),
+            Message::BrightnessLoaded,
+        )
+    }
+
+    fn brightness_balance(&self, name: &str) -> f32 {
+        self.outputs
+            .iter()
+            .find(|o| o.name == name)
+            .and_then(|o| self.settings.output_prefs(&o.identity()).brightness_balance)
+            .unwrap_or(1.0)
+    }
+
+    /// The output a presentation on `idx` mirrors: the enabled built-in panel, or else
+    /// the first other enabled output.
+    fn presentation_source(&self, idx: usize) -> Option<usize> {
+        let others = || {
+            self.outputs
+                .iter()
+                .enumerate()
+                .filter(move |(i, o)| *i != idx && o.enabled)
+        };
+        others()
+            .find(|(_, o)| self.connector_kind(&o.name).is_builtin())
+            .or_else(|| others().next())
+            .map(|(i, _)| i)
+    }
+
+    fn connector_kind(&self, name: &str) -> ConnectorKind {
+        self.connectors
+            .get(name)
+            .map_or_else(|| ConnectorKind::from_name(name), |c| c.kind)
+    }
+
+    fn is_compact(&self) -> bool {
+        self.window_size.width < COMPACT_WIDTH
+    }
+
+    /// Stores the window and view state in the settings file so the next launch looks
+    /// the same. Read-only windows leave the file alone.
+    fn persist_ui_state(&mut self) {
+        if self.read_only() {
+            return;
+        }
+        self.settings.ui.window_width = self.window_size.width;
+        self.settings.ui.window_height = self.window_size.height;
+        self.settings.ui.selected_output = self.selected().map(|out| out.name.clone());
+        if let Err(e) = self.settings.save() {
+            tracing::error!("Error saving settings: {}", e);
+        }
+    }
+
+    /// Frames the canvas around the bounding box of every output.
+    fn fit_view(&mut self) {
+        self.view_frame = layout_bounds(self.outputs.iter().filter(|o| o.enabled));
+        self.layout_cache.clear();
+    }
+
+    /// The compositor sections of the profile named in the sidebar, when the layout being
+    /// saved is that profile's.
+    fn profile_sections(&self) -> BTreeMap<SaveFormat, String> {
+        self.settings
+            .profile(&self.profile_name)
+            .filter(|p| p.is_active(&self.outputs))
+            .map(|p| p.sections.clone())
+            .unwrap_or_default()
+    }
+
+    fn positions(&self) -> HashMap<String, (i32, i32)> {
+        self.outputs
+            .iter()
+            .map(|out| (out.name.clone(), out.position))
+            .collect()
+    }
+
+    /// Glides the outputs that moved from their positions in `from`, as returned by
+    /// [`Self::positions`], to where they are now.
+    fn start_transition(&mut self, from: HashMap<String, (i32, i32)>) {
+        if self
+            .outputs
+            .iter()
+            .all(|out| from.get(&out.name) == Some(&out.position))
+        {
+            return;
+        }
+        self.transition = Some(Transition {
+            from,
+            start: Instant::now(),
+            progress: 0.0,
+        });
+        self.layout_cache.clear();
+    }
+
+    fn normalize_positions(&mut self) {
+        let from = self.positions();
+        let min_x = self.outputs.iter().map(|o| o.position.0).min().unwrap_or(0);
+        let min_y = self.outputs.iter().map(|o| o.position.1).min().unwrap_or(0);
+
+        let mut changed = false;
+        let offset_x = if min_x < 0 { -min_x } else { 0 };
+        let offset_y = if min_y < 0 { -min_y } else { 0 };
+
+        if offset_x > 0 || offset_y > 0 {
+            for out in &mut self.outputs {
+                out.position.0 += offset_x;
+                out.position.1 += offset_y;
+            }
+            changed = true;
+        }
+
+        if changed {
+            self.update_inputs_for_selection();
+            self.layout_cache.clear();
+            self.start_transition(from);
+        }
+    }
+
+    pub fn update(&mut self, message: Message) -> Task<Message> {
+        // Shortcuts and live preview reach these too, not only the disabled buttons.
+        if self.read_only()
+            && matches!(
+                message,
+                Message::ApplyClicked
+                    | Message::SaveClicked
+                    | Message::SaveAsClicked
+                    | Message::RestoreDefaultClicked
+                    | Message::QuickActionClicked(_)
+                    | Message::LivePreviewToggled(_)
+                    | Message::LivePreviewDue(_)
+                    | Message::ProfileSaved
+                    | Message::ProfileImported
+                    | Message::VirtualOutputAdded
+                    | Message::VirtualOutputRemoved(_)
+                    | Message::SettingsToggled
+                    | Message::WallpaperChanged(..)
+                    | Message::ProfileRuleChanged(..)
+                    | Message::DockProfileSelected(..)
+                    | Message::NightLightToggled(_)
+                    | Message::ColorTemperatureChanged(_)
+                    | Message::BrightnessChanged(_)
+                    | Message::BrightnessReleased
+            )
+        {
+            self.status_message =
+                Some(tr("Read-only: this window cannot apply or save").to_string());
+            return Task::none();
+        }
+        // A profile edited offline names monitors that may not be there to apply it to.
+        if let Some(profile) = &self.offline
+            && matches!(
+                message,
+                Message::ApplyClicked
+                    | Message::SaveClicked
+                    | Message::RestoreDefaultClicked
+                    | Message::QuickActionClicked(_)
+                    | Message::LivePreviewDue(_)
+                    | Message::VirtualOutputAdded
+                    | Message::VirtualOutputRemoved(_)
+            )
+        {
+            self.status_message = Some(trf(
+                "Editing {} offline: save it as a profile, or finish editing to apply",
+                &[profile],
+            ));
+            return Task::none();
+        }
+        // Messages that apply or replace the whole layout are not edits to preview.
+        let live = self.settings.ui.live_preview
+            && !matches!(
+                message,
+                Message::ApplyClicked
+                    | Message::ApplyFinished(_)
+                    | Message::OutputsLoaded(_)
+                    | Message::OutputsChanged(_)
+                    | Message::QuickActionClicked(_)
+                    | Message::QuickActionReverted
+                    | Message::LivePreviewDue(_)
+                    | Message::RevertTick(_)
+                    | Message::LiveChangesReverted
+            );
+        if !live {
+            return self.handle(message);
+        }
+        let before = self.outputs.clone();
+        let task = self.handle(message);
+        if self.outputs == before {
+            return task;
+        }
+        self.live_edits += 1;
+        Task::batch([
+            task,
+            after(LIVE_PREVIEW_DELAY, Message::LivePreviewDue(self.live_edits)),
+        ])
+    }
+
+    /// Applies the layout in the background.
+    fn apply_layout(&mut self) -> Task<Message> {
+        if self.busy.is_some() {
+            return Task::none();
+        }
+        self.normalize_positions();
+        self.snap_scales();
+        self.busy = Some(tr("Applying…"));
+        let outputs = self.outputs.clone();
+        let backend = self.backend.clone();
+        let choice = self.settings.backend.clone();
+        Task::perform(
+            blocking(move || {
+                ipc::apply_layout(&*backend, choice.as_deref(), &outputs)?;
+                // Read back what the compositor made of it; not being able to is no
+                // reason to report the apply as failed.
+                let mismatches = backend
+                    .fetch_outputs()
+                    .map(|actual| verify::compare(&outputs, &actual))
+                    .unwrap_or_default();
+                Ok((outputs, mismatches))
+            }),
+            Message::ApplyFinished,
+        )
+    }
+
+    fn handle(&mut self, message: Message) -> Task<Message> {
+        // Dragging keeps the current framing so the monitor under the cursor stays put;
+        // every other edit re-frames the canvas around the new layout.
+        let refit = !matches!(
+            message,
+            Message::MonitorClicked(_)
+                | Message::TabPressed(_)
+                | Message::TabEntered(_)
+                | Message::TabDragFinished
+                | Message::MonitorPositioned(..)
+                | Message::ContextMenuOpened(..)
+                | Message::ContextMenuClosed
+                | Message::OutputDialogOpened(_)
+                | Message::OutputDialogClosed
+                | Message::SpinnerTick
+                | Message::TransitionTick(_)
+                | Message::DaemonPolled
+                | Message::DaemonStatusReceived(_)
+        );
+        // Clicking a monitor may start a drag, so snapshot before it moves; the drag's own
+        // position updates are folded into that single undo step.
+        if !matches!(
+            message,
+            Message::MonitorPositioned(..)
+                | Message::Undo
+                | Message::FitView
+                | Message::DragFinished
+                | Message::SelectNext
+                | Message::SelectPrevious
+                | Message::FocusNext
+                | Message::FocusPrevious
+                | Message::LanguageSelected(_)
+                | Message::BezelChanged(..)
+                | Message::NicknameChanged(_)
+                | Message::NightLightToggled(_)
+                | Message::HideLowResToggled(_)
+                | Message::ColorTemperatureChanged(_)
+                | Message::RestoreDefaultClicked
+                | Message::SpinnerTick
+                | Message::TransitionTick(_)
+                | Message::DaemonPolled
+                | Message::DaemonStatusReceived(_)
+        ) {
+            self.checkpoint();
+        }
+        match message {
+            Message::MonitorClicked(name) => {
+                self.context_menu = None;
+                self.selected_output = Some(name);
+                self.update_inputs_for_selection();
+                self.layout_cache.overlay.clear();
+            }
+            Message::MonitorPositioned(name, x, y) => {
+                if let Some(out) = self.outputs.iter_mut().find(|o| o.name == name) {
+                    out.position = (x, y);
+                }
+                if self.selected_output.as_ref() == Some(&name) {
+                    self.update_inputs_for_selection();
                 }
+                self.layout_cache.clear();
             }
-            Message::XDec => {
-                if let Some(idx) = self.selected_output_idx {
-                    if self.outputs[idx].position.0 > 0 {
-                        self.outputs[idx].position.0 -= 1;
+            Message::MirrorSelected(source) => {
+                if let Some(idx) = self.selected_idx() {
+                    let others = self
+                        .outputs
+                        .iter()
+                        .enumerate()
+                        .filter(|(i, o)| *i != idx && o.enabled)
+                        .map(|(_, o)| o);
+                    let position = match source {
+                        Some(name) => others.clone().find(|o| o.name == name).map(|o| o.position),
+                        // Leaving a group extends the desktop to the right of the others.
+                        None => {
+                            let bounds = layout_bounds(others);
+                            Some(((bounds.x + bounds.width) as i32, bounds.y as i32))
+                        }
+                    };
+                    if let Some(position) = position {
+                        self.outputs[idx].position = position;
                         self.update_inputs_for_selection();
-                        self.layout_cache.clear();
                     }
                 }
             }
+            Message::MonitorUndocked(name, x, y) => {
+                if let Some(out) = self.outputs.iter_mut().find(|o| o.name == name) {
+                    out.enabled = true;
+                    out.position = (x, y);
+                }
+                self.update_inputs_for_selection();
+            }
+            Message::XChanged(val) => {
+                if let (Some(out), Ok(v)) = (self.selected_mut(), parse_position(&val)) {
+                    out.position.0 = v;
+                    self.layout_cache.clear();
+                }
+                self.x_input = val;
+            }
+            Message::YChanged(val) => {
+                if let (Some(out), Ok(v)) = (self.selected_mut(), parse_position(&val)) {
+                    out.position.1 = v;
+                    self.layout_cache.clear();
+                }
+                self.y_input = val;
+            }
+            Message::XInc => {
+                if let Some(out) = self.selected_mut() {
+                    out.position.0 += 1;
+                    self.update_inputs_for_selection();
+                    self.layout_cache.clear();
+                }
+            }
+            Message::XDec => {
+                if let Some(out) = self.selected_mut()
+                    && out.position.0 > 0
+                {
+                    out.position.0 -= 1;
+                    self.update_inputs_for_selection();
+                    self.layout_cache.clear();
+                }
+            }
             Message::YInc => {
-                if let Some(idx) = self.selected_output_idx {
-                    self.outputs[idx].position.1 += 1;
+                if let Some(out) = self.selected_mut() {
+                    out.position.1 += 1;
                     self.update_inputs_for_selection();
                     self.layout_cache.clear();
                 }
             }
             Message::YDec => {
-                if let Some(idx) = self.selected_output_idx {
-                    if self.outputs[idx].position.1 > 0 {
-                        self.outputs[idx].position.1 -= 1;
-                        self.update_inputs_for_selection();
-                        self.layout_cache.clear();
-                    }
+                if let Some(out) = self.selected_mut()
+                    && out.position.1 > 0
+                {
+                    out.position.1 -= 1;
+                    self.update_inputs_for_selection();
+                    self.layout_cache.clear();
                 }
             }
             Message::ScaleChanged(val) => {
-                self.scale_input = val.clone();
-                if let (Some(idx), Ok(v)) = (self.selected_output_idx, f32::from_str(&val)) {
-                    if v > 0.1 {
-                        self.outputs[idx].scale = v;
-                        self.layout_cache.clear();
-                    }
+                if let (Some(out), Ok(v)) = (self.selected_mut(), parse_scale(&val)) {
+                    out.scale = v;
+                    self.layout_cache.clear();
                 }
+                self.scale_input = val;
             }
             Message::ScaleInc => {
-                if let Some(idx) = self.selected_output_idx {
-                    self.outputs[idx].scale += 0.05;
+                if let Some(out) = self.selected_mut() {
+                    out.scale += 0.05;
                     self.update_inputs_for_selection();
                     self.layout_cache.clear();
                 }
             }
             Message::ScaleDec => {
-                if let Some(idx) = self.selected_output_idx {
-                    self.outputs[idx].scale -= 0.05;
+                if let Some(out) = self.selected_mut() {
+                    out.scale -= 0.05;
                     self.update_inputs_for_selection();
                     self.layout_cache.clear();
                 }
             }
-            Message::EnabledToggled(val) => {
-                if let Some(idx) = self.selected_output_idx {
-                    self.outputs[idx].enabled = val;
+            Message::ScalePresetSelected(label) => {
+                if let (Some(out), Ok(percent)) = (
+                    self.selected_mut(),
+                    f32::from_str(label.trim_end_matches('%')),
+                ) {
+                    out.scale = percent / 100.0;
+                    self.update_inputs_for_selection();
+                    self.layout_cache.clear();
+                }
+            }
+            Message::BezelChanged(edge, val) => {
+                self.bezel_inputs[edge as usize] = val.clone();
+                if let (Some(identity), Some(mm)) = (
+                    self.selected().map(Output::identity),
+                    parse_decimal::<f32>(&val),
+                ) && mm >= 0.0
+                {
+                    let prefs = self.settings.outputs.entry(identity).or_default();
+                    match edge {
+                        Edge::Left => prefs.bezels.left = mm,
+                        Edge::Right => prefs.bezels.right = mm,
+                        Edge::Top => prefs.bezels.top = mm,
+                        Edge::Bottom => prefs.bezels.bottom = mm,
+                    }
+                    self.save_settings();
+                }
+            }
+            Message::NicknameChanged(nickname) => {
+                if let Some(identity) = self.selected().map(Output::identity) {
+                    let prefs = self.settings.outputs.entry(identity).or_default();
+                    prefs.nickname = (!nickname.is_empty()).then_some(nickname);
+                    self.save_settings();
                     self.layout_cache.clear();
                 }
             }
+            Message::NightLightToggled(on) => {
+                if let Some(identity) = self.selected().map(Output::identity) {
+                    let prefs = self.settings.outputs.entry(identity).or_default();
+                    prefs.color_temperature = on.then_some(4500);
+                    self.save_settings();
+                }
+            }
+            Message::ColorTemperatureChanged(kelvin) => {
+                if let Some(identity) = self.selected().map(Output::identity) {
+                    let prefs = self.settings.outputs.entry(identity).or_default();
+                    prefs.color_temperature = Some(kelvin);
+                    self.save_settings();
+                }
+            }
+            Message::HideLowResToggled(hide) => {
+                self.settings.hide_low_res_modes = hide;
+                self.save_settings();
+                self.refresh_mode_search();
+            }
+            Message::EnabledToggled(val) => {
+                if let Some(idx) = self.selected_idx() {
+                    self.set_enabled(idx, val);
+                }
+            }
 
             Message::ResolutionSizeSelected(res_str) => {
-                if let Some(idx) = self.selected_output_idx {
-                    let parts: Vec<&str> = res_str.split('x').collect();
-                    if parts.len() == 2 {
-                        if let (Ok(w), Ok(h)) = (i32::from_str(parts[0]), i32::from_str(parts[1])) {
-                            for m in &mut self.outputs[idx].modes {
-                                m.current = false;
-                            }
-                            if let Some(mode) = self.outputs[idx]
-                                .modes
-                                .iter_mut()
-                                .find(|m| m.width == w && m.height == h)
-                            {
-                                mode.current = true;
+                if let Some(out) = self.selected_mut() {
+                    let parts: Vec<&str> = res_str
+                        .trim_end_matches(PREFERRED_MARK)
+                        .split('x')
+                        .collect();
+                    if parts.len() == 2
+                        && let (Ok(w), Ok(h)) = (i32::from_str(parts[0]), i32::from_str(parts[1]))
+                    {
+                        let modes = &mut out.modes;
+                        let target = modes
+                            .iter()
+                            .position(|m| m.width == w && m.height == h && m.preferred)
+                            .or_else(|| modes.iter().position(|m| m.width == w && m.height == h));
+                        if let Some(target) = target {
+                            for (i, m) in modes.iter_mut().enumerate() {
+                                m.current = i == target;
                             }
-                            self.layout_cache.clear();
                         }
+                        self.layout_cache.clear();
                     }
                 }
             }
-            Message::ResolutionSelected(res_idx) => {
-                if let Some(idx) = self.selected_output_idx {
-                    for m in &mut self.outputs[idx].modes {
-                        m.current = false;
+            Message::RefreshRateTyped(value) => self.refresh_input = value,
+            Message::CustomRefreshSubmitted => {
+                if let Some(idx) = self.selected_idx() {
+                    let rate = parse_decimal::<f32>(&self.refresh_input)
+                        .ok_or_else(|| trf("'{}' is not a refresh rate", &[&self.refresh_input]));
+                    self.checkpoint();
+                    match rate.and_then(|hz| self.outputs[idx].set_refresh_rate(hz)) {
+                        Ok(custom) => {
+                            self.outputs[idx].tidy_modes();
+                            self.status_message = custom.then(|| {
+                                tr("Custom mode added; the compositor may still reject it")
+                                    .to_string()
+                            });
+                            self.refresh_input.clear();
+                            self.refresh_mode_search();
+                        }
+                        Err(e) => self.status_message = Some(e),
+                    }
+                }
+            }
+            Message::BlankingSelected(blanking) => {
+                if let Some(identity) = self.selected().map(Output::identity) {
+                    let prefs = self.settings.outputs.entry(identity).or_default();
+                    prefs.blanking = blanking;
+                    self.save_settings();
+                }
+            }
+            Message::ScaleFilterSelected(filter) => {
+                if let Some(identity) = self.selected().map(Output::identity) {
+                    let prefs = self.settings.outputs.entry(identity).or_default();
+                    prefs.scale_filter = filter;
+                    self.save_settings();
+                    if let Err(e) =
+                        compositor::apply_scale_filters(&self.applied_outputs, &self.settings)
+                    {
+                        self.status_message = Some(e);
+                    }
+                }
+            }
+            Message::BitDepthSelected(depth) => {
+                if let Some(identity) = self.selected().map(Output::identity) {
+                    let prefs = self.settings.outputs.entry(identity).or_default();
+                    prefs.bit_depth = depth;
+                    self.save_settings();
+                    if let Err(e) =
+                        compositor::apply_bit_depths(&self.applied_outputs, &self.settings)
+                    {
+                        self.status_message = Some(e);
+                    }
+                }
+            }
+            Message::SubpixelSelected(subpixel) => {
+                if let Some(identity) = self.selected().map(Output::identity) {
+                    let prefs = self.settings.outputs.entry(identity).or_default();
+                    prefs.subpixel = subpixel;
+                    self.save_settings();
+                    if let Err(e) =
+                        compositor::apply_subpixels(&self.applied_outputs, &self.settings)
+                    {
+                        self.status_message = Some(e);
                     }
-                    if res_idx < self.outputs[idx].modes.len() {
-                        self.outputs[idx].modes[res_idx].current = true;
+                }
+            }
+            Message::ModelineCopied(modeline) => return iced::clipboard::write(modeline),
+            Message::ResolutionSelected(res_idx) => {
+                if let Some(out) = self.selected_mut() {
+                    for (i, m) in out.modes.iter_mut().enumerate() {
+                        m.current = i == res_idx;
                     }
                     self.layout_cache.clear();
                 }
             }
             Message::TransformSelected(trans) => {
-                if let Some(idx) = self.selected_output_idx {
-                    self.outputs[idx].transform = trans;
+                if let Some(out) = self.selected_mut() {
+                    out.transform = trans;
                     self.layout_cache.clear();
                 }
             }
             Message::ApplyClicked => {
-                self.normalize_positions();
-                match apply_outputs(&self.outputs) {
-                    Ok(()) => self.status_message = Some("Applied successfully!".to_string()),
-                    Err(e) => self.status_message = Some(format!("Apply error: {}", e)),
+                // Applying by hand settles any live preview.
+                self.live_revert = None;
+                return self.apply_layout();
+            }
+            Message::LivePreviewToggled(on) => {
+                self.settings.ui.live_preview = on;
+                self.save_settings();
+            }
+            Message::LivePreviewDue(edit) => {
+                if edit != self.live_edits || !self.settings.ui.live_preview {
+                    return Task::none();
+                }
+                if self.busy.is_some() {
+                    return after(LIVE_PREVIEW_DELAY, Message::LivePreviewDue(edit));
+                }
+                let countdown = match &mut self.live_revert {
+                    Some(pending) => {
+                        pending.seconds = REVERT_SECONDS;
+                        Task::none()
+                    }
+                    None => {
+                        self.live_revert = Some(PendingRevert {
+                            layout: self.applied_outputs.clone(),
+                            seconds: REVERT_SECONDS,
+                            id: edit,
+                        });
+                        after(Duration::from_secs(1), Message::RevertTick(edit))
+                    }
+                };
+                return Task::batch([self.apply_layout(), countdown]);
+            }
+            Message::RevertTick(id) => {
+                let Some(pending) = self.live_revert.as_mut().filter(|p| p.id == id) else {
+                    return Task::none();
+                };
+                pending.seconds = pending.seconds.saturating_sub(1);
+                if pending.seconds > 0 {
+                    return after(Duration::from_secs(1), Message::RevertTick(id));
+                }
+                return self.update(Message::LiveChangesReverted);
+            }
+            Message::LiveChangesKept => self.live_revert = None,
+            Message::LiveChangesReverted => {
+                // An apply in flight would swallow the revert; try again once it is done.
+                if self.busy.is_some() && self.live_revert.is_some() {
+                    return after(LIVE_PREVIEW_DELAY, Message::LiveChangesReverted);
+                }
+                if let Some(pending) = self.live_revert.take() {
+                    history::record(EventKind::Reverted, "Reverted the live preview");
+                    let mut outputs = pending.layout;
+                    outputs.retain(|o| self.outputs.iter().any(|c| c.name == o.name));
+                    self.outputs = outputs;
+                    self.update_inputs_for_selection();
+                    self.layout_cache.clear();
+                    return self.apply_layout();
+                }
+            }
+            Message::ApplyFinished(result) => {
+                self.busy = None;
+                match result {
+                    Ok((applied, mismatches)) => {
+                        // A loaded profile applied as stored also switches the wallpaper.
+                        if let Some(profile) = self.settings.profile(&self.profile_name)
+                            && profile.is_active(&applied)
+                            && let Err(e) = profile.set_wallpaper()
+                        {
+                            tracing::warn!("{}", e);
+                        }
+                        self.applied_outputs = applied;
+                        self.status_message = Some(if mismatches.is_empty() {
+                            tr("Applied successfully!").to_string()
+                        } else {
+                            let changes: Vec<String> =
+                                mismatches.iter().map(mismatch_text).collect();
+                            trf(
+                                "Applied, but the compositor changed: {}",
+                                &[&changes.join("; ")],
+                            )
+                        })
+                    }
+                    Err(mut e) => {
+                        notify::event(&self.settings, tr("Apply failed"), &e);
+                        // Explain the likely cause when a mode is more than its link carries.
+                        for out in self.outputs.iter().filter(|o| o.enabled) {
+                            if let Some(warning) = self.link_warning(out) {
+                                e.push_str(&format!(
+                                    " ({}: {})",
+                                    out.name,
+                                    link_warning_text(warning)
+                                ));
+                            }
+                        }
+                        self.status_message = Some(trf("Apply error: {}", &[&e]));
+                    }
+                }
+            }
+            Message::OutputsLoaded(result) => {
+                self.busy = None;
+                match result {
+                    Ok(mut outputs) => {
+                        outputs.iter_mut().for_each(Output::tidy_modes);
+                        self.load_error = None;
+                        self.selected_output = self.settings.ui.selected_output.clone();
+                        self.applied_outputs = outputs.clone();
+                        self.outputs = outputs;
+                        self.ensure_selection();
+                        self.history.clear();
+                        self.refresh_connectors();
+                        self.update_inputs_for_selection();
+                        return self.detect_brightness();
+                    }
+                    Err(e) => {
+                        self.status_message = Some(trf("Error reading outputs: {}", &[&e]));
+                        self.load_error = Some(e);
+                    }
                 }
             }
+            Message::PreviewClicked => {
+                self.normalize_positions();
+                self.command_preview = Some(self.backend.preview(&self.outputs));
+            }
+            Message::PreviewClosed => self.command_preview = None,
             Message::SaveClicked => {
+                if self.busy.is_some() {
+                    return Task::none();
+                }
                 self.normalize_positions();
-                match save_config(&self.outputs, &self.settings) {
+                self.busy = Some(tr("Saving…"));
+                let outputs = self.outputs.clone();
+                let settings = self.settings.clone();
+                let sections = self.profile_sections();
+                return Task::perform(
+                    blocking(move || {
+                        let section = sections.get(&settings.save_format);
+                        save_config(&outputs, &settings, section.map(String::as_str))
+                    }),
+                    Message::SaveFinished,
+                );
+            }
+            Message::SaveFinished(result) => {
+                self.busy = None;
+                match result {
                     Ok(()) => {
                         self.status_message =
-                            Some(format!("Saved to {}", self.settings.monitors_conf_path))
+                            Some(trf("Saved to {}", &[&self.settings.monitors_conf_path]))
                     }
-                    Err(e) => self.status_message = Some(format!("Save error: {}", e)),
+                    Err(e) => self.status_message = Some(trf("Save error: {}", &[&e])),
                 }
+                self.monitors_conf_snapshot =
+                    std::fs::read_to_string(self.settings.monitors_path()).ok();
             }
-            Message::RestoreDefaultClicked => match restore_default_config(&self.settings) {
-                Ok(()) => self.status_message = Some("Restored to default config!".to_string()),
-                Err(e) => self.status_message = Some(format!("Restore error: {}", e)),
+            Message::SaveAsClicked => {
+                self.normalize_positions();
+                let outputs = self.outputs.clone();
+                let format = self.settings.save_format;
+                let sections = self.profile_sections();
+                return Task::perform(
+                    blocking(move || {
+                        let Some(path) = portal::save_file(tr("Save As"), format.file_name())?
+                        else {
+                            return Ok(None);
+                        };
+                        // The name picks the format, so one dialog covers all of them.
+                        let format = SaveFormat::from_path(&path).unwrap_or(format);
+                        let section = sections.get(&format).map(String::as_str);
+                        save_config_to(&outputs, format, section, &path)?;
+                        Ok(Some(path))
+                    }),
+                    Message::SaveAsFinished,
+                );
+            }
+            Message::SaveAsFinished(result) => match result {
+                Ok(Some(path)) => {
+                    self.status_message = Some(trf("Saved to {}", &[&path.display().to_string()]))
+                }
+                Ok(None) => {}
+                Err(e) => self.status_message = Some(trf("Save error: {}", &[&e])),
             },
-        }
-        Task::none()
-    }
-
-    pub fn view(&self) -> Element<'_, Message> {
-        let canvas = Canvas::new(LayoutCanvas {
-            outputs: self.outputs.clone(),
-            selected_idx: self.selected_output_idx,
-            cache: &self.layout_cache,
-        })
-        .width(Length::Fill)
-        .height(Length::Fill);
-
-        let mut sidebar = column![].spacing(15).width(Length::Fixed(400.0));
-
-        let mut tabs_row = row![].spacing(0);
-        for (i, out) in self.outputs.iter().enumerate() {
-            let is_selected = Some(i) == self.selected_output_idx;
-            let current_btn = button(text(&out.name).align_x(alignment::Horizontal::Center))
-                .width(Length::Fixed(80.0))
-                .style(if is_selected {
-                    button::primary
-                } else {
-                    button::secondary
-                })
-                .on_press(Message::MonitorClicked(i));
-            tabs_row = tabs_row.push(current_btn);
-        }
-        sidebar = sidebar.push(container(tabs_row).center_x(Length::Fill));
-
-        if let Some(idx) = self.selected_output_idx {
-            if let Some(out) = self.outputs.get(idx) {
-                if self.outputs.len() > 1 {
-                    sidebar = sidebar.push(
-                        row![
-                            Space::new().width(100.0),
-                            checkbox(out.enabled).on_toggle(Message::EnabledToggled),
-                            text("Enabled")
-                        ]
-                        .spacing(10),
+            Message::TransitionTick(now) => {
+                if let Some(transition) = &mut self.transition {
+                    let t = (now
+                        .saturating_duration_since(transition.start)
+                        .as_secs_f32()
+                        / TRANSITION.as_secs_f32())
+                    .min(1.0);
+                    // Ease out, so the outputs settle gently.
+                    transition.progress = 1.0 - (1.0 - t).powi(3);
+                    if t >= 1.0 {
+                        self.transition = None;
+                    }
+                    self.layout_cache.clear();
+                }
+            }
+            Message::SpinnerTick => self.spinner_frame = self.spinner_frame.wrapping_add(1),
+            Message::DaemonPolled => {
+                // Keep the daemon's rules off the layout while it is being edited here.
+                let hold = self.busy.is_some()
+                    || self.live_revert.is_some()
+                    || self.outputs != self.applied_outputs;
+                return Task::perform(
+                    blocking(move || ipc::daemon_status(hold)),
+                    Message::DaemonStatusReceived,
+                );
+            }
+            Message::DaemonStatusReceived(result) => {
+                self.daemon = result.unwrap_or_else(|e| {
+                    tracing::debug!("{}", e);
+                    None
+                });
+            }
+            Message::RestoreDefaultClicked => {
+                if self.busy.is_some() {
+                    return Task::none();
+                }
+                match restore_default_config(&self.settings) {
+                    Ok(()) => {
+                        self.status_message = Some(tr("Restored to default config!").to_string())
+                    }
+                    Err(e) => self.status_message = Some(trf("Restore error: {}", &[&e])),
+                }
+                self.monitors_conf_snapshot =
+                    std::fs::read_to_string(self.settings.monitors_path()).ok();
+            }
+            Message::SettingsFileChanged => {
+                let mut loaded = AppSettings::load();
+                // Window geometry is only written on close; keep the live values.
+                loaded.ui = self.settings.ui.clone();
+                if loaded != self.settings {
+                    let backend_changed = loaded.backend != self.settings.backend;
+                    self.settings = loaded;
+                    crate::i18n::set_language(Language::detect(self.settings.language.as_deref()));
+                    self.refresh_mode_search();
+                    self.update_inputs_for_selection();
+                    self.status_message = Some(tr("Settings reloaded from disk").to_string());
+                    notify::event(
+                        &self.settings,
+                        tr("Settings reloaded"),
+                        &AppSettings::path().display().to_string(),
                     );
+                    if backend_changed {
+                        self.backend = backend::from_settings(&self.settings);
+                        return self.reload_outputs();
+                    }
                 }
-
-                let label_width = 100.0;
-
-                let row_desc = row![
-                    container(text("Description").size(14)).width(label_width),
-                    text(&out.description).size(14)
-                ]
-                .spacing(10)
-                .align_y(alignment::Vertical::Center);
-                sidebar = sidebar.push(row_desc);
-
-                let phys_size_text = if out.physical_size.is_empty() {
-                    "Unknown".to_string()
-                } else {
-                    out.physical_size.clone()
+            }
+            Message::InputMapped(device, output) => {
+                match output {
+                    Some(output) => self.settings.input_mappings.insert(device, output),
+                    None => self.settings.input_mappings.remove(&device),
                 };
-                let row_phys = row![
-                    container(text("Physical Size").size(14)).width(label_width),
-                    text(phys_size_text).size(14)
-                ]
-                .spacing(10)
-                .align_y(alignment::Vertical::Center);
-                sidebar = sidebar.push(row_phys);
-
-                let row_scale = row![
-                    container(text("DPI Scale").size(14)).width(label_width),
-                    text_input("", &self.scale_input)
-                        .on_input(Message::ScaleChanged)
-                        .width(Length::Fixed(60.0)),
-                    button("-").on_press(Message::ScaleDec),
-                    button("+").on_press(Message::ScaleInc),
-                ]
-                .spacing(5)
-                .align_y(alignment::Vertical::Center);
-                sidebar = sidebar.push(row_scale);
-
-                let row_pos = row![
-                    container(text("Position").size(14)).width(label_width),
-                    text_input("", &self.x_input)
-                        .on_input(Message::XChanged)
-                        .width(Length::Fixed(60.0)),
-                    button("-").on_press(Message::XDec),
-                    button("+").on_press(Message::XInc),
-                    text_input("", &self.y_input)
-                        .on_input(Message::YChanged)
-                        .width(Length::Fixed(60.0)),
-                    button("-").on_press(Message::YDec),
-                    button("+").on_press(Message::YInc),
-                ]
-                .spacing(5)
-                .align_y(alignment::Vertical::Center);
-                sidebar = sidebar.push(row_pos);
-
-                let cm = out
-                    .modes
-                    .iter()
-                    .find(|m| m.current)
-                    .cloned()
-                    .unwrap_or(OutputMode {
-                        width: 1920,
-                        height: 1080,
-                        refresh_rate: 60.0,
-                        current: true,
-                        preferred: false,
+                self.save_settings();
+                if let Err(e) = input::apply_mappings(&self.settings.input_mappings) {
+                    self.status_message = Some(e);
+                }
+            }
+            Message::WallpaperChanged(name, command) => {
+                if let Some(profile) = self.settings.profiles.iter_mut().find(|p| p.name == name) {
+                    profile.wallpaper = (!command.is_empty()).then_some(command);
+                    self.save_settings();
+                }
+            }
+            Message::ProfileRuleChanged(name, rule) => {
+                if let Some(profile) = self.settings.profiles.iter_mut().find(|p| p.name == name) {
+                    profile.rule = rule;
+                    self.save_settings();
+                }
+            }
+            Message::DockProfileSelected(dock, profile) => {
+                match profile {
+                    Some(profile) => self.settings.dock_profiles.insert(dock, profile),
+                    None => self.settings.dock_profiles.remove(&dock),
+                };
+                self.save_settings();
+            }
+            Message::ContextMenuOpened(name, position) => {
+                self.selected_output = Some(name);
+                self.update_inputs_for_selection();
+                self.layout_cache.clear();
+                self.context_menu = Some(position);
+            }
+            Message::ContextMenuClosed => {
+                // Esc closes whichever is open.
+                self.context_menu = None;
+                self.output_dialog = false;
+            }
+            Message::OutputDialogOpened(name) => {
+                self.context_menu = None;
+                self.selected_output = Some(name);
+                self.update_inputs_for_selection();
+                self.layout_cache.overlay.clear();
+                self.output_dialog = true;
+            }
+            Message::OutputDialogClosed => self.output_dialog = false,
+            Message::PatternOpened(kind) => {
+                self.context_menu = None;
+                if let Some(out) = self.selected() {
+                    let name = out.name.clone();
+                    return Task::perform(
+                        blocking(move || match kind {
+                            PatternKind::TestPattern => {
+                                overlay::show(&name, &mut TestPattern::default())
+                            }
+                            PatternKind::PixelTest => {
+                                overlay::show(&name, &mut PixelTest::default())
+                            }
+                            PatternKind::MotionTest => {
+                                overlay::show(&name, &mut MotionTest::default())
+                            }
+                        }),
+                        Message::PatternClosed,
+                    );
+                }
+            }
+            Message::OverscanOpened => {
+                if let Some(out) = self.selected() {
+                    let name = out.name.clone();
+                    let identity = out.identity();
+                    let margins = self.settings.output_prefs(&identity).overscan;
+                    return Task::perform(
+                        blocking(move || {
+                            let mut frame = OverscanFrame::new(margins);
+                            overlay::show(&name, &mut frame).map(|()| frame.margins)
+                        }),
+                        move |result| Message::OverscanMeasured(identity.clone(), result),
+                    );
+                }
+            }
+            Message::OverscanMeasured(identity, result) => match result {
+                Ok(margins) => {
+                    self.settings.outputs.entry(identity).or_default().overscan = margins;
+                    self.save_settings();
+                    if let Err(e) =
+                        compositor::apply_overscans(&self.applied_outputs, &self.settings)
+                    {
+                        self.status_message = Some(e);
+                    }
+                }
+                Err(e) => self.status_message = Some(trf("Test pattern error: {}", &[&e])),
+            },
+            Message::OverscanReset => {
+                if let Some(identity) = self.selected().map(Output::identity) {
+                    let prefs = self.settings.outputs.entry(identity).or_default();
+                    prefs.overscan = Overscan::default();
+                    self.save_settings();
+                    if let Err(e) =
+                        compositor::apply_overscans(&self.applied_outputs, &self.settings)
+                    {
+                        self.status_message = Some(e);
+                    }
+                }
+            }
+            Message::PatternClosed(result) => {
+                if let Err(e) = result {
+                    self.status_message = Some(trf("Test pattern error: {}", &[&e]));
+                }
+            }
+            Message::CalibrationOpened => {
+                self.context_menu = None;
+                self.calibration_intro = true;
+            }
+            Message::CalibrationClosed => self.calibration_intro = false,
+            Message::CalibrationStarted => {
+                self.calibration_intro = false;
+                if let Some(out) = self.selected() {
+                    let name = out.name.clone();
+                    let identity = out.identity();
+                    let previous = self.settings.output_prefs(&identity).calibration;
+                    return Task::perform(
+                        blocking(move || {
+                            // The daemon holds the gamma of outputs with a night light or
+                            // a saved calibration, and only one program can at a time.
+                            if let Err(e) = ipc::pause_gamma(&name, true) {
+                                tracing::warn!("{}", e);
+                            }
+                            let result =
+                                CalibrationWizard::new(&name, previous).and_then(|mut wizard| {
+                                    overlay::show(&name, &mut wizard)?;
+                                    Ok(wizard.result())
+                                });
+                            if let Err(e) = ipc::pause_gamma(&name, false) {
+                                tracing::warn!("{}", e);
+                            }
+                            result
+                        }),
+                        move |result| Message::CalibrationFinished(identity.clone(), result),
+                    );
+                }
+            }
+            Message::CalibrationFinished(identity, result) => match result {
+                Ok(Some(calibration)) => {
+                    self.settings
+                        .outputs
+                        .entry(identity)
+                        .or_default()
+                        .calibration = Some(calibration);
+                    self.save_settings();
+                    self.status_message = Some(tr("Calibration saved").to_string());
+                }
+                Ok(None) => {
+                    self.status_message = Some(tr("Calibration cancelled").to_string());
+                }
+                Err(e) => self.status_message = Some(trf("Calibration error: {}", &[&e])),
+            },
+            Message::MonitorsConfigChanged => {
+                let current = std::fs::read_to_string(self.settings.monitors_path()).ok();
+                if current != self.monitors_conf_snapshot {
+                    self.monitors_conf_snapshot = current;
+                    self.status_message = Some(trf(
+                        "{} changed on disk",
+                        &[&self.settings.monitors_conf_path],
+                    ));
+                    notify::event(
+                        &self.settings,
+                        tr("Monitors config changed"),
+                        &self.settings.monitors_conf_path,
+                    );
+                }
+            }
+            Message::ProfileNameChanged(name) => self.profile_name = name,
+            Message::ProfileSaved => {
+                let name = self.profile_name.trim();
+                if !name.is_empty() {
+                    self.settings
+                        .store_profile(Profile::from_outputs(name, &self.outputs));
+                    self.status_message = Some(match self.settings.save() {
+                        Ok(()) => trf("Saved profile {}", &[&name]),
+                        Err(e) => trf("Settings error: {}", &[&e]),
                     });
-
-                let mut unique_resolutions: Vec<String> = Vec::new();
-                for m in &out.modes {
-                    let res = format!("{}x{}", m.width, m.height);
-                    if !unique_resolutions.contains(&res) {
-                        unique_resolutions.push(res);
+                }
+            }
+            Message::ProfileSelected(name) => {
+                if let Some(profile) = self.settings.profile(&name) {
+                    let from = self.positions();
+                    profile.apply_to(&mut self.outputs);
+                    self.start_transition(from);
+                    self.profile_name = name;
+                    self.update_inputs_for_selection();
+                    self.layout_cache.clear();
+                    self.status_message = Some(trf("Loaded profile {}", &[&self.profile_name]));
+                }
+            }
+            Message::OfflineEditStarted(name) => {
+                if let Some(profile) = self.settings.profile(&name) {
+                    let mut outputs = profile.offline_outputs(&self.applied_outputs);
+                    outputs.iter_mut().for_each(Output::tidy_modes);
+                    self.outputs = outputs;
+                    self.profile_name = name.clone();
+                    self.offline = Some(name);
+                    self.selected_output = None;
+                    self.ensure_selection();
+                    self.history.clear();
+                    self.refresh_connectors();
+                    self.update_inputs_for_selection();
+                    self.layout_cache.clear();
+                }
+            }
+            Message::OfflineEditFinished => {
+                self.offline = None;
+                return self.reload_outputs();
+            }
+            Message::ProfileExported => {
+                if let Some(profile) = self.settings.profile(&self.profile_name).cloned() {
+                    let outputs = self.applied_outputs.clone();
+                    return Task::perform(
+                        blocking(move || {
+                            let file = format!("{}.json", profile.name);
+                            let Some(path) = portal::save_file(tr("Export profile"), &file)? else {
+                                return Ok(None);
+                            };
+                            let json = profile::export(&profile, &outputs)?;
+                            std::fs::write(&path, json).map_err(|e| {
+                                trf("Failed to write {}: {}", &[&path.display(), &e])
+                            })?;
+                            Ok(Some(path))
+                        }),
+                        Message::ProfileExportFinished,
+                    );
+                }
+            }
+            Message::ReportExported => {
+                let outputs = self.outputs.clone();
+                return Task::perform(
+                    blocking(move || {
+                        let Some(path) = portal::save_file(tr("Export report"), "displays.md")?
+                        else {
+                            return Ok(None);
+                        };
+                        report::write(&path, &outputs)?;
+                        Ok(Some(path))
+                    }),
+                    Message::ReportExportFinished,
+                );
+            }
+            Message::ProfileExportFinished(result) | Message::ReportExportFinished(result) => {
+                match result {
+                    Ok(Some(path)) => {
+                        self.status_message =
+                            Some(trf("Exported to {}", &[&path.display().to_string()]))
                     }
+                    Ok(None) => {}
+                    Err(e) => self.status_message = Some(e),
+                }
+            }
+            Message::ProfileImported => {
+                let outputs = self.applied_outputs.clone();
+                return Task::perform(
+                    blocking(move || {
+                        let Some(path) = portal::open_file(tr("Import profile"))? else {
+                            return Ok(None);
+                        };
+                        let json = std::fs::read_to_string(&path)
+                            .map_err(|e| trf("Failed to read {}: {}", &[&path.display(), &e]))?;
+                        profile::import(&json, &outputs).map(Some)
+                    }),
+                    Message::ProfileImportFinished,
+                );
+            }
+            Message::ProfileImportFinished(result) => match result {
+                Ok(Some(profile)) => {
+                    self.profile_name = profile.name.clone();
+                    self.settings.store_profile(profile);
+                    self.status_message = Some(match self.settings.save() {
+                        Ok(()) => trf("Imported profile {}", &[&self.profile_name]),
+                        Err(e) => trf("Settings error: {}", &[&e]),
+                    });
+                }
+                Ok(None) => {}
+                Err(e) => self.status_message = Some(e),
+            },
+            Message::PaneSelected(pane) => self.compact_pane = pane,
+            Message::PhysicalViewToggled => {
+                self.settings.ui.physical_view = !self.settings.ui.physical_view;
+                self.layout_cache.clear();
+            }
+            Message::GridToggled => {
+                self.settings.ui.show_grid = !self.settings.ui.show_grid;
+                self.layout_cache.clear();
+            }
+            // The live layout is read afresh once an offline edit is finished.
+            Message::OutputsChanged(_) if self.offline.is_some() => {}
+            Message::OutputsChanged(current) => self.merge_connected_outputs(current),
+            Message::FitView => self.settings.ui.canvas_zoom = 1.0,
+            Message::DragFinished => {}
+            Message::CanvasZoomed(zoom) => self.settings.ui.canvas_zoom = zoom,
+            Message::WindowResized(size) => self.window_size = size,
+            Message::SidebarResizeStarted => self.resizing_sidebar = true,
+            Message::SidebarResized(cursor_x) => {
+                let max = (self.window_size.width - MIN_CANVAS_WIDTH).max(MIN_SIDEBAR_WIDTH);
+                self.settings.ui.sidebar_width =
+                    (self.window_size.width - cursor_x - SIDEBAR_PADDING * 2.0)
+                        .clamp(MIN_SIDEBAR_WIDTH, max);
+            }
+            Message::SidebarResizeFinished => self.resizing_sidebar = false,
+            Message::TabPressed(name) => {
+                self.context_menu = None;
+                self.selected_output = Some(name.clone());
+                self.update_inputs_for_selection();
+                self.layout_cache.overlay.clear();
+                self.tab_drag = Some(name);
+            }
+            Message::TabEntered(name) => {
+                if let Some(dragged) = self.tab_drag.clone()
+                    && dragged != name
+                {
+                    self.move_tab(&dragged, &name);
+                }
+            }
+            Message::TabDragFinished => {
+                self.tab_drag = None;
+                if !self.read_only()
+                    && let Err(e) = self.settings.save()
+                {
+                    tracing::error!("Error saving settings: {}", e);
+                }
+            }
+            Message::CloseRequested => {
+                self.persist_ui_state();
+                return iced::exit();
+            }
+            Message::Undo => {
+                while let Some(previous) = self.history.pop() {
+                    if previous != self.outputs {
+                        let from = self.positions();
+                        self.outputs = previous;
+                        self.start_transition(from);
+                        self.update_inputs_for_selection();
+                        break;
+                    }
+                }
+            }
+            Message::SelectNext => self.select_relative(1),
+            Message::SelectPrevious => self.select_relative(-1),
+            Message::DisableSelected => {
+                self.context_menu = None;
+                if let Some(idx) = self.selected_idx() {
+                    self.set_enabled(idx, false);
                 }
-                let selected_resolution = Some(format!("{}x{}", cm.width, cm.height));
-                let res_options = unique_resolutions.clone();
-                let pick_res = pick_list(res_options, selected_resolution, |s| {
-                    Message::ResolutionSizeSelected(s)
+            }
+            Message::ToggleSelectedEnabled => {
+                if let Some(idx) = self.selected_idx() {
+                    self.set_enabled(idx, !self.outputs[idx].enabled);
+                }
+            }
+            Message::NudgeSelected(dx, dy) => {
+                if let Some(out) = self.selected_mut() {
+                    let pos = &mut out.position;
+                    *pos = ((pos.0 + dx).max(0), (pos.1 + dy).max(0));
+                    self.update_inputs_for_selection();
+                }
+            }
+            Message::CycleMode(step) => {
+                if let Some(out) = self.selected_mut() {
+                    let modes = &mut out.modes;
+                    if !modes.is_empty() {
+                        let len = modes.len() as isize;
+                        let current = modes.iter().position(|m| m.current).unwrap_or(0) as isize;
+                        let next = (current + step).rem_euclid(len) as usize;
+                        for (i, m) in modes.iter_mut().enumerate() {
+                            m.current = i == next;
+                        }
+                    }
+                }
+            }
+            Message::AdaptiveSyncToggled(enabled) => {
+                if let Some(idx) = self.selected_idx() {
+                    self.checkpoint();
+                    self.outputs[idx].adaptive_sync = Some(enabled);
+                }
+            }
+            Message::RgbRangeSelected(range) => {
+                if let Some(idx) = self.selected_idx() {
+                    self.checkpoint();
+                    self.outputs[idx].rgb_range = Some(range);
+                }
+            }
+            Message::PixelFormatSelected(format) => {
+                if let Some(idx) = self.selected_idx() {
+                    self.checkpoint();
+                    self.outputs[idx].pixel_format = Some(format);
+                }
+            }
+            Message::QuickActionClicked(action) => {
+                let Some(idx) = self.selected_idx() else {
+                    return Task::none();
+                };
+                let layout = match action {
+                    QuickAction::Gaming => Some(gaming_layout(
+                        &self.outputs,
+                        idx,
+                        self.settings.gaming_disables_others,
+                    )),
+                    QuickAction::Present => self
+                        .presentation_source(idx)
+                        .and_then(|source| presentation_layout(&self.outputs, source, idx)),
+                };
+                let Some(layout) = layout else {
+                    self.status_message =
+                        Some(tr("The outputs have no resolution in common").to_string());
+                    return Task::none();
+                };
+                self.checkpoint();
+                self.quick_action = Some((action, self.applied_outputs.clone()));
+                let from = self.positions();
+                self.outputs = layout;
+                self.start_transition(from);
+                self.update_inputs_for_selection();
+                self.layout_cache.clear();
+                return self.update(Message::ApplyClicked);
+            }
+            Message::VirtualOutputAdded => {
+                if let Some(compositor) = Compositor::detect() {
+                    return Task::perform(
+                        blocking(move || compositor::create_virtual_output(compositor)),
+                        Message::VirtualOutputFinished,
+                    );
+                }
+            }
+            Message::VirtualOutputRemoved(name) => {
+                if let Some(compositor) = Compositor::detect() {
+                    return Task::perform(
+                        blocking(move || compositor::remove_virtual_output(compositor, &name)),
+                        Message::VirtualOutputFinished,
+                    );
+                }
+            }
+            // The output watcher adds or drops the output once the compositor has it.
+            Message::VirtualOutputFinished(result) => {
+                if let Err(e) = result {
+                    self.status_message = Some(e);
+                }
+            }
+            Message::QuickActionReverted => {
+                if let Some((_, mut outputs)) = self.quick_action.take() {
+                    // A projector may have been unplugged in the meantime.
+                    outputs.retain(|o| self.outputs.iter().any(|c| c.name == o.name));
+                    self.checkpoint();
+                    self.outputs = outputs;
+                    self.update_inputs_for_selection();
+                    self.layout_cache.clear();
+                    return self.update(Message::ApplyClicked);
+                }
+            }
+            Message::AutoRotateToggled(enabled) => {
+                self.settings.auto_rotate = enabled;
+                self.save_settings();
+            }
+            Message::GamingDisablesOthersToggled(disable) => {
+                self.settings.gaming_disables_others = disable;
+                self.save_settings();
+            }
+            Message::GameModeProfileSelected(profile) => {
+                self.settings.gamemode_profile = profile;
+                self.save_settings();
+            }
+            Message::ResetToPreferred => {
+                if let Some(out) = self.selected_mut() {
+                    let modes = &mut out.modes;
+                    if let Some(target) = modes.iter().position(|m| m.preferred) {
+                        for (i, m) in modes.iter_mut().enumerate() {
+                            m.current = i == target;
+                        }
+                    }
+                }
+            }
+            Message::CycleTransform => {
+                if let Some(out) = self.selected_mut() {
+                    let current = TRANSFORMS
+                        .iter()
+                        .position(|t| *t == out.transform)
+                        .unwrap_or(0);
+                    out.transform = TRANSFORMS[(current + 1) % TRANSFORMS.len()].to_string();
+                }
+            }
+            Message::LanguageSelected(lang) => {
+                crate::i18n::set_language(lang);
+                self.settings.language = Some(lang.code().to_string());
+                self.save_settings();
+            }
+            Message::SettingsToggled => {
+                self.settings_open = !self.settings_open;
+                if self.settings_open {
+                    self.history_open = false;
+                    self.compact_pane = Pane::Settings;
+                    self.backend_choices = [AUTO_BACKEND, WAYLAND_BACKEND]
+                        .into_iter()
+                        .map(str::to_string)
+                        .chain(Tool::ALL.into_iter().map(|t| t.name().to_string()))
+                        .chain(plugin::discover().into_iter().map(|p| p.name))
+                        .collect();
+                    self.input_devices = input::list_devices();
+                    self.docks = dock::list();
+                    self.seats = seat::list();
+                }
+            }
+            Message::HistoryToggled => {
+                self.history_open = !self.history_open;
+                if self.history_open {
+                    self.settings_open = false;
+                    self.compact_pane = Pane::Settings;
+                    self.events = history::load();
+                }
+            }
+            Message::HistoryRefreshed => self.events = history::load(),
+            Message::MonitorsPathChanged(path) => {
+                self.settings.monitors_conf_path = path;
+                self.save_settings();
+            }
+            Message::ConfigPathChanged(path) => {
+                self.settings.config_conf_path = path;
+                self.save_settings();
+            }
+            Message::AutoAppendToggled(append) => {
+                self.settings.auto_append_source = append;
+                self.save_settings();
+            }
+            Message::SaveFormatSelected(format) => {
+                self.settings.save_format = format;
+                self.save_settings();
+            }
+            Message::BackendSelected(name) => {
+                self.settings.backend = (name != AUTO_BACKEND).then_some(name);
+                self.save_settings();
+                self.backend = backend::from_settings(&self.settings);
+                return self.reload_outputs();
+            }
+            Message::SeatSelected(seat) => {
+                self.settings.seat = (seat != AUTO_SEAT).then_some(seat);
+                self.save_settings();
+                self.backend = backend::from_settings(&self.settings);
+                return self.reload_outputs();
+            }
+            Message::ToolPathChanged(tool, path) => {
+                if path.is_empty() {
+                    self.settings.tool_paths.remove(tool.name());
+                } else {
+                    self.settings
+                        .tool_paths
+                        .insert(tool.name().to_string(), path);
+                }
+                self.save_settings();
+                self.backend = backend::from_settings(&self.settings);
+            }
+            Message::SnappingToggled(enabled) => {
+                self.settings.snapping.enabled = enabled;
+                self.save_settings();
+            }
+            Message::SnapThresholdChanged(threshold) => {
+                self.settings.snapping.threshold = threshold;
+                self.save_settings();
+            }
+            Message::BrightnessLoaded(controls) => {
+                self.brightness = controls
+                    .into_iter()
+                    .map(|(name, control, percent)| (name, (control, percent)))
+                    .collect();
+            }
+            Message::BrightnessChanged(percent) => {
+                let Some(name) = self.selected().map(|o| o.name.clone()) else {
+                    return Task::none();
+                };
+                if self.settings.brightness_linked {
+                    let balance = self.brightness_balance(&name);
+                    let master = percent as f32 / balance.max(0.01);
+                    let balances: Vec<(String, f32)> = self
+                        .brightness
+                        .keys()
+                        .map(|n| (n.clone(), self.brightness_balance(n)))
+                        .collect();
+                    for (other, balance) in balances {
+                        if let Some((_, level)) = self.brightness.get_mut(&other) {
+                            *level = (master * balance).round().clamp(0.0, 100.0) as u8;
+                        }
+                    }
+                }
+                if let Some((_, level)) = self.brightness.get_mut(&name) {
+                    *level = percent;
+                }
+            }
+            Message::BrightnessReleased => {
+                let selected = self.selected().map(|o| o.name.clone());
+                let changes: Vec<(brightness::Control, u8)> = self
+                    .brightness
+                    .iter()
+                    .filter(|(name, _)| {
+                        self.settings.brightness_linked || Some(*name) == selected.as_ref()
+                    })
+                    .map(|(_, (control, percent))| (control.clone(), *percent))
+                    .collect();
+                return Task::perform(
+                    blocking(move || {
+                        changes
+                            .iter()
+                            .try_for_each(|(control, percent)| control.set(*percent))
+                    }),
+                    Message::BrightnessApplied,
+                );
+            }
+            Message::BrightnessApplied(result) => {
+                if let Err(e) = result {
+                    self.status_message = Some(trf("Brightness error: {}", &[&e]));
+                }
+            }
+            Message::BrightnessLinkToggled(linked) => {
+                self.settings.brightness_linked = linked;
+                if linked {
+                    // Remember how the outputs compare now, so the link keeps them that way.
+                    let brightest = self.brightness.values().map(|(_, p)| *p).max().unwrap_or(0);
+                    for out in &self.outputs {
+                        if let Some((_, percent)) = self.brightness.get(&out.name) {
+                            let balance = if brightest == 0 {
+                                1.0
+                            } else {
+                                *percent as f32 / brightest as f32
+                            };
+                            self.settings
+                                .outputs
+                                .entry(out.identity())
+                                .or_default()
+                                .brightness_balance = Some(balance);
+                        }
+                    }
+                }
+                self.save_settings();
+            }
+            Message::AutoBrightnessToggled(enabled) => {
+                self.settings.auto_brightness.enabled = enabled;
+                self.save_settings();
+            }
+            Message::AutoBrightnessExternalToggled(external) => {
+                self.settings.auto_brightness.external = external;
+                self.save_settings();
+            }
+            Message::CurvePointChanged(i, percent) => {
+                if let Some(point) = self.settings.auto_brightness.curve.get_mut(i) {
+                    point.percent = percent;
+                    self.save_settings();
+                }
+            }
+            Message::BatteryActionSelected(action) => {
+                if let Some(identity) = self.selected().map(Output::identity) {
+                    let prefs = self.settings.outputs.entry(identity).or_default();
+                    prefs.on_battery = action;
+                    self.save_settings();
+                }
+            }
+            Message::BatteryBrightnessChanged(percent) => {
+                self.settings.battery_brightness = percent;
+                self.save_settings();
+            }
+            Message::NightScheduleToggled(enabled) => {
+                self.settings.night_schedule.enabled = enabled;
+                self.save_settings();
+            }
+            Message::LatitudeChanged(value) => {
+                if let Some(latitude) = parse_decimal::<f64>(&value)
+                    && (-90.0..=90.0).contains(&latitude)
+                {
+                    self.settings.night_schedule.latitude = latitude;
+                    self.save_settings();
+                }
+                self.location_inputs[0] = value;
+            }
+            Message::LongitudeChanged(value) => {
+                if let Some(longitude) = parse_decimal::<f64>(&value)
+                    && (-180.0..=180.0).contains(&longitude)
+                {
+                    self.settings.night_schedule.longitude = longitude;
+                    self.save_settings();
+                }
+                self.location_inputs[1] = value;
+            }
+            Message::TransitionChanged(minutes) => {
+                self.settings.night_schedule.transition_minutes = minutes;
+                self.save_settings();
+            }
+            Message::UiScaleSelected(label) => {
+                if let Ok(percent) = f32::from_str(label.trim_end_matches('%')) {
+                    self.settings.ui_scale = percent / 100.0;
+                    self.save_settings();
+                }
+            }
+            Message::ThemeSelected(name) => {
+                self.settings.theme = (name != DEFAULT_THEME).then_some(name);
+                self.save_settings();
+                self.layout_cache.clear();
+            }
+            Message::NotificationsToggled(enabled) => {
+                self.settings.notifications = enabled;
+                self.save_settings();
+            }
+            Message::PreApplyHookChanged(command) => {
+                self.settings.hooks.pre_apply = (!command.is_empty()).then_some(command);
+                self.save_settings();
+            }
+            Message::PostApplyHookChanged(command) => {
+                self.settings.hooks.post_apply = (!command.is_empty()).then_some(command);
+                self.save_settings();
+            }
+            Message::LogLevelSelected(level) => {
+                self.settings.log_level = level;
+                self.save_settings();
+            }
+            Message::LogToFileToggled(enabled) => {
+                self.settings.log_to_file = enabled;
+                self.save_settings();
+            }
+            Message::FocusNext => return operation::focus_next(),
+            Message::FocusPrevious => return operation::focus_previous(),
+        }
+        if refit {
+            self.fit_view();
+        }
+        Task::none()
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        let px_per_mm = if self.settings.ui.physical_view {
+            average_px_per_mm(&self.outputs)
+        } else {
+            None
+        };
+        let mut rects = LayoutCanvas::drawn_rects(&self.outputs, px_per_mm);
+        if let Some(transition) = &self.transition {
+            transition.apply(&self.outputs, &mut rects);
+        }
+        let canvas = Canvas::new(LayoutCanvas {
+            outputs: &self.outputs,
+            rects,
+            selected: self.selected_output.as_deref(),
+            frame: self.view_frame,
+            zoom: self.settings.ui.canvas_zoom,
+            bezels: self
+                .outputs
+                .iter()
+                .map(|out| {
+                    bezel_logical_px(out, &self.settings.output_prefs(&out.identity()).bezels)
                 })
-                .width(Length::Fixed(200.0));
+                .collect(),
+            tints: self
+                .outputs
+                .iter()
+                .map(|out| {
+                    self.settings
+                        .output_prefs(&out.identity())
+                        .color_temperature
+                        .map(night_light_tint)
+                })
+                .collect(),
+            nicknames: self
+                .outputs
+                .iter()
+                .map(|out| self.settings.output_prefs(&out.identity()).nickname)
+                .collect(),
+            grid: self.settings.ui.show_grid,
+            high_contrast: self.high_contrast(),
+            text_scale: if self.high_contrast() {
+                HIGH_CONTRAST_TEXT_SCALE
+            } else {
+                1.0
+            },
+            snapping: self.settings.snapping,
+            cache: &self.layout_cache,
+        })
+        .width(Length::Fill)
+        .height(Length::Fill);
+
+        let compact = self.is_compact();
+        let sidebar_width = if compact {
+            Length::Fill
+        } else {
+            Length::Fixed(self.settings.ui.sidebar_width)
+        };
+        let mut sidebar = column![].spacing(15).width(sidebar_width);
+
+        let mut tabs_row = row![].spacing(0);
+        for out in self.tab_order().into_iter().map(|i| &self.outputs[i]) {
+            let is_selected = self.selected_output.as_ref() == Some(&out.name);
+            let name = self.settings.display_name(out);
+            let label = format!(
+                "{} {}",
+                self.connector_kind(&out.name).icon(),
+                ellipsize(&name, TAB_LABEL_CHARS)
+            );
+            let tip = if name == out.name {
+                format!("{}\n{}", out.name, out.description)
+            } else {
+                format!("{} ({})\n{}", name, out.name, out.description)
+            };
+            // Pressed through the mouse area rather than the button, so a press can start
+            // dragging the tab; the style ignores the button's own disabled look.
+            let current_btn = button(
+                text(label)
+                    .wrapping(text::Wrapping::None)
+                    .align_x(alignment::Horizontal::Center),
+            )
+            .width(Length::Fixed(TAB_WIDTH))
+            .style(move |theme, _| {
+                let status = button::Status::Active;
+                if is_selected {
+                    button::primary(theme, status)
+                } else {
+                    button::secondary(theme, status)
+                }
+            });
+            tabs_row = tabs_row.push(tooltip(
+                mouse_area(current_btn)
+                    .interaction(if self.tab_drag.is_some() {
+                        mouse::Interaction::Grabbing
+                    } else {
+                        mouse::Interaction::Pointer
+                    })
+                    .on_press(Message::TabPressed(out.name.clone()))
+                    .on_enter(Message::TabEntered(out.name.clone())),
+                container(text(tip.trim_end().to_string()).size(12))
+                    .padding(6)
+                    .style(container::rounded_box),
+                tooltip::Position::Bottom,
+            ));
+        }
+        // Scrolls sideways once the tabs are wider than the sidebar.
+        sidebar = sidebar.push(
+            container(Scrollable::new(tabs_row).horizontal().spacing(4)).center_x(Length::Fill),
+        );
+
+        if self.outputs.is_empty() && self.busy.is_none() {
+            let mut empty = column![text(tr("No outputs found")).size(16)].spacing(8);
+            if let Some(e) = &self.load_error {
+                empty = empty.push(text(e).size(13).style(iced::widget::text::warning));
+            }
+            sidebar = sidebar.push(
+                empty.push(
+                    text(tr(
+                        "Choose another backend or set the tool paths in Settings.",
+                    ))
+                    .size(13),
+                ),
+            );
+        }
+        if let Some(problem) = limits::check(&self.outputs, None) {
+            sidebar = sidebar.push(
+                text(limit_problem_text(problem))
+                    .size(13)
+                    .style(iced::widget::text::warning),
+            );
+        }
+
+        if let Some(idx) = self.selected_idx()
+            && let Some(out) = self.outputs.get(idx)
+        {
+            if self.outputs.len() > 1 {
+                sidebar = sidebar.push(
+                    row![
+                        Space::new().width(100.0),
+                        if out.enabled && self.is_last_enabled(idx) {
+                            described(
+                                checkbox(out.enabled),
+                                tr("At least one output must stay enabled"),
+                            )
+                        } else {
+                            described(
+                                checkbox(out.enabled).on_toggle(Message::EnabledToggled),
+                                tr("Enable or disable this monitor (E)"),
+                            )
+                        },
+                        text(tr("Enabled"))
+                    ]
+                    .spacing(10),
+                );
+            }
+
+            let label_width = 100.0;
+
+            let row_nickname = row![
+                container(text(tr("Nickname")).size(14)).width(label_width),
+                described(
+                    text_input(
+                        tr("e.g. Left Dell"),
+                        &self
+                            .settings
+                            .output_prefs(&out.identity())
+                            .nickname
+                            .unwrap_or_default()
+                    )
+                    .on_input(Message::NicknameChanged)
+                    .width(Length::Fixed(200.0)),
+                    tr(
+                        "Shown instead of the connector name, and kept for this monitor on any port"
+                    ),
+                ),
+            ]
+            .spacing(10)
+            .align_y(alignment::Vertical::Center);
+            sidebar = sidebar.push(row_nickname);
+
+            let row_desc = row![
+                container(text(tr("Description")).size(14)).width(label_width),
+                text(&out.description).size(14)
+            ]
+            .spacing(10)
+            .align_y(alignment::Vertical::Center);
+            sidebar = sidebar.push(row_desc);
+
+            let row_connector = row![
+                container(text(tr("Connector")).size(14)).width(label_width),
+                text(self.connector_text(&out.name)).size(14)
+            ]
+            .spacing(10)
+            .align_y(alignment::Vertical::Center);
+            sidebar = sidebar.push(row_connector);
+
+            if let Some(edid) = self.connectors.get(&out.name).and_then(|c| c.edid.as_ref()) {
+                for (label, value) in edid_rows(edid) {
+                    sidebar = sidebar.push(
+                        row![
+                            container(text(tr(label)).size(14)).width(label_width),
+                            text(value).size(14)
+                        ]
+                        .spacing(10)
+                        .align_y(alignment::Vertical::Center),
+                    );
+                }
+            }
+
+            let phys_size_text = if out.physical_size.is_empty() {
+                tr("Unknown").to_string()
+            } else {
+                out.physical_size.clone()
+            };
+            let row_phys = row![
+                container(text(tr("Physical Size")).size(14)).width(label_width),
+                text(phys_size_text).size(14)
+            ]
+            .spacing(10)
+            .align_y(alignment::Vertical::Center);
+            sidebar = sidebar.push(row_phys);
+
+            let cm = out
+                .modes
+                .iter()
+                .find(|m| m.current)
+                .cloned()
+                .unwrap_or(OutputMode {
+                    width: 1920,
+                    height: 1080,
+                    refresh_rate: 60.0,
+                    current: true,
+                    preferred: false,
+                    custom: false,
+                });
+
+            let scale_error = parse_scale(&self.scale_input).err();
+            let row_scale = row![
+                container(text(tr("DPI Scale")).size(14)).width(label_width),
+                described(
+                    text_input(tr("Scale"), &self.scale_input)
+                        .on_input(Message::ScaleChanged)
+                        .style(input_style(scale_error.is_some()))
+                        .width(Length::Fixed(60.0)),
+                    scale_error.unwrap_or(tr("Scale factor, such as 1.5 for 150%")),
+                ),
+                described(
+                    button("-").on_press(Message::ScaleDec),
+                    tr("Decrease scale (-)")
+                ),
+                described(
+                    button("+").on_press(Message::ScaleInc),
+                    tr("Increase scale (+)")
+                ),
+            ]
+            .spacing(5)
+            .align_y(alignment::Vertical::Center);
+            sidebar = sidebar.push(row_scale);
+
+            let preset_labels: Vec<String> = SCALE_PRESETS
+                .iter()
+                .map(|p| scale_preset_label(*p))
+                .collect();
+            let selected_preset = SCALE_PRESETS
+                .iter()
+                .find(|p| (*p - out.scale).abs() < 0.001)
+                .map(|p| scale_preset_label(*p));
+            let (logical_w, logical_h) = LayoutCanvas::logical_size(out, &cm);
+            let pick_preset =
+                pick_list(preset_labels, selected_preset, Message::ScalePresetSelected)
+                    .placeholder(tr("Custom"))
+                    .width(Length::Fixed(100.0));
+
+            let row_preset = row![
+                container(text(tr("Scale Preset")).size(14)).width(label_width),
+                pick_preset,
+                text(trf("Logical {}x{}", &[&logical_w, &logical_h])).size(14)
+            ]
+            .spacing(5)
+            .align_y(alignment::Vertical::Center);
+            sidebar = sidebar.push(row_preset);
+
+            let native = out
+                .modes
+                .iter()
+                .find(|m| m.preferred)
+                .is_none_or(|p| (p.width, p.height) == (cm.width, cm.height));
+            if !native || (out.scale - out.scale.round()).abs() > 0.001 {
+                let supported = Compositor::detect().is_some_and(|c| c.has_scale_filter());
+                let row_filter = row![
+                    container(text(tr("Scaling filter")).size(14)).width(label_width),
+                    described(
+                        pick_list(
+                            ScaleFilter::ALL,
+                            Some(self.settings.output_prefs(&out.identity()).scale_filter),
+                            Message::ScaleFilterSelected
+                        )
+                        .width(Length::Fixed(100.0)),
+                        if supported {
+                            tr("Nearest keeps pixel art sharp; Linear smooths text and photos")
+                        } else {
+                            tr("This compositor has no scale filter setting; the choice is only saved")
+                        }
+                    ),
+                ]
+                .spacing(5)
+                .align_y(alignment::Vertical::Center);
+                sidebar = sidebar.push(row_filter);
+            }
+
+            let density_text = match out.ppi(&cm) {
+                Some(ppi) => trf(
+                    "{} PPI, {} effective",
+                    &[&format!("{:.0}", ppi), &format!("{:.0}", ppi / out.scale)],
+                ),
+                None => tr("Unknown").to_string(),
+            };
+            let row_density = row![
+                container(text(tr("Density")).size(14)).width(label_width),
+                text(density_text).size(14)
+            ]
+            .spacing(10)
+            .align_y(alignment::Vertical::Center);
+            sidebar = sidebar.push(row_density);
+
+            let steps = self.backend.scale_steps();
+            if let Some(warning) = scale_warning(steps, &cm, out.scale) {
+                let mut row_warning = row![text(warning).size(13)]
+                    .spacing(10)
+                    .align_y(alignment::Vertical::Center);
+                if let Some(suggested) = steps.nearest(&cm, out.scale) {
+                    row_warning = row_warning.push(
+                        button(text(trf("Use {}", &[&suggested])).size(13))
+                            .style(button::secondary)
+                            .on_press(Message::ScaleChanged(suggested.to_string())),
+                    );
+                }
+                sidebar = sidebar.push(row_warning);
+            }
+
+            let x_error = parse_position(&self.x_input).err();
+            let y_error = parse_position(&self.y_input).err();
+            let row_pos = row![
+                container(text(tr("Position")).size(14)).width(label_width),
+                described(
+                    text_input("X", &self.x_input)
+                        .on_input(Message::XChanged)
+                        .style(input_style(x_error.is_some()))
+                        .width(Length::Fixed(60.0)),
+                    x_error.unwrap_or(tr("Horizontal position in logical pixels")),
+                ),
+                described(
+                    button("-").on_press(Message::XDec),
+                    tr("Move left (Left arrow)")
+                ),
+                described(
+                    button("+").on_press(Message::XInc),
+                    tr("Move right (Right arrow)")
+                ),
+                described(
+                    text_input("Y", &self.y_input)
+                        .on_input(Message::YChanged)
+                        .style(input_style(y_error.is_some()))
+                        .width(Length::Fixed(60.0)),
+                    y_error.unwrap_or(tr("Vertical position in logical pixels")),
+                ),
+                described(
+                    button("-").on_press(Message::YDec),
+                    tr("Move up (Up arrow)")
+                ),
+                described(
+                    button("+").on_press(Message::YInc),
+                    tr("Move down (Down arrow)")
+                ),
+            ]
+            .spacing(5)
+            .align_y(alignment::Vertical::Center);
+            sidebar = sidebar.push(row_pos);
 
-                let row_res = row![
-                    container(text("Resolution").size(14)).width(label_width),
-                    pick_res
+            if out.enabled && self.outputs.iter().filter(|o| o.enabled).count() > 1 {
+                let extend = tr("Extend").to_string();
+                let group = clone_groups(&self.outputs)
+                    .into_iter()
+                    .find(|names| names.contains(&out.name))
+                    .unwrap_or_default();
+                let mirrored = group.iter().find(|name| **name != out.name).cloned();
+                let choices: Vec<String> = std::iter::once(extend.clone())
+                    .chain(
+                        self.outputs
+                            .iter()
+                            .filter(|o| o.enabled && o.name != out.name)
+                            .map(|o| o.name.clone()),
+                    )
+                    .collect();
+                let row_mirror = row![
+                    container(text(tr("Mirror")).size(14)).width(label_width),
+                    described(
+                        pick_list(
+                            choices,
+                            Some(mirrored.clone().unwrap_or_else(|| extend.clone())),
+                            move |choice: String| {
+                                Message::MirrorSelected((choice != extend).then_some(choice))
+                            }
+                        )
+                        .width(Length::Fixed(200.0)),
+                        tr("Show the same picture as another output, or drop it onto that output's corner on the canvas")
+                    ),
                 ]
                 .spacing(5)
                 .align_y(alignment::Vertical::Center);
-                sidebar = sidebar.push(row_res);
-
-                let mut current_rr_idx = 0;
-                let mut rr_labels = Vec::new();
-                let mut rr_mode_indices = Vec::new();
-                for (i, m) in out.modes.iter().enumerate() {
-                    if m.width == cm.width && m.height == cm.height {
-                        rr_labels.push(format!("{:.3}", m.refresh_rate));
-                        rr_mode_indices.push(i);
-                        if m.current {
-                            current_rr_idx = rr_labels.len() - 1;
+                sidebar = sidebar.push(row_mirror);
+
+                let size = LayoutCanvas::logical_size(out, &cm);
+                if let Some(other) = self.outputs.iter().find(|o| {
+                    group.contains(&o.name)
+                        && o.name != out.name
+                        && o.modes
+                            .iter()
+                            .find(|m| m.current)
+                            .is_some_and(|m| LayoutCanvas::logical_size(o, m) != size)
+                }) {
+                    sidebar = sidebar.push(
+                        text(trf(
+                            "{} has a different logical size, so only part of the desktop is mirrored",
+                            &[&other.name],
+                        ))
+                        .size(13),
+                    );
+                }
+            }
+
+            let mut row_bezels =
+                row![container(text(tr("Bezels (mm)")).size(14)).width(label_width)]
+                    .spacing(5)
+                    .align_y(alignment::Vertical::Center);
+            for (edge, placeholder) in [
+                (Edge::Left, "L"),
+                (Edge::Right, "R"),
+                (Edge::Top, "T"),
+                (Edge::Bottom, "B"),
+            ] {
+                row_bezels = row_bezels.push(
+                    text_input(placeholder, &self.bezel_inputs[edge as usize])
+                        .on_input(move |v| Message::BezelChanged(edge, v))
+                        .width(Length::Fixed(50.0)),
+                );
+            }
+            sidebar = sidebar.push(row_bezels);
+
+            let temperature = self
+                .settings
+                .output_prefs(&out.identity())
+                .color_temperature;
+            let mut row_night = row![
+                container(text(tr("Night Light")).size(14)).width(label_width),
+                checkbox(temperature.is_some()).on_toggle(Message::NightLightToggled),
+            ]
+            .spacing(10)
+            .align_y(alignment::Vertical::Center);
+            if let Some(kelvin) = temperature {
+                row_night = row_night.push(
+                    slider(
+                        gamma::MIN_TEMPERATURE..=gamma::NEUTRAL_TEMPERATURE,
+                        kelvin,
+                        Message::ColorTemperatureChanged,
+                    )
+                    .step(100u32)
+                    .width(Length::Fixed(150.0)),
+                );
+                row_night = row_night.push(text(format!("{}K", kelvin)).size(14));
+            }
+            sidebar = sidebar.push(row_night);
+
+            if let Some((_, percent)) = self.brightness.get(&out.name) {
+                let row_brightness = row![
+                    container(text(tr("Brightness")).size(14)).width(label_width),
+                    slider(0..=100, *percent, Message::BrightnessChanged)
+                        .on_release(Message::BrightnessReleased)
+                        .width(Length::Fixed(150.0)),
+                    text(format!("{}%", percent)).size(14),
+                    described(
+                        checkbox(self.settings.brightness_linked)
+                            .label(tr("Link"))
+                            .on_toggle(Message::BrightnessLinkToggled),
+                        tr("Move the brightness of every output together, keeping how they compare now")
+                    ),
+                ]
+                .spacing(10)
+                .align_y(alignment::Vertical::Center);
+                sidebar = sidebar.push(row_brightness);
+            }
+
+            if !self.connector_kind(&out.name).is_builtin() {
+                let row_battery = row![
+                    container(text(tr("On battery")).size(14)).width(label_width),
+                    described(
+                        pick_list(
+                            BatteryAction::ALL,
+                            Some(self.settings.output_prefs(&out.identity()).on_battery),
+                            Message::BatteryActionSelected
+                        ),
+                        tr(
+                            "What the daemon does with this output while the laptop runs on battery"
+                        )
+                    ),
+                ]
+                .spacing(10)
+                .align_y(alignment::Vertical::Center);
+                sidebar = sidebar.push(row_battery);
+            }
+
+            let row_search = row![
+                container(text(tr("Mode")).size(14)).width(label_width),
+                combo_box(
+                    &self.mode_search,
+                    tr("Type to filter, e.g. 144"),
+                    ModeChoice::from_modes(&out.modes, false)
+                        .iter()
+                        .find(|c| c.current),
+                    |choice: ModeChoice| Message::ResolutionSelected(choice.index),
+                )
+                .width(Length::Fixed(200.0)),
+                checkbox(self.settings.hide_low_res_modes)
+                    .label(tr("Hide low-res"))
+                    .on_toggle(Message::HideLowResToggled),
+            ]
+            .spacing(5)
+            .align_y(alignment::Vertical::Center);
+            sidebar = sidebar.push(row_search);
+
+            let hide_low_res = self.settings.hide_low_res_modes;
+            let mut unique_resolutions: Vec<String> = Vec::new();
+            let resolution_label = |w: i32, h: i32| {
+                let preferred = out
+                    .modes
+                    .iter()
+                    .any(|m| m.preferred && m.width == w && m.height == h);
+                format!("{}x{}{}", w, h, if preferred { PREFERRED_MARK } else { "" })
+            };
+            for m in out
+                .modes
+                .iter()
+                .filter(|m| !hide_low_res || m.current || !is_low_res(m))
+            {
+                let res = resolution_label(m.width, m.height);
+                if !unique_resolutions.contains(&res) {
+                    unique_resolutions.push(res);
+                }
+            }
+            let selected_resolution = Some(resolution_label(cm.width, cm.height));
+            let res_options = unique_resolutions.clone();
+            let pick_res = pick_list(res_options, selected_resolution, |s| {
+                Message::ResolutionSizeSelected(s)
+            })
+            .width(Length::Fixed(200.0));
+
+            let row_res = row![
+                container(text(tr("Resolution")).size(14)).width(label_width),
+                pick_res
+            ]
+            .spacing(5)
+            .align_y(alignment::Vertical::Center);
+            sidebar = sidebar.push(row_res);
+
+            let mut current_rr_idx = 0;
+            let mut rr_labels = Vec::new();
+            let mut rr_mode_indices = Vec::new();
+            for (i, m) in out.modes.iter().enumerate() {
+                if m.width == cm.width && m.height == cm.height {
+                    rr_labels.push(format!(
+                        "{}{}",
+                        decimal(m.refresh_rate, 3),
+                        if m.preferred { PREFERRED_MARK } else { "" }
+                    ));
+                    rr_mode_indices.push(i);
+                    if m.current {
+                        current_rr_idx = rr_labels.len() - 1;
+                    }
+                }
+            }
+            let rr_options = rr_labels.clone();
+            let selected_rr = if current_rr_idx < rr_labels.len() {
+                Some(rr_labels[current_rr_idx].clone())
+            } else {
+                None
+            };
+            let pick_rr = pick_list(rr_options, selected_rr, move |selected: String| {
+                let local_idx = rr_labels.iter().position(|r| *r == selected).unwrap_or(0);
+                let mode_idx = rr_mode_indices[local_idx];
+                Message::ResolutionSelected(mode_idx)
+            })
+            .width(Length::Fixed(100.0));
+
+            let row_rr = row![
+                container(text(tr("Refresh Rate")).size(14)).width(label_width),
+                pick_rr,
+                text(tr("Hz")).size(14),
+                described(
+                    text_input(tr("Custom"), &self.refresh_input)
+                        .on_input(Message::RefreshRateTyped)
+                        .on_submit(Message::CustomRefreshSubmitted)
+                        .width(Length::Fixed(70.0)),
+                    tr("Type any refresh rate, e.g. 23.976, and press Enter to add it as a custom mode")
+                ),
+            ]
+            .spacing(5)
+            .align_y(alignment::Vertical::Center);
+            sidebar = sidebar.push(row_rr);
+
+            if cm.custom {
+                let blanking = self.settings.output_prefs(&out.identity()).blanking;
+                let timings = cvt::timings(
+                    cm.width as u32,
+                    cm.height as u32,
+                    cm.refresh_rate as f64,
+                    blanking,
+                );
+                let clock_mhz = timings.pixel_clock_khz as f64 / 1000.0;
+                let row_blanking = row![
+                    container(text(tr("Blanking")).size(14)).width(label_width),
+                    described(
+                        pick_list(Blanking::ALL, Some(blanking), Message::BlankingSelected),
+                        tr("Reduced blanking lowers the pixel clock so high refresh rates fit the cable")
+                    ),
+                    text(trf("{} MHz pixel clock", &[&decimal(clock_mhz, 2)])).size(14),
+                ]
+                .spacing(10)
+                .align_y(alignment::Vertical::Center);
+                sidebar = sidebar.push(row_blanking);
+
+                let modeline = timings.modeline();
+                sidebar = sidebar.push(
+                    row![
+                        text(tr("Modeline")).size(14),
+                        container(text(modeline.clone()).size(12).font(iced::Font::MONOSPACE))
+                            .padding(6)
+                            .width(Length::Fill)
+                            .style(container::bordered_box),
+                        button(text(tr("Copy")).size(13))
+                            .style(button::secondary)
+                            // The xorg.conf line, so its keyword stays in English.
+                            .on_press(Message::ModelineCopied(format!("Modeline {}", modeline))),
+                    ]
+                    .spacing(5)
+                    .align_y(alignment::Vertical::Center),
+                );
+            }
+            if let Some(warning) = self.link_warning(out) {
+                sidebar = sidebar.push(
+                    text(link_warning_text(warning))
+                        .size(13)
+                        .style(iced::widget::text::warning),
+                );
+            }
+
+            if let Some(preferred) = out.modes.iter().find(|m| m.preferred)
+                && !preferred.current
+            {
+                let row_preferred = row![
+                    text(trf(
+                        "Preferred mode is {}x{} @ {} Hz",
+                        &[
+                            &preferred.width,
+                            &preferred.height,
+                            &decimal(preferred.refresh_rate, 3)
+                        ],
+                    ))
+                    .size(13),
+                    button(text(tr("Reset to preferred")).size(13))
+                        .style(button::secondary)
+                        .on_press(Message::ResetToPreferred),
+                ]
+                .spacing(10)
+                .align_y(alignment::Vertical::Center);
+                sidebar = sidebar.push(row_preferred);
+            }
+
+            if let Some(adaptive_sync) = out.adaptive_sync {
+                let row_vrr = row![
+                    container(text(tr("Adaptive sync")).size(14)).width(label_width),
+                    checkbox(adaptive_sync).on_toggle(Message::AdaptiveSyncToggled),
+                ]
+                .spacing(10)
+                .align_y(alignment::Vertical::Center);
+                sidebar = sidebar.push(row_vrr);
+            }
+
+            if let Some(range) = out.rgb_range {
+                let row_range = row![
+                    container(text(tr("RGB range")).size(14)).width(label_width),
+                    described(
+                        pick_list(RgbRange::ALL, Some(range), Message::RgbRangeSelected)
+                            .width(Length::Fixed(100.0)),
+                        tr("Pick Full if a TV looks washed out, Limited if blacks look crushed")
+                    ),
+                ]
+                .spacing(10)
+                .align_y(alignment::Vertical::Center);
+                sidebar = sidebar.push(row_range);
+            }
+
+            if let Some(format) = out.pixel_format {
+                let row_format = row![
+                    container(text(tr("Pixel format")).size(14)).width(label_width),
+                    described(
+                        pick_list(PixelFormat::ALL, Some(format), Message::PixelFormatSelected)
+                            .width(Length::Fixed(100.0)),
+                        tr("YCbCr 4:2:0 fits 4K at 60 Hz through HDMI 2.0 but blurs colored text")
+                    ),
+                ]
+                .spacing(10)
+                .align_y(alignment::Vertical::Center);
+                sidebar = sidebar.push(row_format);
+            }
+
+            if Compositor::detect().is_some()
+                && ConnectorKind::from_name(&out.name) != ConnectorKind::Virtual
+            {
+                let row_depth = row![
+                    container(text(tr("Bit depth")).size(14)).width(label_width),
+                    described(
+                        pick_list(
+                            BitDepth::ALL,
+                            Some(self.settings.output_prefs(&out.identity()).bit_depth),
+                            Message::BitDepthSelected
+                        )
+                        .width(Length::Fixed(100.0)),
+                        tr("Use 8-bit for TVs that show wrong colors, 10-bit for HDR and wide-gamut panels")
+                    ),
+                ]
+                .spacing(10)
+                .align_y(alignment::Vertical::Center);
+                sidebar = sidebar.push(row_depth);
+            }
+
+            let subpixel = self.settings.output_prefs(&out.identity()).subpixel;
+            if out.subpixel != Subpixel::Unknown || subpixel != Subpixel::Unknown {
+                let supported = Compositor::detect().is_some_and(|c| c.has_subpixel());
+                let mut row_subpixel = row![
+                    container(text(tr("Subpixels")).size(14)).width(label_width),
+                    described(
+                        pick_list(
+                            Subpixel::KNOWN,
+                            (subpixel != Subpixel::Unknown).then_some(subpixel),
+                            Message::SubpixelSelected
+                        )
+                        .placeholder(trf("{} (reported)", &[&out.subpixel]))
+                        .width(Length::Fixed(160.0)),
+                        if supported {
+                            tr("Fonts look fringed when this is wrong; rotated panels usually need a vertical order")
+                        } else {
+                            tr("This compositor has no subpixel setting; the choice is only saved")
                         }
+                    ),
+                ]
+                .spacing(10)
+                .align_y(alignment::Vertical::Center);
+                if subpixel != Subpixel::Unknown {
+                    row_subpixel = row_subpixel.push(
+                        button(text(tr("Reset")).size(13))
+                            .style(button::secondary)
+                            .on_press(Message::SubpixelSelected(Subpixel::Unknown)),
+                    );
+                }
+                sidebar = sidebar.push(row_subpixel);
+            }
+
+            let transforms: Vec<String> = TRANSFORMS.iter().map(|t| t.to_string()).collect();
+            let pick_trans = pick_list(transforms.clone(), Some(out.transform.clone()), |t| {
+                Message::TransformSelected(t)
+            })
+            .width(Length::Fixed(200.0));
+
+            let row_trans = row![
+                container(text(tr("Transform")).size(14)).width(label_width),
+                pick_trans
+            ]
+            .spacing(5)
+            .align_y(alignment::Vertical::Center);
+            sidebar = sidebar.push(row_trans);
+
+            let mut row_quick =
+                row![container(text(tr("Quick actions")).size(14)).width(label_width)]
+                    .spacing(5)
+                    .align_y(alignment::Vertical::Center);
+            match &self.quick_action {
+                Some((action, _)) => {
+                    let label = match action {
+                        QuickAction::Gaming => tr("Revert gaming"),
+                        QuickAction::Present => tr("Stop presenting"),
+                    };
+                    row_quick = row_quick.push(described(
+                        button(text(label).size(13))
+                            .style(button::secondary)
+                            .on_press(Message::QuickActionReverted),
+                        tr("Go back to the layout from before the quick action"),
+                    ));
+                }
+                None => {
+                    row_quick = row_quick.push(described(
+                        button(text(tr("Gaming")).size(13))
+                            .style(button::secondary)
+                            .on_press_maybe(
+                                (!self.read_only())
+                                    .then_some(Message::QuickActionClicked(QuickAction::Gaming))
+                            ),
+                        tr("Switch this output to its highest refresh rate with adaptive sync and apply"),
+                    ));
+                    if self.presentation_source(idx).is_some() {
+                        row_quick = row_quick.push(described(
+                            button(text(tr("Present")).size(13))
+                                .style(button::secondary)
+                                .on_press_maybe(
+                                    (!self.read_only())
+                                        .then_some(Message::QuickActionClicked(QuickAction::Present))
+                                ),
+                            tr("Mirror the built-in panel on this output at the best resolution both share, and apply"),
+                        ));
                     }
                 }
-                let rr_options = rr_labels.clone();
-                let selected_rr = if current_rr_idx < rr_labels.len() {
-                    Some(rr_labels[current_rr_idx].clone())
-                } else {
-                    None
-                };
-                let pick_rr = pick_list(rr_options, selected_rr, move |selected: String| {
-                    let local_idx = rr_labels.iter().position(|r| *r == selected).unwrap_or(0);
-                    let mode_idx = rr_mode_indices[local_idx];
-                    Message::ResolutionSelected(mode_idx)
-                })
-                .width(Length::Fixed(100.0));
+            }
+            sidebar = sidebar.push(row_quick);
 
-                let row_rr = row![
-                    container(text("Refresh Rate").size(14)).width(label_width),
-                    pick_rr,
-                    text("Hz").size(14)
+            if out.enabled {
+                let row_tools = row![
+                    container(text(tr("Tools")).size(14)).width(label_width),
+                    described(
+                        button(text(tr("Test pattern")).size(13))
+                            .style(button::secondary)
+                            .on_press(Message::PatternOpened(PatternKind::TestPattern)),
+                        tr("Color bars, gradients and an alignment grid on this output. Click to go on, Esc to close")
+                    ),
+                    button(text(tr("Calibrate")).size(13))
+                        .style(button::secondary)
+                        .on_press(Message::CalibrationOpened),
                 ]
                 .spacing(5)
                 .align_y(alignment::Vertical::Center);
-                sidebar = sidebar.push(row_rr);
-
-                let transforms = vec![
-                    "normal".to_string(),
-                    "90".to_string(),
-                    "180".to_string(),
-                    "270".to_string(),
-                    "flipped".to_string(),
-                    "flipped-90".to_string(),
-                    "flipped-180".to_string(),
-                    "flipped-270".to_string(),
-                ];
-                let pick_trans = pick_list(transforms.clone(), Some(out.transform.clone()), |t| {
-                    Message::TransformSelected(t)
-                })
-                .width(Length::Fixed(200.0));
+                sidebar = sidebar.push(row_tools);
 
-                let row_trans = row![
-                    container(text("Transform").size(14)).width(label_width),
-                    pick_trans
-                ]
-                .spacing(5)
-                .align_y(alignment::Vertical::Center);
-                sidebar = sidebar.push(row_trans);
+                let kind = ConnectorKind::from_name(&out.name);
+                if !kind.is_builtin() && kind != ConnectorKind::Virtual {
+                    let overscan = self.settings.output_prefs(&out.identity()).overscan;
+                    let supported = Compositor::detect().is_some_and(|c| c.has_overscan());
+                    let mut row_overscan = row![
+                        container(text(tr("Overscan")).size(14)).width(label_width),
+                        described(
+                            button(text(tr("Adjust")).size(13))
+                                .style(button::secondary)
+                                .on_press(Message::OverscanOpened),
+                            if supported {
+                                tr("Line the green frame up with the visible edges: Space picks an edge, Up and Down move it, Esc saves")
+                            } else {
+                                tr("Measure how much a TV crops: Space picks an edge, Up and Down move it, Esc saves. This compositor cannot keep windows clear of the margins")
+                            }
+                        ),
+                    ]
+                    .spacing(5)
+                    .align_y(alignment::Vertical::Center);
+                    if overscan != Overscan::default() {
+                        row_overscan = row_overscan.push(text(overscan.to_string()).size(13));
+                        row_overscan = row_overscan.push(
+                            button(text(tr("Reset")).size(13))
+                                .style(button::secondary)
+                                .on_press(Message::OverscanReset),
+                        );
+                    }
+                    sidebar = sidebar.push(row_overscan);
+                }
+
+                if let Some(calibration) = self.settings.output_prefs(&out.identity()).calibration {
+                    let ddc_value = |value: Option<u16>| {
+                        value.map_or_else(|| "–".to_string(), |v| v.to_string())
+                    };
+                    sidebar = sidebar.push(
+                        text(trf(
+                            "Calibrated: brightness {}, contrast {}, gamma {}",
+                            &[
+                                &ddc_value(calibration.brightness),
+                                &ddc_value(calibration.contrast),
+                                &decimal(calibration.gamma, 2),
+                            ],
+                        ))
+                        .size(13),
+                    );
+                }
+                if self.calibration_intro {
+                    sidebar = sidebar.push(calibration_intro(&out.name));
+                }
+            }
+
+            if ConnectorKind::from_name(&out.name) == ConnectorKind::Virtual
+                && Compositor::detect().is_some()
+            {
+                sidebar = sidebar.push(
+                    row![
+                        container(text(tr("Virtual output")).size(14)).width(label_width),
+                        described(
+                            button(text(tr("Remove")).size(13))
+                                .style(button::danger)
+                                .on_press_maybe(
+                                    (!self.read_only())
+                                        .then(|| Message::VirtualOutputRemoved(out.name.clone()))
+                                ),
+                            tr("Destroy this virtual output"),
+                        ),
+                    ]
+                    .spacing(5)
+                    .align_y(alignment::Vertical::Center),
+                );
             }
         }
 
@@ -445,40 +3803,1243 @@ impl MangoDisplay {
             sidebar = sidebar.push(text(msg).size(13));
         }
 
-        let actions = row![
-            button("Apply").on_press(Message::ApplyClicked),
-            button("Save").on_press(Message::SaveClicked),
-            button("Restore Default").on_press(Message::RestoreDefaultClicked),
+        if let Some(ref preview) = self.command_preview {
+            sidebar = sidebar.push(
+                column![
+                    row![
+                        text(tr("Command the backend would run")).size(14),
+                        Space::new().width(Length::Fill),
+                        button(text(tr("Close")).size(13))
+                            .style(button::secondary)
+                            .on_press(Message::PreviewClosed)
+                    ]
+                    .align_y(alignment::Vertical::Center),
+                    container(text(preview).size(12).font(iced::Font::MONOSPACE))
+                        .padding(8)
+                        .width(Length::Fill)
+                        .style(container::bordered_box)
+                ]
+                .spacing(6),
+            );
+        }
+
+        if let Some(profile) = &self.offline {
+            sidebar = sidebar.push(
+                row![
+                    text(trf("Editing profile {} offline", &[profile]))
+                        .size(13)
+                        .width(Length::Fill),
+                    described(
+                        button(text(tr("Done")).size(13)).on_press(Message::OfflineEditFinished),
+                        tr("Go back to the connected outputs; save the profile first to keep the edits")
+                    ),
+                ]
+                .spacing(10)
+                .align_y(alignment::Vertical::Center),
+            );
+        }
+
+        if let Some(pending) = &self.live_revert {
+            sidebar = sidebar.push(
+                row![
+                    text(trf(
+                        "Reverting the live preview in {} s",
+                        &[&pending.seconds]
+                    ))
+                    .size(13)
+                    .width(Length::Fill),
+                    button(text(tr("Keep")).size(13)).on_press(Message::LiveChangesKept),
+                    button(text(tr("Revert")).size(13))
+                        .style(button::secondary)
+                        .on_press(Message::LiveChangesReverted),
+                ]
+                .spacing(10)
+                .align_y(alignment::Vertical::Center),
+            );
+        }
+
+        let locked = self.read_only();
+        let idle = self.busy.is_none() && !locked && self.offline.is_none();
+        let mut actions = row![
+            described(
+                checkbox(self.settings.ui.live_preview)
+                    .label(tr("Live"))
+                    .on_toggle_maybe((!locked).then_some(Message::LivePreviewToggled)),
+                tr("Apply edits to the screens as you make them; they revert unless kept")
+            ),
+            described(
+                button(tr("Apply")).on_press_maybe(idle.then_some(Message::ApplyClicked)),
+                tr("Apply the layout (Ctrl+Enter)")
+            ),
+            described(
+                button(tr("Save")).on_press_maybe(idle.then_some(Message::SaveClicked)),
+                tr("Save the layout to the config (Ctrl+S)")
+            ),
+            described(
+                button(tr("Save As…"))
+                    .style(button::secondary)
+                    .on_press_maybe((!locked).then_some(Message::SaveAsClicked)),
+                tr("Write the layout to a file of your choice; the name picks the format: .sh for wlr-randr, hypr or sway in it for those, .conf for mangowc")
+            ),
+            described(
+                button(tr("Preview")).on_press(Message::PreviewClicked),
+                tr("Show the command the active backend would run, without applying")
+            ),
+            described(
+                button(tr("Restore Default"))
+                    .on_press_maybe(idle.then_some(Message::RestoreDefaultClicked)),
+                tr("Restore the monitor rules from before the first save")
+            ),
+            described(
+                button(tr("Export report…"))
+                    .style(button::secondary)
+                    .on_press(Message::ReportExported),
+                tr("Save a table of the outputs and a diagram of the layout, e.g. for a support thread")
+            ),
         ]
         .spacing(10);
+        if Compositor::detect().is_some() {
+            actions = actions.push(described(
+                button(tr("Add Virtual Output"))
+                    .on_press_maybe((!locked).then_some(Message::VirtualOutputAdded)),
+                tr("Create a headless output, e.g. to use a tablet as a second screen over VNC"),
+            ));
+        }
+
+        let profile_names: Vec<String> = self
+            .settings
+            .profiles
+            .iter()
+            .map(|p| p.name.clone())
+            .collect();
+        let row_profiles = row![
+            pick_list(
+                profile_names.clone(),
+                None::<String>,
+                Message::ProfileSelected
+            )
+            .placeholder(tr("Load profile"))
+            .width(Length::Fill),
+            described(
+                pick_list(profile_names, None::<String>, Message::OfflineEditStarted)
+                    .placeholder(tr("Edit offline"))
+                    .width(Length::Fill),
+                tr("Edit a profile without its monitors connected, e.g. to prepare the office layout from home")
+            ),
+            text_input(tr("Profile name"), &self.profile_name)
+                .on_input(Message::ProfileNameChanged)
+                .on_submit_maybe((!locked).then_some(Message::ProfileSaved))
+                .width(Length::Fill),
+            described(
+                button(tr("Save Profile"))
+                    .on_press_maybe((!locked).then_some(Message::ProfileSaved)),
+                tr("Store the current layout as a named profile for the tray menu")
+            ),
+            described(
+                button(tr("Export…"))
+                    .style(button::secondary)
+                    .on_press_maybe(
+                        self.settings
+                            .profile(&self.profile_name)
+                            .map(|_| Message::ProfileExported)
+                    ),
+                tr("Save the named profile to a file to share it or attach it to a bug report")
+            ),
+            described(
+                button(tr("Import…"))
+                    .style(button::secondary)
+                    .on_press_maybe((!locked).then_some(Message::ProfileImported)),
+                tr("Add a profile from an exported file")
+            ),
+        ]
+        .spacing(10)
+        .align_y(alignment::Vertical::Center);
 
         sidebar = sidebar.push(Space::new().width(0.0).height(Length::Fill));
+        sidebar = sidebar.push(row_profiles);
         sidebar = sidebar.push(actions);
 
+        let mut canvas_area = stack![
+            canvas,
+            container(
+                row![
+                    described(
+                        button(text(tr("Physical Size")).size(13))
+                            .style(if self.settings.ui.physical_view {
+                                button::primary
+                            } else {
+                                button::secondary
+                            })
+                            .on_press(Message::PhysicalViewToggled),
+                        tr("Draw monitors at their real-world size")
+                    ),
+                    described(
+                        button(text(tr("Grid")).size(13))
+                            .style(if self.settings.ui.show_grid {
+                                button::primary
+                            } else {
+                                button::secondary
+                            })
+                            .on_press(Message::GridToggled),
+                        tr("Draw a faint grid behind the layout at the ruler marks")
+                    ),
+                    button(text(tr("Fit")).size(13))
+                        .style(button::secondary)
+                        .on_press(Message::FitView),
+                    described(
+                        button(text(tr("History")).size(13))
+                            .style(if self.history_open {
+                                button::primary
+                            } else {
+                                button::secondary
+                            })
+                            .on_press(Message::HistoryToggled),
+                        tr("Hotplugs, applies, failures and reverts, newest first")
+                    ),
+                    described(
+                        button(text("⚙").size(13))
+                            .style(if self.settings_open {
+                                button::primary
+                            } else {
+                                button::secondary
+                            })
+                            .on_press_maybe((!locked).then_some(Message::SettingsToggled)),
+                        tr("Settings")
+                    )
+                ]
+                .spacing(10)
+            )
+            .align_right(Length::Fill)
+            .padding(10)
+        ];
+        if let Some(position) = self.context_menu {
+            canvas_area = canvas_area
+                .push(
+                    mouse_area(Space::new().width(Length::Fill).height(Length::Fill))
+                        .on_press(Message::ContextMenuClosed)
+                        .on_right_press(Message::ContextMenuClosed),
+                )
+                .push(
+                    container(self.context_menu_view())
+                        .padding(Padding::ZERO.top(position.y).left(position.x)),
+                );
+        }
+        if self.output_dialog
+            && let Some(dialog) = self.output_dialog_view()
+        {
+            canvas_area = canvas_area
+                .push(
+                    mouse_area(Space::new().width(Length::Fill).height(Length::Fill))
+                        .on_press(Message::OutputDialogClosed),
+                )
+                .push(center(opaque(dialog)));
+        }
+
+        let sidebar_content = if self.settings_open {
+            self.settings_page().width(sidebar_width)
+        } else if self.history_open {
+            self.history_page().width(sidebar_width)
+        } else {
+            sidebar
+        };
+        let sidebar_area = Container::new(Scrollable::new(sidebar_content).height(Length::Fill))
+            .padding(SIDEBAR_PADDING)
+            .style(container::dark);
+
+        if compact {
+            let pane_button = |label: &'static str, pane: Pane| {
+                button(text(tr(label)).align_x(alignment::Horizontal::Center))
+                    .width(Length::Fill)
+                    .style(if self.compact_pane == pane {
+                        button::primary
+                    } else {
+                        button::secondary
+                    })
+                    .on_press(Message::PaneSelected(pane))
+            };
+            let panes = row![
+                pane_button("Layout", Pane::Layout),
+                pane_button("Settings", Pane::Settings)
+            ];
+            let pane: Element<'_, Message> = match self.compact_pane {
+                Pane::Layout => Container::new(canvas_area)
+                    .width(Length::Fill)
+                    .height(Length::Fill)
+                    .style(container::dark)
+                    .into(),
+                Pane::Settings => sidebar_area.width(Length::Fill).into(),
+            };
+            return column![panes, pane, self.status_bar()].into();
+        }
+
         let main_content = row![
-            Container::new(canvas)
+            Container::new(canvas_area)
                 .width(Length::Fill)
                 .height(Length::Fill)
                 .style(container::dark),
-            Container::new(Scrollable::new(sidebar).height(Length::Fill))
-                .padding(20)
-                .style(container::dark)
+            mouse_area(
+                container(Space::new().width(4.0).height(Length::Fill))
+                    .style(container::bordered_box)
+            )
+            .interaction(mouse::Interaction::ResizingHorizontally)
+            .on_press(Message::SidebarResizeStarted),
+            sidebar_area
         ];
 
-        main_content.into()
+        column![main_content, self.status_bar()].into()
+    }
+
+    /// The connector type with whatever link details sysfs offers.
+    fn connector_text(&self, name: &str) -> String {
+        let Some(connector) = self.connectors.get(name) else {
+            return ConnectorKind::from_name(name).label().to_string();
+        };
+        let mut parts = vec![format!(
+            "{} {}",
+            connector.kind.icon(),
+            tr(connector.kind.label())
+        )];
+        if connector.kind.is_builtin() {
+            parts.push(tr("built-in panel").to_string());
+        }
+        if let Some(port) = &connector.usb_c_port {
+            parts.push(trf("DisplayPort Alt Mode on {}", &[port]));
+        }
+        if let Some(card) = &connector.card {
+            parts.push(card.clone());
+        }
+        parts.join(" · ")
+    }
+
+    /// The events the daemon and the window recorded, newest first, shown in place of the
+    /// output editor.
+    fn history_page(&self) -> iced::widget::Column<'_, Message> {
+        let mut page = column![
+            row![
+                text(tr("History")).size(20),
+                Space::new().width(Length::Fill),
+                button(text(tr("Refresh")).size(13))
+                    .style(button::secondary)
+                    .on_press(Message::HistoryRefreshed),
+                button(text(tr("Close")).size(13))
+                    .style(button::secondary)
+                    .on_press(Message::HistoryToggled)
+            ]
+            .spacing(10)
+            .align_y(alignment::Vertical::Center)
+        ]
+        .spacing(10);
+        if self.events.is_empty() {
+            page = page.push(
+                text(tr(
+                    "Nothing recorded yet. The daemon records hotplugs and the rules it applies while it runs.",
+                ))
+                .size(13),
+            );
+        }
+        for event in self.events.iter().rev() {
+            let line = column![
+                text(format!(
+                    "{} · {}",
+                    history::local_time(event.time),
+                    tr(event.kind.label())
+                ))
+                .size(12),
+                text(event.text.clone())
+                    .size(13)
+                    .style(if event.kind == EventKind::Failed {
+                        text::danger
+                    } else {
+                        text::default
+                    })
+            ]
+            .spacing(2);
+            page = page.push(line);
+        }
+        page
+    }
+
+    /// Application settings, shown in place of the output editor. Every change is saved
+    /// immediately.
+    fn settings_page(&self) -> iced::widget::Column<'_, Message> {
+        let label_width = 140.0;
+        let labelled = |label: &'static str, control: Element<'static, Message>| {
+            row![
+                container(text(tr(label)).size(14)).width(label_width),
+                control
+            ]
+            .spacing(10)
+            .align_y(alignment::Vertical::Center)
+        };
+        let heading = |label: &'static str| text(tr(label)).size(16);
+
+        let themes: Vec<String> = [DEFAULT_THEME, HIGH_CONTRAST_THEME]
+            .into_iter()
+            .map(str::to_string)
+            .chain(Theme::ALL.iter().map(|t| t.to_string()))
+            .collect();
+        let log_levels: Vec<String> = LOG_LEVELS.iter().map(|l| l.to_string()).collect();
+        let no_profile = tr("No profile").to_string();
+        let game_profiles: Vec<String> = std::iter::once(no_profile.clone())
+            .chain(self.settings.profiles.iter().map(|p| p.name.clone()))
+            .collect();
+        let game_profile = self
+            .settings
+            .gamemode_profile
+            .clone()
+            .unwrap_or_else(|| no_profile.clone());
+
+        column![
+            row![
+                text(tr("Settings")).size(20),
+                Space::new().width(Length::Fill),
+                button(text(tr("Close")).size(13))
+                    .style(button::secondary)
+                    .on_press(Message::SettingsToggled)
+            ]
+            .align_y(alignment::Vertical::Center),
+            heading("Saving"),
+            labelled(
+                "Format",
+                pick_list(
+                    SaveFormat::ALL,
+                    Some(self.settings.save_format),
+                    Message::SaveFormatSelected
+                )
+                .into()
+            ),
+            labelled(
+                "Monitors file",
+                text_input(
+                    "~/.config/mango/monitors.conf",
+                    &self.settings.monitors_conf_path
+                )
+                .on_input(Message::MonitorsPathChanged)
+                .into()
+            ),
+            labelled(
+                "Main config",
+                text_input(
+                    "~/.config/mango/config.conf",
+                    &self.settings.config_conf_path
+                )
+                .on_input(Message::ConfigPathChanged)
+                .into()
+            ),
+            checkbox(self.settings.auto_append_source)
+                .label(tr(
+                    "Add a source line for the monitors file to the main config"
+                ))
+                .on_toggle(Message::AutoAppendToggled),
+            heading("Backend"),
+            labelled(
+                "Backend",
+                pick_list(
+                    self.backend_choices.clone(),
+                    Some(
+                        self.settings
+                            .backend
+                            .clone()
+                            .unwrap_or_else(|| AUTO_BACKEND.to_string())
+                    ),
+                    Message::BackendSelected
+                )
+                .into()
+            ),
+            self.seat_section(label_width),
+            self.tool_path_section(label_width),
+            heading("Layout editor"),
+            checkbox(self.settings.snapping.enabled)
+                .label(tr("Snap to other outputs and the grid"))
+                .on_toggle(Message::SnappingToggled),
+            labelled(
+                "Snap distance",
+                row![
+                    slider(
+                        0..=200,
+                        self.settings.snapping.threshold,
+                        Message::SnapThresholdChanged
+                    )
+                    .step(5)
+                    .width(Length::Fixed(150.0)),
+                    text(format!("{} px", self.settings.snapping.threshold)).size(14)
+                ]
+                .spacing(10)
+                .into()
+            ),
+            heading("Night Light"),
+            checkbox(self.settings.night_schedule.enabled)
+                .label(tr("Follow sunset and sunrise"))
+                .on_toggle(Message::NightScheduleToggled),
+            labelled(
+                "Latitude",
+                text_input("38.72", &self.location_inputs[0])
+                    .on_input(Message::LatitudeChanged)
+                    .width(Length::Fixed(100.0))
+                    .into()
+            ),
+            labelled(
+                "Longitude",
+                text_input("-9.14", &self.location_inputs[1])
+                    .on_input(Message::LongitudeChanged)
+                    .width(Length::Fixed(100.0))
+                    .into()
+            ),
+            labelled(
+                "Transition",
+                row![
+                    slider(
+                        0..=180,
+                        self.settings.night_schedule.transition_minutes,
+                        Message::TransitionChanged
+                    )
+                    .step(10u32)
+                    .width(Length::Fixed(150.0)),
+                    text(trf(
+                        "{} min",
+                        &[&self.settings.night_schedule.transition_minutes]
+                    ))
+                    .size(14)
+                ]
+                .spacing(10)
+                .into()
+            ),
+            text(tr("Outputs with Night Light on warm up to their temperature after sunset and return to neutral after sunrise. The daemon applies the night light, so run mdisplay daemon.")).size(13),
+            heading("Auto-brightness"),
+            self.brightness_curve_section(),
+            heading("Rotation"),
+            checkbox(self.settings.auto_rotate)
+                .label(tr("Rotate the built-in panel with the device"))
+                .on_toggle(Message::AutoRotateToggled),
+            text(tr("For convertibles and tablets. Needs iio-sensor-proxy and a running mdisplay daemon.")).size(13),
+            heading("Gaming"),
+            checkbox(self.settings.gaming_disables_others)
+                .label(tr("Gaming turns off the other outputs"))
+                .on_toggle(Message::GamingDisablesOthersToggled),
+            labelled(
+                "While a game runs",
+                pick_list(game_profiles, Some(game_profile), move |choice: String| {
+                    Message::GameModeProfileSelected((choice != no_profile).then_some(choice))
+                })
+                .width(Length::Fixed(140.0))
+                .into()
+            ),
+            text(tr("The daemon applies this profile while a game has Feral GameMode on, and puts the layout from before back once the last game exits.")).size(13),
+            heading("On battery"),
+            labelled(
+                "Dim to",
+                row![
+                    slider(
+                        0..=100,
+                        self.settings.battery_brightness,
+                        Message::BatteryBrightnessChanged
+                    )
+                    .width(Length::Fixed(150.0)),
+                    text(format!("{}%", self.settings.battery_brightness)).size(14),
+                ]
+                .spacing(10)
+                .align_y(alignment::Vertical::Center)
+                .into()
+            ),
+            text(tr("External outputs set to Dim or Disable under On battery change when the laptop unplugs and come back on AC. The daemon watches the power source through UPower, so run mdisplay daemon.")).size(13),
+            heading("Input devices"),
+            self.input_mapping_section(),
+            heading("Docks"),
+            self.dock_section(),
+            heading("Wallpapers"),
+            self.wallpaper_section(label_width),
+            heading("Profile rules"),
+            self.profile_rule_section(label_width),
+            text(tr("Automatic profiles are applied by mdisplay daemon when their conditions start to hold; the highest priority wins.")).size(13),
+            heading("Appearance"),
+            labelled(
+                "Theme",
+                pick_list(
+                    themes,
+                    Some(
+                        self.settings
+                            .theme
+                            .clone()
+                            .unwrap_or_else(|| DEFAULT_THEME.to_string())
+                    ),
+                    Message::ThemeSelected
+                )
+                .into()
+            ),
+            labelled(
+                "Interface size",
+                pick_list(
+                    UI_SCALES.map(scale_preset_label).to_vec(),
+                    Some(scale_preset_label(self.settings.ui_scale)),
+                    Message::UiScaleSelected
+                )
+                .into()
+            ),
+            labelled(
+                "Language",
+                pick_list(
+                    Language::ALL,
+                    Some(crate::i18n::language()),
+                    Message::LanguageSelected
+                )
+                .into()
+            ),
+            heading("Daemon and hooks"),
+            checkbox(self.settings.notifications)
+                .label(tr("Desktop notifications"))
+                .on_toggle(Message::NotificationsToggled),
+            labelled(
+                "Pre-apply hook",
+                text_input(
+                    tr("Shell command"),
+                    self.settings.hooks.pre_apply.as_deref().unwrap_or_default()
+                )
+                .on_input(Message::PreApplyHookChanged)
+                .into()
+            ),
+            labelled(
+                "Post-apply hook",
+                text_input(
+                    tr("Shell command"),
+                    self.settings
+                        .hooks
+                        .post_apply
+                        .as_deref()
+                        .unwrap_or_default()
+                )
+                .on_input(Message::PostApplyHookChanged)
+                .into()
+            ),
+            labelled(
+                "Log level",
+                pick_list(
+                    log_levels,
+                    Some(self.settings.log_level.clone()),
+                    Message::LogLevelSelected
+                )
+                .into()
+            ),
+            checkbox(self.settings.log_to_file)
+                .label(tr("Write the log to a file"))
+                .on_toggle(Message::LogToFileToggled),
+            text(tr("Logging changes take effect on the next start.")).size(13),
+            heading("Files"),
+            labelled(
+                "Settings and profiles",
+                text(AppSettings::path().display().to_string()).size(13).into()
+            ),
+            labelled(
+                "Original monitor rules",
+                text(paths::expand(&self.settings.monitors_bak_path).display().to_string())
+                    .size(13)
+                    .into()
+            ),
+            labelled(
+                "Log",
+                text(paths::log_file().display().to_string()).size(13).into()
+            ),
+        ]
+        .spacing(15)
+    }
+
+    /// One row per touchscreen or tablet, choosing the output its input should follow.
+    /// One slider per point of the auto-brightness response curve.
+    fn brightness_curve_section(&self) -> iced::widget::Column<'_, Message> {
+        let auto = &self.settings.auto_brightness;
+        let mut section = column![
+            checkbox(auto.enabled)
+                .label(tr("Follow the ambient light sensor"))
+                .on_toggle(Message::AutoBrightnessToggled),
+            checkbox(auto.external)
+                .label(tr("Adjust external monitors too"))
+                .on_toggle(Message::AutoBrightnessExternalToggled),
+        ]
+        .spacing(10);
+        for (i, point) in auto.curve.iter().enumerate() {
+            section = section.push(
+                row![
+                    container(text(trf("At {} lux", &[&point.lux])).size(14)).width(140.0),
+                    slider(0..=100, point.percent, move |p| {
+                        Message::CurvePointChanged(i, p)
+                    })
+                    .width(Length::Fixed(150.0)),
+                    text(format!("{}%", point.percent)).size(14),
+                ]
+                .spacing(10)
+                .align_y(alignment::Vertical::Center),
+            );
+        }
+        section.push(
+            text(tr(
+                "Needs iio-sensor-proxy and a running mdisplay daemon, which sets the brightness as the light changes.",
+            ))
+            .size(13),
+        )
+    }
+
+    fn input_mapping_section(&self) -> iced::widget::Column<'_, Message> {
+        let mut section = column![].spacing(10);
+        if self.input_devices.is_empty() {
+            return section.push(text(tr("No touchscreens or tablets found")).size(13));
+        }
+        let not_mapped = tr("Not mapped").to_string();
+        let choices: Vec<String> = std::iter::once(not_mapped.clone())
+            .chain(self.outputs.iter().map(|o| o.name.clone()))
+            .collect();
+        for device in &self.input_devices {
+            let kind = match device.kind {
+                InputKind::Touchscreen => tr("Touchscreen"),
+                InputKind::Tablet => tr("Tablet"),
+            };
+            let selected = self
+                .settings
+                .input_mappings
+                .get(&device.name)
+                .cloned()
+                .unwrap_or_else(|| not_mapped.clone());
+            let name = device.name.clone();
+            let none = not_mapped.clone();
+            section = section.push(
+                row![
+                    column![text(&device.name).size(14), text(kind).size(12)].width(Length::Fill),
+                    pick_list(choices.clone(), Some(selected), move |choice: String| {
+                        Message::InputMapped(name.clone(), (choice != none).then_some(choice))
+                    })
+                    .width(Length::Fixed(140.0))
+                ]
+                .spacing(10)
+                .align_y(alignment::Vertical::Center),
+            );
+        }
+        if Compositor::detect().is_none() {
+            section = section.push(
+                text(tr(
+                    "This compositor has no command for input mapping; mappings are only saved",
+                ))
+                .size(13),
+            );
+        }
+        section
+    }
+
+    /// One path per tool the default backend can fall back to; empty means the tool's
+    /// name on `PATH`.
+    /// A seat picker for administrators, shown on multi-seat machines only.
+    fn seat_section(&self, label_width: f32) -> iced::widget::Column<'_, Message> {
+        let section = column![].spacing(10);
+        if self.seats.len() < 2 && self.settings.seat.is_none() {
+            return section;
+        }
+        let choices: Vec<String> = std::iter::once(AUTO_SEAT.to_string())
+            .chain(self.seats.iter().cloned())
+            .collect();
+        section.push(
+            row![
+                container(text(tr("Seat")).size(14)).width(label_width),
+                described(
+                    pick_list(
+                        choices,
+                        Some(
+                            self.settings
+                                .seat
+                                .clone()
+                                .unwrap_or_else(|| AUTO_SEAT.to_string())
+                        ),
+                        Message::SeatSelected
+                    ),
+                    tr("Only the outputs of this seat are shown and changed; auto is the seat of this session")
+                )
+            ]
+            .spacing(10)
+            .align_y(alignment::Vertical::Center),
+        )
+    }
+
+    fn tool_path_section(&self, label_width: f32) -> iced::widget::Column<'_, Message> {
+        let mut section = column![].spacing(10);
+        for tool in Tool::ALL {
+            let found = match tool.program(&self.settings) {
+                Some(path) => path.display().to_string(),
+                None => tr("Not found").to_string(),
+            };
+            let path = self
+                .settings
+                .tool_paths
+                .get(tool.name())
+                .map(String::as_str)
+                .unwrap_or_default();
+            section = section.push(
+                row![
+                    container(text(tool.name()).size(14)).width(label_width),
+                    text_input(&found, path)
+                        .on_input(move |path| Message::ToolPathChanged(tool, path))
+                ]
+                .spacing(10)
+                .align_y(alignment::Vertical::Center),
+            );
+        }
+        section
+    }
+
+    /// A wallpaper command per profile, run when the profile is applied.
+    fn wallpaper_section(&self, label_width: f32) -> iced::widget::Column<'_, Message> {
+        let mut section = column![].spacing(10);
+        if self.settings.profiles.is_empty() {
+            return section.push(text(tr("Save a profile to give it a wallpaper")).size(13));
+        }
+        for profile in &self.settings.profiles {
+            let name = profile.name.clone();
+            section = section.push(
+                row![
+                    container(text(&profile.name).size(14)).width(label_width),
+                    text_input(
+                        "swww img ~/Pictures/wallpaper.png",
+                        profile.wallpaper.as_deref().unwrap_or_default()
+                    )
+                    .on_input(move |command| Message::WallpaperChanged(name.clone(), command))
+                ]
+                .spacing(10)
+                .align_y(alignment::Vertical::Center),
+            );
+        }
+        section
+    }
+
+    /// Each profile's rule: whether it applies automatically, and when.
+    fn profile_rule_section(&self, label_width: f32) -> iced::widget::Column<'_, Message> {
+        let mut section = column![].spacing(10);
+        if self.settings.profiles.is_empty() {
+            return section.push(text(tr("Save a profile to apply it automatically")).size(13));
+        }
+        for profile in &self.settings.profiles {
+            let name = profile.name.clone();
+            section = section.push(
+                row![
+                    container(text(&profile.name).size(14)).width(label_width),
+                    checkbox(profile.rule.is_some())
+                        .label(tr("Automatic"))
+                        .on_toggle(move |on| {
+                            Message::ProfileRuleChanged(name.clone(), on.then(ProfileRule::default))
+                        })
+                ]
+                .spacing(10)
+                .align_y(alignment::Vertical::Center),
+            );
+            let Some(rule) = &profile.rule else {
+                continue;
+            };
+            // Every control sends the whole rule with its one change.
+            fn edited<T: 'static>(
+                name: &str,
+                rule: &ProfileRule,
+                edit: fn(&mut ProfileRule, T),
+            ) -> impl Fn(T) -> Message + 'static {
+                let (name, rule) = (name.to_string(), rule.clone());
+                move |value| {
+                    let mut rule = rule.clone();
+                    edit(&mut rule, value);
+                    Message::ProfileRuleChanged(name.clone(), Some(rule))
+                }
+            }
+            section = section.push(
+                row![
+                    Space::new().width(label_width),
+                    pick_list(
+                        PowerCondition::ALL,
+                        Some(rule.power),
+                        edited(&profile.name, rule, |rule, power| rule.power = power)
+                    ),
+                    pick_list(
+                        LidCondition::ALL,
+                        Some(rule.lid),
+                        edited(&profile.name, rule, |rule, lid| rule.lid = lid)
+                    ),
+                    described(
+                        text_input(tr("Priority"), &rule.priority.to_string())
+                            .on_input(edited(&profile.name, rule, |rule, value: String| {
+                                rule.priority = value.parse().unwrap_or_default()
+                            }))
+                            .width(Length::Fixed(60.0)),
+                        tr("Priority"),
+                    ),
+                ]
+                .spacing(10)
+                .align_y(alignment::Vertical::Center),
+            );
+            // Required outputs are offered from those connected now, plus any required
+            // output that is not.
+            let mut names: Vec<String> = self.outputs.iter().map(|o| o.name.clone()).collect();
+            for output in &rule.outputs {
+                if !names.contains(output) {
+                    names.push(output.clone());
+                }
+            }
+            let mut required = row![
+                Space::new().width(label_width),
+                text(tr("Requires")).size(13)
+            ]
+            .spacing(10)
+            .align_y(alignment::Vertical::Center);
+            for output in names {
+                let wanted = rule.outputs.contains(&output);
+                let name = profile.name.clone();
+                let rule = rule.clone();
+                let label = self
+                    .outputs
+                    .iter()
+                    .find(|o| o.name == output)
+                    .map_or_else(|| output.clone(), |o| self.settings.display_name(o));
+                required = required.push(checkbox(wanted).label(label).on_toggle(move |on| {
+                    let mut rule = rule.clone();
+                    rule.outputs.retain(|o| o != &output);
+                    if on {
+                        rule.outputs.push(output.clone());
+                    }
+                    Message::ProfileRuleChanged(name.clone(), Some(rule))
+                }));
+            }
+            section = section.push(required);
+        }
+        section
+    }
+
+    fn dock_section(&self) -> iced::widget::Column<'_, Message> {
+        let mut section = column![].spacing(10);
+        // Bound docks stay listed while unplugged, so their binding can be removed.
+        let mut docks: Vec<(String, String)> = self
+            .docks
+            .iter()
+            .map(|d| (d.id.clone(), d.name.clone()))
+            .collect();
+        let mut unplugged: Vec<(String, String)> = self
+            .settings
+            .dock_profiles
+            .keys()
+            .filter(|id| !self.docks.iter().any(|d| &&d.id == id))
+            .map(|id| (id.clone(), tr("Not connected").to_string()))
+            .collect();
+        unplugged.sort();
+        docks.extend(unplugged);
+        if docks.is_empty() {
+            return section.push(text(tr("No docks found")).size(13));
+        }
+        let no_profile = tr("No profile").to_string();
+        let choices: Vec<String> = std::iter::once(no_profile.clone())
+            .chain(self.settings.profiles.iter().map(|p| p.name.clone()))
+            .collect();
+        for (id, name) in docks {
+            let selected = self
+                .settings
+                .dock_profiles
+                .get(&id)
+                .cloned()
+                .unwrap_or_else(|| no_profile.clone());
+            let dock = id.clone();
+            let none = no_profile.clone();
+            section = section.push(
+                row![
+                    column![text(name).size(14), text(id).size(12)].width(Length::Fill),
+                    pick_list(choices.clone(), Some(selected), move |choice: String| {
+                        Message::DockProfileSelected(
+                            dock.clone(),
+                            (choice != none).then_some(choice),
+                        )
+                    })
+                    .width(Length::Fixed(140.0))
+                ]
+                .spacing(10)
+                .align_y(alignment::Vertical::Center),
+            );
+        }
+        section.push(
+            text(tr(
+                "The daemon applies a dock's profile when it is plugged in, once the outputs on it are connected.",
+            ))
+            .size(13),
+        )
+    }
+
+    fn status_bar(&self) -> Element<'_, Message> {
+        let enabled = self.outputs.iter().filter(|o| o.enabled).count();
+        let desktop = layout_bounds(self.outputs.iter().filter(|o| o.enabled));
+        let pending = self
+            .outputs
+            .iter()
+            .filter(|out| !self.applied_outputs.contains(out))
+            .count();
+
+        let desktop_text = if enabled == 0 {
+            tr("No enabled outputs").to_string()
+        } else {
+            trf(
+                "Desktop {}x{}",
+                &[&(desktop.width as i32), &(desktop.height as i32)],
+            )
+        };
+        let pending_text = if pending == 0 {
+            tr("No unapplied changes").to_string()
+        } else {
+            trf("{} output(s) with unapplied changes", &[&pending])
+        };
+
+        let mut info = row![
+            text(desktop_text).size(13),
+            text(trf("{} of {} enabled", &[&enabled, &self.outputs.len()])).size(13),
+            Space::new().width(Length::Fill),
+            row![
+                Canvas::new(Spinner {
+                    frame: self.spinner_frame,
+                    spinning: self.busy.is_some(),
+                })
+                .width(Length::Fixed(14.0))
+                .height(Length::Fixed(14.0)),
+                text(self.busy.unwrap_or_default()).size(13),
+            ]
+            .spacing(6)
+            .align_y(alignment::Vertical::Center),
+            text(pending_text).size(13),
+        ]
+        .spacing(20)
+        .align_y(alignment::Vertical::Center);
+        if self.read_only() {
+            info = info.push(text(tr("Read-only")).size(13));
+        }
+        if let Some(daemon) = &self.daemon {
+            let mut status = match &daemon.profile {
+                Some(profile) => trf("Daemon: profile {}", &[profile]),
+                None => tr("Daemon: custom layout").to_string(),
+            };
+            if daemon.held {
+                status = trf("{}, waiting for your changes", &[&status]);
+            }
+            info = info.push(text(status).size(13));
+        }
+
+        container(info)
+            .padding([4, 12])
+            .width(Length::Fill)
+            .style(container::dark)
+            .into()
+    }
+}
+
+/// Sidebar rows for the EDID capabilities that matter when enabling adaptive sync or
+/// adding a custom mode.
+fn edid_rows(edid: &EdidInfo) -> Vec<(&'static str, String)> {
+    let mut rows = Vec::new();
+    if let Some((min, max)) = edid.refresh_range {
+        let range = format!("{}–{} Hz", min, max);
+        rows.push((
+            "Refresh range",
+            if edid.continuous_frequency && max > min {
+                trf("{} (adaptive sync capable)", &[&range])
+            } else {
+                range
+            },
+        ));
+    }
+    if let Some(hdr) = &edid.hdr {
+        let mut formats = Vec::new();
+        if hdr.pq {
+            formats.push("HDR10");
+        }
+        if hdr.hlg {
+            formats.push("HLG");
+        }
+        let mut value = if formats.is_empty() {
+            tr("Metadata only").to_string()
+        } else {
+            formats.join(", ")
+        };
+        if let Some(nits) = hdr.max_luminance {
+            value.push_str(&trf(", up to {} nits", &[&format!("{:.0}", nits)]));
+        }
+        rows.push(("HDR", value));
+    }
+    if let Some(mhz) = edid.max_pixel_clock_mhz {
+        rows.push(("Max pixel clock", format!("{} MHz", mhz)));
+    }
+    rows
+}
+
+/// Wraps a control with a tooltip naming its action and keyboard equivalent.
+fn described<'a>(
+    content: impl Into<Element<'a, Message>>,
+    description: &'static str,
+) -> Element<'a, Message> {
+    tooltip(
+        content,
+        container(text(description).size(12))
+            .padding(6)
+            .style(container::rounded_box),
+        tooltip::Position::Bottom,
+    )
+    .into()
+}
+
+/// Mean logical pixels per millimetre across the enabled outputs that report a panel size,
+/// so the physical view keeps roughly the same on-canvas scale as the logical one.
+fn average_px_per_mm(outputs: &[Output]) -> Option<f32> {
+    let densities: Vec<f32> = outputs
+        .iter()
+        .filter(|out| out.enabled)
+        .filter_map(|out| {
+            let (w_mm, _) = out.physical_size_mm()?;
+            let mode = out.modes.iter().find(|m| m.current)?;
+            Some(mode.width as f32 / out.scale / w_mm as f32)
+        })
+        .collect();
+    (!densities.is_empty()).then(|| densities.iter().sum::<f32>() / densities.len() as f32)
+}
+
+/// Logical bounding box of all outputs, used to frame the canvas.
+fn layout_bounds<'a>(outputs: impl IntoIterator<Item = &'a Output>) -> Rectangle {
+    let mut min = (i32::MAX, i32::MAX);
+    let mut max = (i32::MIN, i32::MIN);
+    let mut count = 0;
+    for out in outputs {
+        count += 1;
+        let cm = out
+            .modes
+            .iter()
+            .find(|m| m.current)
+            .cloned()
+            .unwrap_or(OutputMode {
+                width: 800,
+                height: 600,
+                refresh_rate: 60.0,
+                current: true,
+                preferred: false,
+                custom: false,
+            });
+        let (w, h) = LayoutCanvas::logical_size(out, &cm);
+        min = (min.0.min(out.position.0), min.1.min(out.position.1));
+        max = (max.0.max(out.position.0 + w), max.1.max(out.position.1 + h));
     }
+    if count == 0 {
+        return Rectangle::new(Point::ORIGIN, Size::new(1920.0, 1080.0));
+    }
+    Rectangle::new(
+        Point::new(min.0 as f32, min.1 as f32),
+        Size::new((max.0 - min.0) as f32, (max.1 - min.1) as f32),
+    )
 }
 
 #[derive(Default)]
 pub struct CanvasState {
-    dragging: Option<(usize, Point, (i32, i32))>,
-    hovered: Option<usize>,
+    /// Outputs are named rather than indexed here, since the state outlives a reload of
+    /// the output list.
+    dragging: Option<(String, Point, (i32, i32))>,
+    hovered: Option<String>,
+    undocking: Option<(String, Point)>,
+    /// Held while dragging to change how finely the output is placed.
+    modifiers: keyboard::Modifiers,
+    /// The output last clicked and when, to tell a double-click.
+    last_click: Option<(String, Instant)>,
+}
+
+/// Longest gap between the two clicks of a double-click.
+const DOUBLE_CLICK: Duration = Duration::from_millis(400);
+
+/// The layout canvas is drawn in two layers. The scene, with every output and its text,
+/// only changes with the layout; the overlay redraws just the hovered and selected
+/// outputs on top, so moving the mouse does not lay out the whole scene again.
+#[derive(Default)]
+pub struct CanvasCaches {
+    scene: Cache,
+    overlay: Cache,
+}
+
+impl CanvasCaches {
+    fn clear(&self) {
+        self.scene.clear();
+        self.overlay.clear();
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Highlight {
+    None,
+    Hovered,
+    Selected,
 }
 
+const MIN_ZOOM: f32 = 0.25;
+const MAX_ZOOM: f32 = 8.0;
+
+/// Height of the strip along the bottom of the canvas that holds disabled outputs.
+const DOCK_HEIGHT: f32 = 90.0;
+
+/// Height of the ruler along the top of the canvas and width of the one down its left
+/// side, which is wider to fit the labels.
+const RULER_TOP: f32 = 18.0;
+const RULER_LEFT: f32 = 42.0;
+
+/// Distances in logical pixels between ruler marks; the smallest that leaves room for
+/// its label at the canvas zoom is used.
+const RULER_STEPS: [i32; 9] = [50, 100, 200, 500, 1000, 2000, 5000, 10000, 20000];
+
+/// What an output without a current mode is drawn as.
+const FALLBACK_MODE: OutputMode = OutputMode {
+    width: 800,
+    height: 600,
+    refresh_rate: 60.0,
+    current: true,
+    preferred: false,
+    custom: false,
+};
+
+/// Built on every `view`, so it borrows the outputs rather than cloning their mode lists.
 struct LayoutCanvas<'a> {
-    outputs: Vec<Output>,
-    selected_idx: Option<usize>,
-    cache: &'a Cache,
+    outputs: &'a [Output],
+    /// Where each output sits in logical desktop coordinates at the size it is drawn (see
+    /// [`Self::drawn_size`]), worked out once per layout change rather than on every
+    /// mouse move.
+    rects: Vec<Rectangle>,
+    selected: Option<&'a str>,
+    frame: Rectangle,
+    zoom: f32,
+    bezels: Vec<Bezels>,
+    tints: Vec<Option<Color>>,
+    /// Shown in place of the connector names of the outputs that have one.
+    nicknames: Vec<Option<String>>,
+    grid: bool,
+    /// Thicker outlines and stronger colours, for the high-contrast theme.
+    high_contrast: bool,
+    /// Multiplies the size of the text on the canvas.
+    text_scale: f32,
+    snapping: Snapping,
+    cache: &'a CanvasCaches,
+}
+
+/// Translucent overlay previewing an output's night-light temperature; warmer settings
+/// get a stronger tint so the difference between outputs is visible at a glance.
+fn night_light_tint(kelvin: u32) -> Color {
+    let (r, g, b) = gamma::whitepoint(kelvin);
+    let warmth = gamma::NEUTRAL_TEMPERATURE.saturating_sub(kelvin) as f32
+        / (gamma::NEUTRAL_TEMPERATURE - gamma::MIN_TEMPERATURE) as f32;
+    Color::from_rgba(r, g, b, 0.15 + 0.45 * warmth)
+}
+
+/// Converts bezel widths from millimetres into the output's logical pixels, so the
+/// snapping gap matches what the panel would show if the bezel were part of the screen.
+/// Edges are as the monitor stands on the desk, i.e. after any rotation.
+fn bezel_logical_px(out: &Output, bezels: &Bezels) -> Bezels {
+    let Some(ppi) = out
+        .modes
+        .iter()
+        .find(|m| m.current)
+        .and_then(|m| out.ppi(m))
+    else {
+        return Bezels::default();
+    };
+    let px_per_mm = ppi / 25.4 / out.scale;
+    Bezels {
+        left: (bezels.left * px_per_mm).round(),
+        right: (bezels.right * px_per_mm).round(),
+        top: (bezels.top * px_per_mm).round(),
+        bottom: (bezels.bottom * px_per_mm).round(),
+    }
 }
 
 impl<'a> LayoutCanvas<'a> {
@@ -491,38 +5052,224 @@ impl<'a> LayoutCanvas<'a> {
         }
     }
 
-    fn calculate_layout(&self, bounds: Rectangle) -> (f32, f32, f32, i32, i32) {
-        let mut total_w = 0;
-        let mut max_h = 1080;
+    /// [`Self::logical_size`] at the output's current mode.
+    fn current_logical_size(out: &Output) -> (i32, i32) {
+        let cm = out
+            .modes
+            .iter()
+            .find(|m| m.current)
+            .unwrap_or(&FALLBACK_MODE);
+        Self::logical_size(out, cm)
+    }
 
-        for out in &self.outputs {
-            let cm = out
-                .modes
-                .iter()
-                .find(|m| m.current)
-                .cloned()
-                .unwrap_or(OutputMode {
-                    width: 800,
-                    height: 600,
-                    refresh_rate: 60.0,
-                    current: true,
-                    preferred: false,
-                });
-            let (w_logical, h_logical) = Self::logical_size(out, &cm);
-            total_w += w_logical;
-            if h_logical > max_h {
-                max_h = h_logical;
+    /// Size an output is drawn at: logical pixels, or its panel size in millimetres at a
+    /// common density when the physical view is on.
+    fn drawn_size(px_per_mm: Option<f32>, out: &Output, cm: &OutputMode) -> (i32, i32) {
+        let (Some(px_per_mm), Some((w_mm, h_mm))) = (px_per_mm, out.physical_size_mm()) else {
+            return Self::logical_size(out, cm);
+        };
+        let w = (w_mm as f32 * px_per_mm) as i32;
+        let h = (h_mm as f32 * px_per_mm) as i32;
+        match out.transform.as_str() {
+            "90" | "270" | "flipped-90" | "flipped-270" => (h, w),
+            _ => (w, h),
+        }
+    }
+
+    /// The [`Self::rects`] of `outputs`; `px_per_mm` is logical pixels per millimetre for
+    /// the physical view, `None` drawing logical sizes.
+    fn drawn_rects(outputs: &[Output], px_per_mm: Option<f32>) -> Vec<Rectangle> {
+        outputs
+            .iter()
+            .map(|out| {
+                let cm = out
+                    .modes
+                    .iter()
+                    .find(|m| m.current)
+                    .unwrap_or(&FALLBACK_MODE);
+                let (w, h) = Self::drawn_size(px_per_mm, out, cm);
+                Rectangle::new(
+                    Point::new(out.position.0 as f32, out.position.1 as f32),
+                    Size::new(w as f32, h as f32),
+                )
+            })
+            .collect()
+    }
+
+    /// Where output `idx` lands when dropped at (`new_x`, `new_y`): flush against a nearby
+    /// edge of another enabled output (leaving room for both bezels), or on the grid.
+    /// Shift rounds to [`NUDGE_STEP`] instead of the grid, Ctrl places to the pixel and
+    /// Alt leaves the output exactly where it was dropped, edges or not.
+    fn snap_position(
+        &self,
+        idx: usize,
+        new_x: i32,
+        new_y: i32,
+        modifiers: keyboard::Modifiers,
+    ) -> Option<(i32, i32)> {
+        if modifiers.alt() {
+            return Some((new_x.max(0), new_y.max(0)));
+        }
+        let grid = if modifiers.control() {
+            FINE_NUDGE_STEP
+        } else if modifiers.shift() {
+            NUDGE_STEP
+        } else if self.snapping.enabled {
+            self.snapping.grid.max(1)
+        } else {
+            FINE_NUDGE_STEP
+        } as f32;
+        if !self.snapping.enabled {
+            let round = |v: i32| ((v as f32 / grid).round() * grid) as i32;
+            return Some((round(new_x).max(0), round(new_y).max(0)));
+        }
+        let snap_threshold = self.snapping.threshold;
+        // Positions are logical, so snap with logical sizes even when the physical view
+        // draws the outputs at another size.
+        let (w, h) = Self::current_logical_size(self.outputs.get(idx)?);
+
+        let mut snapped_x = new_x;
+        let mut snapped_y = new_y;
+        let mut min_dist_x = snap_threshold;
+        let mut min_dist_y = snap_threshold;
+
+        let my_bezels = self.bezels.get(idx).copied().unwrap_or_default();
+        let my_left = new_x;
+        let my_right = new_x + w;
+        let my_top = new_y;
+        let my_bottom = new_y + h;
+
+        for (i, other) in self.outputs.iter().enumerate() {
+            if i == idx || !other.enabled {
+                continue;
+            }
+            let (other_w, other_h) = Self::current_logical_size(other);
+            let other_bezels = self.bezels.get(i).copied().unwrap_or_default();
+            let gap_left = (my_bezels.left + other_bezels.right) as i32;
+            let gap_right = (my_bezels.right + other_bezels.left) as i32;
+            let gap_top = (my_bezels.top + other_bezels.bottom) as i32;
+            let gap_bottom = (my_bezels.bottom + other_bezels.top) as i32;
+
+            let other_left = other.position.0;
+            let other_right = other.position.0 + other_w;
+            let other_top = other.position.1;
+            let other_bottom = other.position.1 + other_h;
+
+            let x_overlap =
+                my_left < other_right + snap_threshold && my_right > other_left - snap_threshold;
+            let y_overlap =
+                my_top < other_bottom + snap_threshold && my_bottom > other_top - snap_threshold;
+
+            if y_overlap {
+                if (my_left - other_right - gap_left).abs() < min_dist_x {
+                    min_dist_x = (my_left - other_right - gap_left).abs();
+                    snapped_x = other_right + gap_left;
+                }
+                if (my_right - other_left + gap_right).abs() < min_dist_x {
+                    min_dist_x = (my_right - other_left + gap_right).abs();
+                    snapped_x = other_left - gap_right - w;
+                }
+                if (my_left - other_left).abs() < min_dist_x {
+                    min_dist_x = (my_left - other_left).abs();
+                    snapped_x = other_left;
+                }
+            }
+
+            if x_overlap {
+                if (my_top - other_bottom - gap_top).abs() < min_dist_y {
+                    min_dist_y = (my_top - other_bottom - gap_top).abs();
+                    snapped_y = other_bottom + gap_top;
+                }
+                if (my_bottom - other_top + gap_bottom).abs() < min_dist_y {
+                    min_dist_y = (my_bottom - other_top + gap_bottom).abs();
+                    snapped_y = other_top - gap_bottom - h;
+                }
+                if (my_top - other_top).abs() < min_dist_y {
+                    min_dist_y = (my_top - other_top).abs();
+                    snapped_y = other_top;
+                }
+            }
+        }
+
+        let (xs, ys) = self.axis_lines(idx);
+        for x in xs {
+            if (my_left - x).abs() < min_dist_x {
+                min_dist_x = (my_left - x).abs();
+                snapped_x = x;
+            }
+        }
+        for y in ys {
+            if (my_top - y).abs() < min_dist_y {
+                min_dist_y = (my_top - y).abs();
+                snapped_y = y;
+            }
+        }
+
+        if snapped_x == new_x {
+            snapped_x = ((snapped_x as f32 / grid).round() * grid) as i32;
+        }
+        if snapped_y == new_y {
+            snapped_y = ((snapped_y as f32 / grid).round() * grid) as i32;
+        }
+
+        if snapped_x < 0 {
+            snapped_x = 0;
+        }
+        if snapped_y < 0 {
+            snapped_y = 0;
+        }
+
+        Some((snapped_x, snapped_y))
+    }
+
+    /// The vertical and horizontal lines output `idx` snaps to besides the edges of other
+    /// outputs: the origin, and the left and top of the other enabled outputs together.
+    fn axis_lines(&self, idx: usize) -> ([i32; 2], [i32; 2]) {
+        let others = self
+            .outputs
+            .iter()
+            .enumerate()
+            .filter(|(i, out)| *i != idx && out.enabled)
+            .map(|(_, out)| out.position);
+        let left = others.clone().map(|(x, _)| x).min().unwrap_or(0);
+        let top = others.map(|(_, y)| y).min().unwrap_or(0);
+        ([0, left], [0, top])
+    }
+
+    fn nickname(&self, idx: usize) -> Option<&str> {
+        self.nicknames
+            .get(idx)?
+            .as_deref()
+            .map(str::trim)
+            .filter(|n| !n.is_empty())
+    }
+
+    fn has_dock(&self) -> bool {
+        self.outputs.iter().any(|o| !o.enabled)
+    }
+
+    /// The part of the canvas used for the layout, leaving room for the dock.
+    fn layout_area(&self, bounds: Rectangle) -> Rectangle {
+        if self.has_dock() {
+            Rectangle {
+                height: (bounds.height - DOCK_HEIGHT).max(0.0),
+                ..bounds
             }
+        } else {
+            bounds
         }
+    }
 
-        let span_x = (total_w as f32 * 1.5).max(4000.0);
-        let span_y = (max_h as f32 * 2.5).max(3000.0);
-        let scale = (bounds.width / span_x).min(bounds.height / span_y);
-
-        let first_w = self
-            .outputs
-            .first()
-            .map(|out| {
+    /// Thumbnails of the disabled outputs, laid out left to right inside the dock.
+    fn dock_items(&self, bounds: Rectangle) -> Vec<(usize, Rectangle)> {
+        let item_h = DOCK_HEIGHT - 40.0;
+        let top = bounds.height - DOCK_HEIGHT + 30.0;
+        let mut x = 10.0;
+        self.outputs
+            .iter()
+            .enumerate()
+            .filter(|(_, out)| !out.enabled)
+            .map(|(i, out)| {
                 let cm = out
                     .modes
                     .iter()
@@ -534,46 +5281,517 @@ impl<'a> LayoutCanvas<'a> {
                         refresh_rate: 60.0,
                         current: true,
                         preferred: false,
+                        custom: false,
                     });
-                Self::logical_size(out, &cm).0 as f32
+                let (w, h) = Self::logical_size(out, &cm);
+                let item_w = (item_h * w as f32 / h.max(1) as f32).clamp(item_h, item_h * 3.0);
+                let rect = Rectangle::new(Point::new(x, top), Size::new(item_w, item_h));
+                x += item_w + 10.0;
+                (i, rect)
             })
-            .unwrap_or(1920.0);
+            .collect()
+    }
 
-        let offset_x = bounds.width / 2.0 - (first_w / 2.0) * scale;
-        let offset_y = bounds.height / 2.0 - (max_h as f32 / 2.0) * scale;
+    fn calculate_layout(&self, bounds: Rectangle) -> (f32, f32, f32, i32, i32) {
+        let bounds = self.layout_area(bounds);
+        let span_x = (self.frame.width * 1.5).max(4000.0);
+        let span_y = (self.frame.height * 1.5).max(3000.0);
+        let scale = (bounds.width / span_x).min(bounds.height / span_y) * self.zoom;
+
+        let offset_x = (bounds.width - self.frame.width * scale) / 2.0;
+        let offset_y = (bounds.height - self.frame.height * scale) / 2.0;
 
-        (scale, offset_x, offset_y, 0, 0)
+        (
+            scale,
+            offset_x,
+            offset_y,
+            self.frame.x as i32,
+            self.frame.y as i32,
+        )
     }
 
+    /// Where output `idx` is on the canvas.
     fn transformed_geometry(
         &self,
-        out: &Output,
+        idx: usize,
         scale: f32,
         offset_x: f32,
         offset_y: f32,
         min_x: i32,
         min_y: i32,
     ) -> (f32, f32, f32, f32) {
-        let cm = out
-            .modes
-            .iter()
-            .find(|m| m.current)
-            .cloned()
-            .unwrap_or(OutputMode {
-                width: 800,
-                height: 600,
-                refresh_rate: 60.0,
-                current: true,
-                preferred: false,
+        let rect = self.rects[idx];
+        let x = (rect.x - min_x as f32) * scale + offset_x;
+        let y = (rect.y - min_y as f32) * scale + offset_y;
+        (x, y, rect.width * scale, rect.height * scale)
+    }
+}
+
+impl<'a> LayoutCanvas<'a> {
+    /// Output `i` with its name, description and clone group, in the colours of
+    /// `highlight`.
+    fn draw_output(
+        &self,
+        frame: &mut canvas::Frame,
+        i: usize,
+        groups: &[Vec<String>],
+        (scale, offset_x, offset_y, min_x, min_y): (f32, f32, f32, i32, i32),
+        highlight: Highlight,
+    ) {
+        let out = &self.outputs[i];
+        let nickname = self.nickname(i);
+        let (x, y, w, h) = self.transformed_geometry(i, scale, offset_x, offset_y, min_x, min_y);
+
+        let rect = Rectangle::new(Point::new(x, y), Size::new(w, h));
+
+        let is_selected = highlight == Highlight::Selected;
+        let grey = Color::from_rgb8;
+        // Fill, outline, name and detail text.
+        let (fill_color, stroke_color, name_color, detail_color) =
+            match (self.high_contrast, highlight) {
+                (false, Highlight::Selected) => (
+                    grey(220, 220, 220),
+                    Color::WHITE,
+                    Color::BLACK,
+                    grey(40, 40, 40),
+                ),
+                (false, Highlight::Hovered) => (
+                    grey(60, 60, 60),
+                    grey(150, 150, 150),
+                    grey(230, 230, 230),
+                    grey(160, 160, 160),
+                ),
+                (false, Highlight::None) => (
+                    grey(35, 35, 35),
+                    grey(20, 20, 20),
+                    grey(230, 230, 230),
+                    grey(160, 160, 160),
+                ),
+                (true, Highlight::Selected) => (
+                    HIGH_CONTRAST_ACCENT,
+                    Color::WHITE,
+                    Color::BLACK,
+                    Color::BLACK,
+                ),
+                (true, Highlight::Hovered) => (
+                    grey(50, 50, 50),
+                    HIGH_CONTRAST_ACCENT,
+                    Color::WHITE,
+                    Color::WHITE,
+                ),
+                (true, Highlight::None) => (Color::BLACK, Color::WHITE, Color::WHITE, Color::WHITE),
+            };
+        let stroke_width = if is_selected { 3.0 } else { 2.0 };
+
+        frame.fill_rectangle(rect.position(), rect.size(), fill_color);
+        if let Some(Some(tint)) = self.tints.get(i) {
+            frame.fill_rectangle(rect.position(), rect.size(), *tint);
+        }
+
+        frame.stroke(
+            &Path::rectangle(rect.position(), rect.size()),
+            canvas::Stroke::default()
+                .with_color(stroke_color)
+                .with_width(if self.high_contrast {
+                    2.0 * stroke_width
+                } else {
+                    stroke_width
+                }),
+        );
+
+        let text_x = x + 16.0;
+        let mut text_y = y + 16.0;
+        let font_scale = scale.clamp(0.5, 2.0) * self.text_scale;
+
+        frame.fill_text(canvas::Text {
+            content: nickname.unwrap_or(&out.name).to_string(),
+            position: Point::new(text_x, text_y),
+            size: iced::Pixels(48.0 * font_scale),
+            color: name_color,
+            ..Default::default()
+        });
+
+        text_y += 50.0 * font_scale;
+
+        let text_size = 18.0 * font_scale;
+        let approx_char_width = text_size * 0.6;
+        let max_chars = ((w - 32.0) / approx_char_width).max(10.0) as usize;
+
+        let mut lines = Vec::new();
+        let mut current_line = String::new();
+
+        // A nickname takes the name's place, so the connector leads the description.
+        let description = match nickname {
+            Some(_) => format!("{} · {}", out.name, out.description),
+            None => out.description.clone(),
+        };
+        for word in description.split_whitespace() {
+            if current_line.len() + word.len() + 1 > max_chars && !current_line.is_empty() {
+                lines.push(current_line);
+                current_line = word.to_string();
+            } else {
+                if !current_line.is_empty() {
+                    current_line.push(' ');
+                }
+                current_line.push_str(word);
+            }
+        }
+        if !current_line.is_empty() {
+            lines.push(current_line);
+        }
+
+        for line in lines {
+            frame.fill_text(canvas::Text {
+                content: line,
+                position: Point::new(text_x, text_y),
+                size: iced::Pixels(text_size),
+                color: detail_color,
+                ..Default::default()
+            });
+            text_y += text_size * 1.3;
+        }
+
+        if let Some(group) = groups.iter().find(|names| names.contains(&out.name)) {
+            frame.fill_text(canvas::Text {
+                content: format!("⧉ {}", group.join(" = ")),
+                position: Point::new(text_x, y + h - 16.0 - text_size),
+                size: iced::Pixels(text_size),
+                color: detail_color,
+                ..Default::default()
+            });
+        }
+    }
+
+    /// A card next to output `idx` with what does not fit inside its rectangle at canvas
+    /// scale: the whole description, the mode, the scale and the position.
+    fn draw_info_card(
+        &self,
+        frame: &mut canvas::Frame,
+        idx: usize,
+        bounds: Rectangle,
+        (scale, offset_x, offset_y, min_x, min_y): (f32, f32, f32, i32, i32),
+    ) {
+        const PADDING: f32 = 8.0;
+        let text_size = 13.0 * self.text_scale;
+        let line_height = 18.0 * self.text_scale;
+
+        let out = &self.outputs[idx];
+        let mode = out.modes.iter().find(|m| m.current).map_or_else(
+            || "-".to_string(),
+            |m| {
+                format!(
+                    "{}x{} @ {} Hz",
+                    m.width,
+                    m.height,
+                    decimal(m.refresh_rate, 3)
+                )
+            },
+        );
+        let mut lines = vec![match self.nickname(idx) {
+            Some(nickname) => format!("{} ({})", nickname, out.name),
+            None => out.name.clone(),
+        }];
+        if !out.description.is_empty() {
+            lines.push(out.description.clone());
+        }
+        lines.push(format!("{}: {}", tr("Mode"), mode));
+        lines.push(format!("{}: {}", tr("Scale"), decimal(out.scale, 2)));
+        lines.push(format!(
+            "{}: {}, {}",
+            tr("Position"),
+            out.position.0,
+            out.position.1
+        ));
+
+        let longest = lines.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+        let size = Size::new(
+            longest as f32 * text_size * 0.6 + 2.0 * PADDING,
+            lines.len() as f32 * line_height + 2.0 * PADDING,
+        );
+        let area = self.layout_area(bounds);
+        let (x, y, _, h) = self.transformed_geometry(idx, scale, offset_x, offset_y, min_x, min_y);
+        // Below the output, or above it when that runs off the canvas.
+        let top = if y + h + 8.0 + size.height <= area.height {
+            y + h + 8.0
+        } else {
+            (y - 8.0 - size.height).max(0.0)
+        };
+        let origin = Point::new(x.clamp(0.0, (area.width - size.width).max(0.0)), top);
+
+        frame.fill_rectangle(origin, size, Color::from_rgba8(20, 20, 20, 0.95));
+        frame.stroke(
+            &Path::rectangle(origin, size),
+            canvas::Stroke::default()
+                .with_color(Color::from_rgb8(90, 90, 90))
+                .with_width(1.0),
+        );
+        for (i, line) in lines.into_iter().enumerate() {
+            frame.fill_text(canvas::Text {
+                content: line,
+                position: Point::new(
+                    origin.x + PADDING,
+                    origin.y + PADDING + i as f32 * line_height,
+                ),
+                size: iced::Pixels(text_size),
+                color: if i == 0 {
+                    Color::WHITE
+                } else {
+                    Color::from_rgb8(180, 180, 180)
+                },
+                ..Default::default()
+            });
+        }
+    }
+
+    /// The marks along one axis of the canvas: each as its logical coordinate and where
+    /// it lands on the canvas, from `start` to `end`. `offset` and `origin` are that
+    /// axis' parts of [`Self::calculate_layout`].
+    fn ruler_marks(
+        scale: f32,
+        offset: f32,
+        origin: i32,
+        (start, end): (f32, f32),
+    ) -> Vec<(i32, f32)> {
+        let step = RULER_STEPS
+            .into_iter()
+            .find(|step| *step as f32 * scale >= 60.0)
+            .unwrap_or(RULER_STEPS[RULER_STEPS.len() - 1]);
+        let first = (((start - offset) / scale + origin as f32) / step as f32).ceil() as i32;
+        (first..)
+            .map(|i| {
+                let value = i * step;
+                (value, (value - origin) as f32 * scale + offset)
+            })
+            .take_while(|(_, at)| *at <= end)
+            .collect()
+    }
+
+    fn draw_grid(
+        &self,
+        frame: &mut canvas::Frame,
+        bounds: Rectangle,
+        (scale, offset_x, offset_y, min_x, min_y): (f32, f32, f32, i32, i32),
+    ) {
+        let area = self.layout_area(bounds);
+        let stroke = canvas::Stroke::default()
+            .with_color(Color::from_rgba8(255, 255, 255, 0.06))
+            .with_width(1.0);
+        for (_, x) in Self::ruler_marks(scale, offset_x, min_x, (0.0, area.width)) {
+            frame.stroke(
+                &Path::line(Point::new(x, 0.0), Point::new(x, area.height)),
+                stroke,
+            );
+        }
+        for (_, y) in Self::ruler_marks(scale, offset_y, min_y, (0.0, area.height)) {
+            frame.stroke(
+                &Path::line(Point::new(0.0, y), Point::new(area.width, y)),
+                stroke,
+            );
+        }
+    }
+
+    /// Rulers in logical pixels along the top and left of the layout area.
+    fn draw_rulers(
+        &self,
+        frame: &mut canvas::Frame,
+        bounds: Rectangle,
+        (scale, offset_x, offset_y, min_x, min_y): (f32, f32, f32, i32, i32),
+    ) {
+        let area = self.layout_area(bounds);
+        let background = Color::from_rgb8(28, 28, 28);
+        let stroke = canvas::Stroke::default()
+            .with_color(Color::from_rgb8(110, 110, 110))
+            .with_width(1.0);
+        let label = |content: String, position: Point| canvas::Text {
+            content,
+            position,
+            size: iced::Pixels(10.0),
+            color: Color::from_rgb8(140, 140, 140),
+            ..Default::default()
+        };
+
+        frame.fill_rectangle(Point::ORIGIN, Size::new(area.width, RULER_TOP), background);
+        for (value, x) in Self::ruler_marks(scale, offset_x, min_x, (RULER_LEFT, area.width)) {
+            frame.stroke(
+                &Path::line(Point::new(x, RULER_TOP - 6.0), Point::new(x, RULER_TOP)),
+                stroke,
+            );
+            frame.fill_text(label(value.to_string(), Point::new(x + 2.0, 2.0)));
+        }
+
+        frame.fill_rectangle(
+            Point::ORIGIN,
+            Size::new(RULER_LEFT, area.height),
+            background,
+        );
+        for (value, y) in Self::ruler_marks(scale, offset_y, min_y, (RULER_TOP, area.height)) {
+            frame.stroke(
+                &Path::line(Point::new(RULER_LEFT - 6.0, y), Point::new(RULER_LEFT, y)),
+                stroke,
+            );
+            frame.fill_text(label(value.to_string(), Point::new(2.0, y + 2.0)));
+        }
+    }
+
+    /// The desktop coordinates under the cursor and, during a drag, where the dragged
+    /// output's top left corner is, in the bottom left corner of the layout area.
+    fn draw_readout(
+        &self,
+        frame: &mut canvas::Frame,
+        state: &CanvasState,
+        cursor: mouse::Cursor,
+        bounds: Rectangle,
+        (scale, offset_x, offset_y, min_x, min_y): (f32, f32, f32, i32, i32),
+    ) {
+        const PADDING: f32 = 6.0;
+        let text_size = 13.0 * self.text_scale;
+        let line_height = 18.0 * self.text_scale;
+
+        let area = self.layout_area(bounds);
+        let mut lines = Vec::new();
+        if let Some(p) = cursor.position_in(bounds)
+            && area.contains(p)
+        {
+            let x = ((p.x - offset_x) / scale).round() as i32 + min_x;
+            let y = ((p.y - offset_y) / scale).round() as i32 + min_y;
+            lines.push(format!("x {}  y {}", x, y));
+        }
+        if let Some((name, _, _)) = &state.dragging
+            && let Some(idx) = self.outputs.iter().position(|o| &o.name == name)
+        {
+            let (x, y) = self.outputs[idx].position;
+            let name = self.nickname(idx).unwrap_or(name);
+            lines.push(format!("{}  x {}  y {}", name, x, y));
+        }
+        if lines.is_empty() {
+            return;
+        }
+
+        let longest = lines.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+        let size = Size::new(
+            longest as f32 * text_size * 0.6 + 2.0 * PADDING,
+            lines.len() as f32 * line_height + 2.0 * PADDING,
+        );
+        let origin = Point::new(RULER_LEFT + 10.0, area.height - size.height - 10.0);
+        frame.fill_rectangle(origin, size, Color::from_rgba8(20, 20, 20, 0.85));
+        for (i, line) in lines.into_iter().enumerate() {
+            frame.fill_text(canvas::Text {
+                content: line,
+                position: Point::new(
+                    origin.x + PADDING,
+                    origin.y + PADDING + i as f32 * line_height,
+                ),
+                size: iced::Pixels(text_size),
+                color: Color::from_rgb8(200, 200, 200),
+                font: iced::Font::MONOSPACE,
+                ..Default::default()
+            });
+        }
+    }
+
+    /// Lines across the canvas along the [`Self::axis_lines`] the output being dragged
+    /// has snapped to.
+    fn draw_guides(
+        &self,
+        frame: &mut canvas::Frame,
+        state: &CanvasState,
+        bounds: Rectangle,
+        (scale, offset_x, offset_y, min_x, min_y): (f32, f32, f32, i32, i32),
+    ) {
+        let Some((name, _, _)) = &state.dragging else {
+            return;
+        };
+        let Some(idx) = self.outputs.iter().position(|o| &o.name == name) else {
+            return;
+        };
+        let area = self.layout_area(bounds);
+        let (x, y) = self.outputs[idx].position;
+        let (xs, ys) = self.axis_lines(idx);
+        let stroke = canvas::Stroke::default()
+            .with_color(Color::from_rgb8(90, 160, 255))
+            .with_width(1.0);
+        if xs.contains(&x) {
+            let x = (x - min_x) as f32 * scale + offset_x;
+            frame.stroke(
+                &Path::line(Point::new(x, 0.0), Point::new(x, area.height)),
+                stroke,
+            );
+        }
+        if ys.contains(&y) {
+            let y = (y - min_y) as f32 * scale + offset_y;
+            frame.stroke(
+                &Path::line(Point::new(0.0, y), Point::new(area.width, y)),
+                stroke,
+            );
+        }
+    }
+
+    fn draw_dock(&self, frame: &mut canvas::Frame, bounds: Rectangle) {
+        let top = bounds.height - DOCK_HEIGHT;
+        frame.fill_rectangle(
+            Point::new(0.0, top),
+            Size::new(bounds.width, DOCK_HEIGHT),
+            Color::from_rgb8(25, 25, 25),
+        );
+        frame.fill_text(canvas::Text {
+            content: tr("Disabled outputs: drag onto the layout to enable").to_string(),
+            position: Point::new(10.0, top + 8.0),
+            size: iced::Pixels(13.0),
+            color: Color::from_rgb8(120, 120, 120),
+            ..Default::default()
+        });
+
+        for (i, rect) in self.dock_items(bounds) {
+            frame.fill_rectangle(rect.position(), rect.size(), Color::from_rgb8(45, 45, 45));
+            frame.stroke(
+                &Path::rectangle(rect.position(), rect.size()),
+                canvas::Stroke::default()
+                    .with_color(Color::from_rgb8(70, 70, 70))
+                    .with_width(1.0),
+            );
+            frame.fill_text(canvas::Text {
+                content: self.outputs[i].name.clone(),
+                position: Point::new(rect.x + 6.0, rect.y + 6.0),
+                size: iced::Pixels(13.0),
+                color: Color::from_rgb8(130, 130, 130),
+                ..Default::default()
             });
-        let (w_logical, h_logical) = Self::logical_size(out, &cm);
+        }
+    }
 
-        let w = w_logical as f32 * scale;
-        let h = h_logical as f32 * scale;
-        let x = (out.position.0 - min_x) as f32 * scale + offset_x;
-        let y = (out.position.1 - min_y) as f32 * scale + offset_y;
+    /// The outline of the selected disabled output and the ghost of one being dragged
+    /// out of the dock.
+    fn draw_dock_overlay(
+        &self,
+        frame: &mut canvas::Frame,
+        state: &CanvasState,
+        bounds: Rectangle,
+        scale: f32,
+    ) {
+        if let Some((_, rect)) = self
+            .dock_items(bounds)
+            .into_iter()
+            .find(|(i, _)| self.selected == Some(self.outputs[*i].name.as_str()))
+        {
+            frame.stroke(
+                &Path::rectangle(rect.position(), rect.size()),
+                canvas::Stroke::default()
+                    .with_color(Color::from_rgb8(255, 255, 255))
+                    .with_width(2.0),
+            );
+        }
 
-        (x, y, w, h)
+        if let Some((name, ghost)) = &state.undocking
+            && let Some(idx) = self.outputs.iter().position(|o| &o.name == name)
+        {
+            let size = self.rects[idx].size();
+            frame.stroke(
+                &Path::rectangle(*ghost, Size::new(size.width * scale, size.height * scale)),
+                canvas::Stroke::default()
+                    .with_color(Color::from_rgb8(200, 200, 200))
+                    .with_width(2.0),
+            );
+        }
     }
 }
 
@@ -592,26 +5810,106 @@ impl<'a> Program<Message> for LayoutCanvas<'a> {
         match event {
             Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
                 if let Some(cursor_position) = cursor.position_in(bounds) {
+                    if let Some((i, _)) = self
+                        .dock_items(bounds)
+                        .into_iter()
+                        .find(|(_, rect)| rect.contains(cursor_position))
+                    {
+                        let name = self.outputs[i].name.clone();
+                        state.undocking = Some((name.clone(), cursor_position));
+                        return Some(Action::publish(Message::MonitorClicked(name)).and_capture());
+                    }
                     for (i, out) in self.outputs.iter().enumerate() {
+                        if !out.enabled {
+                            continue;
+                        }
                         let (x, y, w, h) =
-                            self.transformed_geometry(out, scale, offset_x, offset_y, min_x, min_y);
+                            self.transformed_geometry(i, scale, offset_x, offset_y, min_x, min_y);
 
                         let rect = Rectangle::new(Point::new(x, y), Size::new(w, h));
                         if rect.contains(cursor_position) {
-                            state.dragging = Some((i, cursor_position, out.position));
+                            state.dragging =
+                                Some((out.name.clone(), cursor_position, out.position));
                         }
                     }
-                    if let Some((i, _, _)) = state.dragging {
-                        return Some(Action::publish(Message::MonitorClicked(i)).and_capture());
+                    if let Some((name, _, _)) = &state.dragging {
+                        let name = name.clone();
+                        let now = Instant::now();
+                        if state.last_click.take().is_some_and(|(last, at)| {
+                            last == name && now.duration_since(at) < DOUBLE_CLICK
+                        }) {
+                            state.dragging = None;
+                            return Some(
+                                Action::publish(Message::OutputDialogOpened(name)).and_capture(),
+                            );
+                        }
+                        state.last_click = Some((name.clone(), now));
+                        return Some(Action::publish(Message::MonitorClicked(name)).and_capture());
                     }
                     state.dragging = None;
                 }
             }
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Right)) => {
+                let cursor_position = cursor.position_in(bounds)?;
+                let (_, out) = self.outputs.iter().enumerate().rev().find(|(i, out)| {
+                    let (x, y, w, h) =
+                        self.transformed_geometry(*i, scale, offset_x, offset_y, min_x, min_y);
+                    out.enabled
+                        && Rectangle::new(Point::new(x, y), Size::new(w, h))
+                            .contains(cursor_position)
+                })?;
+                return Some(
+                    Action::publish(Message::ContextMenuOpened(
+                        out.name.clone(),
+                        cursor_position,
+                    ))
+                    .and_capture(),
+                );
+            }
+            Event::Keyboard(keyboard::Event::ModifiersChanged(modifiers)) => {
+                state.modifiers = *modifiers;
+            }
+            Event::Mouse(mouse::Event::WheelScrolled { delta }) => {
+                cursor.position_in(bounds)?;
+                let lines = match delta {
+                    mouse::ScrollDelta::Lines { y, .. } => *y,
+                    mouse::ScrollDelta::Pixels { y, .. } => *y / 40.0,
+                };
+                let zoom = (self.zoom * 1.1f32.powf(lines)).clamp(MIN_ZOOM, MAX_ZOOM);
+                return Some(Action::publish(Message::CanvasZoomed(zoom)).and_capture());
+            }
             Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
-                state.dragging = None;
+                if let Some((name, _)) = state.undocking.take() {
+                    self.cache.clear();
+                    let drop = cursor
+                        .position_in(bounds)
+                        .filter(|p| self.layout_area(bounds).contains(*p))?;
+                    let x = ((drop.x - offset_x) / scale).round() as i32 + min_x;
+                    let y = ((drop.y - offset_y) / scale).round() as i32 + min_y;
+                    return Some(Action::publish(Message::MonitorUndocked(
+                        name,
+                        x.max(0),
+                        y.max(0),
+                    )));
+                }
+                let was_dragging = state.dragging.take().is_some();
+                if was_dragging {
+                    // Takes the guide lines away.
+                    self.cache.overlay.clear();
+                    return Some(Action::publish(Message::DragFinished));
+                }
             }
             Event::Mouse(mouse::Event::CursorMoved { position }) => {
-                if let Some((idx, start_cursor, start_logical)) = state.dragging {
+                if let Some((_, ghost)) = &mut state.undocking {
+                    *ghost = *position;
+                    self.cache.overlay.clear();
+                    return Some(Action::request_redraw());
+                } else if let Some((name, start_cursor, start_logical)) = &state.dragging {
+                    // The output may have been unplugged mid-drag.
+                    let Some(idx) = self.outputs.iter().position(|o| &o.name == name) else {
+                        state.dragging = None;
+                        return None;
+                    };
                     let delta_x = (position.x - start_cursor.x) / scale;
                     let delta_y = (position.y - start_cursor.y) / scale;
 
@@ -624,124 +5922,32 @@ impl<'a> Program<Message> for LayoutCanvas<'a> {
                         new_y = 0;
                     }
 
-                    let snap_threshold = 40;
-
-                    if let Some(out) = self.outputs.get(idx) {
-                        let cm = out
-                            .modes
-                            .iter()
-                            .find(|m| m.current)
-                            .cloned()
-                            .unwrap_or(OutputMode {
-                                width: 800,
-                                height: 600,
-                                refresh_rate: 60.0,
-                                current: true,
-                                preferred: false,
-                            });
-                        let (w, h) = Self::logical_size(out, &cm);
-
-                        let mut snapped_x = new_x;
-                        let mut snapped_y = new_y;
-                        let mut min_dist_x = snap_threshold;
-                        let mut min_dist_y = snap_threshold;
-
-                        let my_left = new_x;
-                        let my_right = new_x + w;
-                        let my_top = new_y;
-                        let my_bottom = new_y + h;
-
-                        for (i, other) in self.outputs.iter().enumerate() {
-                            if i == idx {
-                                continue;
-                            }
-                            let other_cm = other
-                                .modes
-                                .iter()
-                                .find(|m| m.current)
-                                .cloned()
-                                .unwrap_or(OutputMode {
-                                    width: 800,
-                                    height: 600,
-                                    refresh_rate: 60.0,
-                                    current: true,
-                                    preferred: false,
-                                });
-                            let (other_w, other_h) = Self::logical_size(other, &other_cm);
-
-                            let other_left = other.position.0;
-                            let other_right = other.position.0 + other_w;
-                            let other_top = other.position.1;
-                            let other_bottom = other.position.1 + other_h;
-
-                            let x_overlap = my_left < other_right + snap_threshold
-                                && my_right > other_left - snap_threshold;
-                            let y_overlap = my_top < other_bottom + snap_threshold
-                                && my_bottom > other_top - snap_threshold;
-
-                            if y_overlap {
-                                if (my_left - other_right).abs() < min_dist_x {
-                                    min_dist_x = (my_left - other_right).abs();
-                                    snapped_x = other_right;
-                                }
-                                if (my_right - other_left).abs() < min_dist_x {
-                                    min_dist_x = (my_right - other_left).abs();
-                                    snapped_x = other_left - w;
-                                }
-                                if (my_left - other_left).abs() < min_dist_x {
-                                    min_dist_x = (my_left - other_left).abs();
-                                    snapped_x = other_left;
-                                }
-                            }
-
-                            if x_overlap {
-                                if (my_top - other_bottom).abs() < min_dist_y {
-                                    min_dist_y = (my_top - other_bottom).abs();
-                                    snapped_y = other_bottom;
-                                }
-                                if (my_bottom - other_top).abs() < min_dist_y {
-                                    min_dist_y = (my_bottom - other_top).abs();
-                                    snapped_y = other_top - h;
-                                }
-                                if (my_top - other_top).abs() < min_dist_y {
-                                    min_dist_y = (my_top - other_top).abs();
-                                    snapped_y = other_top;
-                                }
-                            }
-                        }
-
-                        if snapped_x == new_x {
-                            snapped_x = (snapped_x as f32 / 10.0).round() as i32 * 10;
-                        }
-                        if snapped_y == new_y {
-                            snapped_y = (snapped_y as f32 / 10.0).round() as i32 * 10;
-                        }
-
-                        if snapped_x < 0 {
-                            snapped_x = 0;
-                        }
-                        if snapped_y < 0 {
-                            snapped_y = 0;
-                        }
-
+                    if let Some((x, y)) = self.snap_position(idx, new_x, new_y, state.modifiers) {
                         return Some(Action::publish(Message::MonitorPositioned(
-                            idx, snapped_x, snapped_y,
+                            name.clone(),
+                            x,
+                            y,
                         )));
                     }
                 } else {
                     let mut new_hovered = None;
                     for (i, out) in self.outputs.iter().enumerate() {
+                        if !out.enabled {
+                            continue;
+                        }
                         let (x, y, w, h) =
-                            self.transformed_geometry(out, scale, offset_x, offset_y, min_x, min_y);
+                            self.transformed_geometry(i, scale, offset_x, offset_y, min_x, min_y);
                         let rect = Rectangle::new(Point::new(x, y), Size::new(w, h));
                         if rect.contains(*position) {
-                            new_hovered = Some(i);
+                            new_hovered = Some(out.name.clone());
                         }
                     }
                     if state.hovered != new_hovered {
                         state.hovered = new_hovered;
-                        self.cache.clear();
+                        self.cache.overlay.clear();
                     }
+                    // The coordinate readout follows the cursor.
+                    return Some(Action::request_redraw());
                 }
             }
             _ => {}
@@ -755,102 +5961,462 @@ impl<'a> Program<Message> for LayoutCanvas<'a> {
         renderer: &Renderer,
         _theme: &Theme,
         bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> Vec<Geometry> {
+        let layout = self.calculate_layout(bounds);
+        let scene = self.cache.scene.draw(renderer, bounds.size(), |frame| {
+            let background = if self.high_contrast {
+                Color::BLACK
+            } else {
+                Color::from_rgb8(15, 15, 15)
+            };
+            frame.fill_rectangle(Point::ORIGIN, bounds.size(), background);
+            if self.grid {
+                self.draw_grid(frame, bounds, layout);
+            }
+            let groups = clone_groups(self.outputs);
+            for (i, out) in self.outputs.iter().enumerate() {
+                if out.enabled {
+                    self.draw_output(frame, i, &groups, layout, Highlight::None);
+                }
+            }
+            self.draw_rulers(frame, bounds, layout);
+            if self.has_dock() {
+                self.draw_dock(frame, bounds);
+            }
+        });
+        let overlay = self.cache.overlay.draw(renderer, bounds.size(), |frame| {
+            let groups = clone_groups(self.outputs);
+            for (i, out) in self.outputs.iter().enumerate() {
+                let highlight = if self.selected == Some(out.name.as_str()) {
+                    Highlight::Selected
+                } else if state.hovered.as_ref() == Some(&out.name) {
+                    Highlight::Hovered
+                } else {
+                    continue;
+                };
+                if out.enabled {
+                    self.draw_output(frame, i, &groups, layout, highlight);
+                }
+            }
+            self.draw_guides(frame, state, bounds, layout);
+            if state.dragging.is_none()
+                && let Some(idx) = state
+                    .hovered
+                    .as_ref()
+                    .and_then(|name| self.outputs.iter().position(|o| &o.name == name))
+            {
+                self.draw_info_card(frame, idx, bounds, layout);
+            }
+            if self.has_dock() {
+                self.draw_dock_overlay(frame, state, bounds, layout.0);
+            }
+        });
+
+        // Changes with every mouse move, so it is not worth caching.
+        let mut readout = canvas::Frame::new(renderer, bounds.size());
+        self.draw_readout(&mut readout, state, cursor, bounds, layout);
+
+        vec![scene, overlay, readout.into_geometry()]
+    }
+}
+
+/// A turning arc shown next to the label of the backend job in flight.
+struct Spinner {
+    frame: u32,
+    spinning: bool,
+}
+
+impl Program<Message> for Spinner {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer,
+        theme: &Theme,
+        bounds: Rectangle,
         _cursor: mouse::Cursor,
     ) -> Vec<Geometry> {
-        let geometry = self.cache.draw(renderer, bounds.size(), |frame| {
-            frame.fill_rectangle(Point::ORIGIN, bounds.size(), Color::from_rgb8(15, 15, 15));
+        if !self.spinning {
+            return Vec::new();
+        }
+        let mut frame = canvas::Frame::new(renderer, bounds.size());
+        let start = self.frame as f32 * std::f32::consts::TAU / 12.0;
+        let arc = Path::new(|b| {
+            b.arc(canvas::path::Arc {
+                center: frame.center(),
+                radius: bounds.width.min(bounds.height) / 2.0 - 1.5,
+                start_angle: iced::Radians(start),
+                end_angle: iced::Radians(start + std::f32::consts::PI * 1.5),
+            })
+        });
+        frame.stroke(
+            &arc,
+            canvas::Stroke::default()
+                .with_color(theme.palette().primary)
+                .with_width(2.0),
+        );
+        vec![frame.into_geometry()]
+    }
+}
 
-            let (scale, offset_x, offset_y, min_x, min_y) = self.calculate_layout(bounds);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mdisplay_core::mock::MockBackend;
 
-            for (i, out) in self.outputs.iter().enumerate() {
-                let (x, y, w, h) =
-                    self.transformed_geometry(out, scale, offset_x, offset_y, min_x, min_y);
+    const FIXTURE: &str = include_str!("../mdisplay-core/fixtures/dual_monitor.json");
 
-                let rect = Rectangle::new(Point::new(x, y), Size::new(w, h));
+    /// DP-1 2560x1440 at 0,0 and HDMI-A-1 1920x1080 at 2560,0, plus a disabled eDP-1.
+    fn fixture_app() -> MangoDisplay {
+        crate::testing::isolate_dirs();
+        let backend = MockBackend::from_json(FIXTURE).unwrap();
+        let outputs = backend.fetch_outputs().unwrap();
+        let mut app = MangoDisplay {
+            backend: Arc::new(backend),
+            settings: AppSettings {
+                notifications: false,
+                ..AppSettings::default()
+            },
+            ..MangoDisplay::default()
+        };
+        let _ = app.update(Message::OutputsLoaded(Ok(outputs)));
+        app
+    }
 
-                let is_selected = Some(i) == self.selected_idx;
-                let is_hovered = Some(i) == state.hovered;
+    fn current_mode(app: &MangoDisplay, idx: usize) -> usize {
+        app.outputs[idx]
+            .modes
+            .iter()
+            .position(|m| m.current)
+            .unwrap()
+    }
 
-                let fill_color = if is_selected {
-                    Color::from_rgb8(220, 220, 220)
-                } else if is_hovered {
-                    Color::from_rgb8(60, 60, 60)
-                } else {
-                    Color::from_rgb8(35, 35, 35)
-                };
+    fn snap_with(
+        outputs: &[Output],
+        bezels: Vec<Bezels>,
+        idx: usize,
+        pos: (i32, i32),
+        modifiers: keyboard::Modifiers,
+    ) -> (i32, i32) {
+        snap_drawn(outputs, None, bezels, idx, pos, modifiers)
+    }
 
-                let stroke_color = if is_selected {
-                    Color::from_rgb8(255, 255, 255)
-                } else if is_hovered {
-                    Color::from_rgb8(150, 150, 150)
-                } else {
-                    Color::from_rgb8(20, 20, 20)
-                };
+    /// Snaps with the outputs drawn as [`LayoutCanvas::drawn_rects`] draws them at
+    /// `px_per_mm`.
+    fn snap_drawn(
+        outputs: &[Output],
+        px_per_mm: Option<f32>,
+        bezels: Vec<Bezels>,
+        idx: usize,
+        (x, y): (i32, i32),
+        modifiers: keyboard::Modifiers,
+    ) -> (i32, i32) {
+        let cache = CanvasCaches::default();
+        let canvas = LayoutCanvas {
+            outputs,
+            rects: LayoutCanvas::drawn_rects(outputs, px_per_mm),
+            selected: Some(&outputs[idx].name),
+            frame: layout_bounds(outputs),
+            zoom: 1.0,
+            bezels,
+            tints: Vec::new(),
+            nicknames: Vec::new(),
+            grid: false,
+            high_contrast: false,
+            text_scale: 1.0,
+            snapping: Snapping::default(),
+            cache: &cache,
+        };
+        canvas.snap_position(idx, x, y, modifiers).unwrap()
+    }
 
-                frame.fill_rectangle(rect.position(), rect.size(), fill_color);
+    fn snap(outputs: &[Output], bezels: Vec<Bezels>, idx: usize, x: i32, y: i32) -> (i32, i32) {
+        snap_with(outputs, bezels, idx, (x, y), keyboard::Modifiers::default())
+    }
 
-                frame.stroke(
-                    &Path::rectangle(rect.position(), rect.size()),
-                    canvas::Stroke::default()
-                        .with_color(stroke_color)
-                        .with_width(if is_selected { 3.0 } else { 2.0 }),
-                );
+    #[test]
+    fn loading_outputs_selects_the_first_and_fills_the_inputs() {
+        let app = fixture_app();
+        assert_eq!(app.outputs.len(), 3);
+        assert_eq!(app.selected_idx(), Some(0));
+        assert_eq!(app.x_input, "0");
+        assert_eq!(app.scale_input, "1.00");
+        assert_eq!(app.applied_outputs, app.outputs);
+    }
 
-                let text_x = x + 16.0;
-                let mut text_y = y + 16.0;
-                let font_scale = scale.min(2.0).max(0.5);
+    #[test]
+    fn dragging_a_tab_onto_another_moves_it_there() {
+        let mut app = fixture_app();
+        let names = |app: &MangoDisplay| {
+            app.tab_order()
+                .into_iter()
+                .map(|i| app.outputs[i].name.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(&app), ["DP-1", "HDMI-A-1", "eDP-1"]);
+        let _ = app.update(Message::TabPressed("eDP-1".to_string()));
+        let _ = app.update(Message::TabEntered("DP-1".to_string()));
+        assert_eq!(names(&app), ["eDP-1", "DP-1", "HDMI-A-1"]);
+        assert_eq!(app.selected_output.as_deref(), Some("eDP-1"));
+        let _ = app.update(Message::SelectNext);
+        assert_eq!(app.selected_output.as_deref(), Some("DP-1"));
+    }
 
-                let mut name_text = canvas::Text::default();
-                name_text.content = out.name.clone();
-                name_text.position = Point::new(text_x, text_y);
-                name_text.size = iced::Pixels(48.0 * font_scale);
-                name_text.color = if is_selected {
-                    Color::BLACK
-                } else {
-                    Color::from_rgb8(230, 230, 230)
-                };
-                frame.fill_text(name_text);
+    #[test]
+    fn dragging_near_an_edge_snaps_flush_against_it() {
+        let app = fixture_app();
+        assert_eq!(snap(&app.outputs, Vec::new(), 1, 2585, 17), (2560, 0));
+        assert_eq!(snap(&app.outputs, Vec::new(), 1, 2540, -12), (2560, 0));
+        let alt = keyboard::Modifiers::ALT;
+        assert_eq!(
+            snap_with(&app.outputs, Vec::new(), 1, (2585, 17), alt),
+            (2585, 17)
+        );
+    }
 
-                text_y += 50.0 * font_scale;
+    #[test]
+    fn the_physical_view_snaps_flush_in_logical_pixels() {
+        let app = fixture_app();
+        let none = keyboard::Modifiers::default();
+        assert_eq!(
+            snap_drawn(&app.outputs, Some(1.0), Vec::new(), 1, (2585, 17), none),
+            (2560, 0)
+        );
+    }
 
-                let text_size = 18.0 * font_scale;
-                let approx_char_width = text_size * 0.6;
-                let max_chars = ((w - 32.0) / approx_char_width).max(10.0) as usize;
+    #[test]
+    fn dragging_near_the_origin_or_the_layout_edge_snaps_to_it() {
+        let mut app = fixture_app();
+        assert_eq!(snap(&app.outputs, Vec::new(), 1, 13, 1500), (0, 1500));
+        app.outputs[0].position = (300, 0);
+        assert_eq!(snap(&app.outputs, Vec::new(), 1, 310, 1500), (300, 1500));
+    }
 
-                let mut lines = Vec::new();
-                let mut current_line = String::new();
+    #[test]
+    fn snapping_leaves_room_for_bezels() {
+        let app = fixture_app();
+        let bezels = vec![
+            Bezels {
+                right: 12.0,
+                ..Default::default()
+            },
+            Bezels {
+                left: 8.0,
+                ..Default::default()
+            },
+        ];
+        assert_eq!(snap(&app.outputs, bezels, 1, 2575, 0), (2580, 0));
+    }
 
-                for word in out.description.split_whitespace() {
-                    if current_line.len() + word.len() + 1 > max_chars && !current_line.is_empty() {
-                        lines.push(current_line);
-                        current_line = word.to_string();
-                    } else {
-                        if !current_line.is_empty() {
-                            current_line.push(' ');
-                        }
-                        current_line.push_str(word);
-                    }
-                }
-                if !current_line.is_empty() {
-                    lines.push(current_line);
-                }
+    #[test]
+    fn positions_away_from_other_outputs_round_to_the_grid() {
+        let app = fixture_app();
+        assert_eq!(snap(&app.outputs, Vec::new(), 1, 6003, 2207), (6000, 2210));
+        let ctrl = keyboard::Modifiers::CTRL;
+        assert_eq!(
+            snap_with(&app.outputs, Vec::new(), 1, (6003, 2207), ctrl),
+            (6003, 2207)
+        );
+    }
 
-                for line in lines {
-                    let mut desc_text = canvas::Text::default();
-                    desc_text.content = line;
-                    desc_text.position = Point::new(text_x, text_y);
-                    desc_text.size = iced::Pixels(text_size);
-                    desc_text.color = if is_selected {
-                        Color::from_rgb8(40, 40, 40)
-                    } else {
-                        Color::from_rgb8(160, 160, 160)
-                    };
-                    frame.fill_text(desc_text);
-                    text_y += text_size * 1.3;
-                }
-            }
+    #[test]
+    fn normalization_moves_the_layout_back_to_the_origin() {
+        let mut app = fixture_app();
+        app.outputs[0].position = (-100, -20);
+        app.outputs[1].position = (2460, -20);
+        app.normalize_positions();
+        assert_eq!(app.outputs[0].position, (0, 0));
+        assert_eq!(app.outputs[1].position, (2560, 0));
+        assert_eq!(app.x_input, "0");
+    }
+
+    #[test]
+    fn scales_accept_a_decimal_comma() {
+        let mut app = fixture_app();
+        let _ = app.update(Message::ScaleChanged("1,25".to_string()));
+        assert_eq!(app.outputs[0].scale, 1.25);
+        let _ = app.update(Message::ScaleChanged("1.5".to_string()));
+        assert_eq!(app.outputs[0].scale, 1.5);
+    }
+
+    #[test]
+    fn invalid_fields_keep_their_text_and_leave_the_output_alone() {
+        let mut app = fixture_app();
+        let _ = app.update(Message::XChanged("-40".to_string()));
+        assert_eq!(app.x_input, "-40");
+        assert_eq!(app.outputs[0].position.0, 0);
+        let _ = app.update(Message::ScaleChanged("1,".to_string()));
+        let _ = app.update(Message::YInc);
+        assert_eq!(app.scale_input, "1,");
+        assert_eq!(app.x_input, "0");
+    }
+
+    #[test]
+    fn selecting_a_resolution_leaves_exactly_one_current_mode() {
+        let mut app = fixture_app();
+        let _ = app.update(Message::ResolutionSelected(2));
+        let current: Vec<_> = app.outputs[0].modes.iter().map(|m| m.current).collect();
+        assert_eq!(current, [false, false, true, false]);
+    }
+
+    #[test]
+    fn cycling_modes_wraps_around() {
+        let mut app = fixture_app();
+        let _ = app.update(Message::CycleMode(-1));
+        assert_eq!(current_mode(&app, 0), 3);
+        let _ = app.update(Message::CycleMode(1));
+        assert_eq!(current_mode(&app, 0), 0);
+    }
+
+    #[test]
+    fn reset_to_preferred_picks_the_preferred_mode() {
+        let mut app = fixture_app();
+        let _ = app.update(Message::ResetToPreferred);
+        assert_eq!(current_mode(&app, 0), 1);
+    }
+
+    #[test]
+    fn undo_restores_the_previous_layout() {
+        let mut app = fixture_app();
+        let _ = app.update(Message::NudgeSelected(NUDGE_STEP, 0));
+        assert_eq!(app.outputs[0].position, (NUDGE_STEP, 0));
+        let _ = app.update(Message::Undo);
+        assert_eq!(app.outputs[0].position, (0, 0));
+    }
+
+    #[test]
+    fn the_last_enabled_output_cannot_be_disabled() {
+        let mut app = fixture_app();
+        let _ = app.update(Message::MonitorClicked(app.outputs[1].name.clone()));
+        let _ = app.update(Message::ToggleSelectedEnabled);
+        assert!(!app.outputs[1].enabled);
+        let _ = app.update(Message::MonitorClicked(app.outputs[0].name.clone()));
+        let _ = app.update(Message::ToggleSelectedEnabled);
+        assert!(app.outputs[0].enabled);
+    }
+
+    #[test]
+    fn edits_survive_the_selected_output_being_unplugged() {
+        let mut app = fixture_app();
+        let gone = app.outputs[2].name.clone();
+        let _ = app.update(Message::MonitorClicked(gone.clone()));
+        let remaining = app.outputs[..2].to_vec();
+        let _ = app.update(Message::OutputsChanged(remaining));
+        let _ = app.update(Message::MonitorPositioned(gone, 100, 100));
+        let _ = app.update(Message::NudgeSelected(NUDGE_STEP, 0));
+        assert_eq!(app.selected_idx(), Some(0));
+        assert_eq!(app.outputs[0].position, (NUDGE_STEP, 0));
+    }
+
+    #[test]
+    fn gaming_picks_the_fastest_mode_and_reverts_to_the_applied_layout() {
+        let mut app = fixture_app();
+        app.settings.gaming_disables_others = true;
+        let _ = app.update(Message::ResolutionSelected(1));
+        app.applied_outputs = app.outputs.clone();
+        let _ = app.update(Message::QuickActionClicked(QuickAction::Gaming));
+        assert_eq!(current_mode(&app, 0), 0);
+        assert!(!app.outputs[1].enabled);
+
+        let _ = app.update(Message::QuickActionReverted);
+        assert_eq!(current_mode(&app, 0), 1);
+        assert!(app.outputs[1].enabled);
+        assert!(app.quick_action.is_none());
+    }
+
+    #[test]
+    fn a_live_revert_during_an_apply_waits_for_it() {
+        let mut app = fixture_app();
+        let before = app.outputs.clone();
+        app.outputs[1].position = (0, 1440);
+        app.live_revert = Some(PendingRevert {
+            layout: before.clone(),
+            seconds: 0,
+            id: 1,
         });
+        app.busy = Some(tr("Applying…"));
+        let _ = app.update(Message::LiveChangesReverted);
+        assert!(app.live_revert.is_some());
+        assert_eq!(app.outputs[1].position, (0, 1440));
+
+        app.busy = None;
+        let _ = app.update(Message::LiveChangesReverted);
+        assert!(app.live_revert.is_none());
+        assert_eq!(app.outputs, before);
+        assert!(app.busy.is_some());
+    }
+
+    #[test]
+    fn a_read_only_window_edits_but_neither_applies_nor_saves() {
+        let mut app = fixture_app();
+        app.locked = true;
+        let _ = app.update(Message::ResolutionSelected(1));
+        assert_eq!(current_mode(&app, 0), 1);
+        let _ = app.update(Message::QuickActionClicked(QuickAction::Gaming));
+        assert!(app.quick_action.is_none());
+        let _ = app.update(Message::ApplyClicked);
+        assert!(app.busy.is_none());
+        let _ = app.update(Message::SettingsToggled);
+        assert!(!app.settings_open);
 
-        vec![geometry]
+        let before = app.settings.clone();
+        let _ = app.update(Message::NightLightToggled(true));
+        let _ = app.update(Message::DockProfileSelected(
+            "dock".to_string(),
+            Some("desk".to_string()),
+        ));
+        assert_eq!(app.settings, before);
+        app.status_message = None;
+        app.save_settings();
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("Read-only: this window cannot apply or save")
+        );
+
+        app.window_size = Size::new(1234.0, 567.0);
+        let _ = app.update(Message::TabPressed(app.outputs[1].name.clone()));
+        let _ = app.update(Message::TabDragFinished);
+        let _ = app.update(Message::CloseRequested);
+        assert_ne!(AppSettings::load().ui.window_width, 1234.0);
+    }
+
+    #[test]
+    fn a_profile_opens_offline_with_its_unplugged_monitors() {
+        let mut app = fixture_app();
+        let mut unplugged = app.outputs[1].clone();
+        unplugged.name = "DP-3".to_string();
+        unplugged.position = (-1920, 0);
+        let mut profile = Profile::from_outputs("office", &[app.outputs[0].clone(), unplugged]);
+        profile.outputs[1].width = 1280;
+        profile.outputs[1].height = 720;
+        app.settings.store_profile(profile);
+
+        let _ = app.update(Message::OfflineEditStarted("office".to_string()));
+        assert_eq!(app.outputs.len(), 2);
+        let office = &app.outputs[1];
+        assert_eq!(office.name, "DP-3");
+        assert!(office.modes.len() > 1);
+        let mode = office.modes.iter().find(|m| m.current).unwrap();
+        assert_eq!((mode.width, mode.height), (1280, 720));
+
+        let _ = app.update(Message::ApplyClicked);
+        assert!(app.busy.is_none());
+    }
+
+    #[test]
+    fn presenting_mirrors_at_the_largest_shared_resolution() {
+        let mut app = fixture_app();
+        let _ = app.update(Message::MonitorClicked(app.outputs[1].name.clone()));
+        let _ = app.update(Message::QuickActionClicked(QuickAction::Present));
+        let current = |idx: usize| {
+            let out = &app.outputs[idx];
+            let m = &out.modes[current_mode(&app, idx)];
+            (m.width, m.height)
+        };
+        assert_eq!(current(0), (1920, 1080));
+        assert_eq!(current(1), (1920, 1080));
+        assert_eq!(app.outputs[1].position, app.outputs[0].position);
     }
 }
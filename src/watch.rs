@@ -0,0 +1,89 @@
+use std::path::{Path, PathBuf};
+
+use inotify::{Inotify, WatchMask};
+
+/// Blocks, calling `on_change` with each of `files` that is written or replaced, until it
+/// returns `false`. Parent directories are watched rather than the files themselves, so
+/// editors and dotfile managers that swap in a new file are caught as well.
+pub fn watch_files(
+    files: &[PathBuf],
+    mut on_change: impl FnMut(&Path) -> bool,
+) -> Result<(), String> {
+    let mut inotify = Inotify::init().map_err(|e| format!("Failed to start inotify: {}", e))?;
+    let mut watched = Vec::new();
+    for file in files {
+        let Some(dir) = file.parent() else {
+            continue;
+        };
+        if let Err(e) = std::fs::create_dir_all(dir) {
+            tracing::warn!("Not watching {}: {}", file.display(), e);
+            continue;
+        }
+        let wd = inotify
+            .watches()
+            .add(dir, WatchMask::CLOSE_WRITE | WatchMask::MOVED_TO)
+            .map_err(|e| format!("Failed to watch {}: {}", dir.display(), e))?;
+        watched.push((wd, file));
+    }
+
+    let mut buffer = [0; 4096];
+    loop {
+        let events = inotify
+            .read_events_blocking(&mut buffer)
+            .map_err(|e| format!("Failed to read inotify events: {}", e))?;
+        let mut changed: Vec<&PathBuf> = Vec::new();
+        for event in events {
+            if let Some((_, file)) = watched.iter().find(|(wd, file)| {
+                *wd == event.wd && event.name.is_some_and(|n| Some(n) == file.file_name())
+            }) && !changed.contains(file)
+            {
+                changed.push(file);
+            }
+        }
+        for file in changed {
+            tracing::debug!("{} changed on disk", file.display());
+            if !on_change(file) {
+                return Ok(());
+            }
+        }
+    }
+}
+
+/// Blocks, calling `on_change` whenever a USB device is plugged in or removed, until it
+/// returns `false`. The kernel adds and removes nodes under `/dev/bus/usb` as devices
+/// come and go, including whole buses for Thunderbolt docks, so watching it needs no
+/// udev library.
+pub fn watch_usb(mut on_change: impl FnMut() -> bool) -> Result<(), String> {
+    let root = Path::new("/dev/bus/usb");
+    let mut inotify = Inotify::init().map_err(|e| format!("Failed to start inotify: {}", e))?;
+    let mask = WatchMask::CREATE | WatchMask::DELETE;
+    let root_wd = inotify
+        .watches()
+        .add(root, mask)
+        .map_err(|e| format!("Failed to watch {}: {}", root.display(), e))?;
+    let watch_bus = |inotify: &mut Inotify, bus: &Path| {
+        if let Err(e) = inotify.watches().add(bus, mask) {
+            tracing::warn!("Not watching {}: {}", bus.display(), e);
+        }
+    };
+    for entry in std::fs::read_dir(root).into_iter().flatten().flatten() {
+        watch_bus(&mut inotify, &entry.path());
+    }
+
+    let mut buffer = [0; 4096];
+    loop {
+        let events = inotify
+            .read_events_blocking(&mut buffer)
+            .map_err(|e| format!("Failed to read inotify events: {}", e))?;
+        let new_buses: Vec<PathBuf> = events
+            .filter(|e| e.wd == root_wd && e.mask.contains(inotify::EventMask::CREATE))
+            .filter_map(|e| e.name.map(|n| root.join(n)))
+            .collect();
+        for bus in new_buses {
+            watch_bus(&mut inotify, &bus);
+        }
+        if !on_change() {
+            return Ok(());
+        }
+    }
+}